@@ -0,0 +1,101 @@
+//! Compares the time spent per record by the lum_log logger, which formats the message once and shares it with all appenders,
+//! to a plain log4rs logger with the same configuration, where every appender formats the message again.
+//! Run it with `cargo bench --bench shared_formatting`.
+
+use std::{
+    fmt::{self, Debug, Formatter},
+    hint::black_box,
+    io,
+    time::{Duration, Instant},
+};
+
+use lum_log::{
+    ConfigBuilder,
+    log::{Level, LevelFilter, Log, Record},
+    log4rs::{
+        self,
+        append::Append,
+        config::{Appender, Root},
+        encode::{Encode, pattern::PatternEncoder, writer::simple::SimpleWriter},
+    },
+};
+
+const RECORDS: u32 = 200_000;
+const PATTERN: &str = "{d(%Y-%m-%d %H:%M:%S%.3f)} {l} {t} {m}{n}";
+
+/// An appender encoding records with a pattern encoder and discarding the output, so only encoding is measured.
+#[derive(Debug)]
+struct DiscardAppender(PatternEncoder);
+
+impl DiscardAppender {
+    fn boxed() -> Box<dyn Append> {
+        Box::new(Self(PatternEncoder::new(PATTERN)))
+    }
+}
+
+impl Append for DiscardAppender {
+    fn append(&self, record: &Record) -> anyhow::Result<()> {
+        self.0.encode(&mut SimpleWriter(io::sink()), record)
+    }
+
+    fn flush(&self) {}
+}
+
+/// A value with a costly `Debug` implementation, like a typical request or entity logged with `{:?}`.
+struct Order {
+    id: u64,
+    items: Vec<(&'static str, u32, f64)>,
+}
+
+impl Debug for Order {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Order")
+            .field("id", &self.id)
+            .field("items", &self.items)
+            .finish()
+    }
+}
+
+fn measure(appenders: usize, log: &dyn Fn(&Record)) -> Duration {
+    let order = Order {
+        id: 17,
+        items: vec![("apple", 3, 0.5), ("pear", 1, 0.75), ("plum", 12, 0.2)],
+    };
+
+    let start = Instant::now();
+    for index in 0..RECORDS {
+        log(&Record::builder()
+            .level(Level::Info)
+            .target("bench")
+            .args(format_args!(
+                "Order created: {order:?}, attempt {index}, appenders {appenders}"
+            ))
+            .build());
+    }
+    black_box(start.elapsed() / RECORDS)
+}
+
+fn main() {
+    for appenders in [1, 4] {
+        let mut builder = ConfigBuilder::new().root_log_level(LevelFilter::Info);
+        let mut config = log4rs::Config::builder();
+        for index in 0..appenders {
+            let name = format!("appender{index}");
+            builder = builder.appender(name.clone(), DiscardAppender::boxed());
+            config = config.appender(Appender::builder().build(name, DiscardAppender::boxed()));
+        }
+        builder.apply().expect("The logger can be set up");
+        let config = config
+            .build(
+                Root::builder()
+                    .appenders((0..appenders).map(|index| format!("appender{index}")))
+                    .build(LevelFilter::Info),
+            )
+            .expect("The configuration is valid");
+        let log4rs_logger = log4rs::Logger::new(config);
+
+        let log4rs = measure(appenders, &|record| log4rs_logger.log(record));
+        let lum_log = measure(appenders, &|record| lum_log::log::logger().log(record));
+        println!("{appenders} appender(s): log4rs {log4rs:?}, lum_log {lum_log:?} per record");
+    }
+}
@@ -0,0 +1,221 @@
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use lum_libs::{
+    humantime, log,
+    parking_lot::{Mutex, RwLock, const_rwlock},
+    serde::{Deserialize, Serialize},
+};
+
+use crate::default;
+
+/// The target of the warnings logged for anomalous record rates, see [`set_anomaly_detection`].
+pub const ANOMALY_TARGET: &str = "lum_log::anomaly";
+
+/// The weight of the most recent window in the baseline of a target.
+const SMOOTHING: f64 = 0.2;
+/// The number of windows a target must have been observed for before its rate is compared to its baseline.
+const WARMUP_WINDOWS: u32 = 3;
+
+static DETECTION: RwLock<Option<AnomalyDetection>> = const_rwlock(None);
+static RATES: RwLock<Option<HashMap<String, Arc<Mutex<TargetRate>>>>> = const_rwlock(None);
+
+/// The settings of the detector warning about targets whose record rate jumps far above their baseline, see [`set_anomaly_detection`].
+/// The baseline of a target is the exponentially weighted average of its records per window.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde", default)]
+pub struct AnomalyDetection {
+    /// The window in which records are counted.
+    #[serde(with = "crate::duration")]
+    pub window: Duration,
+    /// The factor by which the records of a window must exceed the baseline to be reported.
+    pub factor: f64,
+    /// The minimum number of records of a window to be reported, so targets with a tiny baseline do not cause noise.
+    pub min_records: u64,
+}
+
+impl Default for AnomalyDetection {
+    /// Creates an `AnomalyDetection` with a window of [`default::anomaly_window`], a factor of [`default::anomaly_factor`],
+    /// and a minimum of [`default::anomaly_min_records`] records.
+    fn default() -> Self {
+        Self {
+            window: default::anomaly_window(),
+            factor: default::anomaly_factor(),
+            min_records: default::anomaly_min_records(),
+        }
+    }
+}
+
+impl AnomalyDetection {
+    /// Same as [`AnomalyDetection::default`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of records in a window at which a target with the given baseline is reported.
+    fn threshold(&self, baseline: f64) -> f64 {
+        (baseline * self.factor).max(self.min_records as f64)
+    }
+}
+
+/// The record rate of a single target.
+#[derive(Debug)]
+struct TargetRate {
+    window_start: Instant,
+    count: u64,
+    baseline: f64,
+    windows: u32,
+    last_warning: Option<Instant>,
+}
+
+/// An anomalous record rate of a target, reported outside of the lock of its rate.
+struct Anomaly {
+    count: u64,
+    baseline: f64,
+}
+
+impl TargetRate {
+    fn new(now: Instant) -> Self {
+        Self {
+            window_start: now,
+            count: 0,
+            baseline: 0.0,
+            windows: 0,
+            last_warning: None,
+        }
+    }
+
+    /// Counts a record, closing the current window first if it is over.
+    /// Returns an anomaly if the count of the current window just crossed the threshold.
+    fn record(&mut self, detection: &AnomalyDetection, now: Instant) -> Option<Anomaly> {
+        let window = detection.window.max(Duration::from_millis(1));
+        let elapsed = now.saturating_duration_since(self.window_start);
+        if elapsed >= window {
+            let windows = elapsed.as_nanos() / window.as_nanos();
+            self.close_window(self.count);
+            // Windows without any record lower the baseline as well. Beyond a few hundred, it is zero anyway.
+            for _ in 1..windows.min(256) {
+                self.close_window(0);
+            }
+            self.window_start =
+                now - Duration::from_nanos((elapsed.as_nanos() % window.as_nanos()) as u64);
+            self.count = 0;
+        }
+
+        self.count += 1;
+        if self.windows < WARMUP_WINDOWS
+            || (self.count as f64) < detection.threshold(self.baseline)
+            || self.last_warning.is_some_and(|last| {
+                now.saturating_duration_since(last) < default::anomaly_warning_interval()
+            })
+        {
+            return None;
+        }
+
+        self.last_warning = Some(now);
+        Some(Anomaly {
+            count: self.count,
+            baseline: self.baseline,
+        })
+    }
+
+    fn close_window(&mut self, count: u64) {
+        self.baseline = match self.windows {
+            0 => count as f64,
+            _ => self.baseline + SMOOTHING * (count as f64 - self.baseline),
+        };
+        self.windows = self.windows.saturating_add(1);
+    }
+}
+
+/// Counts a record of the given target, warning if its rate jumped above its baseline.
+pub(crate) fn observe(target: &str) {
+    if target == ANOMALY_TARGET {
+        return;
+    }
+    let Some(detection) = DETECTION.read().clone() else {
+        return;
+    };
+
+    let now = Instant::now();
+    let Some(anomaly) = rate(target, now).lock().record(&detection, now) else {
+        return;
+    };
+
+    log::warn!(
+        target: ANOMALY_TARGET,
+        "Target {target} logged {} records within {}, while its baseline is {:.1} records per window",
+        anomaly.count,
+        humantime::format_duration(detection.window),
+        anomaly.baseline,
+    );
+}
+
+/// Returns the rate of the given target, creating it if needed.
+fn rate(target: &str, now: Instant) -> Arc<Mutex<TargetRate>> {
+    if let Some(rate) = RATES.read().as_ref().and_then(|rates| rates.get(target)) {
+        return Arc::clone(rate);
+    }
+
+    let mut rates = RATES.write();
+    let rate = rates
+        .get_or_insert_with(HashMap::new)
+        .entry(target.to_string())
+        .or_insert_with(|| Arc::new(Mutex::new(TargetRate::new(now))));
+    Arc::clone(rate)
+}
+
+/// Sets the settings of the anomaly detector, or `None` to disable it.
+/// While enabled, the records of every target are counted per window, and a warning is logged to [`ANOMALY_TARGET`]
+/// when a target logs more than [`AnomalyDetection::factor`] times its baseline in a window, an order of magnitude by default,
+/// at most once per [`default::anomaly_warning_interval`] per target, e.g. to catch a regression suddenly spamming debug output.
+/// Targets are only compared to their baseline after they have been observed for a few windows.
+/// Baselines are kept when the settings change, unless the window changes. The detector is disabled by default.
+/// See also [`ConfigBuilder::anomaly_detection`](crate::ConfigBuilder::anomaly_detection).
+pub fn set_anomaly_detection(detection: Option<AnomalyDetection>) {
+    let mut current = DETECTION.write();
+    let window = current.as_ref().map(|detection| detection.window);
+    if detection.as_ref().map(|detection| detection.window) != window {
+        *RATES.write() = None;
+    }
+    *current = detection;
+}
+
+/// Returns the settings set by [`set_anomaly_detection`], if any.
+pub fn anomaly_detection() -> Option<AnomalyDetection> {
+    DETECTION.read().clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jumps_above_the_baseline_are_reported_once_after_the_warmup() {
+        let detection = AnomalyDetection {
+            window: Duration::from_secs(1),
+            factor: 10.0,
+            min_records: 5,
+        };
+        let start = Instant::now();
+        let mut rate = TargetRate::new(start);
+        let anomalies = |rate: &mut TargetRate, window: u64, records: u64| {
+            let now = start + Duration::from_secs(window);
+            (0..records)
+                .filter_map(|_| rate.record(&detection, now))
+                .map(|anomaly| (anomaly.count, anomaly.baseline))
+                .collect::<Vec<_>>()
+        };
+
+        // The first windows only build the baseline, although their records exceed the minimum.
+        for window in 0..3 {
+            assert_eq!(anomalies(&mut rate, window, 10), []);
+        }
+        // Ten times the baseline of 10 records is reported once, and further records are not.
+        assert_eq!(anomalies(&mut rate, 3, 99), []);
+        assert_eq!(anomalies(&mut rate, 3, 50), [(100, 10.0)]);
+    }
+}
@@ -0,0 +1,54 @@
+/// Defines the [`AlertAppender`], which detects bursts of records.
+pub mod alert;
+/// Defines the [`AsyncAppender`], which passes records to another appender on a background thread.
+pub mod asynchronous;
+/// Defines the [`BroadcastAppender`], which publishes records to subscribers.
+pub mod broadcast;
+/// Defines the [`CallbackAppender`], which invokes a callback for each record.
+pub mod callback;
+/// Defines the [`ChannelAppender`], which sends records into a channel.
+pub mod channel;
+/// Defines the [`FailoverAppender`], which falls back to another appender when its primary fails.
+pub mod failover;
+/// Defines the [`LazyAppender`], which creates another appender on the first record.
+pub mod lazy;
+/// Defines the [`NetworkAppender`], which sends records to a remote sink through a [`Transport`](network::Transport).
+pub mod network;
+/// Defines the [`PartitionedFileAppender`], which writes each time window to its own file.
+pub mod partitioned;
+/// Defines the [`NamedPipeAppender`], which writes records to a FIFO or Windows named pipe that readers can attach to.
+#[cfg(any(unix, windows))]
+pub mod pipe;
+/// Defines the [`PwriteFileAppender`], an experimental Linux file appender using positioned writes.
+#[cfg(all(target_os = "linux", feature = "pwrite"))]
+pub mod pwrite;
+/// Defines the [`MmapRingAppender`], which keeps the most recent records in a memory-mapped circular file.
+#[cfg(feature = "mmap")]
+pub mod ring;
+/// Defines the [`ShardedAppender`], which distributes records over a fixed number of appenders by a hash of one of their fields.
+pub mod sharded;
+/// Defines the [`ShmRingAppender`], an experimental appender writing fixed-size binary records into a shared memory ring.
+#[cfg(feature = "shm")]
+pub mod shm;
+/// Defines the [`SummaryAppender`], which logs periodic summaries of record counts.
+pub mod summary;
+
+pub use alert::AlertAppender;
+pub use asynchronous::AsyncAppender;
+pub use broadcast::BroadcastAppender;
+pub use callback::CallbackAppender;
+pub use channel::ChannelAppender;
+pub use failover::FailoverAppender;
+pub use lazy::LazyAppender;
+pub use network::NetworkAppender;
+pub use partitioned::PartitionedFileAppender;
+#[cfg(any(unix, windows))]
+pub use pipe::NamedPipeAppender;
+#[cfg(all(target_os = "linux", feature = "pwrite"))]
+pub use pwrite::PwriteFileAppender;
+#[cfg(feature = "mmap")]
+pub use ring::MmapRingAppender;
+pub use sharded::ShardedAppender;
+#[cfg(feature = "shm")]
+pub use shm::ShmRingAppender;
+pub use summary::SummaryAppender;
@@ -0,0 +1,194 @@
+use std::{
+    collections::VecDeque,
+    fmt::{self, Debug, Formatter},
+    time::{Duration, Instant},
+};
+
+use lum_libs::{
+    humantime,
+    log::{self, Level, Record},
+    log4rs::append::Append,
+    parking_lot::Mutex,
+};
+
+use crate::internal;
+
+/// The target of the record logged by an [`AlertAppender`] without a callback.
+pub const ALERT_TARGET: &str = "lum_log::alert";
+
+/// Information about a fired alert, passed to the callback of an [`AlertAppender`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Alert {
+    /// The level of the counted records.
+    pub level: Level,
+    /// The number of records counted within the window.
+    pub count: usize,
+    /// The sliding window the records were counted in.
+    pub window: Duration,
+}
+
+/// A callback invoked by the [`AlertAppender`].
+pub type AlertCallback = dyn Fn(&Alert) + Send + Sync;
+
+#[derive(Debug, Default)]
+struct AlertState {
+    timestamps: VecDeque<Instant>,
+    last_fired: Option<Instant>,
+}
+
+/// An appender detecting bursts of records, e.g. "error storms".
+/// It counts records at the given level or above within a sliding window and fires
+/// when the threshold is reached. After firing, it stays silent until the cooldown has passed.
+/// When firing, the callback is invoked, or if no callback is set,
+/// an error record with the target [`ALERT_TARGET`] is logged.
+/// Records with the target [`ALERT_TARGET`] are never counted.
+pub struct AlertAppender {
+    level: Level,
+    threshold: usize,
+    window: Duration,
+    cooldown: Duration,
+    callback: Option<Box<AlertCallback>>,
+    state: Mutex<AlertState>,
+}
+
+impl AlertAppender {
+    /// Creates a new `AlertAppender` firing when `threshold` error records are logged within `window`.
+    /// The cooldown defaults to the window.
+    pub fn new(threshold: usize, window: Duration) -> Self {
+        Self {
+            level: Level::Error,
+            threshold: threshold.max(1),
+            window,
+            cooldown: window,
+            callback: None,
+            state: Mutex::new(AlertState::default()),
+        }
+    }
+
+    /// Sets the minimum level of counted records.
+    pub fn level(mut self, level: Level) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Sets the time to stay silent after firing.
+    pub fn cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+
+    /// Sets the callback to invoke when firing instead of logging a record.
+    /// A panic inside the callback is caught and reported to stderr.
+    pub fn callback(mut self, callback: impl Fn(&Alert) + Send + Sync + 'static) -> Self {
+        self.callback = Some(Box::new(callback));
+        self
+    }
+
+    fn record(&self, now: Instant) -> Option<Alert> {
+        let mut state = self.state.lock();
+
+        state.timestamps.push_back(now);
+        while let Some(&timestamp) = state.timestamps.front() {
+            if now.duration_since(timestamp) <= self.window {
+                break;
+            }
+            state.timestamps.pop_front();
+        }
+
+        let count = state.timestamps.len();
+        if count < self.threshold {
+            return None;
+        }
+
+        let cooling_down = state
+            .last_fired
+            .is_some_and(|last_fired| now.duration_since(last_fired) < self.cooldown);
+        if cooling_down {
+            return None;
+        }
+
+        state.last_fired = Some(now);
+        state.timestamps.clear();
+        Some(Alert {
+            level: self.level,
+            count,
+            window: self.window,
+        })
+    }
+}
+
+impl Debug for AlertAppender {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AlertAppender")
+            .field("level", &self.level)
+            .field("threshold", &self.threshold)
+            .field("window", &self.window)
+            .field("cooldown", &self.cooldown)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Append for AlertAppender {
+    fn append(&self, record: &Record) -> anyhow::Result<()> {
+        if record.level() > self.level || record.target() == ALERT_TARGET {
+            return Ok(());
+        }
+
+        // The state lock is released before firing, as logging the alert re-enters this appender.
+        let Some(alert) = self.record(Instant::now()) else {
+            return Ok(());
+        };
+
+        match &self.callback {
+            Some(callback) => {
+                internal::catch("Alert callback", || callback(&alert));
+            }
+            None => log::error!(
+                target: ALERT_TARGET,
+                "{} records at level {} or above within {}",
+                alert.count,
+                alert.level,
+                humantime::format_duration(alert.window)
+            ),
+        }
+
+        Ok(())
+    }
+
+    fn flush(&self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fires_once_per_burst_within_the_window_and_stays_silent_during_the_cooldown() {
+        let appender =
+            AlertAppender::new(3, Duration::from_secs(10)).cooldown(Duration::from_secs(60));
+        let start = Instant::now();
+        let at = |seconds| start + Duration::from_secs(seconds);
+
+        assert_eq!(appender.record(at(0)), None);
+        assert_eq!(appender.record(at(1)), None);
+        assert_eq!(
+            appender.record(at(2)),
+            Some(Alert {
+                level: Level::Error,
+                count: 3,
+                window: Duration::from_secs(10),
+            })
+        );
+
+        // Cooling down.
+        for seconds in 3..6 {
+            assert_eq!(appender.record(at(seconds)), None);
+        }
+        // Spread too far apart to fall within one window.
+        for seconds in [100, 200, 300] {
+            assert_eq!(appender.record(at(seconds)), None);
+        }
+        assert_eq!(appender.record(at(301)), None);
+        assert!(appender.record(at(302)).is_some());
+    }
+}
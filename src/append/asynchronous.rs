@@ -0,0 +1,813 @@
+use std::{
+    collections::VecDeque,
+    io,
+    sync::{
+        Arc, Weak,
+        atomic::{AtomicU64, Ordering},
+    },
+    thread,
+    time::Instant,
+};
+
+use lum_libs::{
+    log::Record,
+    log4rs::append::Append,
+    parking_lot::{Condvar, Mutex},
+    serde_json,
+};
+
+use crate::{
+    backpressure::Backpressure,
+    default, internal,
+    memory::{self, MemoryPolicy},
+    record::OwnedRecord,
+    spool::Spool,
+    stats,
+};
+
+/// The MDC key under which the sequence number of a record is exposed while an ordered [`AsyncAppender`] appends it,
+/// see [`AsyncAppenderBuilder::ordered`]. Use it in patterns as `{X(sequence)}`.
+pub const SEQUENCE_MDC_KEY: &str = "sequence";
+
+/// The queues of all live [`AsyncAppender`]s in the order they were built, drained by [`drain_all`].
+static QUEUES: Mutex<Vec<Weak<Queue>>> = Mutex::new(Vec::new());
+
+#[derive(Debug, Default)]
+struct QueueState {
+    records: VecDeque<OwnedRecord>,
+    /// The number of workers currently appending a record.
+    appending: usize,
+    /// The sequence number of the next record taken from the buffer.
+    taken: u64,
+    /// The sequence number of the next record to append, if records are ordered.
+    next_to_append: u64,
+    /// The number of buffered records appended so far.
+    appended: u64,
+    /// Whether the appender was dropped or drained, so new records are discarded and workers exit once the buffer is empty.
+    closed: bool,
+}
+
+#[derive(Debug)]
+struct Queue {
+    state: Mutex<QueueState>,
+    changed: Condvar,
+    capacity: usize,
+    backpressure: Backpressure,
+    spool: Option<Spool>,
+    dropped: AtomicU64,
+    ordered: bool,
+}
+
+/// A builder for [`AsyncAppender`]s.
+#[derive(Debug)]
+pub struct AsyncAppenderBuilder {
+    capacity: usize,
+    backpressure: Backpressure,
+    workers: usize,
+    ordered: bool,
+}
+
+impl Default for AsyncAppenderBuilder {
+    /// Creates an `AsyncAppenderBuilder` using [`default::async_buffer_capacity`], [`Backpressure::Block`], one worker thread, and no ordering guarantee.
+    fn default() -> Self {
+        Self {
+            capacity: default::async_buffer_capacity(),
+            backpressure: Backpressure::Block,
+            workers: 1,
+            ordered: false,
+        }
+    }
+}
+
+impl AsyncAppenderBuilder {
+    /// Sets the number of records held in the buffer.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Sets what happens to new records when the buffer is full.
+    pub fn backpressure(mut self, backpressure: Backpressure) -> Self {
+        self.backpressure = backpressure;
+        self
+    }
+
+    /// Sets the number of worker threads passing records to the wrapped appender, at least one.
+    /// Multiple workers help heavy sinks, e.g. compressing, encrypting, or sending records,
+    /// but records may then reach the wrapped appender out of order.
+    pub fn workers(mut self, workers: usize) -> Self {
+        self.workers = workers;
+        self
+    }
+
+    /// Sets whether records reach the wrapped appender in the order they were buffered, i.e. emitted, even with multiple workers.
+    /// Every buffered record gets a monotonic sequence number, exposed under [`SEQUENCE_MDC_KEY`] while it is appended,
+    /// and workers wait for their turn before appending. This trades throughput for strict ordering,
+    /// e.g. for tests and replay tooling. Records spilled by [`Backpressure::Spill`] are replayed after the buffered ones.
+    pub fn ordered(mut self, ordered: bool) -> Self {
+        self.ordered = ordered;
+        self
+    }
+
+    /// Builds the [`AsyncAppender`] wrapping the given appender, spawning its worker threads.
+    pub fn build(self, inner: Box<dyn Append>) -> io::Result<AsyncAppender> {
+        let queue = Arc::new(Queue {
+            state: Mutex::new(QueueState::default()),
+            changed: Condvar::new(),
+            capacity: self.capacity.max(1),
+            spool: self.backpressure.open_spool()?,
+            backpressure: self.backpressure,
+            dropped: AtomicU64::new(0),
+            ordered: self.ordered,
+        });
+
+        let mut queues = QUEUES.lock();
+        queues.retain(|queue| queue.strong_count() > 0);
+        queues.push(Arc::downgrade(&queue));
+        drop(queues);
+
+        let inner: Arc<dyn Append> = Arc::from(inner);
+        for _ in 0..self.workers.max(1) {
+            let worker_queue = Arc::clone(&queue);
+            let worker_inner = Arc::clone(&inner);
+            thread::Builder::new()
+                .name("lum_log-async".to_string())
+                .spawn(move || run(worker_queue, worker_inner))?;
+        }
+
+        Ok(AsyncAppender { queue })
+    }
+}
+
+/// An appender passing records to the wrapped appender on a background thread, or a pool of them,
+/// so slow appenders do not slow down the logging thread.
+/// Records are held in a bounded buffer, and the [`Backpressure`] strategy decides what happens when it is full.
+/// The MDC of the logging thread is restored while a record is appended, and records copied by the wrapped appender
+/// keep the time and thread they were logged at, see [`OwnedRecord::with_record`].
+/// Note that the encoders of log4rs still render the background thread and the time a record is processed.
+#[derive(Debug)]
+pub struct AsyncAppender {
+    queue: Arc<Queue>,
+}
+
+impl AsyncAppender {
+    /// Creates a new `AsyncAppender` wrapping the given appender, using the defaults of [`AsyncAppenderBuilder`].
+    pub fn new(inner: Box<dyn Append>) -> io::Result<Self> {
+        Self::builder().build(inner)
+    }
+
+    /// Creates a new [`AsyncAppenderBuilder`].
+    pub fn builder() -> AsyncAppenderBuilder {
+        AsyncAppenderBuilder::default()
+    }
+
+    /// Returns the number of records dropped because the buffer was full.
+    pub fn dropped(&self) -> u64 {
+        self.queue.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of records currently held in the buffer.
+    pub fn buffered(&self) -> usize {
+        self.queue.state.lock().records.len()
+    }
+
+    fn spill(&self, record: &OwnedRecord) -> bool {
+        let Some(spool) = &self.queue.spool else {
+            return false;
+        };
+
+        serde_json::to_vec(record)
+            .map_err(io::Error::from)
+            .and_then(|payload| spool.push(&payload))
+            .is_ok()
+    }
+}
+
+impl Append for AsyncAppender {
+    fn append(&self, record: &Record) -> anyhow::Result<()> {
+        let record = OwnedRecord::from(record);
+        let size = record.estimated_size();
+
+        let mut state = self.queue.state.lock();
+        loop {
+            // The queue may be closed while waiting for space, after which no worker would take the record.
+            if state.closed {
+                self.queue.dropped.fetch_add(1, Ordering::Relaxed);
+                stats::record_dropped();
+                return Ok(());
+            }
+
+            if state.records.len() < self.queue.capacity {
+                if memory::try_reserve(size) {
+                    break;
+                }
+                if memory::memory_policy() == MemoryPolicy::DropNewest {
+                    self.queue.dropped.fetch_add(1, Ordering::Relaxed);
+                    stats::record_dropped();
+                    return Ok(());
+                }
+            }
+
+            match self.queue.backpressure {
+                Backpressure::Block => {
+                    // Memory released by other buffers does not notify this queue, so it is polled.
+                    self.queue
+                        .changed
+                        .wait_for(&mut state, memory::RETRY_INTERVAL);
+                }
+                Backpressure::DropNewest => {
+                    self.queue.dropped.fetch_add(1, Ordering::Relaxed);
+                    stats::record_dropped();
+                    return Ok(());
+                }
+                Backpressure::DropOldest => {
+                    let oldest = state.records.pop_front();
+                    self.queue.dropped.fetch_add(1, Ordering::Relaxed);
+                    stats::record_dropped();
+                    match oldest {
+                        Some(oldest) => memory::release(oldest.estimated_size()),
+                        None => return Ok(()),
+                    }
+                }
+                Backpressure::Spill { .. } => {
+                    drop(state);
+                    if !self.spill(&record) {
+                        self.queue.dropped.fetch_add(1, Ordering::Relaxed);
+                        stats::record_dropped();
+                    }
+                    return Ok(());
+                }
+            }
+        }
+
+        state.records.push_back(record);
+        self.queue.changed.notify_all();
+        Ok(())
+    }
+
+    /// Waits until the buffer is empty, for at most [`default::flush_timeout`].
+    /// The wrapped appender is flushed by the background thread whenever the buffer runs empty.
+    fn flush(&self) {
+        let deadline = Instant::now() + default::flush_timeout();
+
+        let mut state = self.queue.state.lock();
+        while !state.records.is_empty() || state.appending > 0 {
+            if self
+                .queue
+                .changed
+                .wait_until(&mut state, deadline)
+                .timed_out()
+            {
+                break;
+            }
+        }
+    }
+}
+
+impl Drop for AsyncAppender {
+    fn drop(&mut self) {
+        self.queue.state.lock().closed = true;
+        self.queue.changed.notify_all();
+    }
+}
+
+fn run(queue: Arc<Queue>, inner: Arc<dyn Append>) {
+    let mut replay_failed = false;
+
+    loop {
+        let record = {
+            let mut state = queue.state.lock();
+            while state.records.is_empty() && !state.closed {
+                let spilled = queue.spool.as_ref().is_some_and(|spool| !spool.is_empty());
+                if !spilled {
+                    queue.changed.wait(&mut state);
+                    continue;
+                }
+
+                if replay_failed
+                    && !queue
+                        .changed
+                        .wait_for(&mut state, default::spool_replay_interval())
+                        .timed_out()
+                {
+                    continue;
+                }
+                break;
+            }
+
+            let record = state.records.pop_front().map(|record| {
+                memory::release(record.estimated_size());
+                let sequence = state.taken;
+                state.taken += 1;
+                (sequence, record)
+            });
+            if record.is_none() && state.closed {
+                drop(state);
+                inner.flush();
+                return;
+            }
+            state.appending += 1;
+            queue.changed.notify_all();
+            record
+        };
+
+        let buffered = record.is_some();
+        match record {
+            Some((sequence, record)) if queue.ordered => {
+                append_in_order(&queue, inner.as_ref(), sequence, &record)
+            }
+            Some((_, record)) => record.with_record(|record| {
+                let _ = inner.append(record);
+            }),
+            None => replay_failed = !replay_spool(&queue, inner.as_ref()),
+        }
+
+        if queue.state.lock().records.is_empty() {
+            inner.flush();
+        }
+
+        let mut state = queue.state.lock();
+        state.appending -= 1;
+        state.appended += u64::from(buffered);
+        drop(state);
+        queue.changed.notify_all();
+    }
+}
+
+/// Drains the buffers of all live [`AsyncAppender`]s, waiting until the given deadline at most, see [`drain`].
+pub(crate) fn drain_all(deadline: Instant) -> (u64, u64) {
+    let queues = QUEUES
+        .lock()
+        .iter()
+        .filter_map(Weak::upgrade)
+        .collect::<Vec<_>>();
+
+    drain(&queues, deadline)
+}
+
+/// Closes all given queues first, so records logged afterwards are discarded and counted as dropped by their appenders,
+/// then waits until the given deadline at most for the workers of all queues to append their buffered records concurrently.
+/// Each queue is drained in the order its records were buffered; spilled records stay in the spool to be replayed by the next process.
+/// Records still buffered at the deadline are discarded and counted as dropped.
+/// Returns the number of records appended and the number of records dropped while draining.
+fn drain(queues: &[Arc<Queue>], deadline: Instant) -> (u64, u64) {
+    let appended_before = queues
+        .iter()
+        .map(|queue| {
+            let mut state = queue.state.lock();
+            state.closed = true;
+            queue.changed.notify_all();
+            state.appended
+        })
+        .collect::<Vec<_>>();
+
+    // All workers run concurrently, so waiting for each queue in turn waits for all of them together.
+    for queue in queues {
+        let mut state = queue.state.lock();
+        while !state.records.is_empty() || state.appending > 0 {
+            if queue.changed.wait_until(&mut state, deadline).timed_out() {
+                break;
+            }
+        }
+    }
+
+    let (mut appended, mut dropped) = (0, 0);
+    for (queue, appended_before) in queues.iter().zip(appended_before) {
+        let mut state = queue.state.lock();
+        for record in state.records.drain(..) {
+            memory::release(record.estimated_size());
+            queue.dropped.fetch_add(1, Ordering::Relaxed);
+            stats::record_dropped();
+            dropped += 1;
+        }
+        appended += state.appended - appended_before;
+        drop(state);
+        queue.changed.notify_all();
+    }
+
+    (appended, dropped)
+}
+
+/// Waits until all records taken before the given one have been appended, then appends it.
+fn append_in_order(queue: &Queue, inner: &dyn Append, sequence: u64, record: &OwnedRecord) {
+    let mut state = queue.state.lock();
+    while state.next_to_append != sequence {
+        queue.changed.wait(&mut state);
+    }
+    drop(state);
+
+    {
+        let _sequence = log_mdc::insert_scoped(SEQUENCE_MDC_KEY, sequence.to_string());
+        // The other workers wait for this record, so a panicking appender must not skip advancing the sequence.
+        internal::catch("Ordered appender", || {
+            record.with_record(|record| {
+                let _ = inner.append(record);
+            })
+        });
+    }
+
+    queue.state.lock().next_to_append += 1;
+    queue.changed.notify_all();
+}
+
+/// Replays spilled records to the wrapped appender, returning whether all of them were replayed.
+fn replay_spool(queue: &Queue, inner: &dyn Append) -> bool {
+    let Some(spool) = &queue.spool else {
+        return true;
+    };
+
+    let result = spool.replay(|payload| {
+        // A record that cannot be decoded would never be replayed, so it is skipped.
+        let Ok(record) = serde_json::from_slice::<OwnedRecord>(payload) else {
+            return Ok(());
+        };
+        record.with_record(|record| inner.append(record).map_err(io::Error::other))
+    });
+    result.is_ok() && spool.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::atomic::AtomicBool,
+        time::{Duration, SystemTime},
+    };
+
+    use lum_libs::log::Level;
+
+    use super::*;
+    use crate::testing;
+
+    /// An appender counting and keeping the records it receives, blocking while it is paused.
+    #[derive(Debug, Default)]
+    struct Gate {
+        paused: AtomicBool,
+        appended: AtomicU64,
+        records: Mutex<Vec<OwnedRecord>>,
+    }
+
+    #[derive(Debug)]
+    struct GateAppender(Arc<Gate>);
+
+    impl Append for GateAppender {
+        fn append(&self, record: &Record) -> anyhow::Result<()> {
+            while self.0.paused.load(Ordering::SeqCst) {
+                thread::sleep(Duration::from_millis(1));
+            }
+            self.0.records.lock().push(OwnedRecord::from(record));
+            self.0.appended.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+
+        fn flush(&self) {}
+    }
+
+    fn log(appender: &AsyncAppender, count: usize) {
+        for _ in 0..count {
+            appender
+                .append(
+                    &Record::builder()
+                        .level(Level::Info)
+                        .args(format_args!("Buffered"))
+                        .build(),
+                )
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn drain_appends_all_buffered_records_in_time() {
+        let _global = testing::GLOBAL.lock();
+        let gate = Arc::new(Gate::default());
+        gate.paused.store(true, Ordering::SeqCst);
+        let appender = AsyncAppender::new(Box::new(GateAppender(Arc::clone(&gate)))).unwrap();
+        log(&appender, 10);
+
+        let queue = Arc::clone(&appender.queue);
+        let resume = Arc::clone(&gate);
+        let resumer = thread::spawn(move || {
+            while !queue.state.lock().closed {
+                thread::yield_now();
+            }
+            resume.paused.store(false, Ordering::SeqCst);
+        });
+        let counts = drain(
+            &[Arc::clone(&appender.queue)],
+            Instant::now() + Duration::from_secs(10),
+        );
+        resumer.join().unwrap();
+
+        assert_eq!(counts, (10, 0));
+        assert_eq!(gate.appended.load(Ordering::SeqCst), 10);
+    }
+
+    #[test]
+    fn drain_waits_for_all_queues_together_and_drops_the_rest() {
+        let _global = testing::GLOBAL.lock();
+        let stuck = Arc::new(Gate::default());
+        stuck.paused.store(true, Ordering::SeqCst);
+        let stuck_appender =
+            AsyncAppender::new(Box::new(GateAppender(Arc::clone(&stuck)))).unwrap();
+        let fast = Arc::new(Gate::default());
+        fast.paused.store(true, Ordering::SeqCst);
+        let fast_appender = AsyncAppender::new(Box::new(GateAppender(Arc::clone(&fast)))).unwrap();
+        log(&stuck_appender, 5);
+        log(&fast_appender, 5);
+        while stuck_appender.buffered() != 4 || fast_appender.buffered() != 4 {
+            thread::yield_now();
+        }
+
+        fast.paused.store(false, Ordering::SeqCst);
+        let started = Instant::now();
+        let counts = drain(
+            &[
+                Arc::clone(&stuck_appender.queue),
+                Arc::clone(&fast_appender.queue),
+            ],
+            started + Duration::from_millis(200),
+        );
+
+        // The stuck queue appends nothing and keeps one record in flight, while the other one is drained behind it.
+        assert_eq!(counts, (5, 4));
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert_eq!(stuck_appender.dropped(), 4);
+        assert_eq!(fast_appender.dropped(), 0);
+
+        log(&fast_appender, 1);
+        assert_eq!(fast_appender.dropped(), 1);
+        assert_eq!(fast_appender.buffered(), 0);
+        stuck.paused.store(false, Ordering::SeqCst);
+    }
+
+    #[test]
+    fn producers_blocked_on_a_full_buffer_drop_their_record_once_it_is_drained() {
+        let _global = testing::GLOBAL.lock();
+        let gate = Arc::new(Gate::default());
+        gate.paused.store(true, Ordering::SeqCst);
+        let appender = Arc::new(
+            AsyncAppender::builder()
+                .capacity(1)
+                .build(Box::new(GateAppender(Arc::clone(&gate))))
+                .unwrap(),
+        );
+        log(&appender, 1);
+        while appender.buffered() != 0 {
+            thread::yield_now();
+        }
+        log(&appender, 1);
+        let memory_used = memory::memory_used();
+
+        let blocked = Arc::clone(&appender);
+        let producer = thread::spawn(move || log(&blocked, 1));
+        // The producer waits for space, as the worker holds one record and the other one fills the buffer.
+        thread::sleep(Duration::from_millis(50));
+        let counts = drain(
+            &[Arc::clone(&appender.queue)],
+            Instant::now() + Duration::from_millis(50),
+        );
+        producer.join().unwrap();
+
+        assert_eq!(counts, (0, 1));
+        assert_eq!(appender.dropped(), 2);
+        assert_eq!(appender.buffered(), 0);
+        assert!(memory::memory_used() < memory_used);
+        gate.paused.store(false, Ordering::SeqCst);
+    }
+
+    /// Logs the given number of numbered records on a thread named `caller`, with the MDC entry `request=7`.
+    fn log_from_caller(appender: &Arc<AsyncAppender>, count: usize) {
+        let appender = Arc::clone(appender);
+        thread::Builder::new()
+            .name("caller".to_string())
+            .spawn(move || {
+                let _request = log_mdc::insert_scoped("request", "7");
+                for index in 0..count {
+                    appender
+                        .append(
+                            &Record::builder()
+                                .level(Level::Info)
+                                .args(format_args!("{index}"))
+                                .build(),
+                        )
+                        .unwrap();
+                }
+            })
+            .unwrap()
+            .join()
+            .unwrap()
+    }
+
+    /// Waits until the gate received the given number of records, returning them.
+    fn received(gate: &Gate, count: usize) -> Vec<OwnedRecord> {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while gate.records.lock().len() < count && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(1));
+        }
+        gate.records.lock().clone()
+    }
+
+    fn assert_caller_context(records: &[OwnedRecord], logged_before: SystemTime) {
+        for record in records {
+            assert_eq!(record.thread.as_deref(), Some("caller"));
+            assert!(
+                record
+                    .mdc
+                    .contains(&("request".to_string(), "7".to_string()))
+            );
+            assert!(record.timestamp <= logged_before);
+        }
+    }
+
+    #[test]
+    fn ordered_workers_append_in_emission_order_with_the_context_of_the_caller() {
+        let _global = testing::GLOBAL.lock();
+        let gate = Arc::new(Gate::default());
+        let appender = Arc::new(
+            AsyncAppender::builder()
+                .workers(4)
+                .ordered(true)
+                .build(Box::new(GateAppender(Arc::clone(&gate))))
+                .unwrap(),
+        );
+        log_from_caller(&appender, 50);
+        let logged_before = SystemTime::now();
+
+        let records = received(&gate, 50);
+        let messages = records
+            .iter()
+            .map(|record| record.message.parse::<usize>().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(messages, (0..50).collect::<Vec<_>>());
+        assert_caller_context(&records, logged_before);
+        for (index, record) in records.iter().enumerate() {
+            assert!(
+                record
+                    .mdc
+                    .contains(&(SEQUENCE_MDC_KEY.to_string(), index.to_string()))
+            );
+        }
+    }
+
+    /// An appender panicking on the record with the message `1`, passing the others to the gate.
+    #[derive(Debug)]
+    struct PanickingAppender(GateAppender);
+
+    impl Append for PanickingAppender {
+        fn append(&self, record: &Record) -> anyhow::Result<()> {
+            if record.args().to_string() == "1" {
+                panic!("Appending failed");
+            }
+            self.0.append(record)
+        }
+
+        fn flush(&self) {}
+    }
+
+    #[test]
+    fn ordered_workers_continue_after_a_panicking_append() {
+        let _global = testing::GLOBAL.lock();
+        let gate = Arc::new(Gate::default());
+        let appender = Arc::new(
+            AsyncAppender::builder()
+                .workers(2)
+                .ordered(true)
+                .build(Box::new(PanickingAppender(GateAppender(Arc::clone(&gate)))))
+                .unwrap(),
+        );
+        log_from_caller(&appender, 4);
+
+        let messages = received(&gate, 3)
+            .into_iter()
+            .map(|record| record.message)
+            .collect::<Vec<_>>();
+        assert_eq!(messages, ["0", "2", "3"]);
+        let started = Instant::now();
+        drain(
+            &[Arc::clone(&appender.queue)],
+            started + Duration::from_secs(10),
+        );
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn spilled_records_are_replayed_with_the_context_of_the_caller() {
+        let _global = testing::GLOBAL.lock();
+        let directory = testing::temp_dir("async_spill");
+        let gate = Arc::new(Gate::default());
+        gate.paused.store(true, Ordering::SeqCst);
+        let appender = Arc::new(
+            AsyncAppender::builder()
+                .capacity(1)
+                .backpressure(Backpressure::Spill {
+                    directory,
+                    max_bytes: 1 << 20,
+                })
+                .build(Box::new(GateAppender(Arc::clone(&gate))))
+                .unwrap(),
+        );
+        log_from_caller(&appender, 1);
+        while appender.buffered() != 0 {
+            thread::yield_now();
+        }
+        log_from_caller(&appender, 5);
+        let logged_before = SystemTime::now();
+        assert_eq!(appender.dropped(), 0);
+
+        gate.paused.store(false, Ordering::SeqCst);
+        let records = received(&gate, 6);
+        assert_eq!(records.len(), 6);
+        // The first record was being appended and the second one buffered, the others were spilled and replayed after them.
+        let messages = records
+            .iter()
+            .map(|record| record.message.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(messages, ["0", "0", "1", "2", "3", "4"]);
+        assert_caller_context(&records, logged_before);
+    }
+
+    #[test]
+    fn full_buffers_drop_the_newest_or_the_oldest_records() {
+        let _global = testing::GLOBAL.lock();
+        for (backpressure, expected) in [
+            (Backpressure::DropNewest, ["0", "1", "2"]),
+            (Backpressure::DropOldest, ["0", "3", "4"]),
+        ] {
+            let gate = Arc::new(Gate::default());
+            gate.paused.store(true, Ordering::SeqCst);
+            let appender = Arc::new(
+                AsyncAppender::builder()
+                    .capacity(2)
+                    .backpressure(backpressure)
+                    .build(Box::new(GateAppender(Arc::clone(&gate))))
+                    .unwrap(),
+            );
+            log_from_caller(&appender, 1);
+            while appender.buffered() != 0 {
+                thread::yield_now();
+            }
+            // The worker holds record 0, so only two of the other four fit into the buffer.
+            for index in 1..5 {
+                appender
+                    .append(
+                        &Record::builder()
+                            .level(Level::Info)
+                            .args(format_args!("{index}"))
+                            .build(),
+                    )
+                    .unwrap();
+            }
+            assert_eq!(appender.dropped(), 2);
+
+            gate.paused.store(false, Ordering::SeqCst);
+            let messages = received(&gate, 3)
+                .into_iter()
+                .map(|record| record.message)
+                .collect::<Vec<_>>();
+            assert_eq!(messages, expected);
+        }
+    }
+
+    /// An appender taking a while per record, tracking how many records it appends at the same time.
+    #[derive(Debug, Default)]
+    struct Slow {
+        active: AtomicU64,
+        max_active: AtomicU64,
+        appended: AtomicU64,
+    }
+
+    #[derive(Debug)]
+    struct SlowAppender(Arc<Slow>);
+
+    impl Append for SlowAppender {
+        fn append(&self, _record: &Record) -> anyhow::Result<()> {
+            let active = self.0.active.fetch_add(1, Ordering::SeqCst) + 1;
+            self.0.max_active.fetch_max(active, Ordering::SeqCst);
+            thread::sleep(Duration::from_millis(50));
+            self.0.active.fetch_sub(1, Ordering::SeqCst);
+            self.0.appended.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+
+        fn flush(&self) {}
+    }
+
+    #[test]
+    fn worker_pools_append_to_slow_sinks_concurrently() {
+        let _global = testing::GLOBAL.lock();
+        let slow = Arc::new(Slow::default());
+        let appender = AsyncAppender::builder()
+            .workers(4)
+            .build(Box::new(SlowAppender(Arc::clone(&slow))))
+            .unwrap();
+
+        let started = Instant::now();
+        log(&appender, 8);
+        while slow.appended.load(Ordering::SeqCst) < 8 {
+            assert!(started.elapsed() < Duration::from_secs(10));
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        assert!(slow.max_active.load(Ordering::SeqCst) > 1);
+        assert!(slow.max_active.load(Ordering::SeqCst) <= 4);
+    }
+}
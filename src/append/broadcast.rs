@@ -0,0 +1,27 @@
+use lum_libs::{log::Record, log4rs::append::Append};
+
+use crate::{record::OwnedRecord, subscribe};
+
+/// An appender publishing records to all subscribers created by [`subscribe::subscribe`],
+/// and to the history returned by [`subscribe::recent`].
+#[derive(Debug, Default)]
+pub struct BroadcastAppender;
+
+impl BroadcastAppender {
+    /// Same as [`BroadcastAppender::default`].
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Append for BroadcastAppender {
+    fn append(&self, record: &Record) -> anyhow::Result<()> {
+        if subscribe::is_active() {
+            subscribe::publish(OwnedRecord::from(record));
+        }
+
+        Ok(())
+    }
+
+    fn flush(&self) {}
+}
@@ -0,0 +1,88 @@
+use std::fmt::{self, Debug, Formatter};
+
+use lum_libs::{
+    log::{Level, Record},
+    log4rs::append::Append,
+};
+
+use crate::internal;
+
+/// A callback invoked by the [`CallbackAppender`].
+pub type RecordCallback = dyn Fn(&Record) + Send + Sync;
+
+/// An appender that invokes a callback for each record at the given level or above.
+/// This allows reacting to records, e.g. by incrementing a metric, without writing a full appender.
+/// A panic inside the callback is caught and reported to stderr.
+pub struct CallbackAppender {
+    level: Level,
+    callback: Box<RecordCallback>,
+}
+
+impl CallbackAppender {
+    /// Creates a new `CallbackAppender` invoking the given callback for records at the given level or above.
+    pub fn new(level: Level, callback: impl Fn(&Record) + Send + Sync + 'static) -> Self {
+        Self {
+            level,
+            callback: Box::new(callback),
+        }
+    }
+}
+
+impl Debug for CallbackAppender {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CallbackAppender")
+            .field("level", &self.level)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Append for CallbackAppender {
+    fn append(&self, record: &Record) -> anyhow::Result<()> {
+        if record.level() <= self.level {
+            internal::catch("Record callback", || (self.callback)(record));
+        }
+
+        Ok(())
+    }
+
+    fn flush(&self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+
+    fn record(level: Level, message: &str, f: impl FnOnce(&Record)) {
+        f(&Record::builder()
+            .level(level)
+            .args(format_args!("{message}"))
+            .build());
+    }
+
+    #[test]
+    fn callback_receives_records_at_its_level_or_above_and_survives_panics() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let callback_received = Arc::clone(&received);
+        let appender = CallbackAppender::new(Level::Warn, move |record| {
+            if record.level() == Level::Error {
+                panic!("Metric backend unavailable");
+            }
+            callback_received
+                .lock()
+                .unwrap()
+                .push(record.args().to_string());
+        });
+
+        for (level, message) in [
+            (Level::Info, "Started"),
+            (Level::Warn, "Slow"),
+            (Level::Error, "Failed"),
+        ] {
+            record(level, message, |record| appender.append(record).unwrap());
+        }
+
+        assert_eq!(*received.lock().unwrap(), ["Slow"]);
+    }
+}
@@ -0,0 +1,153 @@
+use std::{
+    fmt::Debug,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc::{Sender, SyncSender, TrySendError},
+    },
+};
+
+use lum_libs::{log::Record, log4rs::append::Append};
+
+use crate::{record::OwnedRecord, stats};
+
+/// Outcome of [`RecordSender::send_record`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SendOutcome {
+    /// The record was sent.
+    Sent,
+    /// The channel is full, so the record was dropped.
+    Full,
+    /// The receiver was dropped, so the record was dropped.
+    Disconnected,
+}
+
+/// The sending half of a channel that the [`ChannelAppender`] can send records into.
+/// Sending must never block.
+pub trait RecordSender: Debug + Send + Sync + 'static {
+    /// Sends the given record without blocking.
+    fn send_record(&self, record: OwnedRecord) -> SendOutcome;
+}
+
+impl RecordSender for Sender<OwnedRecord> {
+    fn send_record(&self, record: OwnedRecord) -> SendOutcome {
+        match self.send(record) {
+            Ok(()) => SendOutcome::Sent,
+            Err(_) => SendOutcome::Disconnected,
+        }
+    }
+}
+
+impl RecordSender for SyncSender<OwnedRecord> {
+    fn send_record(&self, record: OwnedRecord) -> SendOutcome {
+        match self.try_send(record) {
+            Ok(()) => SendOutcome::Sent,
+            Err(TrySendError::Full(_)) => SendOutcome::Full,
+            Err(TrySendError::Disconnected(_)) => SendOutcome::Disconnected,
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl RecordSender for lum_libs::tokio::sync::mpsc::UnboundedSender<OwnedRecord> {
+    fn send_record(&self, record: OwnedRecord) -> SendOutcome {
+        match self.send(record) {
+            Ok(()) => SendOutcome::Sent,
+            Err(_) => SendOutcome::Disconnected,
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl RecordSender for lum_libs::tokio::sync::mpsc::Sender<OwnedRecord> {
+    fn send_record(&self, record: OwnedRecord) -> SendOutcome {
+        use lum_libs::tokio::sync::mpsc::error::TrySendError;
+
+        match self.try_send(record) {
+            Ok(()) => SendOutcome::Sent,
+            Err(TrySendError::Full(_)) => SendOutcome::Full,
+            Err(TrySendError::Closed(_)) => SendOutcome::Disconnected,
+        }
+    }
+}
+
+/// An appender sending each record as an [`OwnedRecord`] into a channel,
+/// so the application itself can consume its own log stream, e.g. in a status pane.
+/// Supports [`std::sync::mpsc`] channels and, with the `tokio` feature, tokio's mpsc channels.
+/// Records that cannot be sent because the channel is full or disconnected are dropped and counted.
+#[derive(Debug)]
+pub struct ChannelAppender<S: RecordSender> {
+    sender: S,
+    dropped: AtomicU64,
+}
+
+impl<S: RecordSender> ChannelAppender<S> {
+    /// Creates a new `ChannelAppender` sending records through the given sender.
+    pub fn new(sender: S) -> Self {
+        Self {
+            sender,
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the number of records dropped so far.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+impl<S: RecordSender> Append for ChannelAppender<S> {
+    fn append(&self, record: &Record) -> anyhow::Result<()> {
+        if self.sender.send_record(OwnedRecord::from(record)) != SendOutcome::Sent {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            stats::record_dropped();
+        }
+
+        Ok(())
+    }
+
+    fn flush(&self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use lum_libs::log::Level;
+
+    use super::*;
+
+    fn append(appender: &impl Append, message: &str) {
+        appender
+            .append(
+                &Record::builder()
+                    .level(Level::Warn)
+                    .target("channel_test")
+                    .args(format_args!("{message}"))
+                    .file(Some("src/main.rs"))
+                    .line(Some(7))
+                    .build(),
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn records_are_sent_owned_and_dropped_when_the_channel_is_full_or_closed() {
+        let (sender, receiver) = mpsc::sync_channel(1);
+        let appender = ChannelAppender::new(sender);
+
+        append(&appender, "Sent");
+        append(&appender, "Full");
+        assert_eq!(appender.dropped(), 1);
+
+        let record = receiver.try_recv().unwrap();
+        assert_eq!(record.level, Level::Warn);
+        assert_eq!(record.target, "channel_test");
+        assert_eq!(record.message, "Sent");
+        assert_eq!(record.file.as_deref(), Some("src/main.rs"));
+        assert_eq!(record.line, Some(7));
+
+        drop(receiver);
+        append(&appender, "Disconnected");
+        assert_eq!(appender.dropped(), 2);
+    }
+}
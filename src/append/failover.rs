@@ -0,0 +1,146 @@
+use std::time::{Duration, Instant};
+
+use lum_libs::{log::Record, log4rs::append::Append, parking_lot::Mutex};
+
+use crate::default;
+
+/// An appender writing to a primary appender, falling back to another appender when the primary fails.
+/// Once the primary has failed, records are written to the fallback until the primary recovers.
+/// Recovery is probed by retrying the primary at most once per retry interval.
+/// If both appenders fail, the error of the fallback is returned.
+#[derive(Debug)]
+pub struct FailoverAppender {
+    primary: Box<dyn Append>,
+    fallback: Box<dyn Append>,
+    retry_interval: Duration,
+    failed_at: Mutex<Option<Instant>>,
+}
+
+impl FailoverAppender {
+    /// Creates a new `FailoverAppender` with the given primary and fallback appenders,
+    /// retrying the primary every [`default::failover_retry_interval`].
+    pub fn new(primary: Box<dyn Append>, fallback: Box<dyn Append>) -> Self {
+        Self {
+            primary,
+            fallback,
+            retry_interval: default::failover_retry_interval(),
+            failed_at: Mutex::new(None),
+        }
+    }
+
+    /// Sets the interval in which the primary appender is retried after it failed.
+    pub fn retry_interval(mut self, retry_interval: Duration) -> Self {
+        self.retry_interval = retry_interval;
+        self
+    }
+
+    /// Returns whether records are currently written to the fallback appender.
+    pub fn is_failed_over(&self) -> bool {
+        self.failed_at.lock().is_some()
+    }
+
+    fn should_try_primary(&self, now: Instant) -> bool {
+        match *self.failed_at.lock() {
+            Some(failed_at) => now.duration_since(failed_at) >= self.retry_interval,
+            None => true,
+        }
+    }
+}
+
+impl Append for FailoverAppender {
+    fn append(&self, record: &Record) -> anyhow::Result<()> {
+        let now = Instant::now();
+
+        if self.should_try_primary(now) {
+            match self.primary.append(record) {
+                Ok(()) => {
+                    *self.failed_at.lock() = None;
+                    return Ok(());
+                }
+                Err(_) => *self.failed_at.lock() = Some(now),
+            }
+        }
+
+        self.fallback.append(record)
+    }
+
+    fn flush(&self) {
+        self.primary.flush();
+        self.fallback.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    };
+
+    use lum_libs::log::Level;
+
+    use super::*;
+    use crate::testing;
+
+    /// An appender failing while `failing` is set, keeping the messages of the records it appended.
+    #[derive(Debug, Default)]
+    struct Flaky {
+        failing: AtomicBool,
+        appended: Mutex<Vec<String>>,
+    }
+
+    #[derive(Debug)]
+    struct FlakyAppender(Arc<Flaky>);
+
+    impl Append for FlakyAppender {
+        fn append(&self, record: &Record) -> anyhow::Result<()> {
+            if self.0.failing.load(Ordering::SeqCst) {
+                anyhow::bail!("Connection refused");
+            }
+            self.0.appended.lock().push(record.args().to_string());
+            Ok(())
+        }
+
+        fn flush(&self) {}
+    }
+
+    fn append(appender: &FailoverAppender, message: &str) {
+        appender
+            .append(
+                &Record::builder()
+                    .level(Level::Info)
+                    .args(format_args!("{message}"))
+                    .build(),
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn records_fall_back_until_the_primary_is_retried_and_recovers() {
+        let primary = Arc::new(Flaky::default());
+        let (fallback, fallback_records) = testing::channel();
+        let mut appender =
+            FailoverAppender::new(Box::new(FlakyAppender(Arc::clone(&primary))), fallback)
+                .retry_interval(Duration::from_secs(3600));
+
+        append(&appender, "Primary");
+        primary.failing.store(true, Ordering::SeqCst);
+        append(&appender, "Failed over");
+        assert!(appender.is_failed_over());
+
+        // The primary is not retried within the retry interval, even though it recovered.
+        primary.failing.store(false, Ordering::SeqCst);
+        append(&appender, "Still failed over");
+        assert!(appender.is_failed_over());
+
+        appender.retry_interval = Duration::ZERO;
+        append(&appender, "Recovered");
+        assert!(!appender.is_failed_over());
+
+        assert_eq!(*primary.appended.lock(), ["Primary", "Recovered"]);
+        assert_eq!(
+            testing::messages(&fallback_records),
+            ["Failed over", "Still failed over"]
+        );
+    }
+}
@@ -0,0 +1,219 @@
+use std::{
+    fmt::{self, Debug, Formatter},
+    io,
+    sync::{Arc, Weak},
+    thread,
+    time::{Duration, Instant},
+};
+
+use lum_libs::{
+    log::Record,
+    log4rs::append::Append,
+    parking_lot::{Mutex, RwLock},
+};
+
+/// A function creating the appender wrapped by a [`LazyAppender`].
+pub type OpenAppender = dyn Fn() -> io::Result<Box<dyn Append>> + Send + Sync;
+
+struct Shared {
+    open: Box<OpenAppender>,
+    inner: RwLock<Option<Box<dyn Append>>>,
+    last_used: Mutex<Instant>,
+}
+
+/// An appender creating the wrapped appender on the first record instead of upfront,
+/// so tools that often run without logging anything do not leave empty log files behind.
+/// If creating the wrapped appender fails, the record fails to append, and creating it is retried on the next record.
+///
+/// With an idle timeout, the wrapped appender is flushed and dropped after not receiving records for that long,
+/// closing its file, and transparently created again on the next record.
+/// This plays nicely with network filesystems and allows unmounting volumes, e.g. on embedded devices.
+/// Note that the rolling schedule of a rolling file appender restarts whenever it is created again.
+pub struct LazyAppender {
+    shared: Arc<Shared>,
+}
+
+impl LazyAppender {
+    /// Creates a new `LazyAppender` calling the given function on the first record to create the wrapped appender.
+    pub fn new<A: Append>(open: impl Fn() -> io::Result<A> + Send + Sync + 'static) -> Self {
+        let shared = Shared {
+            open: Box::new(move || Ok(Box::new(open()?) as Box<dyn Append>)),
+            inner: RwLock::new(None),
+            last_used: Mutex::new(Instant::now()),
+        };
+
+        Self {
+            shared: Arc::new(shared),
+        }
+    }
+
+    /// Creates a new `LazyAppender` like [`LazyAppender::new`], which drops the wrapped appender after the given idle timeout,
+    /// spawning its background thread. The thread stops once the appender is dropped.
+    pub fn with_idle_timeout<A: Append>(
+        open: impl Fn() -> io::Result<A> + Send + Sync + 'static,
+        idle_timeout: Duration,
+    ) -> io::Result<Self> {
+        let appender = Self::new(open);
+
+        let weak = Arc::downgrade(&appender.shared);
+        thread::Builder::new()
+            .name("lum_log-idle-close".to_string())
+            .spawn(move || run(weak, idle_timeout))?;
+
+        Ok(appender)
+    }
+
+    /// Creates the wrapped appender now, if it has not been created yet.
+    pub fn open(&self) -> io::Result<()> {
+        let mut inner = self.shared.inner.write();
+        if inner.is_none() {
+            *inner = Some((self.shared.open)()?);
+            *self.shared.last_used.lock() = Instant::now();
+        }
+        Ok(())
+    }
+
+    /// Returns whether the wrapped appender is currently created.
+    pub fn is_open(&self) -> bool {
+        self.shared.inner.read().is_some()
+    }
+}
+
+impl Debug for LazyAppender {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LazyAppender")
+            .field("inner", &*self.shared.inner.read())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Append for LazyAppender {
+    fn append(&self, record: &Record) -> anyhow::Result<()> {
+        loop {
+            if let Some(inner) = &*self.shared.inner.read() {
+                *self.shared.last_used.lock() = Instant::now();
+                return inner.append(record);
+            }
+            self.open()?;
+        }
+    }
+
+    fn flush(&self) {
+        if let Some(inner) = &*self.shared.inner.read() {
+            inner.flush();
+        }
+    }
+}
+
+fn run(shared: Weak<Shared>, idle_timeout: Duration) {
+    let mut wait = idle_timeout;
+    loop {
+        thread::sleep(wait);
+        let Some(shared) = shared.upgrade() else {
+            return;
+        };
+
+        let mut inner = shared.inner.write();
+        let idle = shared.last_used.lock().elapsed();
+        if idle < idle_timeout {
+            wait = idle_timeout - idle;
+            continue;
+        }
+
+        if let Some(closed) = inner.take() {
+            closed.flush();
+        }
+        wait = idle_timeout;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use lum_libs::{
+        log::Level,
+        log4rs::{append::file::FileAppender, encode::pattern::PatternEncoder},
+    };
+
+    use super::*;
+    use crate::testing;
+
+    fn append(appender: &LazyAppender, message: &str) -> anyhow::Result<()> {
+        appender.append(
+            &Record::builder()
+                .level(Level::Info)
+                .args(format_args!("{message}"))
+                .build(),
+        )
+    }
+
+    fn file_appender(path: &std::path::Path) -> io::Result<FileAppender> {
+        FileAppender::builder()
+            .encoder(Box::new(PatternEncoder::new("{m}{n}")))
+            .build(path)
+    }
+
+    #[test]
+    fn files_are_created_on_the_first_record_and_failed_creations_are_retried() {
+        let path = testing::temp_dir("lazy_files").join("app.log");
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let appender = LazyAppender::new({
+            let path = path.clone();
+            let attempts = attempts.clone();
+            move || {
+                if attempts.fetch_add(1, Ordering::Relaxed) == 0 {
+                    return Err(io::Error::other("Volume not mounted"));
+                }
+                file_appender(&path)
+            }
+        });
+
+        assert!(!appender.is_open());
+        assert!(!path.exists());
+
+        assert!(append(&appender, "Lost").is_err());
+        assert!(!appender.is_open());
+        append(&appender, "First").unwrap();
+        append(&appender, "Second").unwrap();
+
+        assert!(appender.is_open());
+        assert_eq!(attempts.load(Ordering::Relaxed), 2);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "First\nSecond\n");
+    }
+
+    #[test]
+    fn idle_files_are_closed_and_reopened_on_the_next_record() {
+        let path = testing::temp_dir("idle_files").join("app.log");
+        let opened = Arc::new(AtomicUsize::new(0));
+        let appender = LazyAppender::with_idle_timeout(
+            {
+                let path = path.clone();
+                let opened = opened.clone();
+                move || {
+                    opened.fetch_add(1, Ordering::Relaxed);
+                    file_appender(&path)
+                }
+            },
+            Duration::from_millis(20),
+        )
+        .unwrap();
+
+        append(&appender, "Before").unwrap();
+        assert!(appender.is_open());
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while appender.is_open() && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(5));
+        }
+        assert!(!appender.is_open());
+
+        append(&appender, "After").unwrap();
+        assert!(appender.is_open());
+        assert_eq!(opened.load(Ordering::Relaxed), 2);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "Before\nAfter\n");
+    }
+}
@@ -0,0 +1,620 @@
+/// Defines the [`GrpcTransport`](grpc::GrpcTransport), which streams records to a gRPC server.
+#[cfg(feature = "grpc")]
+pub mod grpc;
+/// Defines the [`MqttTransport`](mqtt::MqttTransport), which publishes records to an MQTT topic.
+#[cfg(feature = "mqtt")]
+pub mod mqtt;
+/// Defines the [`NatsTransport`](nats::NatsTransport), which publishes records to a NATS subject.
+#[cfg(feature = "nats")]
+pub mod nats;
+/// Defines the [`WebSocketTransport`](websocket::WebSocketTransport), which streams records to a WebSocket server.
+#[cfg(feature = "websocket")]
+pub mod websocket;
+/// Defines the [`ZmqTransport`](zeromq::ZmqTransport), which publishes records on a ZeroMQ PUB socket.
+#[cfg(feature = "zeromq")]
+pub mod zeromq;
+
+use std::{
+    collections::VecDeque,
+    fmt::Debug,
+    io::{self, Write},
+    net::{TcpStream, ToSocketAddrs},
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use lum_libs::{
+    log::Record,
+    log4rs::{
+        append::Append,
+        encode::{Encode, writer::simple::SimpleWriter},
+    },
+    parking_lot::{Condvar, Mutex},
+};
+
+use crate::{
+    backpressure::Backpressure,
+    default,
+    encode::{SafeEncoder, StripAnsiEncoder},
+    memory::{self, MemoryPolicy},
+    retry::RetryPolicy,
+    spool::Spool,
+    stats,
+    wal::WriteAheadLog,
+};
+
+/// A connection to a remote log sink, used by the [`NetworkAppender`].
+pub trait Transport: Debug + Send + 'static {
+    /// Sends the given encoded record.
+    /// On error, the transport should reset its connection so that the next call reconnects.
+    fn send(&mut self, payload: &[u8]) -> io::Result<()>;
+}
+
+/// A [`Transport`] writing records to a TCP connection, e.g. a Logstash or Vector TCP input.
+/// The connection is established lazily and re-established after errors.
+#[derive(Debug)]
+pub struct TcpTransport {
+    address: String,
+    timeout: Duration,
+    stream: Option<TcpStream>,
+}
+
+impl TcpTransport {
+    /// Creates a new `TcpTransport` connecting to the given address, like `logs.example.com:5000`.
+    /// The connect and write timeout defaults to [`default::network_timeout`].
+    pub fn new(address: impl Into<String>) -> Self {
+        Self {
+            address: address.into(),
+            timeout: default::network_timeout(),
+            stream: None,
+        }
+    }
+
+    /// Sets the connect and write timeout.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    fn connect(&self) -> io::Result<TcpStream> {
+        let mut last_error = None;
+        for address in self.address.to_socket_addrs()? {
+            match TcpStream::connect_timeout(&address, self.timeout) {
+                Ok(stream) => {
+                    stream.set_write_timeout(Some(self.timeout))?;
+                    stream.set_nodelay(true)?;
+                    return Ok(stream);
+                }
+                Err(error) => last_error = Some(error),
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::AddrNotAvailable,
+                format!("{} did not resolve to any address", self.address),
+            )
+        }))
+    }
+}
+
+impl Transport for TcpTransport {
+    fn send(&mut self, payload: &[u8]) -> io::Result<()> {
+        let stream = match &mut self.stream {
+            Some(stream) => stream,
+            None => self.stream.insert(self.connect()?),
+        };
+
+        let result = stream.write_all(payload).and_then(|()| stream.flush());
+        if result.is_err() {
+            self.stream = None;
+        }
+
+        result
+    }
+}
+
+#[derive(Debug, Default)]
+struct QueueState {
+    records: VecDeque<(u64, Vec<u8>)>,
+    next_id: u64,
+    sending: bool,
+    closed: bool,
+}
+
+#[derive(Debug)]
+struct Queue {
+    state: Mutex<QueueState>,
+    changed: Condvar,
+    capacity: usize,
+    backpressure: Backpressure,
+    dropped: AtomicU64,
+    failed: AtomicU64,
+    spool: Option<Spool>,
+    wal: Option<WriteAheadLog>,
+}
+
+impl Queue {
+    fn is_idle(&self, state: &QueueState) -> bool {
+        let confirmed = self.wal.as_ref().is_none_or(WriteAheadLog::is_confirmed);
+        state.records.is_empty() && !state.sending && confirmed
+    }
+}
+
+/// A builder for [`NetworkAppender`]s.
+#[derive(Debug)]
+pub struct NetworkAppenderBuilder {
+    capacity: usize,
+    backpressure: Backpressure,
+    policy: RetryPolicy,
+    spool: Option<Spool>,
+    wal: Option<WriteAheadLog>,
+}
+
+impl Default for NetworkAppenderBuilder {
+    /// Creates a `NetworkAppenderBuilder` using [`default::network_buffer_capacity`], [`Backpressure::DropOldest`],
+    /// the default [`RetryPolicy`], no spool, and no write-ahead log.
+    fn default() -> Self {
+        Self {
+            capacity: default::network_buffer_capacity(),
+            backpressure: Backpressure::DropOldest,
+            policy: RetryPolicy::default(),
+            spool: None,
+            wal: None,
+        }
+    }
+}
+
+impl NetworkAppenderBuilder {
+    /// Sets the number of records held in the buffer.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Sets what happens to new records when the buffer is full.
+    /// With [`Backpressure::Spill`], records are spilled to the spool set by [`NetworkAppenderBuilder::spool`],
+    /// or, if none is set, to a spool opened from the strategy, which is then also used for failed records.
+    pub fn backpressure(mut self, backpressure: Backpressure) -> Self {
+        self.backpressure = backpressure;
+        self
+    }
+
+    /// Sets the retry policy.
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Sets a spool persisting records that could not be sent after all attempts.
+    /// Spooled records are replayed once sending succeeds again, or periodically while idle.
+    pub fn spool(mut self, spool: Spool) -> Self {
+        self.spool = Some(spool);
+        self
+    }
+
+    /// Sets a write-ahead log, giving at-least-once delivery.
+    /// Logging a record then only returns after the record was durably written to the write-ahead log,
+    /// and records are trimmed from it once the transport sent them successfully.
+    /// Records are retried until they are delivered, so neither the buffer capacity, the maximum attempts
+    /// of the retry policy, nor the spool apply. Undelivered records are sent again after a restart.
+    pub fn write_ahead_log(mut self, wal: WriteAheadLog) -> Self {
+        self.wal = Some(wal);
+        self
+    }
+
+    /// Builds the [`NetworkAppender`], spawning its background thread.
+    /// The encoder is wrapped in a [`StripAnsiEncoder`] and a [`SafeEncoder`].
+    pub fn build(
+        self,
+        transport: impl Transport,
+        encoder: Box<dyn Encode>,
+    ) -> io::Result<NetworkAppender> {
+        let spool = match self.spool {
+            Some(spool) => Some(spool),
+            None => self.backpressure.open_spool()?,
+        };
+
+        let queue = Arc::new(Queue {
+            state: Mutex::new(QueueState::default()),
+            changed: Condvar::new(),
+            capacity: self.capacity.max(1),
+            backpressure: self.backpressure,
+            dropped: AtomicU64::new(0),
+            failed: AtomicU64::new(0),
+            spool,
+            wal: self.wal,
+        });
+
+        let worker = Worker {
+            queue: Arc::clone(&queue),
+            transport,
+            policy: self.policy,
+        };
+        thread::Builder::new()
+            .name("lum_log-network".to_string())
+            .spawn(move || worker.run())?;
+
+        let encoder = Box::new(SafeEncoder::new(Box::new(StripAnsiEncoder::new(encoder))));
+        Ok(NetworkAppender { encoder, queue })
+    }
+}
+
+/// An appender encoding records and sending them to a remote sink through a [`Transport`].
+/// Records are held in a bounded buffer and sent by a background thread, so logging does not wait for the network.
+/// Failed sends are retried according to the [`RetryPolicy`], while the record stays in the buffer.
+/// If the buffer is full, the [`Backpressure`] strategy applies. Records still failing after the last attempt are discarded,
+/// unless a [`Spool`] is set.
+#[derive(Debug)]
+pub struct NetworkAppender {
+    encoder: Box<dyn Encode>,
+    queue: Arc<Queue>,
+}
+
+impl NetworkAppender {
+    /// Creates a new `NetworkAppender` sending records encoded by the given encoder through the given transport,
+    /// using the defaults of [`NetworkAppenderBuilder`].
+    pub fn new(transport: impl Transport, encoder: Box<dyn Encode>) -> io::Result<Self> {
+        Self::builder().build(transport, encoder)
+    }
+
+    /// Creates a new [`NetworkAppenderBuilder`].
+    pub fn builder() -> NetworkAppenderBuilder {
+        NetworkAppenderBuilder::default()
+    }
+
+    /// Returns the number of records dropped because the buffer was full.
+    pub fn dropped(&self) -> u64 {
+        self.queue.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of records discarded after all attempts to send them failed.
+    /// With a [`Spool`], such records are spooled instead and not counted here.
+    pub fn failed(&self) -> u64 {
+        self.queue.failed.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of records currently held in the buffer.
+    pub fn buffered(&self) -> usize {
+        self.queue.state.lock().records.len()
+    }
+}
+
+impl Append for NetworkAppender {
+    fn append(&self, record: &Record) -> anyhow::Result<()> {
+        let mut writer = SimpleWriter(Vec::new());
+        self.encoder.encode(&mut writer, record)?;
+
+        if let Some(wal) = &self.queue.wal {
+            wal.append(&writer.0)?;
+            let _state = self.queue.state.lock();
+            self.queue.changed.notify_all();
+            return Ok(());
+        }
+
+        let size = buffered_size(&writer.0);
+        let mut state = self.queue.state.lock();
+        loop {
+            if state.records.len() < self.queue.capacity {
+                if memory::try_reserve(size) {
+                    break;
+                }
+                if memory::memory_policy() == MemoryPolicy::DropNewest {
+                    self.queue.dropped.fetch_add(1, Ordering::Relaxed);
+                    stats::record_dropped();
+                    return Ok(());
+                }
+            }
+
+            match self.queue.backpressure {
+                Backpressure::Block => {
+                    // Memory released by other buffers does not notify this queue, so it is polled.
+                    self.queue
+                        .changed
+                        .wait_for(&mut state, memory::RETRY_INTERVAL);
+                }
+                Backpressure::DropNewest => {
+                    self.queue.dropped.fetch_add(1, Ordering::Relaxed);
+                    stats::record_dropped();
+                    return Ok(());
+                }
+                Backpressure::DropOldest => {
+                    let oldest = state.records.pop_front();
+                    self.queue.dropped.fetch_add(1, Ordering::Relaxed);
+                    stats::record_dropped();
+                    match oldest {
+                        Some((_, oldest)) => memory::release(buffered_size(&oldest)),
+                        None => return Ok(()),
+                    }
+                }
+                Backpressure::Spill { .. } => {
+                    drop(state);
+                    let spilled = self
+                        .queue
+                        .spool
+                        .as_ref()
+                        .is_some_and(|spool| spool.push(&writer.0).is_ok());
+                    if !spilled {
+                        self.queue.dropped.fetch_add(1, Ordering::Relaxed);
+                        stats::record_dropped();
+                    }
+                    return Ok(());
+                }
+            }
+        }
+        let id = state.next_id;
+        state.next_id += 1;
+        state.records.push_back((id, writer.0));
+        self.queue.changed.notify_all();
+
+        Ok(())
+    }
+
+    /// Waits until all records were sent, for at most [`default::flush_timeout`].
+    fn flush(&self) {
+        let deadline = Instant::now() + default::flush_timeout();
+
+        let mut state = self.queue.state.lock();
+        while !self.queue.is_idle(&state) {
+            if self
+                .queue
+                .changed
+                .wait_until(&mut state, deadline)
+                .timed_out()
+            {
+                break;
+            }
+        }
+    }
+}
+
+impl Drop for NetworkAppender {
+    fn drop(&mut self) {
+        self.queue.state.lock().closed = true;
+        self.queue.changed.notify_all();
+    }
+}
+
+struct Worker<T: Transport> {
+    queue: Arc<Queue>,
+    transport: T,
+    policy: RetryPolicy,
+}
+
+impl<T: Transport> Worker<T> {
+    fn run(mut self) {
+        if self.queue.wal.is_some() {
+            self.run_write_ahead_log();
+            return;
+        }
+
+        while let Some((id, payload, closed)) = self.next() {
+            let sent = self.send(&payload, closed);
+            if sent {
+                self.replay_spool();
+            } else {
+                self.discard(&payload);
+            }
+
+            let mut state = self.queue.state.lock();
+            // The record may have been dropped by the appender while sending, if the buffer was full.
+            if state
+                .records
+                .front()
+                .is_some_and(|(front_id, _)| *front_id == id)
+                && let Some((_, payload)) = state.records.pop_front()
+            {
+                memory::release(buffered_size(&payload));
+            }
+            state.sending = false;
+            self.queue.changed.notify_all();
+        }
+    }
+
+    fn run_write_ahead_log(mut self) {
+        let queue = Arc::clone(&self.queue);
+        let wal = queue
+            .wal
+            .as_ref()
+            .expect("Only called with a write-ahead log");
+
+        loop {
+            {
+                let mut state = queue.state.lock();
+                while !wal.has_unread() && !state.closed {
+                    queue.changed.wait(&mut state);
+                }
+                // Records left in the write-ahead log are delivered after a restart.
+                if state.closed {
+                    return;
+                }
+                state.sending = true;
+            }
+
+            let delivered = match wal.next() {
+                Ok(Some((position, payload))) => {
+                    self.send_until_delivered(&payload) && wal.confirm(position).is_ok()
+                }
+                Ok(None) => true,
+                Err(_) => false,
+            };
+            if !delivered {
+                wal.rewind();
+            }
+
+            queue.state.lock().sending = false;
+            queue.changed.notify_all();
+        }
+    }
+
+    /// Sends the given record until it was delivered, backing off between attempts.
+    /// Returns `false` if the appender is gone before the record was delivered.
+    fn send_until_delivered(&mut self, payload: &[u8]) -> bool {
+        let mut failed_attempts = 0;
+        loop {
+            if self.transport.send(payload).is_ok() {
+                return true;
+            }
+
+            if self.queue.state.lock().closed {
+                return false;
+            }
+
+            failed_attempts += 1;
+            thread::sleep(self.policy.backoff(failed_attempts));
+        }
+    }
+
+    /// Waits for the next record, replaying the spool periodically while idle.
+    /// Returns `None` once the appender is gone and the buffer is empty.
+    fn next(&mut self) -> Option<(u64, Vec<u8>, bool)> {
+        let mut state = self.queue.state.lock();
+        while state.records.is_empty() && !state.closed {
+            let spooled = self
+                .queue
+                .spool
+                .as_ref()
+                .is_some_and(|spool| !spool.is_empty());
+            if !spooled {
+                self.queue.changed.wait(&mut state);
+                continue;
+            }
+
+            let timed_out = self
+                .queue
+                .changed
+                .wait_for(&mut state, self.policy.max_backoff)
+                .timed_out();
+            if timed_out {
+                drop(state);
+                self.replay_spool();
+                state = self.queue.state.lock();
+            }
+        }
+
+        let (id, payload) = state.records.front().cloned()?;
+        state.sending = true;
+        Some((id, payload, state.closed))
+    }
+
+    fn send(&mut self, payload: &[u8], closed: bool) -> bool {
+        let mut failed_attempts = 0;
+        loop {
+            if self.transport.send(payload).is_ok() {
+                return true;
+            }
+
+            failed_attempts += 1;
+            // Once the appender is gone, nobody waits for the records anymore, so they are not retried.
+            if closed || !self.policy.should_retry(failed_attempts) {
+                return false;
+            }
+            thread::sleep(self.policy.backoff(failed_attempts));
+        }
+    }
+
+    fn discard(&self, payload: &[u8]) {
+        let spooled = self
+            .queue
+            .spool
+            .as_ref()
+            .is_some_and(|spool| spool.push(payload).is_ok());
+        if !spooled {
+            self.queue.failed.fetch_add(1, Ordering::Relaxed);
+            stats::record_dropped();
+        }
+    }
+
+    fn replay_spool(&mut self) {
+        if let Some(spool) = &self.queue.spool
+            && !spool.is_empty()
+        {
+            let transport = &mut self.transport;
+            let _ = spool.replay(|payload| transport.send(payload));
+        }
+    }
+}
+
+/// Returns an estimate of the number of bytes a buffered record with the given payload occupies in memory.
+fn buffered_size(payload: &[u8]) -> usize {
+    size_of::<(u64, Vec<u8>)>() + payload.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use lum_libs::{log::Level, log4rs::encode::pattern::PatternEncoder};
+
+    use super::*;
+    use crate::testing;
+
+    /// A transport failing the given number of sends before succeeding, keeping what it sent.
+    #[derive(Debug, Default)]
+    struct Script {
+        failures: u32,
+        sent: Vec<String>,
+    }
+
+    #[derive(Debug)]
+    struct ScriptTransport(Arc<Mutex<Script>>);
+
+    impl Transport for ScriptTransport {
+        fn send(&mut self, payload: &[u8]) -> io::Result<()> {
+            let mut script = self.0.lock();
+            if script.failures > 0 {
+                script.failures -= 1;
+                return Err(io::ErrorKind::ConnectionRefused.into());
+            }
+            script
+                .sent
+                .push(String::from_utf8_lossy(payload).into_owned());
+            Ok(())
+        }
+    }
+
+    fn append(appender: &NetworkAppender, message: &str) {
+        appender
+            .append(
+                &Record::builder()
+                    .level(Level::Info)
+                    .args(format_args!("{message}"))
+                    .build(),
+            )
+            .unwrap();
+        appender.flush();
+    }
+
+    #[test]
+    fn failed_sends_are_retried_until_the_last_attempt() {
+        let _global = testing::GLOBAL.lock();
+        let script = Arc::new(Mutex::new(Script {
+            failures: 2,
+            ..Script::default()
+        }));
+        let appender = NetworkAppender::builder()
+            .retry_policy(RetryPolicy {
+                max_attempts: 3,
+                initial_backoff: Duration::ZERO,
+                ..RetryPolicy::default()
+            })
+            .build(
+                ScriptTransport(Arc::clone(&script)),
+                Box::new(PatternEncoder::new("{m}")),
+            )
+            .unwrap();
+
+        append(&appender, "Delivered on the third attempt");
+        script.lock().failures = 3;
+        append(&appender, "Discarded after three attempts");
+        append(&appender, "Delivered");
+
+        assert_eq!(
+            script.lock().sent,
+            ["Delivered on the third attempt", "Delivered"]
+        );
+        assert_eq!(appender.failed(), 1);
+    }
+}
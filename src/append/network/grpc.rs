@@ -0,0 +1,253 @@
+use std::{fmt::Debug, io, str::FromStr, time::Duration};
+
+use http::uri::PathAndQuery;
+use lum_libs::tokio::{
+    runtime::{self, Runtime},
+    sync::mpsc,
+    task::JoinHandle,
+    time,
+};
+use prost::Message;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{
+    Request, Response, Status,
+    client::Grpc,
+    metadata::{AsciiMetadataKey, AsciiMetadataValue, MetadataMap},
+    transport::Endpoint,
+};
+use tonic_prost::ProstCodec;
+
+use crate::{
+    append::network::Transport,
+    default,
+    proto::{LogRecord, StreamResponse},
+};
+
+/// A [`Transport`] streaming records to a gRPC server through a client-streaming method,
+/// for ingestion services only reachable via gRPC.
+/// The method takes a stream of [`LogRecord`]s as defined by `proto/lum_log.proto`, by default `LogIngestion.Stream`.
+/// Payloads must be encoded with the [`ProtobufEncoder`](crate::encode::protobuf::ProtobufEncoder).
+///
+/// The stream is opened lazily and reopened after the server closed it or failed it.
+/// Records are handed to the stream without waiting for an acknowledgement,
+/// so records sent shortly before the stream fails may be lost. The stream is closed gracefully on drop.
+///
+/// ```text
+/// let transport = GrpcTransport::new("http://ingest.internal:50051")
+///     .method("/acme.logging.v1.Ingest/Push")
+///     .metadata("authorization", "Bearer secret");
+/// let appender = NetworkAppender::new(transport, Box::new(ProtobufEncoder::new()))?;
+/// ```
+#[derive(Debug)]
+pub struct GrpcTransport {
+    endpoint: String,
+    method: String,
+    metadata: Vec<(String, String)>,
+    timeout: Duration,
+    runtime: Option<Runtime>,
+    stream: Option<RecordStream>,
+}
+
+#[derive(Debug)]
+struct RecordStream {
+    records: mpsc::Sender<LogRecord>,
+    call: JoinHandle<Result<Response<StreamResponse>, Status>>,
+}
+
+impl GrpcTransport {
+    /// Creates a new `GrpcTransport` connecting to the given endpoint, e.g. `http://127.0.0.1:50051`,
+    /// calling [`default::grpc_method`] with a timeout of [`default::network_timeout`].
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            method: default::grpc_method().to_string(),
+            metadata: Vec::new(),
+            timeout: default::network_timeout(),
+            runtime: None,
+            stream: None,
+        }
+    }
+
+    /// Sets the path of the client-streaming method to call, e.g. `/acme.logging.v1.Ingest/Push`.
+    pub fn method(mut self, method: impl Into<String>) -> Self {
+        self.method = method.into();
+        self
+    }
+
+    /// Adds an ASCII metadata entry sent when opening the stream, e.g. an `authorization` header.
+    pub fn metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.metadata.push((key.into(), value.into()));
+        self
+    }
+
+    /// Sets the timeout for connecting and for handing a record to the stream.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    fn open(&mut self) -> io::Result<RecordStream> {
+        let endpoint = Endpoint::from_shared(self.endpoint.clone())
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?
+            .connect_timeout(self.timeout);
+        let method = PathAndQuery::from_str(&self.method)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+        let mut metadata = MetadataMap::new();
+        for (key, value) in &self.metadata {
+            let key = AsciiMetadataKey::from_str(key)
+                .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+            let value = AsciiMetadataValue::from_str(value)
+                .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+            metadata.insert(key, value);
+        }
+
+        let runtime = match &mut self.runtime {
+            Some(runtime) => runtime,
+            None => self.runtime.insert(
+                runtime::Builder::new_multi_thread()
+                    .worker_threads(1)
+                    .thread_name("lum_log-grpc")
+                    .enable_all()
+                    .build()?,
+            ),
+        };
+
+        let timeout = self.timeout;
+        runtime.block_on(async move {
+            let channel = time::timeout(timeout, endpoint.connect())
+                .await
+                .map_err(|_| io::Error::from(io::ErrorKind::TimedOut))?
+                .map_err(io::Error::other)?;
+            let mut client = Grpc::new(channel);
+            client.ready().await.map_err(io::Error::other)?;
+
+            let (records, receiver) = mpsc::channel(default::grpc_stream_capacity());
+            let mut request = Request::new(ReceiverStream::new(receiver));
+            *request.metadata_mut() = metadata;
+            let call = lum_libs::tokio::spawn(async move {
+                let codec = ProstCodec::<LogRecord, StreamResponse>::default();
+                client.client_streaming(request, method, codec).await
+            });
+            Ok(RecordStream { records, call })
+        })
+    }
+}
+
+impl Transport for GrpcTransport {
+    fn send(&mut self, payload: &[u8]) -> io::Result<()> {
+        let record = payload
+            .get(4..)
+            .and_then(|frame| LogRecord::decode(frame).ok())
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "payload is not a protobuf LogRecord frame",
+                )
+            })?;
+
+        if self
+            .stream
+            .as_ref()
+            .is_some_and(|stream| stream.call.is_finished())
+        {
+            self.stream = None;
+        }
+        let stream = match self.stream.take() {
+            Some(stream) => stream,
+            None => self.open()?,
+        };
+
+        let Some(runtime) = &self.runtime else {
+            unreachable!("the runtime is created when opening the stream");
+        };
+        let timeout = self.timeout;
+        let sent =
+            runtime.block_on(async { time::timeout(timeout, stream.records.send(record)).await });
+        match sent {
+            Ok(Ok(())) => {
+                self.stream = Some(stream);
+                Ok(())
+            }
+            Ok(Err(_)) => Err(io::Error::new(
+                io::ErrorKind::ConnectionAborted,
+                "the gRPC stream was closed",
+            )),
+            Err(_) => {
+                stream.call.abort();
+                Err(io::Error::from(io::ErrorKind::TimedOut))
+            }
+        }
+    }
+}
+
+impl Drop for GrpcTransport {
+    fn drop(&mut self) {
+        if let (Some(runtime), Some(stream)) = (&self.runtime, self.stream.take()) {
+            drop(stream.records);
+            let timeout = self.timeout;
+            let _ = runtime.block_on(async move { time::timeout(timeout, stream.call).await });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Read, net::TcpListener, thread};
+
+    use super::*;
+
+    fn frame(record: &LogRecord) -> Vec<u8> {
+        let payload = record.encode_to_vec();
+        let mut frame = u32::try_from(payload.len()).unwrap().to_le_bytes().to_vec();
+        frame.extend(payload);
+        frame
+    }
+
+    #[test]
+    fn the_first_record_opens_an_http2_connection_to_the_endpoint() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut preface = [0; 24];
+            stream.read_exact(&mut preface).unwrap();
+            // The connection is kept open, so the stream is not failed before the record is handed to it.
+            (preface, stream)
+        });
+
+        let mut transport = GrpcTransport::new(endpoint).timeout(Duration::from_secs(10));
+        transport
+            .send(&frame(&LogRecord {
+                message: "Started".to_string(),
+                ..LogRecord::default()
+            }))
+            .unwrap();
+
+        let (preface, _connection) = server.join().unwrap();
+        assert_eq!(&preface, b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n");
+    }
+
+    #[test]
+    fn invalid_payloads_and_settings_are_rejected_before_connecting() {
+        let record = frame(&LogRecord::default());
+
+        let mut transport = GrpcTransport::new("http://127.0.0.1:1");
+        assert_eq!(
+            transport.send(b"Not protobuf").unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+
+        let mut transport =
+            GrpcTransport::new("http://127.0.0.1:1").metadata("invalid key", "value");
+        assert_eq!(
+            transport.send(&record).unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+
+        let mut transport = GrpcTransport::new("http://127.0.0.1:1").method("not a path");
+        assert_eq!(
+            transport.send(&record).unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+    }
+}
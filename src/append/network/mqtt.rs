@@ -0,0 +1,173 @@
+use std::{
+    fmt::{self, Debug, Formatter},
+    io, thread,
+};
+
+use rumqttc::{Client, MqttOptions, QoS};
+
+use crate::{append::network::Transport, default, internal};
+
+/// A [`Transport`] publishing records to an MQTT topic, e.g. on the connection an IoT device already maintains.
+/// Every record is published as a separate message. Publishing fails while the request queue of the client is full,
+/// e.g. while the broker is unreachable, so the [`NetworkAppender`](crate::append::NetworkAppender) retries or spools the record.
+///
+/// The topic is rendered from a template with the following placeholders:
+/// - `{device_id}`: the device ID given to the transport
+/// - `{hostname}`: the host name of this machine
+///
+/// Note that rumqttc logs its connection handling at debug and trace level,
+/// so keep its level at info or above to avoid these records feeding back into the transport.
+///
+/// ```text
+/// let transport = MqttTransport::connect(MqttOptions::new("sensor-17", "broker.local", 1883), "sensor-17")?
+///     .topic("fleet/{device_id}/logs")
+///     .qos(QoS::AtMostOnce);
+/// let appender = NetworkAppender::new(transport, Box::new(JsonEncoder::new()))?;
+/// ```
+pub struct MqttTransport {
+    client: Client,
+    device_id: String,
+    topic: String,
+    qos: QoS,
+}
+
+impl MqttTransport {
+    /// Creates a new `MqttTransport` publishing through the given client, whose connection is driven by the application,
+    /// to the topic [`default::mqtt_topic`] with [`QoS::AtLeastOnce`].
+    pub fn new(client: Client, device_id: impl Into<String>) -> Self {
+        let device_id = device_id.into();
+        let topic = render_topic(default::mqtt_topic(), &device_id);
+        Self {
+            client,
+            device_id,
+            topic,
+            qos: QoS::AtLeastOnce,
+        }
+    }
+
+    /// Creates a new `MqttTransport` like [`MqttTransport::new`] with its own client connecting with the given options,
+    /// spawning a background thread driving its connection. The thread stops once the transport is dropped.
+    pub fn connect(options: MqttOptions, device_id: impl Into<String>) -> io::Result<Self> {
+        let (client, mut connection) = Client::new(options, default::mqtt_request_capacity());
+        thread::Builder::new()
+            .name("lum_log-mqtt".to_string())
+            .spawn(move || {
+                // The iterator ends once all clients are dropped, and reconnects on the next poll after an error.
+                for event in connection.iter() {
+                    if let Err(error) = event {
+                        internal::report(format_args!("MQTT connection failed: {error}"));
+                        thread::sleep(default::mqtt_reconnect_interval());
+                    }
+                }
+            })?;
+
+        Ok(Self::new(client, device_id))
+    }
+
+    /// Sets the template of the topic, see [`MqttTransport`] for the placeholders.
+    pub fn topic(mut self, template: &str) -> Self {
+        self.topic = render_topic(template, &self.device_id);
+        self
+    }
+
+    /// Sets the quality of service records are published with.
+    pub fn qos(mut self, qos: QoS) -> Self {
+        self.qos = qos;
+        self
+    }
+}
+
+impl Debug for MqttTransport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MqttTransport")
+            .field("topic", &self.topic)
+            .field("qos", &self.qos)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Transport for MqttTransport {
+    fn send(&mut self, payload: &[u8]) -> io::Result<()> {
+        self.client
+            .try_publish(self.topic.as_str(), self.qos, false, payload)
+            .map_err(io::Error::other)
+    }
+}
+
+fn render_topic(template: &str, device_id: &str) -> String {
+    template
+        .replace("{device_id}", device_id)
+        .replace("{hostname}", &internal::hostname())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Read, Write},
+        net::{TcpListener, TcpStream},
+    };
+
+    use super::*;
+
+    /// Reads an MQTT packet, returning its type and its body.
+    fn read_packet(stream: &mut TcpStream) -> (u8, Vec<u8>) {
+        let mut header = [0];
+        stream.read_exact(&mut header).unwrap();
+
+        let mut length = 0;
+        for shift in (0..4).map(|index| index * 7) {
+            let mut byte = [0];
+            stream.read_exact(&mut byte).unwrap();
+            length |= usize::from(byte[0] & 0x7f) << shift;
+            if byte[0] & 0x80 == 0 {
+                break;
+            }
+        }
+
+        let mut body = vec![0; length];
+        stream.read_exact(&mut body).unwrap();
+        (header[0] >> 4, body)
+    }
+
+    /// Accepts a single client, returning the topic and payload of the first message it publishes.
+    fn broker(listener: TcpListener) -> (String, Vec<u8>) {
+        const CONNECT: u8 = 1;
+        const PUBLISH: u8 = 3;
+
+        let (mut stream, _) = listener.accept().unwrap();
+        stream
+            .set_read_timeout(Some(std::time::Duration::from_secs(10)))
+            .unwrap();
+        assert_eq!(read_packet(&mut stream).0, CONNECT);
+        stream.write_all(&[0x20, 0x02, 0x00, 0x00]).unwrap();
+
+        loop {
+            let (kind, body) = read_packet(&mut stream);
+            if kind == PUBLISH {
+                let topic_length = usize::from(u16::from_be_bytes([body[0], body[1]]));
+                let topic = String::from_utf8(body[2..2 + topic_length].to_vec()).unwrap();
+                return (topic, body[2 + topic_length..].to_vec());
+            }
+        }
+    }
+
+    #[test]
+    fn records_are_published_to_the_rendered_topic() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let broker = thread::spawn(move || broker(listener));
+
+        let mut transport = MqttTransport::connect(
+            MqttOptions::new("mqtt_test", "127.0.0.1", port),
+            "sensor-17",
+        )
+        .unwrap()
+        .topic("fleet/{device_id}/logs")
+        .qos(QoS::AtMostOnce);
+        transport.send(b"{\"message\":\"Started\"}").unwrap();
+
+        let (topic, payload) = broker.join().unwrap();
+        assert_eq!(topic, "fleet/sensor-17/logs");
+        assert_eq!(payload, b"{\"message\":\"Started\"}");
+    }
+}
@@ -0,0 +1,291 @@
+use std::{
+    io::{self, Read, Write},
+    net::{TcpStream, ToSocketAddrs},
+    time::Duration,
+};
+
+use lum_libs::{serde::Serialize, serde_json};
+
+use crate::{append::network::Transport, default, internal};
+
+/// The options sent to the NATS server in the `CONNECT` message.
+#[derive(Debug, Default, Serialize)]
+#[serde(crate = "lum_libs::serde")]
+struct Connect {
+    verbose: bool,
+    pedantic: bool,
+    name: &'static str,
+    lang: &'static str,
+    version: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    user: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pass: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    auth_token: Option<String>,
+}
+
+/// A [`Transport`] publishing records to a NATS subject, e.g. with a JSON encoder, so services already using NATS need no dedicated log transport.
+/// Every record is published as a separate message. The connection is established lazily and re-established after errors.
+/// TLS is not supported.
+///
+/// The subject is rendered from a template with the following placeholders:
+/// - `{hostname}`: the host name of this machine
+/// - `{pid}`: the ID of this process
+///
+/// ```text
+/// let transport = NatsTransport::new("nats.local:4222").subject("logs.orders.{hostname}");
+/// let appender = NetworkAppender::new(transport, Box::new(JsonEncoder::new()))?;
+/// ```
+#[derive(Debug)]
+pub struct NatsTransport {
+    address: String,
+    subject: String,
+    timeout: Duration,
+    connect: Connect,
+    connection: Option<Connection>,
+}
+
+/// A connection to a NATS server, buffering the lines received from it.
+#[derive(Debug)]
+struct Connection {
+    stream: TcpStream,
+    incoming: Vec<u8>,
+}
+
+impl Connection {
+    /// Returns the next line received from the server, waiting for it up to the read timeout.
+    /// In non-blocking mode, fails with [`io::ErrorKind::WouldBlock`] if no complete line has been received yet.
+    fn read_line(&mut self) -> io::Result<String> {
+        loop {
+            if let Some(end) = self
+                .incoming
+                .windows(2)
+                .position(|window| window == b"\r\n")
+            {
+                let line = String::from_utf8_lossy(&self.incoming[..end]).into_owned();
+                self.incoming.drain(..end + 2);
+                return Ok(line);
+            }
+
+            let mut buffer = [0; 1024];
+            match self.stream.read(&mut buffer)? {
+                0 => return Err(io::ErrorKind::UnexpectedEof.into()),
+                read => self.incoming.extend_from_slice(&buffer[..read]),
+            }
+        }
+    }
+}
+
+impl NatsTransport {
+    /// Creates a new `NatsTransport` connecting to the given address, like `nats.local:4222`,
+    /// and publishing to the subject [`default::nats_subject`].
+    /// The connect and write timeout defaults to [`default::network_timeout`].
+    pub fn new(address: impl Into<String>) -> Self {
+        Self {
+            address: address.into(),
+            subject: render_subject(default::nats_subject()),
+            timeout: default::network_timeout(),
+            connect: Connect {
+                name: "lum_log",
+                lang: "rust",
+                version: env!("CARGO_PKG_VERSION"),
+                ..Connect::default()
+            },
+            connection: None,
+        }
+    }
+
+    /// Sets the template of the subject, see [`NatsTransport`] for the placeholders.
+    pub fn subject(mut self, template: &str) -> Self {
+        self.subject = render_subject(template);
+        self
+    }
+
+    /// Sets the user and password to authenticate with.
+    pub fn credentials(mut self, user: impl Into<String>, password: impl Into<String>) -> Self {
+        self.connect.user = Some(user.into());
+        self.connect.pass = Some(password.into());
+        self
+    }
+
+    /// Sets the token to authenticate with.
+    pub fn token(mut self, token: impl Into<String>) -> Self {
+        self.connect.auth_token = Some(token.into());
+        self
+    }
+
+    /// Sets the connect and write timeout.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    fn connect(&self) -> io::Result<Connection> {
+        let mut last_error = None;
+        for address in self.address.to_socket_addrs()? {
+            match TcpStream::connect_timeout(&address, self.timeout) {
+                Ok(stream) => return self.handshake(stream),
+                Err(error) => last_error = Some(error),
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::AddrNotAvailable,
+                format!("{} did not resolve to any address", self.address),
+            )
+        }))
+    }
+
+    /// Reads the `INFO` of the server and sends `CONNECT`, followed by a `PING` whose `PONG` confirms the connection.
+    fn handshake(&self, stream: TcpStream) -> io::Result<Connection> {
+        stream.set_read_timeout(Some(self.timeout))?;
+        stream.set_write_timeout(Some(self.timeout))?;
+        stream.set_nodelay(true)?;
+        let mut connection = Connection {
+            stream,
+            incoming: Vec::new(),
+        };
+
+        let info = connection.read_line()?;
+        if !info.starts_with("INFO ") {
+            return Err(protocol_error(&info));
+        }
+
+        let connect = serde_json::to_string(&self.connect)?;
+        connection
+            .stream
+            .write_all(format!("CONNECT {connect}\r\nPING\r\n").as_bytes())?;
+        match connection.read_line()?.as_str() {
+            "PONG" => Ok(connection),
+            line => Err(protocol_error(line)),
+        }
+    }
+}
+
+impl Transport for NatsTransport {
+    fn send(&mut self, payload: &[u8]) -> io::Result<()> {
+        let connection = match &mut self.connection {
+            Some(connection) => connection,
+            None => self.connection.insert(self.connect()?),
+        };
+
+        let result = answer_pings(connection).and_then(|()| {
+            let stream = &mut connection.stream;
+            stream.write_all(format!("PUB {} {}\r\n", self.subject, payload.len()).as_bytes())?;
+            stream.write_all(payload)?;
+            stream.write_all(b"\r\n")?;
+            stream.flush()
+        });
+        if result.is_err() {
+            self.connection = None;
+        }
+
+        result
+    }
+}
+
+/// Reads the lines the server sent so far, answering `PING`s, so the server keeps the connection open.
+fn answer_pings(connection: &mut Connection) -> io::Result<()> {
+    connection.stream.set_nonblocking(true)?;
+    let result = loop {
+        let line = match connection.read_line() {
+            Ok(line) => line,
+            Err(error) if error.kind() == io::ErrorKind::WouldBlock => break Ok(()),
+            Err(error) => break Err(error),
+        };
+
+        match line.as_str() {
+            "PING" => {
+                connection.stream.set_nonblocking(false)?;
+                connection.stream.write_all(b"PONG\r\n")?;
+                connection.stream.set_nonblocking(true)?;
+            }
+            line if line.starts_with("-ERR") => break Err(protocol_error(line)),
+            _ => {}
+        }
+    };
+
+    connection.stream.set_nonblocking(false)?;
+    result
+}
+
+fn protocol_error(line: &str) -> io::Error {
+    io::Error::other(format!("Unexpected message from NATS server: {line}"))
+}
+
+fn render_subject(template: &str) -> String {
+    template
+        .replace("{hostname}", &internal::hostname())
+        .replace("{pid}", &std::process::id().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader},
+        net::TcpListener,
+        sync::mpsc,
+        thread,
+    };
+
+    use super::*;
+
+    #[test]
+    fn records_are_published_after_the_handshake_and_pings_are_answered() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let (pinged, ping_sent) = mpsc::channel();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            stream
+                .set_read_timeout(Some(Duration::from_secs(10)))
+                .unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut read_line = || {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                line
+            };
+
+            stream
+                .write_all(b"INFO {\"server_id\":\"test\"}\r\n")
+                .unwrap();
+            let connect = read_line();
+            assert_eq!(read_line(), "PING\r\n");
+            stream.write_all(b"PONG\r\n").unwrap();
+            let mut lines = vec![read_line(), read_line()];
+            stream.write_all(b"PING\r\n").unwrap();
+            pinged.send(()).unwrap();
+            lines.extend([read_line(), read_line(), read_line()]);
+            (connect, lines)
+        });
+
+        let mut transport = NatsTransport::new(address)
+            .subject("logs.{pid}")
+            .credentials("service", "secret");
+        transport.send(b"{\"message\":\"Started\"}").unwrap();
+        ping_sent.recv().unwrap();
+        // The ping of the server is answered before the next record is published.
+        thread::sleep(Duration::from_millis(50));
+        transport.send(b"Second").unwrap();
+
+        let (connect, lines) = server.join().unwrap();
+        let connect =
+            serde_json::from_str::<serde_json::Value>(connect.strip_prefix("CONNECT ").unwrap())
+                .unwrap();
+        assert_eq!(connect["user"], "service");
+        assert_eq!(connect["pass"], "secret");
+        assert_eq!(
+            lines,
+            [
+                format!("PUB logs.{} 21\r\n", std::process::id()),
+                "{\"message\":\"Started\"}\r\n".to_string(),
+                "PONG\r\n".to_string(),
+                format!("PUB logs.{} 6\r\n", std::process::id()),
+                "Second\r\n".to_string(),
+            ]
+        );
+    }
+}
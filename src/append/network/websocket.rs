@@ -0,0 +1,169 @@
+use std::{
+    io,
+    net::{TcpStream, ToSocketAddrs},
+    time::Duration,
+};
+
+use tungstenite::{Message, WebSocket, client::IntoClientRequest};
+
+use crate::{append::network::Transport, default};
+
+/// A [`Transport`] streaming records to a WebSocket server, e.g. a live debugging UI watching the logs of a device.
+/// Every record is sent as a separate text message, or as a binary message if it is not valid UTF-8, e.g. with framing encoders.
+/// The connection is established lazily and re-established after errors. Only `ws://` URLs are supported.
+/// Messages from the server are discarded, but pings are answered.
+///
+/// ```text
+/// let appender = NetworkAppender::new(WebSocketTransport::new("ws://debug-ui.local:8080/devices/17"), Box::new(JsonEncoder::new()))?;
+/// ```
+#[derive(Debug)]
+pub struct WebSocketTransport {
+    url: String,
+    timeout: Duration,
+    socket: Option<WebSocket<TcpStream>>,
+}
+
+impl WebSocketTransport {
+    /// Creates a new `WebSocketTransport` connecting to the given URL, like `ws://localhost:8080/logs`.
+    /// The connect and write timeout defaults to [`default::network_timeout`].
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            timeout: default::network_timeout(),
+            socket: None,
+        }
+    }
+
+    /// Sets the connect and write timeout.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    fn connect(&self) -> io::Result<WebSocket<TcpStream>> {
+        let request = self
+            .url
+            .as_str()
+            .into_client_request()
+            .map_err(io::Error::other)?;
+        let uri = request.uri();
+        if uri.scheme_str() != Some("ws") {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} is not a ws:// URL", self.url),
+            ));
+        }
+        let host = uri.host().unwrap_or_default();
+        let port = uri.port_u16().unwrap_or(80);
+
+        let mut last_error = None;
+        for address in (host, port).to_socket_addrs()? {
+            match TcpStream::connect_timeout(&address, self.timeout) {
+                Ok(stream) => {
+                    stream.set_read_timeout(Some(self.timeout))?;
+                    stream.set_write_timeout(Some(self.timeout))?;
+                    stream.set_nodelay(true)?;
+                    let (socket, _) =
+                        tungstenite::client(request, stream).map_err(io::Error::other)?;
+                    return Ok(socket);
+                }
+                Err(error) => last_error = Some(error),
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::AddrNotAvailable,
+                format!("{} did not resolve to any address", self.url),
+            )
+        }))
+    }
+}
+
+impl Transport for WebSocketTransport {
+    fn send(&mut self, payload: &[u8]) -> io::Result<()> {
+        let socket = match &mut self.socket {
+            Some(socket) => socket,
+            None => self.socket.insert(self.connect()?),
+        };
+
+        let message = match str::from_utf8(payload) {
+            Ok(text) => Message::text(text),
+            Err(_) => Message::binary(payload.to_vec()),
+        };
+        if let Err(error) = socket.send(message) {
+            self.socket = None;
+            return Err(into_io_error(error));
+        }
+
+        // The record has been sent, so a failure only resets the connection instead of sending it again.
+        if answer_pings(socket).is_err() {
+            self.socket = None;
+        }
+        Ok(())
+    }
+}
+
+/// Reads the messages the server sent so far, so pings are answered and closing is noticed, discarding all other messages.
+fn answer_pings(socket: &mut WebSocket<TcpStream>) -> tungstenite::Result<()> {
+    let timeout = socket.get_ref().read_timeout()?;
+    socket.get_ref().set_nonblocking(true)?;
+
+    let result = loop {
+        match socket.read() {
+            Ok(_) => continue,
+            Err(tungstenite::Error::Io(error)) if error.kind() == io::ErrorKind::WouldBlock => {
+                break socket.flush();
+            }
+            Err(error) => break Err(error),
+        }
+    };
+
+    socket.get_ref().set_nonblocking(false)?;
+    socket.get_ref().set_read_timeout(timeout)?;
+    result
+}
+
+fn into_io_error(error: tungstenite::Error) -> io::Error {
+    match error {
+        tungstenite::Error::Io(error) => error,
+        error => io::Error::other(error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{net::TcpListener, thread};
+
+    use super::*;
+
+    #[test]
+    fn records_are_sent_as_text_or_binary_messages() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("ws://{}/logs", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut socket = tungstenite::accept(stream).unwrap();
+            [socket.read().unwrap(), socket.read().unwrap()]
+        });
+
+        let mut transport = WebSocketTransport::new(url);
+        transport.send(b"{\"message\":\"Started\"}").unwrap();
+        transport.send(&[0x92, 0xff]).unwrap();
+
+        assert_eq!(
+            server.join().unwrap(),
+            [
+                Message::text("{\"message\":\"Started\"}"),
+                Message::binary(vec![0x92, 0xff]),
+            ]
+        );
+        assert_eq!(
+            WebSocketTransport::new("wss://localhost/logs")
+                .send(b"Secure")
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::InvalidInput
+        );
+    }
+}
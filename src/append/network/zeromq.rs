@@ -0,0 +1,116 @@
+use std::{
+    fmt::{self, Debug, Formatter},
+    io,
+};
+
+use crate::{append::network::Transport, default};
+
+/// A [`Transport`] publishing records on a ZeroMQ PUB socket, for low-latency local fan-out to multiple collectors or debug tools,
+/// e.g. on `ipc:///tmp/my_app.logs` or `tcp://127.0.0.1:5556`.
+/// The socket is bound lazily on the first record. Subscribers only receive records published after they connected.
+/// Once the high-water mark of a subscriber is reached, ZeroMQ drops records for it instead of blocking.
+///
+/// ```text
+/// let transport = ZmqTransport::new("ipc:///tmp/my_app.logs").high_water_mark(10_000).topic("my_app");
+/// let appender = NetworkAppender::new(transport, Box::new(JsonEncoder::new()))?;
+/// ```
+pub struct ZmqTransport {
+    endpoint: String,
+    high_water_mark: i32,
+    topic: Option<Vec<u8>>,
+    context: zmq::Context,
+    socket: Option<zmq::Socket>,
+}
+
+impl ZmqTransport {
+    /// Creates a new `ZmqTransport` binding to the given endpoint, with a high-water mark of [`default::zeromq_high_water_mark`].
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            high_water_mark: default::zeromq_high_water_mark(),
+            topic: None,
+            context: zmq::Context::new(),
+            socket: None,
+        }
+    }
+
+    /// Sets the number of records queued per subscriber before further records are dropped for it.
+    pub fn high_water_mark(mut self, high_water_mark: i32) -> Self {
+        self.high_water_mark = high_water_mark;
+        self
+    }
+
+    /// Sets the topic sent as the first frame of every message, so subscribers can filter by it.
+    /// By default, messages consist of the record only.
+    pub fn topic(mut self, topic: impl Into<Vec<u8>>) -> Self {
+        self.topic = Some(topic.into());
+        self
+    }
+
+    fn bind(&self) -> zmq::Result<zmq::Socket> {
+        let socket = self.context.socket(zmq::PUB)?;
+        socket.set_sndhwm(self.high_water_mark)?;
+        socket.set_linger(0)?;
+        socket.bind(&self.endpoint)?;
+        Ok(socket)
+    }
+}
+
+impl Debug for ZmqTransport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ZmqTransport")
+            .field("endpoint", &self.endpoint)
+            .field("high_water_mark", &self.high_water_mark)
+            .field("topic", &self.topic.as_deref().map(String::from_utf8_lossy))
+            .finish_non_exhaustive()
+    }
+}
+
+impl Transport for ZmqTransport {
+    fn send(&mut self, payload: &[u8]) -> io::Result<()> {
+        let socket = match &mut self.socket {
+            Some(socket) => socket,
+            None => self.socket.insert(self.bind().map_err(io::Error::other)?),
+        };
+
+        let result = match &self.topic {
+            Some(topic) => socket.send_multipart([topic.as_slice(), payload], zmq::DONTWAIT),
+            None => socket.send(payload, zmq::DONTWAIT),
+        };
+        result.map_err(io::Error::other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::*;
+    use crate::testing;
+
+    #[test]
+    fn records_are_published_with_their_topic_to_subscribers() {
+        let path = testing::temp_dir("zeromq").join("logs.ipc");
+        let endpoint = format!("ipc://{}", path.display());
+        let mut transport = ZmqTransport::new(endpoint.as_str()).topic("zeromq_test");
+        // The first record binds the socket, before any subscriber connected.
+        transport.send(b"Unheard").unwrap();
+
+        let subscriber = transport.context.socket(zmq::SUB).unwrap();
+        subscriber.set_subscribe(b"zeromq_test").unwrap();
+        subscriber.set_rcvtimeo(50).unwrap();
+        subscriber.connect(&endpoint).unwrap();
+
+        // Subscriptions take a moment to reach the publisher, so publish until a record arrives.
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let message = loop {
+            assert!(Instant::now() < deadline);
+            transport.send(b"Started").unwrap();
+            if let Ok(message) = subscriber.recv_multipart(0) {
+                break message;
+            }
+        };
+
+        assert_eq!(message, [b"zeromq_test".to_vec(), b"Started".to_vec()]);
+    }
+}
@@ -0,0 +1,247 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, BufWriter, Write as _},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use lum_libs::{
+    log::Record,
+    log4rs::{
+        append::Append,
+        encode::{Encode, writer::simple::SimpleWriter},
+    },
+    parking_lot::Mutex,
+    serde::{Deserialize, Serialize},
+};
+
+use crate::{
+    default,
+    encode::{SafeEncoder, StripAnsiEncoder},
+    rotate::{self, Rotation},
+    timestamp,
+};
+
+/// The time window each file of a [`PartitionedFileAppender`] covers. Windows are aligned to UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde", rename_all = "snake_case")]
+pub enum PartitionInterval {
+    /// One file per minute, e.g. `app-2025-01-30-14-05.log`.
+    Minutely,
+    /// One file per hour, e.g. `app-2025-01-30-14.log`.
+    #[default]
+    Hourly,
+    /// One file per day, e.g. `app-2025-01-30.log`.
+    Daily,
+}
+
+impl PartitionInterval {
+    /// Returns the length of a window.
+    pub fn duration(self) -> Duration {
+        match self {
+            PartitionInterval::Minutely => Duration::from_secs(60),
+            PartitionInterval::Hourly => Duration::from_secs(3600),
+            PartitionInterval::Daily => Duration::from_secs(86_400),
+        }
+    }
+
+    /// Returns the index of the window containing the given time, counted from the Unix epoch.
+    fn window(self, time: SystemTime) -> u64 {
+        let seconds = time
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        seconds / self.duration().as_secs()
+    }
+
+    /// Returns the name of the window with the given index, e.g. `2025-01-30-14` for an hourly window.
+    fn window_name(self, window: u64) -> String {
+        let start = UNIX_EPOCH + Duration::from_secs(self.duration().as_secs() * window);
+        let (date, time) = timestamp::utc_date_time(start);
+        match self {
+            PartitionInterval::Minutely => format!("{date}-{}-{}", &time[..2], &time[3..5]),
+            PartitionInterval::Hourly => format!("{date}-{}", &time[..2]),
+            PartitionInterval::Daily => date,
+        }
+    }
+}
+
+/// The file of the current window.
+#[derive(Debug)]
+struct Partition {
+    window: u64,
+    path: PathBuf,
+    writer: SimpleWriter<BufWriter<File>>,
+}
+
+/// A file appender writing each time window to its own file named after the window, e.g. `logs/app-2025-01-30-14.log` for `logs/app.log`,
+/// see [`default::partition_file_path`].
+/// When the first record of a new window arrives, the file of the previous window is flushed and closed, and the file of the new window is opened.
+/// Unlike rolling file appenders, files are never renamed, so the file a record ends up in only depends on when it was logged,
+/// which makes hourly partitions simpler to ship and query. Existing files are appended to, e.g. after a restart within the same window.
+/// Closed files are reported to the callbacks registered by [`rotate::on_rotation`] as rotated to themselves, e.g. for archiving them.
+/// Old files are not cleaned up.
+#[derive(Debug)]
+pub struct PartitionedFileAppender {
+    path: PathBuf,
+    interval: PartitionInterval,
+    encoder: Box<dyn Encode>,
+    partition: Mutex<Option<Partition>>,
+}
+
+impl PartitionedFileAppender {
+    /// Creates a new `PartitionedFileAppender` writing records encoded by the given encoder to files derived from the given path,
+    /// opening the file of the current window and creating its parent directories if needed.
+    /// The encoder is wrapped in a [`StripAnsiEncoder`] and a [`SafeEncoder`].
+    pub fn new(
+        path: impl AsRef<Path>,
+        interval: PartitionInterval,
+        encoder: Box<dyn Encode>,
+    ) -> io::Result<Self> {
+        let appender = Self {
+            path: path.as_ref().to_path_buf(),
+            interval,
+            encoder: Box::new(SafeEncoder::new(Box::new(StripAnsiEncoder::new(encoder)))),
+            partition: Mutex::new(None),
+        };
+
+        let partition = appender.open(interval.window(SystemTime::now()))?;
+        *appender.partition.lock() = Some(partition);
+        Ok(appender)
+    }
+
+    /// Returns the window each file covers.
+    pub fn interval(&self) -> PartitionInterval {
+        self.interval
+    }
+
+    /// Returns the path of the file records logged at the given time are written to.
+    pub fn path_at(&self, time: SystemTime) -> PathBuf {
+        let window = self.interval.window_name(self.interval.window(time));
+        default::partition_file_path(&self.path, &window)
+    }
+
+    /// Returns the path of the file currently written to, if any.
+    pub fn current_path(&self) -> Option<PathBuf> {
+        self.partition
+            .lock()
+            .as_ref()
+            .map(|partition| partition.path.clone())
+    }
+
+    fn open(&self, window: u64) -> io::Result<Partition> {
+        let path = default::partition_file_path(&self.path, &self.interval.window_name(window));
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        Ok(Partition {
+            window,
+            path,
+            writer: SimpleWriter(BufWriter::new(file)),
+        })
+    }
+}
+
+impl Append for PartitionedFileAppender {
+    fn append(&self, record: &Record) -> anyhow::Result<()> {
+        let window = self.interval.window(SystemTime::now());
+        let mut partition = self.partition.lock();
+
+        if partition
+            .as_ref()
+            .is_none_or(|partition| partition.window != window)
+        {
+            let opened = self.open(window)?;
+            if let Some(mut closed) = partition.replace(opened) {
+                closed.writer.flush()?;
+                drop(closed.writer);
+                rotate::notify(Rotation {
+                    file: closed.path.clone(),
+                    rotated: closed.path,
+                })?;
+            }
+        }
+
+        let partition = partition
+            .as_mut()
+            .expect("The partition has just been opened");
+        self.encoder.encode(&mut partition.writer, record)?;
+        partition.writer.flush()?;
+        Ok(())
+    }
+
+    fn flush(&self) {
+        if let Some(partition) = &mut *self.partition.lock() {
+            let _ = partition.writer.flush();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lum_libs::{log::Level, log4rs::encode::pattern::PatternEncoder};
+
+    use super::*;
+    use crate::testing;
+
+    fn appender(path: &Path, interval: PartitionInterval) -> PartitionedFileAppender {
+        PartitionedFileAppender::new(path, interval, Box::new(PatternEncoder::new("{m}{n}")))
+            .unwrap()
+    }
+
+    fn append(appender: &PartitionedFileAppender, message: &str) {
+        appender
+            .append(
+                &Record::builder()
+                    .level(Level::Info)
+                    .args(format_args!("{message}"))
+                    .build(),
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn files_are_named_after_the_utc_window_of_the_records() {
+        let path = testing::temp_dir("partitioned_names").join("app.log");
+        // 2025-01-30 14:05:30 UTC.
+        let time = UNIX_EPOCH + Duration::from_secs(1_738_245_930);
+        let names = [
+            PartitionInterval::Minutely,
+            PartitionInterval::Hourly,
+            PartitionInterval::Daily,
+        ]
+        .map(|interval| appender(&path, interval).path_at(time));
+
+        assert_eq!(
+            names,
+            [
+                path.with_file_name("app-2025-01-30-14-05.log"),
+                path.with_file_name("app-2025-01-30-14.log"),
+                path.with_file_name("app-2025-01-30.log"),
+            ]
+        );
+    }
+
+    #[test]
+    fn records_are_appended_to_the_file_of_the_current_window() {
+        let path = testing::temp_dir("partitioned_append")
+            .join("logs")
+            .join("app.log");
+
+        append(
+            &appender(&path, PartitionInterval::Daily),
+            "Before the restart",
+        );
+        let restarted = appender(&path, PartitionInterval::Daily);
+        append(&restarted, "After the restart");
+
+        let current = restarted.current_path().unwrap();
+        assert_eq!(current, restarted.path_at(SystemTime::now()));
+        assert_eq!(
+            fs::read_to_string(current).unwrap(),
+            "Before the restart\nAfter the restart\n"
+        );
+    }
+}
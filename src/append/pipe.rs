@@ -0,0 +1,290 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use lum_libs::{
+    log::Record,
+    log4rs::{
+        append::Append,
+        encode::{Encode, writer::simple::SimpleWriter},
+    },
+    parking_lot::Mutex,
+};
+
+use crate::{
+    encode::{SafeEncoder, StripAnsiEncoder},
+    stats,
+};
+
+/// An appender writing records to a named pipe, so external tools can attach on demand to consume the stream,
+/// e.g. with `cat /tmp/my_app.logs`.
+///
+/// On Unix, the appender creates a FIFO at the given path if none exists and writes to it without blocking.
+/// On Windows, the reader creates the pipe server, e.g. `\\.\pipe\my_app.logs`, and the appender connects to it as a client.
+///
+/// While no reader is attached, records are discarded, and the pipe is opened again on the next record.
+/// On Unix, records arriving while the pipe is full because the reader is too slow are dropped and counted.
+/// Records are never written partially, except when a reader detaches in the middle of a record.
+#[derive(Debug)]
+pub struct NamedPipeAppender {
+    path: PathBuf,
+    encoder: Box<dyn Encode>,
+    state: Mutex<PipeState>,
+    dropped: AtomicU64,
+}
+
+#[derive(Debug, Default)]
+struct PipeState {
+    pipe: Option<File>,
+    /// The rest of a record that did not fit into the pipe, written before the next record.
+    pending: Vec<u8>,
+}
+
+impl NamedPipeAppender {
+    /// Creates a new `NamedPipeAppender` writing records encoded by the given encoder to the named pipe at the given path.
+    /// On Unix, a FIFO is created at the path if nothing exists there, and an error is returned if something else does.
+    /// The encoder is wrapped in a [`StripAnsiEncoder`] and a [`SafeEncoder`].
+    pub fn new(path: impl AsRef<Path>, encoder: Box<dyn Encode>) -> io::Result<Self> {
+        let path = path.as_ref();
+        #[cfg(unix)]
+        create_fifo(path)?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            encoder: Box::new(SafeEncoder::new(Box::new(StripAnsiEncoder::new(encoder)))),
+            state: Mutex::new(PipeState::default()),
+            dropped: AtomicU64::new(0),
+        })
+    }
+
+    /// Returns the path of the named pipe.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns whether a reader is currently attached, as far as the appender knows from its last write.
+    pub fn is_attached(&self) -> bool {
+        self.state.lock().pipe.is_some()
+    }
+
+    /// Returns the number of records dropped so far because the pipe was full.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    fn drop_record(&self) {
+        self.dropped.fetch_add(1, Ordering::Relaxed);
+        stats::record_dropped();
+    }
+}
+
+impl Append for NamedPipeAppender {
+    fn append(&self, record: &Record) -> anyhow::Result<()> {
+        let mut writer = SimpleWriter(Vec::new());
+        self.encoder.encode(&mut writer, record)?;
+
+        let mut state = self.state.lock();
+        let PipeState { pipe, pending } = &mut *state;
+        let file = match pipe {
+            Some(file) => file,
+            None => match open_pipe(&self.path) {
+                Ok(file) => pipe.insert(file),
+                Err(error) if is_unattached(&error) => return Ok(()),
+                Err(error) => return Err(error.into()),
+            },
+        };
+
+        let result = write_pending(file, pending).and_then(|written| {
+            if !written {
+                return Ok(false);
+            }
+
+            *pending = writer.0;
+            write_pending(file, pending)?;
+            Ok(true)
+        });
+        match result {
+            Ok(true) => {}
+            Ok(false) => self.drop_record(),
+            Err(error) if is_unattached(&error) => {
+                *pipe = None;
+                pending.clear();
+            }
+            Err(error) => {
+                *pipe = None;
+                pending.clear();
+                return Err(error.into());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn flush(&self) {}
+}
+
+/// Writes as much of the pending bytes as the pipe accepts without blocking.
+/// Returns whether all of them were written.
+fn write_pending(file: &mut File, pending: &mut Vec<u8>) -> io::Result<bool> {
+    while !pending.is_empty() {
+        match file.write(pending) {
+            Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+            Ok(written) => {
+                pending.drain(..written);
+            }
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+            Err(error) if error.kind() == io::ErrorKind::WouldBlock => return Ok(false),
+            Err(error) => return Err(error),
+        }
+    }
+
+    Ok(true)
+}
+
+/// Creates a FIFO at the given path unless one already exists there.
+#[cfg(unix)]
+fn create_fifo(path: &Path) -> io::Result<()> {
+    use std::{ffi::CString, os::unix::ffi::OsStrExt, os::unix::fs::FileTypeExt};
+
+    match path.symlink_metadata() {
+        Ok(metadata) if metadata.file_type().is_fifo() => return Ok(()),
+        Ok(_) => {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("{} exists and is not a named pipe", path.display()),
+            ));
+        }
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {}
+        Err(error) => return Err(error),
+    }
+
+    let path = CString::new(path.as_os_str().as_bytes())
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+    // SAFETY: `path` is a valid, NUL-terminated C string.
+    let result = unsafe { libc::mkfifo(path.as_ptr(), 0o600) };
+    if result != 0 {
+        let error = io::Error::last_os_error();
+        if error.kind() != io::ErrorKind::AlreadyExists {
+            return Err(error);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(unix)]
+fn open_pipe(path: &Path) -> io::Result<File> {
+    use std::os::unix::fs::OpenOptionsExt;
+
+    OpenOptions::new()
+        .write(true)
+        .custom_flags(libc::O_NONBLOCK)
+        .open(path)
+}
+
+#[cfg(windows)]
+fn open_pipe(path: &Path) -> io::Result<File> {
+    OpenOptions::new().write(true).open(path)
+}
+
+/// Returns whether the given error means that no reader is attached to the pipe.
+#[cfg(unix)]
+fn is_unattached(error: &io::Error) -> bool {
+    error.raw_os_error() == Some(libc::ENXIO) || error.kind() == io::ErrorKind::BrokenPipe
+}
+
+/// Returns whether the given error means that no reader is attached to the pipe.
+#[cfg(windows)]
+fn is_unattached(error: &io::Error) -> bool {
+    // ERROR_PIPE_BUSY: the pipe server is connected to another client.
+    const ERROR_PIPE_BUSY: i32 = 231;
+
+    matches!(
+        error.kind(),
+        io::ErrorKind::NotFound | io::ErrorKind::BrokenPipe
+    ) || error.raw_os_error() == Some(ERROR_PIPE_BUSY)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::{fs, io::Read, os::unix::fs::OpenOptionsExt};
+
+    use lum_libs::{log::Level, log4rs::encode::pattern::PatternEncoder};
+
+    use super::*;
+    use crate::testing;
+
+    fn append(appender: &NamedPipeAppender, message: &str) {
+        appender
+            .append(
+                &Record::builder()
+                    .level(Level::Info)
+                    .args(format_args!("{message}"))
+                    .build(),
+            )
+            .unwrap();
+    }
+
+    fn attach(path: &Path) -> File {
+        OpenOptions::new()
+            .read(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open(path)
+            .unwrap()
+    }
+
+    #[test]
+    fn records_reach_attached_readers_and_are_discarded_otherwise() {
+        let path = testing::temp_dir("named_pipe").join("app.logs");
+        let appender =
+            NamedPipeAppender::new(&path, Box::new(PatternEncoder::new("{m}{n}"))).unwrap();
+
+        append(&appender, "Unheard");
+        assert!(!appender.is_attached());
+
+        let mut reader = attach(&path);
+        append(&appender, "First");
+        append(&appender, "Second");
+        assert!(appender.is_attached());
+        let mut received = String::new();
+        // As the appender keeps the pipe open, reading ends with `WouldBlock` once it is empty.
+        reader.read_to_string(&mut received).unwrap_err();
+        assert_eq!(received, "First\nSecond\n");
+
+        drop(reader);
+        append(&appender, "Detached");
+        assert!(!appender.is_attached());
+        assert_eq!(appender.dropped(), 0);
+    }
+
+    #[test]
+    fn records_are_dropped_while_the_pipe_is_full() {
+        let path = testing::temp_dir("full_pipe").join("app.logs");
+        let appender =
+            NamedPipeAppender::new(&path, Box::new(PatternEncoder::new("{m}{n}"))).unwrap();
+        let _reader = attach(&path);
+
+        let message = "x".repeat(1023);
+        for _ in 0..256 {
+            append(&appender, &message);
+        }
+
+        assert!(appender.is_attached());
+        assert!(appender.dropped() > 0);
+    }
+
+    #[test]
+    fn other_files_are_not_replaced_by_a_pipe() {
+        let path = testing::temp_dir("not_a_pipe").join("app.log");
+        fs::write(&path, "Existing").unwrap();
+
+        let error =
+            NamedPipeAppender::new(&path, Box::new(PatternEncoder::new("{m}{n}"))).unwrap_err();
+
+        assert_eq!(error.kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "Existing");
+    }
+}
@@ -0,0 +1,211 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, ErrorKind},
+    path::Path,
+};
+
+use lum_libs::{
+    log::Record,
+    log4rs::{
+        append::Append,
+        encode::{Encode, writer::simple::SimpleWriter},
+    },
+    parking_lot::Mutex,
+};
+use memmap2::MmapMut;
+
+use crate::encode::{SafeEncoder, StripAnsiEncoder};
+
+const MAGIC: &[u8; 8] = b"LUMRING1";
+/// The header consists of the magic bytes, the capacity, the write position, and whether the ring wrapped, each 8 bytes.
+const HEADER_LEN: usize = 32;
+
+#[derive(Debug)]
+struct RingState {
+    map: MmapMut,
+    head: usize,
+    wrapped: bool,
+}
+
+impl RingState {
+    fn data(&mut self) -> &mut [u8] {
+        &mut self.map[HEADER_LEN..]
+    }
+
+    fn write(&mut self, mut bytes: &[u8]) {
+        let capacity = self.data().len();
+        if bytes.len() > capacity {
+            bytes = &bytes[bytes.len() - capacity..];
+        }
+
+        let head = self.head;
+        let first = bytes.len().min(capacity - head);
+        self.data()[head..head + first].copy_from_slice(&bytes[..first]);
+        self.data()[..bytes.len() - first].copy_from_slice(&bytes[first..]);
+
+        let end = head + bytes.len();
+        self.wrapped |= end >= capacity;
+        self.head = end % capacity;
+
+        let head = self.head as u64;
+        let wrapped = u64::from(self.wrapped);
+        self.map[16..24].copy_from_slice(&head.to_le_bytes());
+        self.map[24..32].copy_from_slice(&wrapped.to_le_bytes());
+    }
+}
+
+/// A flight-recorder appender writing records into a fixed-size, memory-mapped circular file,
+/// overwriting the oldest records when it is full.
+/// Writes to the mapping reach the operating system immediately, so the last records survive the process dying without flushing,
+/// and [`MmapRingAppender::recover`] or crash analysis tools can always retrieve the most recent logging.
+/// If the file already holds a ring of the same capacity, e.g. from before a crash, writing continues after its records.
+#[derive(Debug)]
+pub struct MmapRingAppender {
+    encoder: Box<dyn Encode>,
+    state: Mutex<RingState>,
+}
+
+impl MmapRingAppender {
+    /// Creates a new `MmapRingAppender` keeping the given number of bytes of records encoded by the given encoder
+    /// in the file at the given path, creating the file and its parent directories if needed.
+    /// The encoder is wrapped in a [`StripAnsiEncoder`] and a [`SafeEncoder`].
+    pub fn new(
+        path: impl AsRef<Path>,
+        capacity: usize,
+        encoder: Box<dyn Encode>,
+    ) -> io::Result<Self> {
+        if capacity == 0 {
+            return Err(io::Error::new(
+                ErrorKind::InvalidInput,
+                "Ring capacity must not be zero",
+            ));
+        }
+
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+        file.set_len((HEADER_LEN + capacity) as u64)?;
+        // SAFETY: The file is only modified through this mapping, as long as no other process writes to it.
+        let mut map = unsafe { MmapMut::map_mut(&file)? };
+
+        let (head, wrapped) = match parse_header(&map) {
+            Some((existing, head, wrapped)) if existing == capacity => (head, wrapped),
+            _ => {
+                map[..8].copy_from_slice(MAGIC);
+                map[8..16].copy_from_slice(&(capacity as u64).to_le_bytes());
+                map[16..].fill(0);
+                (0, false)
+            }
+        };
+
+        Ok(Self {
+            encoder: Box::new(SafeEncoder::new(Box::new(StripAnsiEncoder::new(encoder)))),
+            state: Mutex::new(RingState { map, head, wrapped }),
+        })
+    }
+
+    /// Returns the records kept in the ring file at the given path, oldest first.
+    /// If the ring wrapped, the partially overwritten oldest line is skipped.
+    pub fn recover(path: impl AsRef<Path>) -> io::Result<Vec<u8>> {
+        let bytes = fs::read(path)?;
+        let Some((capacity, head, wrapped)) = parse_header(&bytes) else {
+            return Err(io::Error::new(ErrorKind::InvalidData, "Not a ring file"));
+        };
+        let data = bytes
+            .get(HEADER_LEN..HEADER_LEN + capacity)
+            .ok_or_else(|| io::Error::new(ErrorKind::UnexpectedEof, "Truncated ring file"))?;
+
+        if !wrapped {
+            return Ok(data[..head].to_vec());
+        }
+
+        let mut records = [&data[head..], &data[..head]].concat();
+        let start = records
+            .iter()
+            .position(|&byte| byte == b'\n')
+            .map_or(0, |index| index + 1);
+        records.drain(..start);
+        Ok(records)
+    }
+}
+
+impl Append for MmapRingAppender {
+    fn append(&self, record: &Record) -> anyhow::Result<()> {
+        let mut writer = SimpleWriter(Vec::new());
+        self.encoder.encode(&mut writer, record)?;
+        self.state.lock().write(&writer.0);
+        Ok(())
+    }
+
+    /// Writes the mapping back to the file, so the records survive a crash of the operating system as well.
+    fn flush(&self) {
+        let _ = self.state.lock().map.flush();
+    }
+}
+
+/// Returns the capacity, write position, and whether the ring wrapped, if the given bytes start with a valid header.
+fn parse_header(bytes: &[u8]) -> Option<(usize, usize, bool)> {
+    if bytes.get(..8)? != MAGIC {
+        return None;
+    }
+
+    let field = |range: std::ops::Range<usize>| {
+        Some(u64::from_le_bytes(bytes.get(range)?.try_into().ok()?))
+    };
+    let capacity = usize::try_from(field(8..16)?).ok()?;
+    let head = usize::try_from(field(16..24)?).ok()?;
+    let wrapped = field(24..32)? != 0;
+
+    (head < capacity.max(1)).then_some((capacity, head, wrapped))
+}
+
+#[cfg(test)]
+mod tests {
+    use lum_libs::{log::Level, log4rs::encode::pattern::PatternEncoder};
+
+    use super::*;
+    use crate::testing;
+
+    fn append(appender: &MmapRingAppender, message: &str) {
+        appender
+            .append(
+                &Record::builder()
+                    .level(Level::Info)
+                    .args(format_args!("{message}"))
+                    .build(),
+            )
+            .unwrap();
+    }
+
+    fn recovered(path: &Path) -> String {
+        String::from_utf8(MmapRingAppender::recover(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn the_newest_records_are_recovered_after_reopening_and_wrapping() {
+        let path = testing::temp_dir("mmap_ring").join("flight.ring");
+        let open = || MmapRingAppender::new(&path, 32, Box::new(PatternEncoder::new("{m}{n}")));
+
+        let appender = open().unwrap();
+        append(&appender, "Record 1");
+        append(&appender, "Record 2");
+        drop(appender);
+        assert_eq!(recovered(&path), "Record 1\nRecord 2\n");
+
+        // Writing continues after the records kept before, overwriting the oldest ones.
+        let appender = open().unwrap();
+        append(&appender, "Record 3");
+        append(&appender, "Record 4");
+        assert_eq!(recovered(&path), "Record 2\nRecord 3\nRecord 4\n");
+
+        assert!(MmapRingAppender::new(&path, 0, Box::new(PatternEncoder::new("{m}"))).is_err());
+    }
+}
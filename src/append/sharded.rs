@@ -0,0 +1,172 @@
+use std::io;
+
+use lum_libs::{
+    log::Record,
+    log4rs::append::Append,
+    serde_json::{self, Value},
+};
+
+use crate::json;
+
+/// An appender distributing records over a fixed number of shards by a hash of one of their fields, e.g. `tenant_id`,
+/// so high-volume multi-tenant services keep the records of each tenant together without configuring an appender per tenant.
+///
+/// The field is read from the MDC, or else from the top-level fields of the JSON payload attached by [`log_json!`](crate::log_json).
+/// The shard of a value is deterministic across processes and restarts, as long as the number of shards stays the same.
+/// Records without the field go to shard 0.
+///
+/// ```text
+/// let appender = ShardedAppender::new("tenant_id", 8, |shard| {
+///     FileAppender::builder()
+///         .encoder(Box::new(PatternEncoder::new(default::format())))
+///         .build(default::shard_file_path("logs/tenants.log", shard))
+/// })?;
+/// ```
+#[derive(Debug)]
+pub struct ShardedAppender {
+    field: String,
+    shards: Vec<Box<dyn Append>>,
+}
+
+impl ShardedAppender {
+    /// Creates a new `ShardedAppender` distributing records by the given field over the given number of shards, at least one.
+    /// The appender of each shard is created by calling the given function with the shard's index, from 0 upwards.
+    pub fn new<A: Append>(
+        field: impl Into<String>,
+        shards: usize,
+        mut open: impl FnMut(usize) -> io::Result<A>,
+    ) -> io::Result<Self> {
+        let shards = (0..shards.max(1))
+            .map(|shard| Ok(Box::new(open(shard)?) as Box<dyn Append>))
+            .collect::<io::Result<_>>()?;
+
+        Ok(Self {
+            field: field.into(),
+            shards,
+        })
+    }
+
+    /// Returns the field records are distributed by.
+    pub fn field(&self) -> &str {
+        &self.field
+    }
+
+    /// Returns the number of shards.
+    pub fn shards(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Returns the index of the shard records with the given value of the field go to.
+    pub fn shard_of(&self, value: &str) -> usize {
+        (fnv1a(value.as_bytes()) % self.shards.len() as u64) as usize
+    }
+
+    /// Returns the value of the field of the record currently being logged on this thread, if any.
+    fn current_value(&self) -> Option<String> {
+        if let Some(value) = log_mdc::get(&self.field, |value| value.map(str::to_string)) {
+            return Some(value);
+        }
+
+        let payload = json::current()?;
+        match serde_json::from_str::<Value>(&payload)
+            .ok()?
+            .get(&self.field)?
+        {
+            Value::String(value) => Some(value.clone()),
+            value => Some(value.to_string()),
+        }
+    }
+}
+
+impl Append for ShardedAppender {
+    fn append(&self, record: &Record) -> anyhow::Result<()> {
+        let shard = self
+            .current_value()
+            .map_or(0, |value| self.shard_of(&value));
+        self.shards[shard].append(record)
+    }
+
+    fn flush(&self) {
+        for shard in &self.shards {
+            shard.flush();
+        }
+    }
+}
+
+/// Returns the 64-bit FNV-1a hash of the given bytes, which unlike the standard library's hashers is stable across Rust versions.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    bytes.iter().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use lum_libs::log::Level;
+
+    use super::*;
+    use crate::append::ChannelAppender;
+
+    fn append(appender: &ShardedAppender, message: &str) {
+        appender
+            .append(
+                &Record::builder()
+                    .level(Level::Info)
+                    .target("sharded_test")
+                    .args(format_args!("{message}"))
+                    .build(),
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn records_go_to_the_shard_of_their_field_value() {
+        let mut receivers = Vec::new();
+        let appender = ShardedAppender::new("tenant_id", 4, |_| {
+            let (sender, receiver) = mpsc::channel();
+            receivers.push(receiver);
+            Ok(ChannelAppender::new(sender))
+        })
+        .unwrap();
+
+        {
+            let _tenant = log_mdc::insert_scoped("tenant_id", "acme");
+            append(&appender, "From the MDC");
+        }
+        {
+            let _json = json::scope(r#"{"tenant_id":42}"#);
+            append(&appender, "From the payload");
+        }
+        append(&appender, "Without a tenant");
+
+        let messages = receivers
+            .iter()
+            .map(|receiver| {
+                receiver
+                    .try_iter()
+                    .map(|record| record.message)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let mut expected = vec![Vec::new(); 4];
+        expected[appender.shard_of("acme")].push("From the MDC".to_string());
+        expected[appender.shard_of("42")].push("From the payload".to_string());
+        expected[0].push("Without a tenant".to_string());
+        assert_eq!(messages, expected);
+    }
+
+    #[test]
+    fn hashes_are_stable_and_there_is_at_least_one_shard() {
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+        let appender = ShardedAppender::new("tenant_id", 0, |_| {
+            Ok(ChannelAppender::new(mpsc::channel().0))
+        })
+        .unwrap();
+        assert_eq!(appender.shards(), 1);
+    }
+}
@@ -0,0 +1,496 @@
+use std::{
+    fmt::{self, Write},
+    fs::{self, File, OpenOptions},
+    io::{self, ErrorKind, Write as _},
+    path::Path,
+    slice,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use lum_libs::{
+    log::{Level, Record},
+    log4rs::append::Append,
+};
+use memmap2::MmapRaw;
+
+use crate::{default, record::OwnedRecord, stats};
+
+const MAGIC: &[u8; 8] = b"LUMSHM01";
+/// The header consists of the magic bytes, the slot size and count as `u32`s, the next sequence to write,
+/// the next sequence to read, and the number of dropped records, each 8 bytes, padded to a cache line.
+const HEADER_LEN: usize = 64;
+const WRITE_OFFSET: usize = 16;
+const READ_OFFSET: usize = 24;
+const DROPPED_OFFSET: usize = 32;
+/// Each slot starts with its commit marker, which is its sequence plus one once the record is written,
+/// followed by the timestamp in nanoseconds since the Unix epoch, the level, flags,
+/// and the lengths of the target and message as `u16`s, which follow the slot header.
+const SLOT_HEADER_LEN: usize = 24;
+const TRUNCATED_FLAG: u8 = 1;
+/// The smallest slot size, leaving room for a short target and message.
+const MIN_SLOT_SIZE: usize = 64;
+/// The largest slot size, so the lengths of the target and message fit into `u16`s.
+const MAX_SLOT_SIZE: usize = 65536;
+
+/// A ring of fixed-size slots in a shared memory mapping.
+#[derive(Debug)]
+struct Ring {
+    map: MmapRaw,
+    slot_size: usize,
+    slots: u64,
+}
+
+impl Ring {
+    fn open(file: &File) -> io::Result<Self> {
+        let map = MmapRaw::map_raw(file)?;
+        // SAFETY: The mapping is at least as long as the header, as checked by the callers.
+        let header = unsafe { slice::from_raw_parts(map.as_ptr(), HEADER_LEN) };
+        if &header[..8] != MAGIC {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                "Not a shared memory ring",
+            ));
+        }
+
+        let slot_size = u32::from_le_bytes([header[8], header[9], header[10], header[11]]) as usize;
+        let slots = u32::from_le_bytes([header[12], header[13], header[14], header[15]]) as usize;
+        if !(MIN_SLOT_SIZE..=MAX_SLOT_SIZE).contains(&slot_size)
+            || !slot_size.is_multiple_of(8)
+            || slots == 0
+            || map.len() < HEADER_LEN + slot_size * slots
+        {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                "Corrupt shared memory ring",
+            ));
+        }
+
+        Ok(Self {
+            map,
+            slot_size,
+            slots: slots as u64,
+        })
+    }
+
+    /// Returns the 8-byte aligned atomic at the given offset of the mapping.
+    fn atomic(&self, offset: usize) -> &AtomicU64 {
+        // SAFETY: The offset lies within the mapping and is a multiple of 8, and the mapping is page-aligned.
+        // The mapping lives as long as `self`, and its words are only accessed atomically.
+        unsafe { AtomicU64::from_ptr(self.map.as_mut_ptr().add(offset).cast()) }
+    }
+
+    fn slot_offset(&self, sequence: u64) -> usize {
+        HEADER_LEN + (sequence % self.slots) as usize * self.slot_size
+    }
+
+    /// Claims the slot for the next sequence, or returns `None` if the ring is full.
+    fn claim(&self) -> Option<u64> {
+        let write = self.atomic(WRITE_OFFSET);
+        let mut sequence = write.load(Ordering::Relaxed);
+        loop {
+            let read = self.atomic(READ_OFFSET).load(Ordering::Acquire);
+            if sequence.wrapping_sub(read) >= self.slots {
+                return None;
+            }
+
+            match write.compare_exchange_weak(
+                sequence,
+                sequence + 1,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => return Some(sequence),
+                Err(current) => sequence = current,
+            }
+        }
+    }
+}
+
+/// A builder for [`ShmRingAppender`]s.
+#[derive(Debug)]
+pub struct ShmRingAppenderBuilder {
+    slot_size: usize,
+    slots: usize,
+}
+
+impl Default for ShmRingAppenderBuilder {
+    /// Creates a `ShmRingAppenderBuilder` using [`default::shm_slot_size`] and [`default::shm_slots`].
+    fn default() -> Self {
+        Self {
+            slot_size: default::shm_slot_size(),
+            slots: default::shm_slots(),
+        }
+    }
+}
+
+impl ShmRingAppenderBuilder {
+    /// Sets the size of each slot in bytes, rounded up to a multiple of 8 and to at least 64, and at most 64 KiB.
+    /// 24 bytes of each slot are taken by the slot header, the rest holds the target and message.
+    pub fn slot_size(mut self, slot_size: usize) -> Self {
+        self.slot_size = slot_size;
+        self
+    }
+
+    /// Sets the number of slots, which is the number of records the ring holds before records are dropped.
+    pub fn slots(mut self, slots: usize) -> Self {
+        self.slots = slots;
+        self
+    }
+
+    /// Builds the [`ShmRingAppender`] writing to the file at the given path, e.g. in `/dev/shm` on Linux,
+    /// creating the file and its parent directories if needed. An existing ring in the file is discarded.
+    pub fn build(self, path: impl AsRef<Path>) -> io::Result<ShmRingAppender> {
+        let slot_size = self.slot_size.max(MIN_SLOT_SIZE).next_multiple_of(8);
+        if slot_size > MAX_SLOT_SIZE {
+            return Err(io::Error::new(
+                ErrorKind::InvalidInput,
+                "Slot size must not exceed 64 KiB",
+            ));
+        }
+        let Ok(slots_field) = u32::try_from(self.slots) else {
+            return Err(io::Error::new(ErrorKind::InvalidInput, "Too many slots"));
+        };
+        if self.slots == 0 {
+            return Err(io::Error::new(
+                ErrorKind::InvalidInput,
+                "Ring must have at least one slot",
+            ));
+        }
+
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        file.set_len((HEADER_LEN + slot_size * self.slots) as u64)?;
+
+        let mut header = [0; HEADER_LEN];
+        header[..8].copy_from_slice(MAGIC);
+        header[8..12].copy_from_slice(&(slot_size as u32).to_le_bytes());
+        header[12..16].copy_from_slice(&slots_field.to_le_bytes());
+        (&file).write_all(&header)?;
+
+        Ok(ShmRingAppender {
+            ring: Ring::open(&file)?,
+        })
+    }
+}
+
+/// An experimental appender writing records into a ring of fixed-size binary slots in a shared memory file,
+/// which an external process drains with a [`ShmRingReader`], for latency-critical processes
+/// where even buffered file I/O is too expensive.
+///
+/// Appending a record only claims a slot with an atomic operation and copies the timestamp, level, target, and message into it,
+/// without locking, encoding, allocating, or calling into the operating system.
+/// Targets and messages not fitting into a slot are truncated. When the reader falls behind and the ring is full,
+/// records are dropped and counted in the ring, so the reader sees them as well.
+/// A writer dying while writing a slot stalls the reader at that slot.
+///
+/// The file layout is a 64-byte header followed by the slots, all integers are little-endian:
+///
+/// ```text
+/// header: magic "LUMSHM01", slot size: u32, slot count: u32,
+///         next sequence to write: u64, next sequence to read: u64, dropped records: u64, padding
+/// slot:   commit marker (sequence + 1): u64, timestamp in nanoseconds since the Unix epoch: u64,
+///         level (1 = error to 5 = trace): u8, flags (1 = truncated): u8, target length: u16, message length: u16,
+///         padding: u16, target and message as UTF-8, padding
+/// ```
+#[derive(Debug)]
+pub struct ShmRingAppender {
+    ring: Ring,
+}
+
+impl ShmRingAppender {
+    /// Creates a new `ShmRingAppender` using the defaults of [`ShmRingAppenderBuilder`], see [`ShmRingAppenderBuilder::build`].
+    pub fn new(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::builder().build(path)
+    }
+
+    /// Creates a new [`ShmRingAppenderBuilder`].
+    pub fn builder() -> ShmRingAppenderBuilder {
+        ShmRingAppenderBuilder::default()
+    }
+
+    /// Returns the number of records dropped so far because the ring was full.
+    pub fn dropped(&self) -> u64 {
+        self.ring.atomic(DROPPED_OFFSET).load(Ordering::Relaxed)
+    }
+}
+
+impl Append for ShmRingAppender {
+    fn append(&self, record: &Record) -> anyhow::Result<()> {
+        let Some(sequence) = self.ring.claim() else {
+            self.ring
+                .atomic(DROPPED_OFFSET)
+                .fetch_add(1, Ordering::Relaxed);
+            stats::record_dropped();
+            return Ok(());
+        };
+
+        let offset = self.ring.slot_offset(sequence);
+        // SAFETY: The slot lies within the mapping after its 8-byte commit marker, and claiming it gave this thread
+        // exclusive access until the commit marker is set, as the reader only reads slots after their commit marker.
+        let slot = unsafe {
+            slice::from_raw_parts_mut(
+                self.ring.map.as_mut_ptr().add(offset + 8),
+                self.ring.slot_size - 8,
+            )
+        };
+        let (header, data) = slot.split_at_mut(SLOT_HEADER_LEN - 8);
+
+        let target = truncate(record.target(), data.len() / 2);
+        data[..target.len()].copy_from_slice(target.as_bytes());
+        let mut message = SlotWriter {
+            buffer: &mut data[target.len()..],
+            len: 0,
+            truncated: false,
+        };
+        let _ = message.write_fmt(*record.args());
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos() as u64;
+        let flags = if target.len() < record.target().len() || message.truncated {
+            TRUNCATED_FLAG
+        } else {
+            0
+        };
+        header[..8].copy_from_slice(&timestamp.to_le_bytes());
+        header[8] = record.level() as u8;
+        header[9] = flags;
+        header[10..12].copy_from_slice(&(target.len() as u16).to_le_bytes());
+        header[12..14].copy_from_slice(&(message.len as u16).to_le_bytes());
+
+        self.ring
+            .atomic(offset)
+            .store(sequence + 1, Ordering::Release);
+        Ok(())
+    }
+
+    fn flush(&self) {}
+}
+
+/// A reader draining the records written by a [`ShmRingAppender`], usually in another process.
+/// Only one reader may drain a ring at a time.
+#[derive(Debug)]
+pub struct ShmRingReader {
+    ring: Ring,
+}
+
+impl ShmRingReader {
+    /// Opens the ring in the file at the given path.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+        if file.metadata()?.len() < HEADER_LEN as u64 {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                "Not a shared memory ring",
+            ));
+        }
+
+        Ok(Self {
+            ring: Ring::open(&file)?,
+        })
+    }
+
+    /// Returns the next record and frees its slot, or `None` if no further record was written yet.
+    /// Truncated messages end with [`default::truncation_marker`].
+    /// Only the timestamp, level, target, and message of the returned record are set.
+    pub fn read(&mut self) -> Option<OwnedRecord> {
+        let read = self.ring.atomic(READ_OFFSET);
+        let sequence = read.load(Ordering::Relaxed);
+        let offset = self.ring.slot_offset(sequence);
+        if self.ring.atomic(offset).load(Ordering::Acquire) != sequence + 1 {
+            return None;
+        }
+
+        // SAFETY: The slot lies within the mapping, and its commit marker shows that its writer finished writing it.
+        // No writer claims it again before the read sequence is advanced below.
+        let slot = unsafe {
+            slice::from_raw_parts(
+                self.ring.map.as_ptr().add(offset + 8),
+                self.ring.slot_size - 8,
+            )
+        };
+        let (header, data) = slot.split_at(SLOT_HEADER_LEN - 8);
+        let field = |index: usize| u16::from_le_bytes([header[index], header[index + 1]]) as usize;
+        let target_len = field(10).min(data.len());
+        let message_len = field(12).min(data.len() - target_len);
+
+        let timestamp = u64::from_le_bytes(header[..8].try_into().unwrap_or_default());
+        let level = match header[8] {
+            1 => Level::Error,
+            2 => Level::Warn,
+            4 => Level::Debug,
+            5 => Level::Trace,
+            _ => Level::Info,
+        };
+        let mut message =
+            String::from_utf8_lossy(&data[target_len..target_len + message_len]).into_owned();
+        if header[9] & TRUNCATED_FLAG != 0 {
+            message.push_str(default::truncation_marker());
+        }
+        let record = OwnedRecord {
+            timestamp: UNIX_EPOCH + Duration::from_nanos(timestamp),
+            level,
+            target: String::from_utf8_lossy(&data[..target_len]).into_owned(),
+            message,
+            module_path: None,
+            file: None,
+            line: None,
+            thread: None,
+            event_id: None,
+            aux_level: None,
+            json: None,
+            level_name: None,
+            mdc: Vec::new(),
+        };
+
+        read.store(sequence + 1, Ordering::Release);
+        Some(record)
+    }
+
+    /// Returns the number of records the writer dropped so far because the ring was full.
+    pub fn dropped(&self) -> u64 {
+        self.ring.atomic(DROPPED_OFFSET).load(Ordering::Relaxed)
+    }
+}
+
+/// A [`fmt::Write`] filling a slot, stopping the formatting once the slot is full.
+struct SlotWriter<'a> {
+    buffer: &'a mut [u8],
+    len: usize,
+    truncated: bool,
+}
+
+impl Write for SlotWriter<'_> {
+    fn write_str(&mut self, string: &str) -> fmt::Result {
+        let available = self.buffer.len() - self.len;
+        let fitting = truncate(string, available);
+        self.buffer[self.len..self.len + fitting.len()].copy_from_slice(fitting.as_bytes());
+        self.len += fitting.len();
+
+        if fitting.len() < string.len() {
+            self.truncated = true;
+            return Err(fmt::Error);
+        }
+        Ok(())
+    }
+}
+
+/// Returns the longest prefix of the given string with at most the given number of bytes, ending at a character boundary.
+fn truncate(string: &str, max_len: usize) -> &str {
+    if string.len() <= max_len {
+        return string;
+    }
+
+    let mut end = max_len;
+    while !string.is_char_boundary(end) {
+        end -= 1;
+    }
+    &string[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn append(appender: &ShmRingAppender, level: Level, message: &str) {
+        appender
+            .append(
+                &Record::builder()
+                    .level(level)
+                    .target("shm_test")
+                    .args(format_args!("{message}"))
+                    .build(),
+            )
+            .unwrap();
+    }
+
+    fn read_all(reader: &mut ShmRingReader) -> Vec<(Level, String, String)> {
+        std::iter::from_fn(|| reader.read())
+            .map(|record| (record.level, record.target, record.message))
+            .collect()
+    }
+
+    #[test]
+    fn records_are_read_back_until_the_ring_is_full() {
+        let path = testing::temp_dir("shm_ring").join("app.ring");
+        // The slot size is rounded up to 64 bytes, leaving 32 bytes for the message after the header and target.
+        let appender = ShmRingAppender::builder()
+            .slot_size(60)
+            .slots(2)
+            .build(&path)
+            .unwrap();
+        let mut reader = ShmRingReader::open(&path).unwrap();
+
+        append(&appender, Level::Warn, "Short");
+        append(
+            &appender,
+            Level::Debug,
+            "A message longer than thirty-two bytes",
+        );
+        append(&appender, Level::Info, "Dropped");
+
+        assert_eq!(
+            read_all(&mut reader),
+            [
+                (Level::Warn, "shm_test".to_string(), "Short".to_string()),
+                (
+                    Level::Debug,
+                    "shm_test".to_string(),
+                    format!(
+                        "A message longer than thirty-two{}",
+                        default::truncation_marker()
+                    )
+                ),
+            ]
+        );
+        assert_eq!(appender.dropped(), 1);
+        assert_eq!(reader.dropped(), 1);
+
+        // Reading freed the slots for the next records.
+        append(&appender, Level::Error, "After");
+        assert_eq!(
+            read_all(&mut reader),
+            [(Level::Error, "shm_test".to_string(), "After".to_string())]
+        );
+    }
+
+    #[test]
+    fn invalid_rings_and_settings_are_rejected() {
+        let directory = testing::temp_dir("shm_invalid");
+        let path = directory.join("not_a_ring");
+        fs::write(&path, [0; HEADER_LEN]).unwrap();
+
+        assert_eq!(
+            ShmRingReader::open(&path).unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+        assert_eq!(
+            ShmRingAppender::builder()
+                .slot_size(MAX_SLOT_SIZE + 1)
+                .build(directory.join("large.ring"))
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidInput
+        );
+        assert_eq!(
+            ShmRingAppender::builder()
+                .slots(0)
+                .build(directory.join("empty.ring"))
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidInput
+        );
+    }
+}
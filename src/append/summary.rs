@@ -0,0 +1,173 @@
+use std::{
+    collections::BTreeMap,
+    io,
+    sync::{Arc, Weak},
+    thread,
+    time::Duration,
+};
+
+use lum_libs::{
+    humantime,
+    log::{self, Level, LevelFilter, Record},
+    log4rs::{
+        append::Append,
+        filter::{Filter, Response},
+    },
+    parking_lot::Mutex,
+};
+
+/// The target of the summary records logged by a [`SummaryAppender`].
+pub const INTERVAL_SUMMARY_TARGET: &str = "lum_log::interval_summary";
+
+type Counts = Mutex<BTreeMap<(Level, String), u64>>;
+
+/// An appender counting records per level and target, which logs one summary record per level and target
+/// with the counts since the last summary to [`INTERVAL_SUMMARY_TARGET`] at the end of every interval.
+/// Only records reaching the appender are counted, so the log levels must allow the summarized records.
+/// Combine it with a [`SummarizedFilter`] on the other appenders to replace noisy records with their summaries.
+#[derive(Debug)]
+pub struct SummaryAppender {
+    counts: Arc<Counts>,
+}
+
+impl SummaryAppender {
+    /// Creates a new `SummaryAppender` logging summaries in the given interval, spawning its background thread.
+    /// The thread stops once the appender is dropped.
+    pub fn new(interval: Duration) -> io::Result<Self> {
+        let counts = Arc::new(Counts::default());
+
+        let weak = Arc::downgrade(&counts);
+        thread::Builder::new()
+            .name("lum_log-summary".to_string())
+            .spawn(move || run(weak, interval))?;
+
+        Ok(Self { counts })
+    }
+}
+
+impl Append for SummaryAppender {
+    fn append(&self, record: &Record) -> anyhow::Result<()> {
+        if record.target() == INTERVAL_SUMMARY_TARGET {
+            return Ok(());
+        }
+
+        let key = (record.level(), record.target().to_string());
+        *self.counts.lock().entry(key).or_default() += 1;
+        Ok(())
+    }
+
+    fn flush(&self) {}
+}
+
+fn run(counts: Weak<Counts>, interval: Duration) {
+    loop {
+        thread::sleep(interval);
+        let Some(counts) = counts.upgrade() else {
+            return;
+        };
+
+        let summary = std::mem::take(&mut *counts.lock());
+        drop(counts);
+
+        for ((level, target), count) in summary {
+            log::info!(
+                target: INTERVAL_SUMMARY_TARGET,
+                "{level} {target}: {count} records in the last {}",
+                humantime::format_duration(interval)
+            );
+        }
+    }
+}
+
+/// A filter rejecting records of the given targets, and their children, at the given level or more verbose,
+/// e.g. to replace them with the summaries of a [`SummaryAppender`].
+#[derive(Debug, Clone)]
+pub struct SummarizedFilter {
+    targets: Vec<String>,
+    level: LevelFilter,
+}
+
+impl SummarizedFilter {
+    /// Creates a new `SummarizedFilter` rejecting records of the given targets at the given level or more verbose.
+    pub fn new<I, S>(targets: I, level: LevelFilter) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            targets: targets.into_iter().map(Into::into).collect(),
+            level,
+        }
+    }
+}
+
+impl Filter for SummarizedFilter {
+    fn filter(&self, record: &Record) -> Response {
+        if record.level() < self.level {
+            return Response::Neutral;
+        }
+
+        let target = record.target();
+        let summarized = self.targets.iter().any(|name| {
+            target
+                .strip_prefix(name.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+        });
+        match summarized {
+            true => Response::Reject,
+            false => Response::Neutral,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::*;
+    use crate::{ConfigBuilder, testing};
+
+    #[test]
+    fn summarized_records_are_replaced_by_their_counts() {
+        let _global = testing::GLOBAL.lock();
+        let records = testing::capture(
+            ConfigBuilder::new()
+                .root_log_level(LevelFilter::Debug)
+                .interval_summary(Duration::from_millis(100), ["summary_test::noisy"])
+                .unwrap(),
+        );
+
+        for _ in 0..3 {
+            log::debug!(target: "summary_test::noisy::poll", "Polled");
+        }
+        log::info!(target: "summary_test::noisy", "Connected");
+        log::debug!(target: "summary_test::quiet", "Kept");
+
+        // The records may be split across two intervals, so the counts of all summaries are added up.
+        let mut passed = Vec::new();
+        let mut summarized = 0;
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while summarized < 3 && Instant::now() < deadline {
+            let Ok(record) = records.recv_timeout(Duration::from_millis(100)) else {
+                continue;
+            };
+            match record
+                .message
+                .strip_prefix("DEBUG summary_test::noisy::poll: ")
+            {
+                Some(summary) => {
+                    summarized += summary
+                        .split(' ')
+                        .next()
+                        .and_then(|count| count.parse::<u64>().ok())
+                        .expect("The summary starts with the count");
+                }
+                None if record.target != INTERVAL_SUMMARY_TARGET => passed.push(record.message),
+                None => {}
+            }
+        }
+
+        assert_eq!(summarized, 3);
+        assert_eq!(passed, ["Connected", "Kept"]);
+    }
+}
@@ -0,0 +1,230 @@
+use std::{
+    fs::{self, File},
+    io,
+    path::Path,
+    time::{Duration, SystemTime},
+};
+
+use lum_libs::{humantime, log};
+pub use rusty_s3::Credentials;
+use rusty_s3::{Bucket, BucketError, S3Action, UrlStyle};
+use thiserror::Error;
+use url::Url;
+
+use crate::{internal, rotate};
+
+/// The target of the records logged by an [`S3Archiver`].
+pub const ARCHIVE_TARGET: &str = "lum_log::archive";
+
+/// Errors that can occur when archiving files to S3.
+#[derive(Debug, Error)]
+pub enum S3ArchiverError {
+    #[error("Invalid S3 endpoint: {0}")]
+    Endpoint(#[from] url::ParseError),
+
+    #[error("Invalid S3 bucket: {0}")]
+    Bucket(#[from] BucketError),
+
+    #[error("I/O error while reading the file to archive: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("Error while uploading to S3: {0}")]
+    Upload(#[from] ureq::Error),
+}
+
+/// Uploads rotated log files to an S3-compatible bucket.
+/// Register it with [`S3Archiver::register`] to archive every file rotated by a [`NotifyingRoller`](rotate::NotifyingRoller).
+///
+/// The object key is rendered from a template with the following placeholders:
+/// - `{hostname}`: the host name of this machine
+/// - `{date}`: the UTC date of the upload, e.g. `2024-11-12`
+/// - `{datetime}`: the UTC date and time of the upload without colons, e.g. `2024-11-12T211032Z`
+/// - `{file}`: the file name of the active log file, e.g. `app.log`
+#[derive(Debug, Clone)]
+pub struct S3Archiver {
+    bucket: Bucket,
+    credentials: Credentials,
+    key_template: String,
+    delete_after_upload: bool,
+    timeout: Duration,
+}
+
+impl S3Archiver {
+    /// Creates a new `S3Archiver` for the given bucket at the given endpoint, e.g. `https://s3.eu-central-1.amazonaws.com`.
+    /// Path-style URLs are used, as they are supported by all S3-compatible services.
+    pub fn new(
+        endpoint: &str,
+        region: impl Into<String>,
+        bucket: impl Into<String>,
+        credentials: Credentials,
+    ) -> Result<Self, S3ArchiverError> {
+        let endpoint = Url::parse(endpoint)?;
+        let bucket = Bucket::new(endpoint, UrlStyle::Path, bucket.into(), region.into())?;
+
+        Ok(Self {
+            bucket,
+            credentials,
+            key_template: "{hostname}/{date}/{datetime}-{file}".to_string(),
+            delete_after_upload: false,
+            timeout: Duration::from_secs(300),
+        })
+    }
+
+    /// Sets the template of the object key. Defaults to `{hostname}/{date}/{datetime}-{file}`.
+    pub fn key_template(mut self, key_template: impl Into<String>) -> Self {
+        self.key_template = key_template.into();
+        self
+    }
+
+    /// Sets whether archived files are deleted locally after a successful upload. Defaults to `false`.
+    pub fn delete_after_upload(mut self, delete_after_upload: bool) -> Self {
+        self.delete_after_upload = delete_after_upload;
+        self
+    }
+
+    /// Sets the timeout of a single upload. Defaults to 5 minutes.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Returns the object key for the given active log file at the current time.
+    pub fn key(&self, file: &Path) -> String {
+        let datetime = humantime::format_rfc3339_seconds(SystemTime::now()).to_string();
+        let file_name = file.file_name().unwrap_or_default().to_string_lossy();
+
+        self.key_template
+            .replace("{hostname}", &internal::hostname())
+            .replace("{date}", &datetime[..10])
+            .replace("{datetime}", &datetime.replace(':', ""))
+            .replace("{file}", &file_name)
+    }
+
+    /// Uploads the given file under the given key, deleting it afterwards if configured.
+    pub fn upload(&self, path: &Path, key: &str) -> Result<(), S3ArchiverError> {
+        let action = self.bucket.put_object(Some(&self.credentials), key);
+        let url = action.sign(self.timeout);
+
+        let file = File::open(path)?;
+        let agent = ureq::Agent::config_builder()
+            .timeout_global(Some(self.timeout))
+            .build()
+            .new_agent();
+        agent.put(url.as_str()).send(file)?;
+
+        if self.delete_after_upload {
+            fs::remove_file(path)?;
+        }
+
+        Ok(())
+    }
+
+    /// Registers this archiver with [`rotate::on_rotation`], uploading every rotated file.
+    /// Failed uploads are logged to [`ARCHIVE_TARGET`] and the file is kept.
+    pub fn register(self) {
+        rotate::on_rotation(move |rotation| {
+            let key = self.key(&rotation.file);
+            match self.upload(&rotation.rotated, &key) {
+                Ok(()) => log::info!(
+                    target: ARCHIVE_TARGET,
+                    "Archived {} as {key}",
+                    rotation.rotated.display()
+                ),
+                Err(error) => log::error!(
+                    target: ARCHIVE_TARGET,
+                    "Failed to archive {}: {error}",
+                    rotation.rotated.display()
+                ),
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        thread,
+    };
+
+    use super::*;
+    use crate::testing;
+
+    /// Accepts a single request, responds with `200 OK`, and returns the request line and the raw body.
+    fn serve_once(listener: TcpListener) -> thread::JoinHandle<(String, String)> {
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().expect("The archiver connects");
+            let mut reader = BufReader::new(stream);
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+
+            let mut content_length = None;
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).unwrap();
+                if header.trim().is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = header.split_once(':')
+                    && name.eq_ignore_ascii_case("content-length")
+                {
+                    content_length = value.trim().parse::<usize>().ok();
+                }
+            }
+
+            let mut body = Vec::new();
+            match content_length {
+                Some(length) => {
+                    body.resize(length, 0);
+                    reader.read_exact(&mut body).unwrap();
+                }
+                // A chunked body ends with an empty chunk.
+                None => {
+                    while !body.ends_with(b"0\r\n\r\n") {
+                        let mut line = Vec::new();
+                        reader.read_until(b'\n', &mut line).unwrap();
+                        body.extend_from_slice(&line);
+                    }
+                }
+            }
+
+            reader
+                .get_mut()
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n")
+                .unwrap();
+            (
+                request_line.trim_end().to_string(),
+                String::from_utf8_lossy(&body).to_string(),
+            )
+        })
+    }
+
+    #[test]
+    fn rotated_files_are_uploaded_under_the_rendered_key_and_deleted() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let server = serve_once(listener);
+        let path = testing::temp_dir("s3_archive").join("app.0.log");
+        fs::write(&path, "Rotated records\n").unwrap();
+        let archiver = S3Archiver::new(
+            &endpoint,
+            "eu-central-1",
+            "logs",
+            Credentials::new("key", "secret"),
+        )
+        .unwrap()
+        .key_template("{date}/{file}")
+        .delete_after_upload(true);
+
+        let key = archiver.key(Path::new("/var/log/app.log"));
+        archiver.upload(&path, &key).unwrap();
+
+        let (request_line, body) = server.join().unwrap();
+        assert_eq!(key.len(), "2024-11-12/app.log".len());
+        assert!(key.ends_with("/app.log"));
+        assert!(request_line.starts_with(&format!("PUT /logs/{key}?")));
+        assert!(body.contains("Rotated records\n"));
+        assert!(!path.exists());
+    }
+}
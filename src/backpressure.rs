@@ -0,0 +1,33 @@
+use std::{io, path::PathBuf};
+
+use lum_libs::serde::{Deserialize, Serialize};
+
+use crate::spool::Spool;
+
+/// What a buffering appender does with a new record when its buffer is full.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde", rename_all = "snake_case", tag = "strategy")]
+pub enum Backpressure {
+    /// Blocks the logging thread until there is room in the buffer. No records are lost.
+    Block,
+    /// Drops the new record and counts it.
+    DropNewest,
+    /// Drops the oldest buffered record to make room for the new one and counts it.
+    DropOldest,
+    /// Persists the new record to a [`Spool`] in the given directory, replaying it once the buffer has drained.
+    /// If spilling fails, the record is dropped and counted.
+    Spill { directory: PathBuf, max_bytes: u64 },
+}
+
+impl Backpressure {
+    /// Opens the [`Spool`] for [`Backpressure::Spill`], or returns `None` for all other strategies.
+    pub fn open_spool(&self) -> io::Result<Option<Spool>> {
+        match self {
+            Backpressure::Spill {
+                directory,
+                max_bytes,
+            } => Spool::open(directory, *max_bytes).map(Some),
+            _ => Ok(None),
+        }
+    }
+}
@@ -1,17 +1,53 @@
-use std::{collections::HashMap, io, path::Path};
+#[cfg(feature = "yaml")]
+use std::collections::BTreeMap;
+use std::{
+    collections::{HashMap, HashSet},
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use lum_libs::{
-    log::LevelFilter,
+    humantime,
+    log::{Level, LevelFilter, Record, SetLoggerError},
     log4rs::{
         Config,
-        append::Append,
+        append::{Append, console::Target},
         config::{Appender, Logger, Root, runtime::ConfigErrors},
-        filter::Filter,
+        encode::Encode,
+        filter::{Filter, Response, threshold::ThresholdFilter},
     },
 };
 use thiserror::Error;
 
-use crate::default;
+#[cfg(feature = "yaml")]
+use crate::log4rs_file::{Document, LoggerSpec, RootSpec};
+use crate::{
+    anomaly::{self, AnomalyDetection},
+    append::{
+        AlertAppender, AsyncAppender, BroadcastAppender, CallbackAppender, LazyAppender,
+        PartitionedFileAppender, SummaryAppender, partitioned::PartitionInterval,
+        summary::SummarizedFilter,
+    },
+    backpressure::Backpressure,
+    config::{self, ConsoleStream, Output, RollingPolicy},
+    console::{LineOverflow, TerminalWidthEncoder},
+    cost, default, dirs,
+    emergency::{self, EmergencyOutput},
+    encode::{self, LevelNameEncoder, PrettyJsonEncoder, StripAnsiEncoder},
+    health, heartbeat, internal,
+    latency::{self, LatencyTracker},
+    layer::{self, Layer},
+    level::{LevelNames, LevelStyle},
+    log4rs_file::{AppenderSpec, EncoderSpec},
+    logger,
+    memory::{self, MemoryPolicy},
+    profile::{self, Profile, Profiles},
+    route::{Route, RouteFilter, RouteRule},
+    stats, target,
+    timestamp::TimestampFormat,
+};
 
 /// Errors that can occur when building a configuration.
 /// By wrapping possible errors in this type, a user does not need to handle multiple error types when building a configuration.
@@ -22,26 +58,154 @@ pub enum ConfigBuilderError {
 
     #[error("Error while building log4rs configuration: {0}")]
     Log4rs(#[from] ConfigErrors),
+
+    #[error("I/O error while creating async appender: {0}")]
+    AsyncAppenderIo(io::Error),
+
+    #[error("I/O error while creating summary appender: {0}")]
+    SummaryAppenderIo(io::Error),
+
+    #[error("I/O error while starting heartbeat: {0}")]
+    HeartbeatIo(io::Error),
+
+    #[cfg(all(feature = "signals", unix))]
+    #[error("I/O error while installing signal handlers: {0}")]
+    SignalIo(io::Error),
+
+    #[error("No appender named {0} has been added")]
+    UnknownAppender(String),
+
+    #[error("No log directory could be determined for {0}")]
+    NoLogDirectory(String),
+
+    #[error("Error while setting the global logger: {0}")]
+    SetLogger(#[from] SetLoggerError),
+
+    /// A logger not set up by this crate, e.g. `env_logger`, is already the global logger, so it cannot be replaced.
+    /// The error of [`log::set_boxed_logger`](lum_libs::log::set_boxed_logger) is its source.
+    #[error("Another logger has already been set as the global logger")]
+    ForeignLoggerInstalled(#[source] SetLoggerError),
+}
+
+/// A logger entry added by [`ConfigBuilder::logger`].
+#[derive(Debug, Clone)]
+struct LoggerEntry {
+    level: LevelFilter,
+    appenders: Vec<String>,
+    additive: bool,
+}
+
+/// The format of the default appenders, selected by [`ConfigBuilder::json_format`] and [`ConfigBuilder::logfmt_format`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    Pattern,
+    Json,
+    Logfmt,
 }
 
 /// A simplified builder for log4rs configurations.
-/// Note that this supports adding appenders to the root logger only.
-#[derive(Debug)]
+/// Appenders are added to the root logger, unless they are assigned to a logger by [`ConfigBuilder::logger`].
+/// Cloning a `ConfigBuilder` shares its appenders and filters, so that multiple configurations can be built from it,
+/// e.g. to reconfigure the logger at runtime.
+/// Settings affecting the whole process, e.g. layers, redaction rules, and the heartbeat, take effect when the configuration is applied by [`ConfigBuilder::apply`].
+#[derive(Debug, Clone)]
 pub struct ConfigBuilder {
     root_log_level: LevelFilter,
     log_levels: HashMap<String, LevelFilter>,
-    appenders: HashMap<String, Box<dyn Append>>,
-    filters: HashMap<String, Vec<Box<dyn Filter>>>,
+    loggers: HashMap<String, LoggerEntry>,
+    deny_unknown_targets: bool,
+    allowed_targets: Vec<String>,
+    appenders: HashMap<String, Arc<dyn Append>>,
+    filters: HashMap<String, Vec<Arc<dyn Filter>>>,
+    routes: Vec<Route>,
+    level_names: Option<LevelNames>,
+    output_format: OutputFormat,
+    pattern: Option<String>,
+    colors: bool,
+    timestamp_format: Option<TimestampFormat>,
+    #[cfg(feature = "event-id")]
+    event_ids: bool,
+    emergency_output: EmergencyOutput,
+    shutdown_summary: bool,
+    heartbeat: Option<Duration>,
+    latency_budget: Option<Duration>,
+    anomaly_detection: Option<AnomalyDetection>,
+    cost_accounting: bool,
+    #[cfg(all(feature = "signals", unix))]
+    flush_on_signal: bool,
+    #[cfg(feature = "checksum")]
+    checksum_sidecars: bool,
+    strip_ansi: bool,
+    summarized: Option<Arc<SummarizedFilter>>,
+    memory_budget: Option<(usize, MemoryPolicy)>,
+    lazy_files: bool,
+    close_idle_files: Option<Duration>,
+    level_style: LevelStyle,
+    console_line_overflow: LineOverflow,
+    default_target_prefix: Option<String>,
+    #[cfg(feature = "redaction")]
+    redaction_rules: Option<crate::redaction::RedactionRules>,
+    layers: Vec<Arc<dyn Layer>>,
+    disabled_appenders: HashSet<String>,
+    async_appenders: HashSet<String>,
+    appender_specs: HashMap<String, AppenderSpec>,
+    outputs: HashMap<String, Output>,
+    profiles: HashMap<String, Profile>,
+    active_profile: Option<String>,
 }
 
 impl Default for ConfigBuilder {
-    /// Creates a default `ConfigBuilder`, using the root log level from [`default::log_level`], no log levels, no appenders, and no filters.
+    /// Creates a default `ConfigBuilder`, using the root log level from [`default::log_level`], with no log levels, loggers, appenders, filters, or routes.
+    /// All targets are allowed, no default target prefix is set, and there are no redaction rules, layers, or profiles.
+    /// Records are written with the pattern of [`default::format_with_style`], the default level names, and the timestamp of [`default::format`].
+    /// Only the level token is colored, console appenders are colored, and console lines are kept in full.
+    /// Event IDs, the shutdown summary, the heartbeat, the latency budget, anomaly detection, cost accounting, checksum sidecars, and the memory budget are disabled,
+    /// and the default emergency output is used.
+    /// File appenders create their files upfront and keep them open.
     fn default() -> Self {
         Self {
             root_log_level: default::log_level(),
             log_levels: HashMap::new(),
+            loggers: HashMap::new(),
+            deny_unknown_targets: false,
+            allowed_targets: Vec::new(),
             appenders: HashMap::new(),
             filters: HashMap::new(),
+            routes: Vec::new(),
+            level_names: None,
+            output_format: OutputFormat::Pattern,
+            pattern: None,
+            colors: true,
+            timestamp_format: None,
+            #[cfg(feature = "event-id")]
+            event_ids: false,
+            emergency_output: EmergencyOutput::default(),
+            shutdown_summary: false,
+            heartbeat: None,
+            latency_budget: None,
+            anomaly_detection: None,
+            cost_accounting: false,
+            #[cfg(all(feature = "signals", unix))]
+            flush_on_signal: false,
+            #[cfg(feature = "checksum")]
+            checksum_sidecars: false,
+            strip_ansi: true,
+            summarized: None,
+            memory_budget: None,
+            lazy_files: false,
+            close_idle_files: None,
+            level_style: LevelStyle::default(),
+            console_line_overflow: LineOverflow::default(),
+            default_target_prefix: None,
+            #[cfg(feature = "redaction")]
+            redaction_rules: None,
+            layers: Vec::new(),
+            disabled_appenders: HashSet::new(),
+            async_appenders: HashSet::new(),
+            appender_specs: HashMap::new(),
+            outputs: HashMap::new(),
+            profiles: HashMap::new(),
+            active_profile: None,
         }
     }
 }
@@ -52,6 +216,40 @@ impl ConfigBuilder {
         Self::default()
     }
 
+    /// Creates a `ConfigBuilder` from the given [`Config`](crate::config::Config), e.g. as loaded from a user's configuration file,
+    /// so it can be tweaked programmatically before it is applied.
+    /// The levels, level names, format, colors, and routes are taken over, and the outputs are added by [`ConfigBuilder::output`] in order,
+    /// creating the files of file outputs.
+    ///
+    /// ```text
+    /// let config = lum_log::config::Config::from_yaml(&std::fs::read_to_string("logging.yaml")?)?;
+    /// ConfigBuilder::from_config(&config)?
+    ///     .log_level("my_crate::db", LevelFilter::Trace)
+    ///     .event_ids(true)
+    ///     .apply()?;
+    /// ```
+    pub fn from_config(config: &config::Config) -> Result<Self, ConfigBuilderError> {
+        let mut builder = Self::new()
+            .root_log_level(config.level)
+            .level_style(config.level_style)
+            .colors(config.colors);
+        builder.log_levels = config.levels.clone();
+        builder.pattern = config.pattern.clone();
+        builder.routes = config.routes.clone();
+        builder.level_names =
+            (config.level_names != LevelNames::default()).then(|| config.level_names.clone());
+        builder.output_format = match config.format {
+            config::Format::Pattern => OutputFormat::Pattern,
+            config::Format::Json => OutputFormat::Json,
+            config::Format::Logfmt => OutputFormat::Logfmt,
+        };
+
+        config
+            .outputs
+            .iter()
+            .try_fold(builder, |builder, output| builder.output(output.clone()))
+    }
+
     /// Sets the log level of the root logger.
     pub fn root_log_level(mut self, level: LevelFilter) -> Self {
         self.root_log_level = level;
@@ -64,60 +262,1526 @@ impl ConfigBuilder {
         self
     }
 
+    /// Adds the same log level for each of the given first-party crates, like [`ConfigBuilder::log_level`].
+    /// Dashes in crate names are replaced by underscores, matching the module paths used as default targets.
+    pub fn workspace_level<I, S>(mut self, crates: I, level: LevelFilter) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        for name in crates {
+            self.log_levels
+                .insert(name.as_ref().replace('-', "_"), level);
+        }
+        self
+    }
+
+    /// Sets whether records of unknown targets are denied.
+    /// In this strict mode, only targets listed by [`ConfigBuilder::log_level`], [`ConfigBuilder::logger`],
+    /// or [`ConfigBuilder::allow_target`], and their children, are logged at all,
+    /// e.g. for security-sensitive builds that must not leak records of unaudited dependencies.
+    /// This includes the targets of lum_log itself, like [`ACCESS_TARGET`](crate::http::ACCESS_TARGET), so allow `lum_log` if needed.
+    pub fn deny_unknown_targets(mut self, enabled: bool) -> Self {
+        self.deny_unknown_targets = enabled;
+        self
+    }
+
+    /// Allows the given target and its children to be logged at the root log level
+    /// when unknown targets are denied by [`ConfigBuilder::deny_unknown_targets`].
+    /// Has no effect otherwise, or if the target has a log level or logger of its own.
+    pub fn allow_target(mut self, target: impl Into<String>) -> Self {
+        self.allowed_targets.push(target.into());
+        self
+    }
+
+    /// Adds a logger for the given target, writing to the given appenders.
+    /// Appenders assigned to a logger are no longer added to the root logger.
+    /// If `additive` is true, records are also passed to the appenders of the parent loggers.
+    /// This takes precedence over a log level set by [`ConfigBuilder::log_level`] for the same target.
+    pub fn logger<I, S>(
+        mut self,
+        target: impl Into<String>,
+        level: LevelFilter,
+        appenders: I,
+        additive: bool,
+    ) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let entry = LoggerEntry {
+            level,
+            appenders: appenders.into_iter().map(Into::into).collect(),
+            additive,
+        };
+        self.loggers.insert(target.into(), entry);
+        self
+    }
+
     /// Adds an appender to the configuration.
     pub fn appender(mut self, name: impl Into<String>, appender: Box<dyn Append>) -> Self {
-        self.appenders.insert(name.into(), appender);
+        let name = name.into();
+        self.appender_specs.remove(&name);
+        self.outputs.remove(&name);
+        self.appenders.insert(name, Arc::from(appender));
+        self
+    }
+
+    /// Invokes the given callback for each record at the given level or above,
+    /// e.g. to increment a metric or trip a health check on errors.
+    /// The callback is added as a [`CallbackAppender`] named "on_record.{n}".
+    pub fn on_record(
+        self,
+        level: Level,
+        callback: impl Fn(&Record) + Send + Sync + 'static,
+    ) -> Self {
+        let name = self.unused_appender_name("on_record");
+        self.appender(name, Box::new(CallbackAppender::new(level, callback)))
+    }
+
+    /// Adds the given [`AlertAppender`], e.g. to detect error storms.
+    /// The appender is named "alert.{n}".
+    pub fn alert(self, alert: AlertAppender) -> Self {
+        let name = self.unused_appender_name("alert");
+        self.appender(name, Box::new(alert))
+    }
+
+    /// Wraps the previously added appender with the given name in an [`AsyncAppender`],
+    /// using the given [`Backpressure`] strategy when its buffer is full.
+    /// This allows choosing per appender whether records may be dropped, e.g. for the console,
+    /// or whether logging must block, e.g. for an audit file.
+    pub fn asynchronous(
+        self,
+        name: impl Into<String>,
+        backpressure: Backpressure,
+    ) -> Result<Self, ConfigBuilderError> {
+        self.asynchronous_with_workers(name, backpressure, 1)
+    }
+
+    /// Like [`ConfigBuilder::asynchronous`], but passing records to the appender on the given number of dedicated worker threads,
+    /// so heavy sinks, e.g. compressing, encrypting, or sending records, keep up. See [`AsyncAppenderBuilder::workers`](crate::append::asynchronous::AsyncAppenderBuilder::workers).
+    pub fn asynchronous_with_workers(
+        self,
+        name: impl Into<String>,
+        backpressure: Backpressure,
+        workers: usize,
+    ) -> Result<Self, ConfigBuilderError> {
+        self.wrap_asynchronous(name, backpressure, workers, false)
+    }
+
+    /// Like [`ConfigBuilder::asynchronous_with_workers`], but guaranteeing that records reach the appender in the order they were emitted,
+    /// trading some throughput, e.g. for tests and replay tooling. See [`AsyncAppenderBuilder::ordered`](crate::append::asynchronous::AsyncAppenderBuilder::ordered).
+    pub fn asynchronous_ordered(
+        self,
+        name: impl Into<String>,
+        backpressure: Backpressure,
+        workers: usize,
+    ) -> Result<Self, ConfigBuilderError> {
+        self.wrap_asynchronous(name, backpressure, workers, true)
+    }
+
+    fn wrap_asynchronous(
+        mut self,
+        name: impl Into<String>,
+        backpressure: Backpressure,
+        workers: usize,
+        ordered: bool,
+    ) -> Result<Self, ConfigBuilderError> {
+        let name = name.into();
+        let Some(appender) = self.appenders.remove(&name) else {
+            return Err(ConfigBuilderError::UnknownAppender(name));
+        };
+
+        let appender = AsyncAppender::builder()
+            .backpressure(backpressure)
+            .workers(workers)
+            .ordered(ordered)
+            .build(Box::new(SharedAppender::new(appender, Some(&name))))
+            .map_err(ConfigBuilderError::AsyncAppenderIo)?;
+        self.async_appenders.insert(name.clone());
+        let spec = self.appender_specs.remove(&name);
+        let output = self.outputs.remove(&name);
+        let mut builder = self.appender(name.clone(), Box::new(appender));
+        builder
+            .appender_specs
+            .extend(spec.map(|spec| (name.clone(), spec)));
+        builder.outputs.extend(output.map(|output| (name, output)));
+        Ok(builder)
+    }
+
+    /// Adds a [`SummaryAppender`] as "interval_summary", logging summaries of the record counts per level and target in the given interval.
+    /// Debug and trace records of the given targets and their children are replaced by their summaries,
+    /// i.e. rejected by all other appenders by a [`SummarizedFilter`].
+    pub fn interval_summary<I, S>(
+        mut self,
+        interval: Duration,
+        replaced_targets: I,
+    ) -> Result<Self, ConfigBuilderError>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let appender =
+            SummaryAppender::new(interval).map_err(ConfigBuilderError::SummaryAppenderIo)?;
+        let filter = SummarizedFilter::new(replaced_targets, LevelFilter::Debug);
+        self.summarized = Some(Arc::new(filter));
+        Ok(self.appender("interval_summary", Box::new(appender)))
+    }
+
+    /// Sets the display names used to render log levels.
+    /// This affects the default appenders added by this builder after this call.
+    pub fn level_names(mut self, level_names: LevelNames) -> Self {
+        self.level_names = Some(level_names);
+        self
+    }
+
+    /// Sets whether the default appenders added by this builder after this call emit every record as a single line of JSON
+    /// with [`default::json_encoder`] instead of the pattern of [`default::format`], for log shippers like Loki that need machine-parseable output.
+    /// The JSON contains the timestamp, level, target, thread, message, source location, and the MDC as key-values.
+    /// Level styles, timestamp formats, and console line overflow do not apply to JSON output.
+    /// Enabling it disables [`ConfigBuilder::logfmt_format`].
+    pub fn json_format(self, enabled: bool) -> Self {
+        self.select_output_format(OutputFormat::Json, enabled)
+    }
+
+    /// Sets whether the default appenders added by this builder after this call write every record as a logfmt line of `key=value` pairs
+    /// with [`default::logfmt_encoder`] instead of the pattern of [`default::format`], for ingestion pipelines expecting logfmt, e.g. Grafana or Heroku-style drains.
+    /// Level styles, timestamp formats, and console line overflow do not apply to logfmt output.
+    /// Enabling it disables [`ConfigBuilder::json_format`].
+    pub fn logfmt_format(self, enabled: bool) -> Self {
+        self.select_output_format(OutputFormat::Logfmt, enabled)
+    }
+
+    /// Sets the timestamp format used by the default appenders added by this builder after this call,
+    /// rendering their format like [`default::format_with_timestamp`].
+    pub fn timestamp_format(mut self, timestamp_format: TimestampFormat) -> Self {
+        self.timestamp_format = Some(timestamp_format);
+        self
+    }
+
+    /// Sets which part of a rendered line the default appenders added by this builder after this call color by its level,
+    /// rendering their format like [`default::format_with_style`].
+    pub fn level_style(mut self, level_style: LevelStyle) -> Self {
+        self.level_style = level_style;
+        self
+    }
+
+    /// Sets the pattern of the default appenders added by this builder after this call, in the syntax of log4rs' pattern encoder,
+    /// replacing the pattern of [`default::format_with_style`] for the configured timestamp format and level style.
+    pub fn pattern(mut self, pattern: impl Into<String>) -> Self {
+        self.pattern = Some(pattern.into());
+        self
+    }
+
+    /// Sets whether the console appenders added by this builder after this call are colored. Enabled by default.
+    /// Without colors, their output is stripped like that of file appenders, see [`StripAnsiEncoder`].
+    pub fn colors(mut self, enabled: bool) -> Self {
+        self.colors = enabled;
+        self
+    }
+
+    /// Sets how the console appenders added by this builder after this call render lines wider than the terminal,
+    /// by wrapping their encoders in a [`TerminalWidthEncoder`]. File and network appenders always get full lines.
+    pub fn console_line_overflow(mut self, overflow: LineOverflow) -> Self {
+        self.console_line_overflow = overflow;
+        self
+    }
+
+    /// Sets the prefix prepended to the target of records logged without an explicit target, see [`target::set_default_target_prefix`]. Rules and loggers match the prefixed targets.
+    pub fn default_target_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.default_target_prefix = Some(prefix.into());
+        self
+    }
+
+    /// Sets the rules removing sensitive data from the message of every record, see [`redaction::set_redaction_rules`](crate::redaction::set_redaction_rules) and [`redaction::check`](crate::redaction::check).
+    #[cfg(feature = "redaction")]
+    pub fn redaction_rules(mut self, rules: crate::redaction::RedactionRules) -> Self {
+        self.redaction_rules = Some(rules);
+        self
+    }
+
+    /// Adds the given [`Layer`] to the end of the pipeline every record passes before it reaches the appenders, see [`layer::set_layers`].
+    pub fn layer(mut self, layer: impl Layer) -> Self {
+        self.layers.push(Arc::new(layer));
+        self
+    }
+
+    /// Sets whether a unique event ID is attached to every record, see [`event::set_event_ids`](crate::event::set_event_ids).
+    #[cfg(feature = "event-id")]
+    pub fn event_ids(mut self, enabled: bool) -> Self {
+        self.event_ids = enabled;
+        self
+    }
+
+    /// Sets where error records are written when all appenders failed to append them, see [`emergency::set_emergency_output`].
+    pub fn emergency_output(mut self, output: EmergencyOutput) -> Self {
+        self.emergency_output = output;
+        self
+    }
+
+    /// Sets whether [`logger::shutdown`] logs a summary of the logger's statistics, see [`stats::set_shutdown_summary`].
+    pub fn shutdown_summary(mut self, enabled: bool) -> Self {
+        self.shutdown_summary = enabled;
+        self
+    }
+
+    /// Sets the interval in which a heartbeat record is logged, see [`heartbeat::set_heartbeat`].
+    pub fn heartbeat(mut self, interval: Duration) -> Self {
+        self.heartbeat = Some(interval);
+        self
+    }
+
+    /// Sets whether the records logged and bytes written are counted per target, see [`cost::set_cost_accounting`].
+    pub fn cost_accounting(mut self, enabled: bool) -> Self {
+        self.cost_accounting = enabled;
+        self
+    }
+
+    /// Sets whether the logger is flushed and shut down when the process receives SIGINT or SIGTERM, see [`signal::flush_on_signal`](crate::signal::flush_on_signal).
+    #[cfg(all(feature = "signals", unix))]
+    pub fn flush_on_signal(mut self, enabled: bool) -> Self {
+        self.flush_on_signal = enabled;
+        self
+    }
+
+    /// Sets whether a `.sha256` sidecar is written next to every rotated or closed log file, see [`checksum::set_checksum_sidecars`](crate::checksum::set_checksum_sidecars).
+    #[cfg(feature = "checksum")]
+    pub fn checksum_sidecars(mut self, enabled: bool) -> Self {
+        self.checksum_sidecars = enabled;
         self
     }
 
+    /// Sets the time appending a record may take before appenders are reported as slow, see [`latency::set_latency_budget`].
+    pub fn latency_budget(mut self, budget: Duration) -> Self {
+        self.latency_budget = Some(budget);
+        self
+    }
+
+    /// Sets the settings of the detector warning about targets whose record rate jumps far above their baseline, see [`anomaly::set_anomaly_detection`].
+    pub fn anomaly_detection(mut self, detection: AnomalyDetection) -> Self {
+        self.anomaly_detection = Some(detection);
+        self
+    }
+
+    /// Sets whether ANSI escape sequences are removed from the output of file and network appenders, see [`encode::set_strip_ansi`].
+    pub fn strip_ansi(mut self, enabled: bool) -> Self {
+        self.strip_ansi = enabled;
+        self
+    }
+
+    /// Caps the memory used by internal buffers at the given number of bytes with the given policy, see [`memory::set_memory_budget`] and [`memory::set_memory_policy`].
+    pub fn memory_budget(mut self, bytes: usize, policy: MemoryPolicy) -> Self {
+        self.memory_budget = Some((bytes, policy));
+        self
+    }
+
+    /// Sets whether the file appenders added by this builder after this call create their files on the first record instead of upfront,
+    /// by wrapping them in a [`LazyAppender`]. This keeps tools that often run without logging anything from leaving empty log files behind.
+    pub fn lazy_files(mut self, enabled: bool) -> Self {
+        self.lazy_files = enabled;
+        self
+    }
+
+    /// Sets an idle timeout after which the file appenders added by this builder after this call close their files,
+    /// by wrapping them in a [`LazyAppender`] with [`LazyAppender::with_idle_timeout`].
+    /// The files are transparently reopened on the next record,
+    /// which plays nicely with network filesystems and allows unmounting volumes, e.g. on embedded devices.
+    pub fn close_idle_files(mut self, idle_timeout: Duration) -> Self {
+        self.close_idle_files = Some(idle_timeout);
+        self
+    }
+
+    /// Adds the appender described by the given [`Output`] under its name, with a threshold filter if it has a level.
+    /// Its encoder renders levels and auxiliary levels with the configured [`LevelNames`], like [`default::level_name_encoder`].
+    pub fn output(self, output: Output) -> Result<Self, ConfigBuilderError> {
+        let name = output.name();
+        let encoder_spec = self.encoder_spec();
+        let (appender, spec): (Box<dyn Append>, _) = match &output {
+            Output::Console { stream, .. } => {
+                let encoder = self.console_encoder(self.default_encoder());
+                let (target, spec) = match stream {
+                    ConsoleStream::Stdout => {
+                        (Target::Stdout, encoder_spec.map(AppenderSpec::console))
+                    }
+                    ConsoleStream::Stderr => (
+                        Target::Stderr,
+                        encoder_spec.map(|encoder| AppenderSpec::console(encoder).stderr()),
+                    ),
+                };
+                let appender = default::console_appender_with_target(encoder, target);
+                (Box::new(appender), spec)
+            }
+            Output::File {
+                path,
+                rolling: Some(rolling),
+                ..
+            } => {
+                let trigger = rolling.time_trigger_config();
+                let pattern = rolling.roller_pattern(path);
+                let count = rolling.keep;
+                let spec = encoder_spec.map(|encoder| {
+                    AppenderSpec::rolling_file_with_policy(
+                        path,
+                        encoder,
+                        rolling.interval.log4rs_interval(),
+                        &pattern,
+                        count,
+                    )
+                });
+                let appender = self.file_appender(path, move |path, encoder| {
+                    default::rolling_file_appender_with_policy(
+                        path, encoder, trigger, &pattern, count,
+                    )
+                })?;
+                (appender, spec)
+            }
+            Output::File {
+                path,
+                rolling: None,
+                ..
+            } => {
+                let spec = encoder_spec.map(|encoder| AppenderSpec::file(path, encoder));
+                let appender = self.file_appender(path, default::file_appender_with_encoder)?;
+                (appender, spec)
+            }
+        };
+
+        let spec = match output.level() {
+            Some(level) => spec.map(|spec| spec.threshold(level)),
+            None => spec,
+        };
+        let mut builder = self.appender(name.clone(), appender);
+        if let Some(level) = output.level() {
+            builder = builder.filter(name.clone(), Box::new(ThresholdFilter::new(level)));
+        }
+        Ok(builder
+            .appender_spec(&name, spec)
+            .output_config(&name, output))
+    }
+
     /// Adds [`default::console_appender`] as "stdout".
+    /// Its encoder renders levels and auxiliary levels with the configured [`LevelNames`], like [`default::level_name_encoder`].
     pub fn stdout_console_appender(self) -> Self {
-        let console_appender = default::console_appender();
+        let encoder = self.console_encoder(self.default_encoder());
+        let console_appender = default::console_appender_with_encoder(encoder);
+        let spec = self.encoder_spec().map(AppenderSpec::console);
+        self.appender("stdout", Box::new(console_appender))
+            .appender_spec("stdout", spec)
+            .output_config("stdout", Output::console())
+    }
+
+    /// Adds a console appender as "stdout", rendering records as colored JSON blocks with a [`PrettyJsonEncoder`] for local development.
+    /// Use it instead of [`ConfigBuilder::stdout_console_appender`].
+    pub fn pretty_json_console_appender(self) -> Self {
+        let encoder = self.console_encoder(Box::new(
+            PrettyJsonEncoder::new().level_names(self.level_names.clone().unwrap_or_default()),
+        ));
+        let console_appender = default::console_appender_with_encoder(encoder);
         self.appender("stdout", Box::new(console_appender))
     }
 
+    /// Adds a [`BroadcastAppender`] as "broadcast", enabling [`crate::subscribe()`].
+    pub fn broadcast_appender(self) -> Self {
+        self.appender("broadcast", Box::new(BroadcastAppender::new()))
+    }
+
     /// Adds [`default::rolling_file_appender`] as "file".
+    /// Its encoder renders levels and auxiliary levels with the configured [`LevelNames`], like [`default::level_name_encoder`].
     pub fn file_rolling_appender(self, path: impl AsRef<Path>) -> Result<Self, ConfigBuilderError> {
-        let rolling_file_appender = default::rolling_file_appender(path)?;
-        Ok(self.appender("file", Box::new(rolling_file_appender)))
+        let path = path.as_ref();
+        let rolling_file_appender =
+            self.file_appender(path, default::rolling_file_appender_with_encoder)?;
+        let spec = self
+            .encoder_spec()
+            .map(|encoder| AppenderSpec::rolling_file(path, encoder, "{}.log"));
+        let output = Output::file(
+            path,
+            Some(RollingPolicy {
+                pattern: Some("{}.log".to_string()),
+                ..RollingPolicy::default()
+            }),
+        );
+        Ok(self
+            .appender("file", rolling_file_appender)
+            .appender_spec("file", spec)
+            .output_config("file", output))
+    }
+
+    /// Adds [`default::rolling_file_appender`] as "file", writing to the platform-appropriate [`dirs::log_file`] of the given application,
+    /// so desktop and CLI applications do not need to hardcode paths.
+    pub fn platform_file_rolling_appender(
+        self,
+        app_name: &str,
+    ) -> Result<Self, ConfigBuilderError> {
+        let path = dirs::log_file(app_name)
+            .ok_or_else(|| ConfigBuilderError::NoLogDirectory(app_name.to_string()))?;
+        self.file_rolling_appender(path)
+    }
+
+    /// Adds [`default::rolling_file_appender`] as "file"
+    /// and [`default::errors_rolling_file_appender`] as "errors_file".
+    /// The latter only receives records at [`default::errors_log_level`] or above.
+    /// Their encoders render levels and auxiliary levels with the configured [`LevelNames`], like [`default::level_name_encoder`].
+    pub fn file_rolling_appender_with_errors(
+        self,
+        path: impl AsRef<Path>,
+    ) -> Result<Self, ConfigBuilderError> {
+        let path = path.as_ref();
+        let errors_rolling_file_appender =
+            self.file_appender(path, default::errors_rolling_file_appender_with_encoder)?;
+
+        let spec = self.encoder_spec().map(|encoder| {
+            AppenderSpec::rolling_file(default::errors_file_path(path), encoder, "{}.errors.log")
+                .threshold(default::errors_log_level())
+        });
+
+        Ok(self
+            .file_rolling_appender(path)?
+            .appender("errors_file", errors_rolling_file_appender)
+            .filter(
+                "errors_file",
+                Box::new(ThresholdFilter::new(default::errors_log_level())),
+            )
+            .appender_spec("errors_file", spec)
+            .output_config(
+                "errors_file",
+                Output::File {
+                    name: Some("errors_file".to_string()),
+                    path: default::errors_file_path(path),
+                    rolling: Some(RollingPolicy {
+                        pattern: Some("{}.errors.log".to_string()),
+                        ..RollingPolicy::default()
+                    }),
+                    level: Some(default::errors_log_level()),
+                },
+            ))
+    }
+
+    /// Adds a [`PartitionedFileAppender`] as "file", writing each window of the given interval to its own file derived from the given path,
+    /// e.g. `logs/app-2025-01-30-14.log` for `logs/app.log` and hourly windows. Use it instead of [`ConfigBuilder::file_rolling_appender`].
+    /// Its encoder renders levels and auxiliary levels with the configured [`LevelNames`], like [`default::level_name_encoder`].
+    pub fn file_partitioned_appender(
+        self,
+        path: impl AsRef<Path>,
+        interval: PartitionInterval,
+    ) -> Result<Self, ConfigBuilderError> {
+        let partitioned_file_appender = self.file_appender(path, move |path, encoder| {
+            PartitionedFileAppender::new(path, interval, encoder)
+        })?;
+        Ok(self.appender("file", partitioned_file_appender))
+    }
+
+    /// Adds the given profiles, which can be switched at runtime by [`profile::activate_profile`].
+    /// If the profiles name an active one, it becomes active when the configuration is applied by [`ConfigBuilder::apply`].
+    pub fn profiles(mut self, profiles: Profiles) -> Self {
+        self.profiles.extend(profiles.profiles);
+        if profiles.active.is_some() {
+            self.active_profile = profiles.active;
+        }
+        self
+    }
+
+    /// Returns the profile with the given name, if it has been added.
+    pub fn profile(&self, name: &str) -> Option<&Profile> {
+        self.profiles.get(name)
     }
 
     /// Adds a filter to the configuration.
     pub fn filter(mut self, name: impl Into<String>, filter: Box<dyn Filter>) -> Self {
-        self.filters.entry(name.into()).or_default().push(filter);
+        self.filters
+            .entry(name.into())
+            .or_default()
+            .push(Arc::from(filter));
+        self
+    }
+
+    /// Routes records whose target matches the given rule to the given appender.
+    /// See [`Route`] for details.
+    pub fn route(self, rule: RouteRule, appender: impl Into<String>) -> Self {
+        self.routes([Route::new(rule, appender)])
+    }
+
+    /// Routes records whose target matches the given rule exclusively to the given appender.
+    /// See [`Route`] for details.
+    pub fn exclusive_route(self, rule: RouteRule, appender: impl Into<String>) -> Self {
+        self.routes([Route::exclusive(rule, appender)])
+    }
+
+    /// Adds the given routes to the configuration, e.g. as deserialized from a configuration file.
+    pub fn routes(mut self, routes: impl IntoIterator<Item = Route>) -> Self {
+        self.routes.extend(routes);
+        self
+    }
+
+    /// Guards the given appenders with the given [`DiskGuard`](crate::disk::DiskGuard) by adding its filter to them.
+    /// Start the watchdog with [`DiskGuard::spawn`](crate::disk::DiskGuard::spawn).
+    #[cfg(feature = "disk-guard")]
+    pub fn disk_guard<I, S>(mut self, guard: &crate::disk::DiskGuard, appenders: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        for appender in appenders {
+            self = self.filter(appender, Box::new(guard.filter()));
+        }
         self
     }
 
     /// Builds the [`Config`] from the provided settings.
-    pub fn build(mut self) -> Result<Config, ConfigBuilderError> {
+    pub fn build(self) -> Result<Config, ConfigBuilderError> {
+        Ok(self.build_config()?)
+    }
+
+    /// Builds the [`Config`] from the provided settings and sets up the logger with it, like [`logger::setup`].
+    /// The builder is kept, so the configuration can be rebuilt at runtime, e.g. by [`crate::verbosity::verbose_scope`].
+    ///
+    /// Applying a builder after the logger has been set up by this crate replaces the existing configuration atomically,
+    /// after flushing the appenders of the existing one.
+    /// If a logger not set up by this crate, e.g. `env_logger`, is already the global logger,
+    /// [`ConfigBuilderError::ForeignLoggerInstalled`] is returned.
+    /// If the heartbeat thread or the signal handlers cannot be started, all other settings are still applied before the error is returned.
+    pub fn apply(self) -> Result<(), ConfigBuilderError> {
+        #[cfg(feature = "event-id")]
+        let event_ids = self.event_ids;
+        let emergency_output = self.emergency_output.clone();
+        let shutdown_summary = self.shutdown_summary;
+        let heartbeat = self.heartbeat;
+        let latency_budget = self.latency_budget;
+        let anomaly_detection = self.anomaly_detection.clone();
+        let cost_accounting = self.cost_accounting;
+        #[cfg(all(feature = "signals", unix))]
+        let flush_on_signal = self.flush_on_signal;
+        #[cfg(feature = "checksum")]
+        let checksum_sidecars = self.checksum_sidecars;
+        let strip_ansi = self.strip_ansi;
+        let memory_budget = self.memory_budget;
+        let default_target_prefix = self.default_target_prefix.clone();
+        #[cfg(feature = "redaction")]
+        let redaction_rules = self.redaction_rules.clone();
+        let layers = self.layers.clone();
+        if let Some(active_profile) = &self.active_profile {
+            profile::set_active(Some(active_profile.clone()));
+        }
+        // Records logged by other threads once the configuration is installed must already be redacted, pass the layers,
+        // and have their targets prefixed, as the loggers and routing rules of the configuration match the prefixed targets.
+        #[cfg(feature = "redaction")]
+        crate::redaction::set_redaction_rules(redaction_rules);
+        target::set_default_target_prefix(default_target_prefix);
+        layer::set_layers(layers);
+        logger::setup_builder(self)?;
+        #[cfg(feature = "event-id")]
+        crate::event::set_event_ids(event_ids);
+        emergency::set_emergency_output(emergency_output);
+        stats::set_shutdown_summary(shutdown_summary);
+        let heartbeat_started =
+            heartbeat::set_heartbeat(heartbeat).map_err(ConfigBuilderError::HeartbeatIo);
+        latency::set_latency_budget(latency_budget);
+        anomaly::set_anomaly_detection(anomaly_detection);
+        cost::set_cost_accounting(cost_accounting);
+        #[cfg(all(feature = "signals", unix))]
+        let signal_handlers_installed = match flush_on_signal {
+            true => crate::signal::flush_on_signal().map_err(ConfigBuilderError::SignalIo),
+            false => Ok(()),
+        };
+        #[cfg(feature = "checksum")]
+        crate::checksum::set_checksum_sidecars(checksum_sidecars);
+        encode::set_strip_ansi(strip_ansi);
+        memory::set_memory_budget(memory_budget.map(|(bytes, _)| bytes));
+        if let Some((_, policy)) = memory_budget {
+            memory::set_memory_policy(policy);
+        }
+
+        heartbeat_started?;
+        #[cfg(all(feature = "signals", unix))]
+        signal_handlers_installed?;
+        Ok(())
+    }
+
+    /// Returns a human-readable description of what this builder configures, without applying it,
+    /// to help debugging a logging setup: levels per target, loggers, appenders with their outputs, formats, and rotation,
+    /// filters, routes, profiles, and the global settings applied by [`ConfigBuilder::apply`].
+    /// Appenders are described by their [`Debug`](std::fmt::Debug) output. Names are sorted.
+    /// Note that file appenders create their files when they are added, unless [`ConfigBuilder::lazy_files`] is enabled.
+    pub fn explain(&self) -> String {
+        let mut lines = Vec::new();
+        let mut push = |line: String| lines.push(line);
+        fn sorted<V>(map: &HashMap<String, V>) -> Vec<&String> {
+            let mut names = map.keys().collect::<Vec<_>>();
+            names.sort();
+            names
+        }
+
+        match self.deny_unknown_targets {
+            true => push(format!(
+                "Root level: {} (unknown targets denied, allowed: {})",
+                self.root_log_level,
+                self.allowed_targets.join(", ")
+            )),
+            false => push(format!("Root level: {}", self.root_log_level)),
+        }
+        if let Some(prefix) = &self.default_target_prefix {
+            push(format!("Default target prefix: {prefix}"));
+        }
+
+        push("Log levels:".to_string());
+        for name in sorted(&self.log_levels) {
+            push(format!("  {name} = {}", self.log_levels[name]));
+        }
+
+        push("Loggers:".to_string());
+        for name in sorted(&self.loggers) {
+            let logger = &self.loggers[name];
+            push(format!(
+                "  {name} = {} -> [{}]{}",
+                logger.level,
+                logger.appenders.join(", "),
+                if logger.additive { " (additive)" } else { "" }
+            ));
+        }
+
+        push("Appenders:".to_string());
+        for name in sorted(&self.appenders) {
+            push(format!("  {name}: {:?}", self.appenders[name]));
+            for filter in self.filters.get(name).into_iter().flatten() {
+                push(format!("    filter: {filter:?}"));
+            }
+            for route in self.routes.iter().filter(|route| &route.appender == name) {
+                let kind = if route.exclusive {
+                    "exclusive route"
+                } else {
+                    "route"
+                };
+                push(format!("    {kind}: {:?}", route.rule));
+            }
+        }
+
+        push("Profiles:".to_string());
+        for name in sorted(&self.profiles) {
+            let active = if self.active_profile.as_ref() == Some(name) {
+                " (active)"
+            } else {
+                ""
+            };
+            push(format!("  {name}{active}: {:?}", self.profiles[name]));
+        }
+
+        match self.output_format {
+            OutputFormat::Pattern => push(format!("Default format: {}", self.default_format())),
+            OutputFormat::Json => push("Default format: JSON".to_string()),
+            OutputFormat::Logfmt => push("Default format: logfmt".to_string()),
+        }
+        push(format!(
+            "Level names: {:?}",
+            self.level_names.clone().unwrap_or_default()
+        ));
+        push(format!(
+            "Console line overflow: {:?}",
+            self.console_line_overflow
+        ));
+        push(format!("Console colors: {}", self.colors));
+        push(format!(
+            "File appenders: {}, {}",
+            if self.lazy_files {
+                "created on the first record"
+            } else {
+                "created upfront"
+            },
+            match self.close_idle_files {
+                Some(timeout) =>
+                    format!("closed after {} idle", humantime::format_duration(timeout)),
+                None => "kept open".to_string(),
+            }
+        ));
+        #[cfg(feature = "redaction")]
+        push(format!(
+            "Redaction rules: {}",
+            match &self.redaction_rules {
+                Some(rules) => rules
+                    .rules()
+                    .map(|rule| format!("{} ({})", rule.name, rule.pattern))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                None => "none".to_string(),
+            }
+        ));
+        match self.layers.is_empty() {
+            true => push("Layers: none".to_string()),
+            false => {
+                push("Layers:".to_string());
+                for layer in &self.layers {
+                    push(format!("  {layer:?}"));
+                }
+            }
+        }
+        #[cfg(feature = "event-id")]
+        push(format!("Event IDs: {}", self.event_ids));
+        push(format!("Strip ANSI: {}", self.strip_ansi));
+        push(format!("Shutdown summary: {}", self.shutdown_summary));
+        push(format!(
+            "Latency budget: {}",
+            match self.latency_budget {
+                Some(budget) => humantime::format_duration(budget).to_string(),
+                None => "none".to_string(),
+            }
+        ));
+        push(format!(
+            "Anomaly detection: {}",
+            match &self.anomaly_detection {
+                Some(detection) => format!("{detection:?}"),
+                None => "none".to_string(),
+            }
+        ));
+        push(format!("Cost accounting: {}", self.cost_accounting));
+        #[cfg(all(feature = "signals", unix))]
+        push(format!("Flush on signal: {}", self.flush_on_signal));
+        #[cfg(feature = "checksum")]
+        push(format!("Checksum sidecars: {}", self.checksum_sidecars));
+        push(format!(
+            "Heartbeat: {}",
+            match self.heartbeat {
+                Some(interval) => format!("every {}", humantime::format_duration(interval)),
+                None => "none".to_string(),
+            }
+        ));
+        push(format!("Emergency output: {:?}", self.emergency_output));
+        push(format!(
+            "Memory budget: {}",
+            match self.memory_budget {
+                Some((bytes, policy)) => format!("{bytes} bytes, {policy:?}"),
+                None => "none".to_string(),
+            }
+        ));
+
+        lines.join("\n")
+    }
+
+    /// Returns the configuration of this builder as a log4rs YAML configuration file, see [`log4rs_file::export_log4rs_yaml`](crate::log4rs_file::export_log4rs_yaml).
+    /// Levels and loggers are exported completely. Of the appenders, only those added by [`ConfigBuilder::stdout_console_appender`]
+    /// and the file rolling appender methods are exported, with their encoder, path, rotation, and threshold filter,
+    /// unless they use [`ConfigBuilder::logfmt_format`], which log4rs has no encoder for.
+    /// Other appenders, their filters, and disabled appenders are left out and named in comments at the top of the file, and so are custom level names.
+    /// Settings only lum_log supports, e.g. routes, layers, and redaction rules, are not part of log4rs configuration files.
+    #[cfg(feature = "yaml")]
+    pub fn to_log4rs_yaml(&self) -> Result<String, serde_yaml::Error> {
+        fn sorted<V>(map: &HashMap<String, V>) -> Vec<&String> {
+            let mut names = map.keys().collect::<Vec<_>>();
+            names.sort();
+            names
+        }
+
+        let mut notes = Vec::new();
+        let mut appenders = BTreeMap::new();
+        for name in sorted(&self.appenders) {
+            let spec = self.appender_specs.get(name);
+            if self.disabled_appenders.contains(name) {
+                notes.push(format!("appender {name} (disabled)"));
+                continue;
+            }
+            let Some(spec) = spec else {
+                notes.push(format!("appender {name} ({:?})", self.appenders[name]));
+                continue;
+            };
+
+            let filters = self.filters.get(name).map_or(0, Vec::len);
+            if filters > spec.filter_count() {
+                notes.push(format!(
+                    "{} filter(s) of appender {name}",
+                    filters - spec.filter_count()
+                ));
+            }
+            appenders.insert(name.as_str(), spec);
+        }
+        if self.level_names.is_some() {
+            notes.push("custom level names".to_string());
+        }
+
+        let mut loggers = BTreeMap::new();
+        for (name, level) in &self.log_levels {
+            loggers.insert(name.as_str(), LoggerSpec::new(*level));
+        }
+        if self.deny_unknown_targets {
+            for name in &self.allowed_targets {
+                loggers
+                    .entry(name.as_str())
+                    .or_insert_with(|| LoggerSpec::new(self.root_log_level));
+            }
+        }
+        for (name, logger) in &self.loggers {
+            let logger_appenders = logger
+                .appenders
+                .iter()
+                .map(String::as_str)
+                .filter(|name| appenders.contains_key(name))
+                .collect();
+            loggers.insert(
+                name.as_str(),
+                LoggerSpec {
+                    level: logger.level,
+                    appenders: logger_appenders,
+                    additive: logger.additive,
+                },
+            );
+        }
+
+        let root_appenders = appenders
+            .keys()
+            .filter(|name| {
+                !self
+                    .loggers
+                    .values()
+                    .any(|logger| logger.appenders.iter().any(|appender| appender == *name))
+            })
+            .map(|name| name.to_string())
+            .collect();
+        let root = RootSpec {
+            level: match self.deny_unknown_targets {
+                true => LevelFilter::Off,
+                false => self.root_log_level,
+            },
+            appenders: root_appenders,
+        };
+
+        Document {
+            root,
+            appenders,
+            loggers,
+        }
+        .to_yaml(&notes)
+    }
+
+    /// Returns the [`Config`](crate::config::Config) describing this builder, e.g. to save a configuration tweaked programmatically.
+    /// Levels, level names, the format, and colors are described completely, and loggers by their levels.
+    /// Of the appenders, only those added by [`ConfigBuilder::output`], [`ConfigBuilder::stdout_console_appender`],
+    /// and the file rolling appender methods are described, sorted by name, unless they are disabled.
+    /// All routes are described, even those routing to other appenders, as exclusive ones still keep records away from the outputs.
+    /// Other settings, e.g. layers and the timestamp format of the default pattern, have no equivalent in a `Config`.
+    pub fn to_config(&self) -> config::Config {
+        let mut levels = self.log_levels.clone();
+        for (name, logger) in &self.loggers {
+            levels.insert(name.clone(), logger.level);
+        }
+
+        let mut names = self
+            .outputs
+            .keys()
+            .filter(|name| !self.disabled_appenders.contains(*name))
+            .collect::<Vec<_>>();
+        names.sort();
+
+        config::Config {
+            level: self.root_log_level,
+            levels,
+            format: match self.output_format {
+                OutputFormat::Pattern => config::Format::Pattern,
+                OutputFormat::Json => config::Format::Json,
+                OutputFormat::Logfmt => config::Format::Logfmt,
+            },
+            pattern: self.pattern.clone(),
+            level_style: self.level_style,
+            level_names: self.level_names.clone().unwrap_or_default(),
+            colors: self.colors,
+            outputs: names
+                .into_iter()
+                .map(|name| self.outputs[name].clone())
+                .collect(),
+            routes: self.routes.clone(),
+        }
+    }
+
+    /// Returns whether an appender with the given name has been added.
+    pub(crate) fn has_appender(&self, name: &str) -> bool {
+        self.appenders.contains_key(name)
+    }
+
+    /// Returns the names of all added appenders.
+    pub(crate) fn appender_names(&self) -> Vec<String> {
+        self.appenders.keys().cloned().collect()
+    }
+
+    /// Makes the appenders with the given names reject all records, see [`toggle::AppenderHandle::set_enabled`](crate::toggle::AppenderHandle::set_enabled).
+    pub(crate) fn disable_appenders(&mut self, names: impl IntoIterator<Item = String>) {
+        self.disabled_appenders.extend(names);
+    }
+
+    /// Applies the log levels of the given profile.
+    pub(crate) fn apply_profile(&mut self, profile: &Profile) {
+        if let Some(root_level) = profile.root_level {
+            self.root_log_level = root_level;
+        }
+        self.log_levels.extend(profile.levels.clone());
+    }
+
+    /// Raises the level of the given targets and their children, or of the root logger and all targets if `None`, to at least the given level.
+    pub(crate) fn raise_level(&mut self, targets: Option<&[String]>, level: LevelFilter) {
+        let Some(targets) = targets else {
+            self.root_log_level = self.root_log_level.max(level);
+            for target_level in self.log_levels.values_mut() {
+                *target_level = (*target_level).max(level);
+            }
+            for logger in self.loggers.values_mut() {
+                logger.level = logger.level.max(level);
+            }
+            return;
+        };
+
+        for target in targets {
+            let raised = self.effective_level(target).max(level);
+            match self.loggers.get_mut(target) {
+                Some(logger) => logger.level = raised,
+                None => {
+                    self.log_levels.insert(target.clone(), raised);
+                }
+            }
+
+            let is_child = |name: &str| {
+                name.strip_prefix(target.as_str())
+                    .is_some_and(|rest| rest.starts_with("::"))
+            };
+            for (_, child_level) in self
+                .log_levels
+                .iter_mut()
+                .filter(|(name, _)| is_child(name))
+            {
+                *child_level = (*child_level).max(level);
+            }
+            for (_, logger) in self.loggers.iter_mut().filter(|(name, _)| is_child(name)) {
+                logger.level = logger.level.max(level);
+            }
+        }
+    }
+
+    /// Returns the level that applies to the given target, considering its closest configured parent.
+    fn effective_level(&self, target: &str) -> LevelFilter {
+        let is_parent = |name: &str| {
+            target == name
+                || target
+                    .strip_prefix(name)
+                    .is_some_and(|rest| rest.starts_with("::"))
+        };
+
+        let levels = self.log_levels.iter().map(|(name, level)| (name, *level));
+        let loggers = self
+            .loggers
+            .iter()
+            .map(|(name, logger)| (name, logger.level));
+
+        loggers
+            .chain(levels)
+            .filter(|(name, _)| is_parent(name))
+            .max_by_key(|(name, _)| name.len())
+            .map_or(self.root_log_level, |(_, level)| level)
+    }
+
+    /// Sets the log level of the given target, updating the logger added by [`ConfigBuilder::logger`] for it, if any,
+    /// and the log level set by [`ConfigBuilder::log_level`] otherwise.
+    pub(crate) fn target_level(mut self, target: &str, level: LevelFilter) -> Self {
+        match self.loggers.get_mut(target) {
+            Some(logger) => logger.level = level,
+            None => {
+                self.log_levels.insert(target.to_string(), level);
+            }
+        }
+        self
+    }
+
+    /// Builds a [`Config`] from the provided settings without consuming the builder.
+    /// Appenders and filters are shared with the builder.
+    pub(crate) fn build_config(&self) -> Result<Config, ConfigErrors> {
         let mut appender_names = Vec::with_capacity(self.appenders.len());
 
         let mut builder = Config::builder();
-        for (name, append) in self.appenders {
-            let filters = self.filters.remove(&name);
+        for (name, append) in &self.appenders {
+            let is_logger_appender = self
+                .loggers
+                .values()
+                .any(|logger| logger.appenders.contains(name));
 
             let mut appender = Appender::builder();
-            if let Some(filters) = filters {
-                for filter in filters {
-                    appender = appender.filter(filter);
-                }
+            if self.disabled_appenders.contains(name) {
+                appender = appender.filter(Box::new(DisabledFilter));
+            }
+            if let Some(route_filter) = RouteFilter::for_appender(&self.routes, name) {
+                appender = appender.filter(Box::new(route_filter));
+            }
+            if let Some(summarized) = &self.summarized
+                && name != "interval_summary"
+            {
+                appender = appender.filter(Box::new(SharedFilter(summarized.clone())));
+            }
+            for filter in self.filters.get(name).into_iter().flatten() {
+                appender = appender.filter(Box::new(SharedFilter(Arc::clone(filter))));
             }
-            let appender = appender.build(name.as_str(), append);
+            let tracked = (!self.async_appenders.contains(name)).then_some(name.as_str());
+            let appender = appender.build(
+                name.as_str(),
+                Box::new(SharedAppender::new(Arc::clone(append), tracked)),
+            );
 
             builder = builder.appender(appender);
-            appender_names.push(name);
+            if !is_logger_appender {
+                appender_names.push(name.clone());
+            }
         }
 
-        for (name, level) in self.log_levels {
-            builder = builder.logger(Logger::builder().build(name.as_str(), level));
+        for (name, level) in &self.log_levels {
+            if self.loggers.contains_key(name) {
+                continue;
+            }
+
+            builder = builder.logger(Logger::builder().build(name.as_str(), *level));
         }
 
-        let config = builder.build(
+        if self.deny_unknown_targets {
+            for name in &self.allowed_targets {
+                if self.log_levels.contains_key(name) || self.loggers.contains_key(name) {
+                    continue;
+                }
+
+                builder =
+                    builder.logger(Logger::builder().build(name.as_str(), self.root_log_level));
+            }
+        }
+
+        for (name, logger) in &self.loggers {
+            builder = builder.logger(
+                Logger::builder()
+                    .appenders(logger.appenders.iter().cloned())
+                    .additive(logger.additive)
+                    .build(name.as_str(), logger.level),
+            );
+        }
+
+        let root_log_level = match self.deny_unknown_targets {
+            true => LevelFilter::Off,
+            false => self.root_log_level,
+        };
+        builder.build(
             Root::builder()
                 .appenders(appender_names)
-                .build(self.root_log_level),
-        )?;
+                .build(root_log_level),
+        )
+    }
+
+    fn unused_appender_name(&self, prefix: &str) -> String {
+        (0..)
+            .map(|index| format!("{prefix}.{index}"))
+            .find(|name| !self.appenders.contains_key(name))
+            .expect("There is always an unused appender name")
+    }
+
+    /// Records how the appender with the given name can be exported to a log4rs configuration file.
+    /// Appenders without a spec are named in comments of the exported file instead.
+    fn appender_spec(mut self, name: &str, spec: Option<AppenderSpec>) -> Self {
+        if let Some(spec) = spec {
+            self.appender_specs.insert(name.to_string(), spec);
+        }
+        self
+    }
+
+    /// Records which [`Output`] the appender with the given name was created from, for [`ConfigBuilder::to_config`].
+    fn output_config(mut self, name: &str, output: Output) -> Self {
+        self.outputs.insert(name.to_string(), output);
+        self
+    }
+
+    fn default_format(&self) -> String {
+        match &self.pattern {
+            Some(pattern) => pattern.clone(),
+            None => default::format_with_style(self.timestamp_format.as_ref(), self.level_style),
+        }
+    }
+
+    /// Selects the given output format if enabled is true, else falls back to the pattern if the given format is selected.
+    fn select_output_format(mut self, format: OutputFormat, enabled: bool) -> Self {
+        if enabled {
+            self.output_format = format;
+        } else if self.output_format == format {
+            self.output_format = OutputFormat::Pattern;
+        }
+        self
+    }
+
+    /// Describes the encoder returned by [`ConfigBuilder::default_encoder`] for exporting it.
+    /// Returns `None` for logfmt, which log4rs has no encoder for.
+    fn encoder_spec(&self) -> Option<EncoderSpec> {
+        match self.output_format {
+            OutputFormat::Pattern => Some(EncoderSpec::pattern(self.default_format())),
+            OutputFormat::Json => Some(EncoderSpec::json()),
+            OutputFormat::Logfmt => None,
+        }
+    }
+
+    fn default_encoder(&self) -> Box<dyn Encode> {
+        self.default_encoder_factory()()
+    }
+
+    /// Wraps the given console encoder in a [`TerminalWidthEncoder`] if [`ConfigBuilder::console_line_overflow`] is set,
+    /// unless [`ConfigBuilder::json_format`] or [`ConfigBuilder::logfmt_format`] is enabled, as truncated or wrapped lines could not be parsed anymore.
+    fn console_encoder(&self, encoder: Box<dyn Encode>) -> Box<dyn Encode> {
+        let encoder = match self.colors {
+            true => encoder,
+            false => Box::new(StripAnsiEncoder::new(encoder)),
+        };
+        match self.console_line_overflow {
+            _ if self.output_format != OutputFormat::Pattern => encoder,
+            LineOverflow::Full => encoder,
+            overflow => Box::new(TerminalWidthEncoder::new(encoder, overflow)),
+        }
+    }
+
+    /// Returns a function creating the encoder returned by [`ConfigBuilder::default_encoder`],
+    /// for appenders created after the builder is gone.
+    fn default_encoder_factory(&self) -> impl Fn() -> Box<dyn Encode> + Send + Sync + 'static {
+        let output_format = self.output_format;
+        let format = self.default_format();
+
+        let level_names = self.level_names.clone().unwrap_or_default();
+        move || -> Box<dyn Encode> {
+            match output_format {
+                OutputFormat::Pattern => {
+                    Box::new(LevelNameEncoder::pattern(&format, level_names.clone()))
+                }
+                OutputFormat::Json => {
+                    Box::new(default::json_encoder().level_names(level_names.clone()))
+                }
+                OutputFormat::Logfmt => {
+                    Box::new(default::logfmt_encoder().level_names(level_names.clone()))
+                }
+            }
+        }
+    }
+
+    /// Creates a file appender with the given function and the default encoder,
+    /// deferring its creation to the first record if [`ConfigBuilder::lazy_files`] is enabled,
+    /// and closing it when idle if [`ConfigBuilder::close_idle_files`] is set.
+    fn file_appender<A: Append>(
+        &self,
+        path: impl AsRef<Path>,
+        create: impl Fn(PathBuf, Box<dyn Encode>) -> io::Result<A> + Send + Sync + 'static,
+    ) -> io::Result<Box<dyn Append>> {
+        let path = path.as_ref().to_path_buf();
+        if !self.lazy_files && self.close_idle_files.is_none() {
+            return Ok(Box::new(create(path, self.default_encoder())?));
+        }
+
+        let encoder = self.default_encoder_factory();
+        let open = move || create(path.clone(), encoder());
+        let appender = match self.close_idle_files {
+            Some(idle_timeout) => LazyAppender::with_idle_timeout(open, idle_timeout)?,
+            None => LazyAppender::new(open),
+        };
+
+        if !self.lazy_files {
+            appender.open()?;
+        }
+        Ok(Box::new(appender))
+    }
+}
+
+/// An appender shared between all configurations built by a [`ConfigBuilder`] and its clones.
+/// If tracked, its results and latencies are recorded for the [`health::appender_health`] and [`latency::appender_latencies`]
+/// of the appender with the given name.
+#[derive(Debug)]
+struct SharedAppender {
+    appender: Arc<dyn Append>,
+    tracking: Option<(String, Arc<LatencyTracker>)>,
+}
+
+impl SharedAppender {
+    fn new(appender: Arc<dyn Append>, tracked: Option<&str>) -> Self {
+        let tracking = tracked.map(|name| (name.to_string(), latency::tracker(name)));
+        Self { appender, tracking }
+    }
+}
+
+impl Append for SharedAppender {
+    fn append(&self, record: &Record) -> anyhow::Result<()> {
+        let start = Instant::now();
+        let result = internal::catch("Appender", || self.appender.append(record))
+            .unwrap_or_else(|| Err(anyhow::anyhow!("Appender panicked")));
+        let elapsed = start.elapsed();
+        emergency::record_append(result.is_ok());
+        if result.is_err() {
+            stats::appender_error();
+        }
+        if let Some((name, latency)) = &self.tracking {
+            health::record_append(name, &result);
+            latency.record(name, elapsed);
+        }
+        result
+    }
+
+    fn flush(&self) {
+        internal::catch("Flushing an appender", || self.appender.flush());
+    }
+}
+
+/// A filter rejecting all records, added to appenders disabled at runtime.
+#[derive(Debug)]
+struct DisabledFilter;
+
+impl Filter for DisabledFilter {
+    fn filter(&self, _record: &Record) -> Response {
+        Response::Reject
+    }
+}
+
+/// A filter shared between all configurations built by a [`ConfigBuilder`] and its clones.
+#[derive(Debug)]
+struct SharedFilter(Arc<dyn Filter>);
+
+impl Filter for SharedFilter {
+    fn filter(&self, record: &Record) -> Response {
+        self.0.filter(record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lum_libs::log;
+
+    use super::*;
+    use crate::testing;
+
+    #[test]
+    fn loggers_write_to_their_own_appenders_and_to_the_root_if_additive() {
+        let _global = testing::GLOBAL.lock();
+        let (db, db_records) = testing::channel();
+        let (http, http_records) = testing::channel();
+        let root_records = testing::capture(
+            ConfigBuilder::new()
+                .root_log_level(LevelFilter::Info)
+                .appender("db", db)
+                .appender("http", http)
+                .logger("loggers_test::db", LevelFilter::Debug, ["db"], false)
+                .logger("loggers_test::http", LevelFilter::Info, ["http"], true),
+        );
+
+        log::debug!(target: "loggers_test::db", "Query");
+        log::info!(target: "loggers_test::http", "Request");
+        log::info!(target: "loggers_test", "Started");
+
+        assert_eq!(testing::messages(&db_records), ["Query"]);
+        assert_eq!(testing::messages(&http_records), ["Request"]);
+        assert_eq!(testing::messages(&root_records), ["Request", "Started"]);
+    }
+
+    #[test]
+    fn errors_file_receives_only_warnings_and_errors() {
+        let _global = testing::GLOBAL.lock();
+        let path = testing::temp_dir("errors_file").join("app.log");
+        ConfigBuilder::new()
+            .root_log_level(LevelFilter::Info)
+            .file_rolling_appender_with_errors(&path)
+            .unwrap()
+            .apply()
+            .unwrap();
+
+        log::info!(target: "errors_file_test", "Started");
+        log::warn!(target: "errors_file_test", "Disk almost full");
+        logger::flush();
+
+        let log = std::fs::read_to_string(&path).unwrap();
+        let errors = std::fs::read_to_string(default::errors_file_path(&path)).unwrap();
+        assert!(log.contains("Started") && log.contains("Disk almost full"));
+        assert!(!errors.contains("Started") && errors.contains("Disk almost full"));
+    }
+
+    #[test]
+    fn unknown_targets_are_denied_in_strict_mode() {
+        let _global = testing::GLOBAL.lock();
+        let records = testing::capture(
+            ConfigBuilder::new()
+                .root_log_level(LevelFilter::Info)
+                .deny_unknown_targets(true)
+                .log_level("strict_test::db", LevelFilter::Debug)
+                .allow_target("strict_test::http"),
+        );
+
+        log::debug!(target: "strict_test::db::pool", "Query");
+        log::info!(target: "strict_test::http", "Request");
+        log::debug!(target: "strict_test::http", "Headers");
+        log::error!(target: "strict_test::dependency", "Leaked");
+
+        assert_eq!(testing::messages(&records), ["Query", "Request"]);
+    }
+
+    #[test]
+    fn workspace_levels_apply_to_each_crate_with_underscores() {
+        let _global = testing::GLOBAL.lock();
+        let records = testing::capture(
+            ConfigBuilder::new()
+                .root_log_level(LevelFilter::Warn)
+                .workspace_level(["workspace-core", "workspace_cli"], LevelFilter::Debug),
+        );
+
+        log::debug!(target: "workspace_core::db", "Core");
+        log::debug!(target: "workspace_cli", "Cli");
+        log::info!(target: "workspace_other", "Other");
+
+        assert_eq!(testing::messages(&records), ["Core", "Cli"]);
+    }
+
+    #[test]
+    fn explain_describes_the_configuration_without_applying_it() {
+        let (appender, _records) = testing::channel();
+        let builder = ConfigBuilder::new()
+            .root_log_level(LevelFilter::Info)
+            .log_level("explain_test::http", LevelFilter::Warn)
+            .log_level("explain_test::db", LevelFilter::Debug)
+            .appender("capture", appender)
+            .logger("explain_test::db", LevelFilter::Trace, ["capture"], false)
+            .lazy_files(true)
+            .close_idle_files(Duration::from_secs(60));
+
+        let explanation = builder.explain();
+
+        assert!(explanation.starts_with(
+            "Root level: INFO\n\
+             Log levels:\n  explain_test::db = DEBUG\n  explain_test::http = WARN\n\
+             Loggers:\n  explain_test::db = TRACE -> [capture]\n\
+             Appenders:\n  capture: ChannelAppender"
+        ));
+        assert!(
+            explanation
+                .contains("\nFile appenders: created on the first record, closed after 1m idle\n")
+        );
+    }
+
+    /// An appender delaying earlier records longer than later ones before passing them on.
+    #[derive(Debug)]
+    struct Delayed(Box<dyn Append>);
+
+    impl Append for Delayed {
+        fn append(&self, record: &Record) -> anyhow::Result<()> {
+            let index = record.args().to_string().parse::<u64>()?;
+            std::thread::sleep(Duration::from_millis(10 - index % 10));
+            self.0.append(record)
+        }
+
+        fn flush(&self) {}
+    }
+
+    #[test]
+    fn ordered_async_appenders_keep_the_emission_order_with_varying_delays() {
+        let _global = testing::GLOBAL.lock();
+        let (sink, records) = testing::channel();
+        ConfigBuilder::new()
+            .root_log_level(LevelFilter::Info)
+            .appender("sink", Box::new(Delayed(sink)))
+            .asynchronous_ordered("sink", Backpressure::Block, 4)
+            .unwrap()
+            .apply()
+            .unwrap();
+
+        for index in 0..20 {
+            log::info!(target: "ordered_test", "{index}");
+        }
+
+        let messages = (0..20)
+            .map(|_| {
+                records
+                    .recv_timeout(Duration::from_secs(10))
+                    .unwrap()
+                    .message
+            })
+            .collect::<Vec<_>>();
+        let expected = (0..20).map(|index| index.to_string()).collect::<Vec<_>>();
+        assert_eq!(messages, expected);
+    }
+
+    #[test]
+    fn json_format_writes_every_record_as_a_line_of_json() {
+        let _global = testing::GLOBAL.lock();
+        let path = testing::temp_dir("json_format").join("app.log");
+        ConfigBuilder::new()
+            .root_log_level(LevelFilter::Info)
+            .json_format(true)
+            .file_rolling_appender(&path)
+            .unwrap()
+            .apply()
+            .unwrap();
+
+        log::info!(target: "json_format_test", "Started \"worker\"");
+        log::warn!(target: "json_format_test", "Disk almost full");
+        logger::flush();
+
+        let lines = std::fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .map(|line| {
+                lum_libs::serde_json::from_str::<lum_libs::serde_json::Value>(line).unwrap()
+            })
+            .map(|line| {
+                (
+                    line["level"].clone(),
+                    line["target"].clone(),
+                    line["message"].clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            [
+                (
+                    "INFO".into(),
+                    "json_format_test".into(),
+                    "Started \"worker\"".into()
+                ),
+                (
+                    "WARN".into(),
+                    "json_format_test".into(),
+                    "Disk almost full".into()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn configs_survive_the_round_trip_through_the_builder() {
+        let path = testing::temp_dir("config_round_trip").join("app.log");
+        let config = config::Config {
+            level: LevelFilter::Warn,
+            levels: HashMap::from([("round_trip_test::db".to_string(), LevelFilter::Debug)]),
+            format: config::Format::Json,
+            colors: false,
+            outputs: vec![
+                Output::file(
+                    &path,
+                    Some(RollingPolicy {
+                        interval: config::RollingInterval::Hourly,
+                        keep: 3,
+                        pattern: None,
+                    }),
+                ),
+                Output::Console {
+                    name: None,
+                    stream: ConsoleStream::Stderr,
+                    level: Some(LevelFilter::Error),
+                },
+            ],
+            routes: vec![Route::exclusive(
+                RouteRule::StartsWith("round_trip_test::audit".to_string()),
+                "file",
+            )],
+            ..config::Config::default()
+        };
+
+        let (channel, _records) = testing::channel();
+        let builder = ConfigBuilder::from_config(&config)
+            .unwrap()
+            .appender("channel", channel)
+            .log_level("round_trip_test::http", LevelFilter::Trace)
+            .route(RouteRule::Contains("payment".to_string()), "channel");
 
-        Ok(config)
+        let mut expected = config.clone();
+        expected
+            .levels
+            .insert("round_trip_test::http".to_string(), LevelFilter::Trace);
+        expected.routes.push(Route::new(
+            RouteRule::Contains("payment".to_string()),
+            "channel",
+        ));
+        assert_eq!(builder.to_config(), expected);
     }
 }
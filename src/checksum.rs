@@ -0,0 +1,136 @@
+use std::{
+    fmt::Write as _,
+    fs::{self, File},
+    io::{self, Read},
+    path::{Path, PathBuf},
+    sync::{
+        Once,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use lum_libs::log;
+use sha2::{Digest, Sha256};
+
+use crate::{default, rotate};
+
+/// The target of the records logged when writing a checksum sidecar fails.
+pub const CHECKSUM_TARGET: &str = "lum_log::checksum";
+
+static CHECKSUM_SIDECARS: AtomicBool = AtomicBool::new(false);
+static REGISTER: Once = Once::new();
+
+/// Sets whether a `.sha256` sidecar is written next to every rotated or closed log file, see [`write_sidecar`],
+/// so archival pipelines can verify the integrity of the files after transferring them. Sidecars are disabled by default.
+///
+/// Sidecars are written by a callback registered with [`rotate::on_rotation`] when they are enabled for the first time.
+/// Callbacks run in the order they are registered, so enable sidecars before registering archivers, e.g. an [`S3Archiver`](crate::archive::S3Archiver),
+/// for the sidecar to exist by the time the file is archived. Failures are logged to [`CHECKSUM_TARGET`].
+/// See also [`ConfigBuilder::checksum_sidecars`](crate::ConfigBuilder::checksum_sidecars).
+pub fn set_checksum_sidecars(enabled: bool) {
+    CHECKSUM_SIDECARS.store(enabled, Ordering::Relaxed);
+    if enabled {
+        REGISTER.call_once(|| rotate::on_rotation(|rotation| write_rotated(&rotation.rotated)));
+    }
+}
+
+/// Returns whether checksum sidecars are written, see [`set_checksum_sidecars`].
+pub fn checksum_sidecars() -> bool {
+    CHECKSUM_SIDECARS.load(Ordering::Relaxed)
+}
+
+/// Returns the lowercase hexadecimal SHA-256 digest of the file at the given path.
+pub fn sha256_file(path: impl AsRef<Path>) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; 64 * 1024];
+    loop {
+        match file.read(&mut buffer)? {
+            0 => break,
+            read => hasher.update(&buffer[..read]),
+        }
+    }
+
+    Ok(hasher
+        .finalize()
+        .iter()
+        .fold(String::new(), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        }))
+}
+
+/// Writes the SHA-256 digest of the file at the given path to its sidecar at [`default::checksum_file_path`],
+/// returning the path of the sidecar.
+/// The sidecar uses the format of `sha256sum`, so `sha256sum -c app.log.0.sha256` verifies the file in its directory:
+/// ```text
+/// 3a7bd3e2360a3d29eea436fcfb7e44c735d117c42d1c1835420b6b9942dd4f1b  app.log.0
+/// ```
+pub fn write_sidecar(path: impl AsRef<Path>) -> io::Result<PathBuf> {
+    let path = path.as_ref();
+    let digest = sha256_file(path)?;
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+
+    let sidecar = default::checksum_file_path(path);
+    fs::write(&sidecar, format!("{digest}  {file_name}\n"))?;
+    Ok(sidecar)
+}
+
+/// Writes the sidecar of the given rotated file if sidecars are enabled, logging failures.
+fn write_rotated(path: &Path) {
+    if !checksum_sidecars() {
+        return;
+    }
+
+    if let Err(error) = write_sidecar(path) {
+        log::error!(
+            target: CHECKSUM_TARGET,
+            "Failed to write checksum sidecar of {}: {error}",
+            path.display()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use super::*;
+    use crate::{rotate::Rotation, testing};
+
+    #[test]
+    fn sidecars_are_written_for_rotated_files_while_enabled() {
+        let _global = testing::GLOBAL.lock();
+        let directory = testing::temp_dir("checksum");
+        set_checksum_sidecars(true);
+        // Callbacks run in order on a background thread, so one registered after the sidecar writer reports when it is done.
+        let (sender, handled) = mpsc::channel();
+        let file = directory.join("app.log");
+        rotate::on_rotation(move |rotation| {
+            if rotation.file == file {
+                let _ = sender.send(());
+            }
+        });
+        let rotate = |name: &str| {
+            let rotated = directory.join(name);
+            fs::write(&rotated, "abc").unwrap();
+            rotate::notify(Rotation {
+                file: directory.join("app.log"),
+                rotated: rotated.clone(),
+            })
+            .unwrap();
+            handled.recv().unwrap();
+            default::checksum_file_path(rotated)
+        };
+
+        let sidecar = rotate("app.log.0");
+        set_checksum_sidecars(false);
+        let skipped = rotate("app.log.1");
+
+        assert_eq!(
+            fs::read_to_string(sidecar).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad  app.log.0\n"
+        );
+        assert!(!skipped.exists());
+    }
+}
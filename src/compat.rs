@@ -0,0 +1,331 @@
+#[cfg(feature = "fern")]
+use std::fmt::{self, Debug, Formatter};
+
+#[cfg(feature = "slog")]
+use lum_libs::serde_json::{Map, Number, Value};
+#[cfg(feature = "fern")]
+use lum_libs::{
+    log::{Log, Record, SetLoggerError},
+    log4rs::{
+        Config,
+        append::Append,
+        config::{Appender, Root, runtime::ConfigErrors},
+    },
+};
+#[cfg(feature = "fern")]
+use thiserror::Error;
+
+#[cfg(feature = "slog")]
+use crate::json;
+#[cfg(feature = "fern")]
+use crate::logger;
+
+/// The name of the appender wrapping the logger built from a `fern::Dispatch` by [`from_fern_dispatch`].
+#[cfg(feature = "fern")]
+pub const FERN_APPENDER: &str = "fern";
+
+/// Errors that can occur when installing a logger of another logging library.
+#[cfg(feature = "fern")]
+#[derive(Debug, Error)]
+pub enum CompatError {
+    #[error("Error while building log4rs configuration: {0}")]
+    Log4rs(#[from] ConfigErrors),
+
+    #[error("Error while setting the global logger: {0}")]
+    SetLogger(#[from] SetLoggerError),
+}
+
+/// Installs the given `fern::Dispatch` as the global logger through [`setup`](crate::setup),
+/// so projects with elaborate fern setups can migrate to lum_log without rewriting them.
+///
+/// The dispatch keeps its own formatting, filtering, and outputs. It is added as the single appender [`FERN_APPENDER`],
+/// so records pass through lum_log's pipeline before reaching it, e.g. counted by [`stats`](crate::stats) and redacted by [`redaction`](crate::redaction),
+/// [`flush`](crate::flush) and [`shutdown`](crate::shutdown) reach the dispatch, and the handle is stored for later reconfiguration.
+/// The maximum level of the dispatch becomes the root level.
+///
+/// ```text
+/// let dispatch = fern::Dispatch::new()
+///     .level(log::LevelFilter::Info)
+///     .chain(std::io::stdout());
+/// lum_log::compat::from_fern_dispatch(dispatch)?;
+/// ```
+#[cfg(feature = "fern")]
+pub fn from_fern_dispatch(dispatch: fern::Dispatch) -> Result<(), CompatError> {
+    let (level, log) = dispatch.into_log();
+    let appender = Appender::builder().build(FERN_APPENDER, Box::new(LogAppender(log)));
+    let config = Config::builder()
+        .appender(appender)
+        .build(Root::builder().appender(FERN_APPENDER).build(level))?;
+
+    logger::setup(config)?;
+    Ok(())
+}
+
+/// An appender passing records to a [`Log`] implementation of another logging library.
+#[cfg(feature = "fern")]
+struct LogAppender(Box<dyn Log>);
+
+#[cfg(feature = "fern")]
+impl Debug for LogAppender {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("LogAppender").finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "fern")]
+impl Append for LogAppender {
+    fn append(&self, record: &Record) -> anyhow::Result<()> {
+        self.0.log(record);
+        Ok(())
+    }
+
+    fn flush(&self) {
+        self.0.flush();
+    }
+}
+
+/// A `slog::Drain` passing records of slog-based libraries into lum_log's pipeline, so they reach the same appenders as records of the `log` crate.
+///
+/// The key-value pairs of a record and its logger become a JSON object exposed as the payload of the record,
+/// like the value of [`log_json!`](crate::log_json), so encoders render them as structured fields, see [`JSON_MDC_KEY`](json::JSON_MDC_KEY).
+/// Pairs of the record take precedence over pairs of its logger with the same key.
+/// The target is the tag of the record if it has one, else its module. `Critical` records are logged at [`Level::Error`](lum_libs::log::Level::Error).
+///
+/// ```text
+/// let logger = slog::Logger::root(lum_log::compat::SlogDrain, slog::o!("component" => "storage"));
+/// slog::info!(logger, "Flushed memtable"; "bytes" => 4096);
+/// ```
+#[cfg(feature = "slog")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SlogDrain;
+
+#[cfg(feature = "slog")]
+impl slog::Drain for SlogDrain {
+    type Ok = ();
+    type Err = slog::Never;
+
+    fn log(
+        &self,
+        record: &slog::Record<'_>,
+        values: &slog::OwnedKVList,
+    ) -> Result<Self::Ok, Self::Err> {
+        use slog::KV;
+
+        let level = slog_level(record.level());
+        let target = match record.tag() {
+            "" => record.module(),
+            tag => tag,
+        };
+        let metadata = lum_libs::log::Metadata::builder()
+            .level(level)
+            .target(target)
+            .build();
+        let logger = lum_libs::log::logger();
+        if !logger.enabled(&metadata) {
+            return Ok(());
+        }
+
+        let mut fields = JsonFields::default();
+        let _ = record.kv().serialize(record, &mut fields);
+        let _ = values.serialize(record, &mut fields);
+        let payload = (!fields.0.is_empty()).then(|| Value::Object(fields.0).to_string());
+        let _json = payload.as_deref().map(json::scope);
+
+        logger.log(
+            &lum_libs::log::Record::builder()
+                .metadata(metadata)
+                .args(*record.msg())
+                .module_path_static(Some(record.module()))
+                .file_static(Some(record.file()))
+                .line(Some(record.line()))
+                .build(),
+        );
+        Ok(())
+    }
+
+    fn is_enabled(&self, level: slog::Level) -> bool {
+        slog_level(level) <= lum_libs::log::max_level()
+    }
+}
+
+/// Returns the `log` level of the given slog level.
+#[cfg(feature = "slog")]
+fn slog_level(level: slog::Level) -> lum_libs::log::Level {
+    use lum_libs::log::Level;
+
+    match level {
+        slog::Level::Critical | slog::Level::Error => Level::Error,
+        slog::Level::Warning => Level::Warn,
+        slog::Level::Info => Level::Info,
+        slog::Level::Debug => Level::Debug,
+        slog::Level::Trace => Level::Trace,
+    }
+}
+
+/// A slog serializer collecting key-value pairs into a JSON object, keeping the first value of each key.
+#[cfg(feature = "slog")]
+#[derive(Default)]
+struct JsonFields(Map<String, Value>);
+
+#[cfg(feature = "slog")]
+impl JsonFields {
+    fn insert(&mut self, key: slog::Key, value: Value) -> slog::Result {
+        self.0.entry(key.to_string()).or_insert(value);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "slog")]
+impl slog::Serializer for JsonFields {
+    fn emit_arguments(&mut self, key: slog::Key, value: &std::fmt::Arguments<'_>) -> slog::Result {
+        self.insert(key, Value::String(value.to_string()))
+    }
+
+    fn emit_bool(&mut self, key: slog::Key, value: bool) -> slog::Result {
+        self.insert(key, Value::Bool(value))
+    }
+
+    fn emit_str(&mut self, key: slog::Key, value: &str) -> slog::Result {
+        self.insert(key, Value::String(value.to_string()))
+    }
+
+    fn emit_u64(&mut self, key: slog::Key, value: u64) -> slog::Result {
+        self.insert(key, Value::from(value))
+    }
+
+    fn emit_i64(&mut self, key: slog::Key, value: i64) -> slog::Result {
+        self.insert(key, Value::from(value))
+    }
+
+    fn emit_u32(&mut self, key: slog::Key, value: u32) -> slog::Result {
+        self.emit_u64(key, value.into())
+    }
+
+    fn emit_i32(&mut self, key: slog::Key, value: i32) -> slog::Result {
+        self.emit_i64(key, value.into())
+    }
+
+    fn emit_u16(&mut self, key: slog::Key, value: u16) -> slog::Result {
+        self.emit_u64(key, value.into())
+    }
+
+    fn emit_i16(&mut self, key: slog::Key, value: i16) -> slog::Result {
+        self.emit_i64(key, value.into())
+    }
+
+    fn emit_u8(&mut self, key: slog::Key, value: u8) -> slog::Result {
+        self.emit_u64(key, value.into())
+    }
+
+    fn emit_i8(&mut self, key: slog::Key, value: i8) -> slog::Result {
+        self.emit_i64(key, value.into())
+    }
+
+    fn emit_usize(&mut self, key: slog::Key, value: usize) -> slog::Result {
+        self.insert(key, Value::from(value))
+    }
+
+    fn emit_isize(&mut self, key: slog::Key, value: isize) -> slog::Result {
+        self.insert(key, Value::from(value))
+    }
+
+    fn emit_f64(&mut self, key: slog::Key, value: f64) -> slog::Result {
+        // Non-finite numbers have no JSON representation and are rendered as strings.
+        match Number::from_f64(value) {
+            Some(number) => self.insert(key, Value::Number(number)),
+            None => self.insert(key, Value::String(value.to_string())),
+        }
+    }
+
+    fn emit_f32(&mut self, key: slog::Key, value: f32) -> slog::Result {
+        self.emit_f64(key, value.into())
+    }
+
+    fn emit_unit(&mut self, key: slog::Key) -> slog::Result {
+        self.insert(key, Value::Null)
+    }
+
+    fn emit_none(&mut self, key: slog::Key) -> slog::Result {
+        self.insert(key, Value::Null)
+    }
+}
+
+#[cfg(all(test, any(feature = "fern", feature = "slog")))]
+mod tests {
+    #[cfg(feature = "fern")]
+    use std::sync::{Arc, Mutex};
+
+    use lum_libs::log::{self, LevelFilter};
+
+    use super::*;
+    use crate::testing;
+
+    #[cfg(feature = "fern")]
+    #[test]
+    fn fern_dispatches_keep_their_formatting_and_filtering() {
+        let _global = testing::GLOBAL.lock();
+        let lines = Arc::new(Mutex::new(Vec::new()));
+        let output = Arc::clone(&lines);
+        let dispatch = fern::Dispatch::new()
+            .format(|out, message, record| {
+                out.finish(format_args!("[{}] {message}", record.level()))
+            })
+            .level(LevelFilter::Info)
+            .level_for("compat_test::noisy", LevelFilter::Error)
+            .chain(fern::Output::call(move |record| {
+                output.lock().unwrap().push(record.args().to_string());
+            }));
+
+        from_fern_dispatch(dispatch).unwrap();
+        log::info!(target: "compat_test", "Started");
+        log::debug!(target: "compat_test", "Below the dispatch level");
+        log::warn!(target: "compat_test::noisy", "Below the target level");
+        crate::flush();
+
+        assert_eq!(log::max_level(), LevelFilter::Info);
+        assert_eq!(*lines.lock().unwrap(), ["[INFO] Started"]);
+    }
+
+    #[cfg(feature = "slog")]
+    #[test]
+    fn slog_records_are_logged_with_their_key_values_as_payload() {
+        let _global = testing::GLOBAL.lock();
+        let records =
+            testing::capture(crate::ConfigBuilder::new().root_log_level(LevelFilter::Info));
+        let logger =
+            slog::Logger::root(SlogDrain, slog::o!("component" => "storage", "bytes" => 0));
+
+        slog::info!(logger, "Flushed memtable"; "bytes" => 4096);
+        slog::debug!(logger, "Below the root level");
+        slog::crit!(logger, # "audit", "Disk lost");
+
+        let records = records
+            .try_iter()
+            .map(|record| {
+                let json = record
+                    .json
+                    .map(|json| lum_libs::serde_json::from_str::<Value>(&json).unwrap());
+                (record.level, record.target, record.message, json)
+            })
+            .collect::<Vec<_>>();
+        let fields =
+            |bytes| Some(lum_libs::serde_json::json!({ "bytes": bytes, "component": "storage" }));
+        assert_eq!(
+            records,
+            [
+                (
+                    log::Level::Info,
+                    module_path!().to_string(),
+                    "Flushed memtable".to_string(),
+                    fields(4096)
+                ),
+                (
+                    log::Level::Error,
+                    "audit".to_string(),
+                    "Disk lost".to_string(),
+                    fields(0)
+                ),
+            ]
+        );
+    }
+}
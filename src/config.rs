@@ -0,0 +1,621 @@
+use std::{
+    collections::HashMap,
+    fmt::{self, Display, Formatter},
+    io,
+    path::{Path, PathBuf},
+};
+
+use lum_libs::{
+    log::{LevelFilter, STATIC_MAX_LEVEL},
+    log4rs::{
+        self,
+        append::{
+            Append,
+            console::Target,
+            rolling_file::policy::compound::trigger::time::{
+                TimeTriggerConfig, TimeTriggerInterval,
+            },
+        },
+        config::{Appender, Logger, Root, runtime::ConfigErrors},
+        encode::Encode,
+        filter::threshold::ThresholdFilter,
+    },
+    serde::{Deserialize, Serialize},
+};
+use thiserror::Error;
+
+use crate::{
+    default,
+    encode::{LevelNameEncoder, StripAnsiEncoder},
+    level::{LevelNames, LevelStyle},
+    route::{Route, RouteFilter},
+};
+
+/// Errors that can occur when turning a [`Config`] into a log4rs configuration.
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("I/O error while creating file appender: {0}")]
+    FileAppenderIo(#[from] io::Error),
+
+    #[error("Error while building log4rs configuration: {0}")]
+    Log4rs(#[from] ConfigErrors),
+}
+
+/// A serializable logging configuration describing levels, format, and outputs, e.g. as deserialized from a configuration file.
+/// It can drive [`setup`](crate::setup) through [`Config::into_log4rs_config`].
+/// In TOML, it looks like this:
+/// ```toml
+/// level = "info"
+/// format = "pattern"
+/// level_style = "level_and_message"
+///
+/// [level_names]
+/// warn = "WARNING"
+///
+/// [levels]
+/// "my_crate::db" = "debug"
+///
+/// [[outputs]]
+/// kind = "console"
+/// stream = "stderr"
+///
+/// [[outputs]]
+/// kind = "file"
+/// path = "logs/app.log"
+/// rolling = { interval = "hourly", keep = 24 }
+///
+/// [[routes]]
+/// rule = { starts_with = "my_crate::audit" }
+/// appender = "file"
+/// exclusive = true
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde", default)]
+pub struct Config {
+    /// The log level of the root logger.
+    pub level: LevelFilter,
+    /// Log levels for specific logger names, e.g. modules.
+    pub levels: HashMap<String, LevelFilter>,
+    /// The format records are written in by all outputs.
+    pub format: Format,
+    /// The pattern of [`Format::Pattern`], in the syntax of log4rs' pattern encoder.
+    /// Without one, the pattern returned by [`default::format_with_style`] for the [`Config::level_style`] is used.
+    pub pattern: Option<String>,
+    /// Which part of a line the default pattern colors by its level.
+    pub level_style: LevelStyle,
+    /// The display names levels are rendered with by all formats.
+    pub level_names: LevelNames,
+    /// Whether console outputs are colored. File outputs are never colored.
+    pub colors: bool,
+    /// The outputs records are written to.
+    pub outputs: Vec<Output>,
+    /// Routes records to outputs by their target, referring to outputs by their [name](Output::name).
+    pub routes: Vec<Route>,
+}
+
+/// The format records are written in, see [`Config::format`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde", rename_all = "snake_case")]
+pub enum Format {
+    /// Lines rendered by a pattern, see [`Config::pattern`].
+    #[default]
+    Pattern,
+    /// Single lines of JSON, like [`default::json_encoder`].
+    Json,
+    /// Single logfmt lines, like [`default::logfmt_encoder`].
+    Logfmt,
+}
+
+/// An output records are written to, see [`Config::outputs`].
+/// Outputs without a name are named after their stream or kind, i.e. `stdout`, `stderr`, or `file`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde", tag = "kind", rename_all = "snake_case")]
+pub enum Output {
+    /// A console appender like [`default::console_appender`].
+    Console {
+        /// The name of the appender, if it differs from the stream.
+        name: Option<String>,
+        /// The stream written to.
+        #[serde(default)]
+        stream: ConsoleStream,
+        /// Only records at this level or above are written, if set.
+        level: Option<LevelFilter>,
+    },
+    /// A file appender, rolling the file if a rolling policy is set.
+    File {
+        /// The name of the appender, if it differs from `file`.
+        name: Option<String>,
+        /// The path of the file.
+        path: PathBuf,
+        /// When and how the file is rolled, if at all.
+        rolling: Option<RollingPolicy>,
+        /// Only records at this level or above are written, if set.
+        level: Option<LevelFilter>,
+    },
+}
+
+/// The stream a console output writes to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde", rename_all = "snake_case")]
+pub enum ConsoleStream {
+    #[default]
+    Stdout,
+    Stderr,
+}
+
+/// When and how a file output is rolled, like [`default::rolling_file_appender_with_policy`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde", default)]
+pub struct RollingPolicy {
+    /// How often the file is rolled.
+    pub interval: RollingInterval,
+    /// The number of rolled files kept.
+    pub keep: u32,
+    /// The pattern of rolled files, where `{}` is the index of a file, 0 being the most recent one.
+    /// Without one, rolled files are named after the file with the index appended, e.g. `logs/app.log.0`.
+    pub pattern: Option<String>,
+}
+
+/// How often a file output is rolled, see [`RollingPolicy::interval`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde", rename_all = "snake_case")]
+pub enum RollingInterval {
+    Hourly,
+    #[default]
+    Daily,
+    Weekly,
+}
+
+/// A suspicious setup found by [`Config::lint`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lint {
+    /// The kind of setup found.
+    pub kind: LintKind,
+    /// What is suspicious, e.g. `The level of root is trace in a release build`.
+    pub message: String,
+    /// How to fix it.
+    pub suggestion: String,
+}
+
+/// The kind of a [`Lint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintKind {
+    /// The root or a module is logged at the trace level in a release build, which is rarely intended and costly.
+    TraceInRelease,
+    /// Colors are enabled, but all outputs are files, which are never colored.
+    ColorsWithoutConsole,
+    /// A level can never fire, as no record that verbose can reach an output.
+    UnreachableLevel,
+    /// Multiple outputs have the same name.
+    DuplicateOutputName,
+}
+
+impl Display for Lint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}. {}.", self.message, self.suggestion)
+    }
+}
+
+impl Default for Config {
+    /// Creates a `Config` with the root log level from [`default::log_level`], no log levels, the pattern format with the default pattern,
+    /// only the level token colored, the default level names, colors enabled, a single console output writing to stdout, and no routes.
+    fn default() -> Self {
+        Self {
+            level: default::log_level(),
+            levels: HashMap::new(),
+            format: Format::default(),
+            pattern: None,
+            level_style: LevelStyle::default(),
+            level_names: LevelNames::default(),
+            colors: true,
+            outputs: vec![Output::console()],
+            routes: Vec::new(),
+        }
+    }
+}
+
+impl Config {
+    /// Same as [`Config::default`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses a `Config` from a YAML document, see [`Config`] for the layout.
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml(document: &str) -> Result<Self, serde_yaml::Error> {
+        serde_yaml::from_str(document)
+    }
+
+    /// Parses a `Config` from a TOML document, see [`Config`] for the layout.
+    #[cfg(feature = "toml")]
+    pub fn from_toml(document: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(document)
+    }
+
+    /// Builds the log4rs configuration described by this `Config`, creating the files of its file outputs.
+    /// All outputs are attached to the root logger, and the loggers of [`Config::levels`] inherit them.
+    ///
+    /// ```text
+    /// let config = lum_log::config::Config::from_yaml(&std::fs::read_to_string("logging.yaml")?)?;
+    /// lum_log::setup(config.into_log4rs_config()?)?;
+    /// ```
+    pub fn into_log4rs_config(self) -> Result<log4rs::Config, ConfigError> {
+        let mut builder = log4rs::Config::builder();
+        let mut root = Root::builder();
+
+        for output in &self.outputs {
+            let name = output.name();
+            let appender: Box<dyn Append> = match output {
+                Output::Console { stream, .. } => {
+                    let encoder = match self.colors {
+                        true => self.encoder(),
+                        false => Box::new(StripAnsiEncoder::new(self.encoder())),
+                    };
+                    let target = match stream {
+                        ConsoleStream::Stdout => Target::Stdout,
+                        ConsoleStream::Stderr => Target::Stderr,
+                    };
+                    Box::new(default::console_appender_with_target(encoder, target))
+                }
+                Output::File {
+                    path,
+                    rolling: Some(rolling),
+                    ..
+                } => Box::new(default::rolling_file_appender_with_policy(
+                    path,
+                    self.encoder(),
+                    rolling.time_trigger_config(),
+                    &rolling.roller_pattern(path),
+                    rolling.keep,
+                )?),
+                Output::File {
+                    path,
+                    rolling: None,
+                    ..
+                } => Box::new(default::file_appender_with_encoder(path, self.encoder())?),
+            };
+
+            let mut appender_builder = Appender::builder();
+            if let Some(level) = output.level() {
+                appender_builder = appender_builder.filter(Box::new(ThresholdFilter::new(level)));
+            }
+            if let Some(route_filter) = RouteFilter::for_appender(&self.routes, &name) {
+                appender_builder = appender_builder.filter(Box::new(route_filter));
+            }
+            builder = builder.appender(appender_builder.build(name.clone(), appender));
+            root = root.appender(name);
+        }
+
+        for (name, level) in self.levels {
+            builder = builder.logger(Logger::builder().build(name, level));
+        }
+
+        Ok(builder.build(root.build(self.level))?)
+    }
+
+    /// Checks this `Config` for suspicious setups, returning a warning with a suggestion for each, e.g. to print them at startup.
+    /// An empty result does not mean the configuration is valid, e.g. paths are not checked.
+    ///
+    /// The following setups are flagged:
+    /// - the trace level in a release build, i.e. without debug assertions
+    /// - console colors without any console output
+    /// - levels that can never fire, as they are more verbose than [`STATIC_MAX_LEVEL`]
+    ///   or than every output's level
+    /// - outputs with the same name, which log4rs rejects
+    pub fn lint(&self) -> Vec<Lint> {
+        let mut lints = Vec::new();
+        let mut levels = self.levels.iter().collect::<Vec<_>>();
+        levels.sort();
+
+        if !cfg!(debug_assertions) {
+            let trace = std::iter::once(("root", &self.level))
+                .chain(levels.iter().map(|(name, level)| (name.as_str(), *level)))
+                .filter(|(_, level)| **level == LevelFilter::Trace);
+            for (name, _) in trace {
+                lints.push(Lint {
+                    kind: LintKind::TraceInRelease,
+                    message: format!("The level of {name} is trace in a release build"),
+                    suggestion: "Use debug or info, or raise the level at runtime with set_level or set_module_level when tracing an issue".to_string(),
+                });
+            }
+        }
+
+        if self.colors
+            && !self.outputs.is_empty()
+            && !self
+                .outputs
+                .iter()
+                .any(|output| matches!(output, Output::Console { .. }))
+        {
+            lints.push(Lint {
+                kind: LintKind::ColorsWithoutConsole,
+                message: "Colors are enabled, but there is no console output".to_string(),
+                suggestion: "Set colors to false, as file outputs are never colored".to_string(),
+            });
+        }
+
+        let output_level = self
+            .outputs
+            .iter()
+            .map(|output| output.level().unwrap_or(LevelFilter::Trace))
+            .max();
+        for (name, level) in std::iter::once(("root", &self.level))
+            .chain(levels.iter().map(|(name, level)| (name.as_str(), *level)))
+        {
+            if *level > STATIC_MAX_LEVEL {
+                lints.push(Lint {
+                    kind: LintKind::UnreachableLevel,
+                    message: format!(
+                        "The level of {name} is {level}, but records more verbose than {STATIC_MAX_LEVEL} are compiled out by the max_level features of the log crate"
+                    ),
+                    suggestion: format!("Set the level of {name} to {STATIC_MAX_LEVEL} at most, or enable a more verbose max_level feature"),
+                });
+            } else if let Some(output_level) =
+                output_level.filter(|output_level| level > output_level)
+            {
+                lints.push(Lint {
+                    kind: LintKind::UnreachableLevel,
+                    message: format!(
+                        "The level of {name} is {level}, but no output writes records more verbose than {output_level}"
+                    ),
+                    suggestion: format!("Set the level of {name} to {output_level} at most, or lower the level of an output"),
+                });
+            }
+        }
+
+        let mut names = HashMap::<String, usize>::new();
+        for output in &self.outputs {
+            *names.entry(output.name()).or_default() += 1;
+        }
+        let mut duplicates = names
+            .into_iter()
+            .filter(|(_, count)| *count > 1)
+            .collect::<Vec<_>>();
+        duplicates.sort();
+        for (name, count) in duplicates {
+            lints.push(Lint {
+                kind: LintKind::DuplicateOutputName,
+                message: format!("{count} outputs are named {name}"),
+                suggestion: "Give each output a distinct name".to_string(),
+            });
+        }
+
+        lints
+    }
+
+    /// Returns the pattern of [`Format::Pattern`].
+    pub fn effective_pattern(&self) -> String {
+        self.pattern
+            .clone()
+            .unwrap_or_else(|| default::format_with_style(None, self.level_style))
+    }
+
+    fn encoder(&self) -> Box<dyn Encode> {
+        match self.format {
+            Format::Pattern => Box::new(LevelNameEncoder::pattern(
+                &self.effective_pattern(),
+                self.level_names.clone(),
+            )),
+            Format::Json => Box::new(default::json_encoder().level_names(self.level_names.clone())),
+            Format::Logfmt => {
+                Box::new(default::logfmt_encoder().level_names(self.level_names.clone()))
+            }
+        }
+    }
+}
+
+impl Output {
+    /// Returns a console output writing to stdout.
+    pub fn console() -> Self {
+        Self::Console {
+            name: None,
+            stream: ConsoleStream::Stdout,
+            level: None,
+        }
+    }
+
+    /// Returns a file output writing to the given path, rolling the file by the given policy if any.
+    pub fn file(path: impl Into<PathBuf>, rolling: Option<RollingPolicy>) -> Self {
+        Self::File {
+            name: None,
+            path: path.into(),
+            rolling,
+            level: None,
+        }
+    }
+
+    /// Returns the name of the appender of this output.
+    pub fn name(&self) -> String {
+        match self {
+            Self::Console {
+                name: Some(name), ..
+            }
+            | Self::File {
+                name: Some(name), ..
+            } => name.clone(),
+            Self::Console {
+                stream: ConsoleStream::Stdout,
+                ..
+            } => "stdout".to_string(),
+            Self::Console {
+                stream: ConsoleStream::Stderr,
+                ..
+            } => "stderr".to_string(),
+            Self::File { .. } => "file".to_string(),
+        }
+    }
+
+    /// Returns the level records must have at least to be written to this output, if any.
+    pub fn level(&self) -> Option<LevelFilter> {
+        match self {
+            Self::Console { level, .. } | Self::File { level, .. } => *level,
+        }
+    }
+}
+
+impl Default for RollingPolicy {
+    /// Creates a `RollingPolicy` rolling daily and keeping [`default::rolled_file_count`] files named after the file.
+    fn default() -> Self {
+        Self {
+            interval: RollingInterval::default(),
+            keep: default::rolled_file_count(),
+            pattern: None,
+        }
+    }
+}
+
+impl RollingPolicy {
+    /// Returns the pattern of rolled files of the file at the given path.
+    pub(crate) fn roller_pattern(&self, path: &Path) -> String {
+        self.pattern
+            .clone()
+            .unwrap_or_else(|| format!("{}.{{}}", path.display()))
+    }
+
+    /// Returns the [`TimeTriggerConfig`] returned by [`default::time_trigger_config`] with the interval of this policy.
+    pub(crate) fn time_trigger_config(&self) -> TimeTriggerConfig {
+        let mut config = default::time_trigger_config();
+        config.interval = match self.interval {
+            RollingInterval::Hourly => TimeTriggerInterval::Hour(1),
+            RollingInterval::Daily => TimeTriggerInterval::Day(1),
+            RollingInterval::Weekly => TimeTriggerInterval::Week(1),
+        };
+        config
+    }
+}
+
+impl RollingInterval {
+    /// Returns the interval in the syntax of log4rs configuration files, e.g. `1 day`.
+    pub(crate) fn log4rs_interval(self) -> &'static str {
+        match self {
+            RollingInterval::Hourly => "1 hour",
+            RollingInterval::Daily => "1 day",
+            RollingInterval::Weekly => "1 week",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lum_libs::log::{self, Level};
+
+    use super::*;
+    use crate::{ConfigBuilder, logger, testing};
+
+    #[test]
+    fn level_names_are_read_and_kept_by_the_builder() {
+        let config =
+            lum_libs::serde_json::from_str::<Config>(r#"{"level_names": {"warn": "WARNING"}}"#)
+                .unwrap();
+        assert_eq!(
+            config.level_names,
+            LevelNames::new().name(Level::Warn, "WARNING")
+        );
+
+        let builder = ConfigBuilder::from_config(&config).unwrap();
+        assert_eq!(builder.to_config().level_names, config.level_names);
+    }
+
+    #[test]
+    fn deserialized_configs_set_up_their_outputs_with_their_format() {
+        let _global = testing::GLOBAL.lock();
+        let path = testing::temp_dir("config_outputs").join("app.log");
+        let config = lum_libs::serde_json::from_value::<Config>(lum_libs::serde_json::json!({
+            "levels": { "config_test::db": "debug" },
+            "format": "logfmt",
+            "outputs": [{ "kind": "file", "path": path, "level": "info" }],
+        }))
+        .unwrap();
+        assert_eq!(config.level, default::log_level());
+        assert_eq!(config.outputs[0].name(), "file");
+
+        logger::setup(config.into_log4rs_config().unwrap()).unwrap();
+        log::debug!(target: "config_test::db", "Query");
+        log::info!(target: "config_test::db", "Connected");
+        logger::flush();
+
+        let log = std::fs::read_to_string(&path).unwrap();
+        assert!(
+            log.contains(" level=info target=config_test::db ")
+                && log.ends_with(" msg=Connected\n")
+                && !log.contains("Query"),
+            "{log}"
+        );
+    }
+
+    #[test]
+    fn deserialized_routes_send_matching_records_to_their_output() {
+        let _global = testing::GLOBAL.lock();
+        let dir = testing::temp_dir("config_routes");
+        let (app, audit) = (dir.join("app.log"), dir.join("audit.log"));
+        let config = lum_libs::serde_json::from_value::<Config>(lum_libs::serde_json::json!({
+            "level": "info",
+            "format": "logfmt",
+            "outputs": [
+                { "kind": "file", "path": app },
+                { "kind": "file", "name": "audit", "path": audit },
+            ],
+            "routes": [{
+                "rule": { "starts_with": "config_test::audit" },
+                "appender": "audit",
+                "exclusive": true,
+            }],
+        }))
+        .unwrap();
+
+        logger::setup(config.into_log4rs_config().unwrap()).unwrap();
+        log::info!(target: "config_test::audit", "Login");
+        log::info!(target: "config_test::http", "Request");
+        logger::flush();
+
+        let app = std::fs::read_to_string(&app).unwrap();
+        let audit = std::fs::read_to_string(&audit).unwrap();
+        assert!(
+            app.ends_with(" msg=Request\n") && !app.contains("Login"),
+            "{app}"
+        );
+        assert!(
+            audit.ends_with(" msg=Login\n") && !audit.contains("Request"),
+            "{audit}"
+        );
+    }
+
+    #[test]
+    fn lint_flags_suspicious_setups() {
+        let file = |path: &str, level| Output::File {
+            name: None,
+            path: PathBuf::from(path),
+            rolling: None,
+            level: Some(level),
+        };
+        let config = Config {
+            level: LevelFilter::Info,
+            levels: HashMap::from([("lint_test::db".to_string(), LevelFilter::Debug)]),
+            outputs: vec![
+                file("logs/app.log", LevelFilter::Info),
+                file("logs/errors.log", LevelFilter::Warn),
+            ],
+            ..Config::default()
+        };
+
+        let kinds = config
+            .lint()
+            .iter()
+            .map(|lint| lint.kind)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            [
+                LintKind::ColorsWithoutConsole,
+                LintKind::UnreachableLevel,
+                LintKind::DuplicateOutputName,
+            ]
+        );
+        assert_eq!(
+            config.lint()[1].to_string(),
+            "The level of lint_test::db is DEBUG, but no output writes records more verbose than INFO. \
+             Set the level of lint_test::db to INFO at most, or lower the level of an output."
+        );
+        assert_eq!(Config::default().lint(), []);
+    }
+}
@@ -0,0 +1,379 @@
+use std::{io, sync::Arc};
+
+use lum_libs::{
+    log::Record,
+    log4rs::encode::{Encode, Style, Write},
+    parking_lot::{RwLock, const_rwlock},
+    serde::{Deserialize, Serialize},
+};
+
+use crate::default;
+
+/// A hook wrapping every write of the console appenders created by this crate, see [`set_suspend_hook`].
+/// It must call the given function exactly once.
+pub type SuspendHook = dyn Fn(&mut dyn FnMut()) + Send + Sync;
+
+static SUSPEND_HOOK: RwLock<Option<Arc<SuspendHook>>> = const_rwlock(None);
+
+/// Sets a hook wrapping every write of the console appenders created by this crate,
+/// e.g. to hide active progress bars while a line is printed and redraw them afterwards,
+/// so log lines are printed above the bars instead of tearing them.
+/// The hook must call the given function exactly once.
+///
+/// ```text
+/// let bars = indicatif::MultiProgress::new();
+/// console::set_suspend_hook(move |write| bars.suspend(write));
+/// ```
+///
+/// With the `indicatif` feature, [`suspend_progress`] does this for a `MultiProgress`.
+pub fn set_suspend_hook(hook: impl Fn(&mut dyn FnMut()) + Send + Sync + 'static) {
+    *SUSPEND_HOOK.write() = Some(Arc::new(hook));
+}
+
+/// Removes the hook set by [`set_suspend_hook`].
+pub fn clear_suspend_hook() {
+    *SUSPEND_HOOK.write() = None;
+}
+
+/// Prints the lines of the console appenders created by this crate above the given progress bars,
+/// by suspending them while a line is written. This replaces any hook set by [`set_suspend_hook`].
+/// Add every progress bar to the `MultiProgress`, as bars outside of it are not suspended.
+#[cfg(feature = "indicatif")]
+pub fn suspend_progress(progress: indicatif::MultiProgress) {
+    set_suspend_hook(move |write| progress.suspend(write));
+}
+
+/// An encoder calling the wrapped encoder within the hook set by [`set_suspend_hook`], if any,
+/// and flushing the writer before the hook returns.
+/// Console appenders created by this crate are wrapped in it.
+#[derive(Debug)]
+pub struct SuspendingEncoder {
+    inner: Box<dyn Encode>,
+}
+
+impl SuspendingEncoder {
+    /// Creates a new `SuspendingEncoder` wrapping the given encoder.
+    pub fn new(inner: Box<dyn Encode>) -> Self {
+        Self { inner }
+    }
+}
+
+impl Encode for SuspendingEncoder {
+    fn encode(&self, w: &mut dyn Write, record: &Record) -> anyhow::Result<()> {
+        // The hook is cloned, so the lock is not held while it runs and may be replaced from within.
+        let Some(hook) = SUSPEND_HOOK.read().clone() else {
+            return self.inner.encode(w, record);
+        };
+
+        let mut result = None;
+        hook(&mut || {
+            result = Some(self.inner.encode(w, record).and_then(|()| Ok(w.flush()?)));
+        });
+
+        result.unwrap_or_else(|| {
+            Err(anyhow::anyhow!(
+                "The suspend hook did not call the write function"
+            ))
+        })
+    }
+}
+
+/// How lines wider than the terminal are rendered by a [`TerminalWidthEncoder`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde", rename_all = "snake_case")]
+pub enum LineOverflow {
+    /// Lines are rendered in full, letting the terminal wrap them.
+    #[default]
+    Full,
+    /// Lines are cut at the terminal width, ending with [`default::truncation_marker`].
+    Truncate,
+    /// Lines are wrapped at the terminal width, continuing with [`default::wrap_marker`].
+    Wrap,
+}
+
+/// Returns the width of the terminal stdout is attached to, in columns.
+/// Returns `None` if stdout is not a terminal, e.g. when it is redirected to a file or pipe.
+#[cfg(unix)]
+pub fn terminal_width() -> Option<usize> {
+    // SAFETY: `winsize` is a plain C struct, for which all zeroes is a valid value.
+    let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+    // SAFETY: `TIOCGWINSZ` writes a `winsize` to the given pointer, which points to one.
+    let result = unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) };
+    (result == 0 && size.ws_col > 0).then_some(usize::from(size.ws_col))
+}
+
+/// Returns the width of the terminal stdout is attached to, in columns, as reported by the `COLUMNS` environment variable.
+/// Returns `None` if stdout is not a terminal, e.g. when it is redirected to a file or pipe.
+#[cfg(not(unix))]
+pub fn terminal_width() -> Option<usize> {
+    use std::io::IsTerminal;
+
+    if !io::stdout().is_terminal() {
+        return None;
+    }
+    std::env::var("COLUMNS").ok()?.parse().ok()
+}
+
+/// An encoder truncating or wrapping the lines rendered by the wrapped encoder to the terminal width, keeping their styles.
+/// If stdout is not a terminal, lines are rendered in full, so redirected output is never cut.
+/// Widths are counted in characters, so wide characters, e.g. CJK or emoji, may still overflow.
+#[derive(Debug)]
+pub struct TerminalWidthEncoder {
+    inner: Box<dyn Encode>,
+    overflow: LineOverflow,
+    width: Option<usize>,
+    marker: Option<String>,
+}
+
+impl TerminalWidthEncoder {
+    /// Creates a new `TerminalWidthEncoder` wrapping the given encoder, handling wide lines as given.
+    pub fn new(inner: Box<dyn Encode>, overflow: LineOverflow) -> Self {
+        Self {
+            inner,
+            overflow,
+            width: None,
+            marker: None,
+        }
+    }
+
+    /// Sets a fixed width instead of the one returned by [`terminal_width`], which also applies if stdout is not a terminal.
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    /// Sets the marker ending truncated lines or starting wrapped continuation lines,
+    /// instead of [`default::truncation_marker`] or [`default::wrap_marker`].
+    pub fn marker(mut self, marker: impl Into<String>) -> Self {
+        self.marker = Some(marker.into());
+        self
+    }
+}
+
+impl Encode for TerminalWidthEncoder {
+    fn encode(&self, w: &mut dyn Write, record: &Record) -> anyhow::Result<()> {
+        let width = match self.overflow {
+            LineOverflow::Full => None,
+            LineOverflow::Truncate | LineOverflow::Wrap => self.width.or_else(terminal_width),
+        };
+        let Some(width) = width else {
+            return self.inner.encode(w, record);
+        };
+
+        let mut recorder = StyleRecorder::default();
+        self.inner.encode(&mut recorder, record)?;
+
+        let marker = match (&self.marker, self.overflow) {
+            (Some(marker), _) => marker.as_str(),
+            (None, LineOverflow::Wrap) => default::wrap_marker(),
+            (None, _) => default::truncation_marker(),
+        };
+        let marker_width = marker.chars().count();
+        // The width must leave room for at least one character next to the marker.
+        let width = width.max(marker_width + 1);
+
+        // Styles do not take up columns, so line widths are measured across text chunks first.
+        let mut line_widths = vec![0];
+        for chunk in &recorder.chunks {
+            if let Chunk::Text(text) = chunk {
+                for char in String::from_utf8_lossy(text).chars() {
+                    match char {
+                        '\n' => line_widths.push(0),
+                        '\r' => {}
+                        _ => *line_widths.last_mut().expect("There is always a line") += 1,
+                    }
+                }
+            }
+        }
+
+        let mut line = 0;
+        let mut column = 0;
+        let mut output = String::new();
+        for chunk in recorder.chunks {
+            let text = match chunk {
+                Chunk::Style(style) => {
+                    w.write_all(output.as_bytes())?;
+                    output.clear();
+                    w.set_style(&style)?;
+                    continue;
+                }
+                Chunk::Text(text) => text,
+            };
+
+            for char in String::from_utf8_lossy(&text).chars() {
+                if char == '\n' {
+                    output.push(char);
+                    line += 1;
+                    column = 0;
+                    continue;
+                }
+                if char == '\r' {
+                    output.push(char);
+                    continue;
+                }
+
+                match self.overflow {
+                    LineOverflow::Truncate if line_widths[line] > width => {
+                        let kept = width - marker_width;
+                        if column < kept {
+                            output.push(char);
+                        } else if column == kept {
+                            output.push_str(marker);
+                        }
+                    }
+                    LineOverflow::Wrap if column == width => {
+                        output.push('\n');
+                        output.push_str(marker);
+                        output.push(char);
+                        column = marker_width;
+                    }
+                    _ => output.push(char),
+                }
+                column += 1;
+            }
+        }
+
+        w.write_all(output.as_bytes())?;
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+enum Chunk {
+    Text(Vec<u8>),
+    Style(Style),
+}
+
+/// A writer recording the text and style changes written to it, to replay them after processing the text.
+#[derive(Debug, Default)]
+struct StyleRecorder {
+    chunks: Vec<Chunk>,
+}
+
+impl io::Write for StyleRecorder {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self.chunks.last_mut() {
+            Some(Chunk::Text(last)) => last.extend_from_slice(buf),
+            _ => self.chunks.push(Chunk::Text(buf.to_vec())),
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Write for StyleRecorder {
+    fn set_style(&mut self, style: &Style) -> io::Result<()> {
+        self.chunks.push(Chunk::Style(style.clone()));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lum_libs::{
+        log4rs::encode::{pattern::PatternEncoder, writer::simple::SimpleWriter},
+        parking_lot::Mutex,
+    };
+
+    use super::*;
+    use crate::testing;
+
+    fn encode(encoder: &dyn Encode) -> anyhow::Result<String> {
+        encode_message(encoder, "Message")
+    }
+
+    fn encode_message(encoder: &dyn Encode, message: &str) -> anyhow::Result<String> {
+        let mut output = SimpleWriter(Vec::new());
+        encoder.encode(
+            &mut output,
+            &Record::builder().args(format_args!("{message}")).build(),
+        )?;
+        Ok(String::from_utf8(output.0).expect("The output is UTF-8"))
+    }
+
+    #[test]
+    fn lines_are_written_within_the_suspend_hook() {
+        let _global = testing::GLOBAL.lock();
+        let encoder = SuspendingEncoder::new(Box::new(PatternEncoder::new("{m}{n}")));
+        let events = Arc::new(Mutex::new(Vec::new()));
+        set_suspend_hook({
+            let events = events.clone();
+            move |write| {
+                events.lock().push("hide");
+                write();
+                events.lock().push("redraw");
+            }
+        });
+
+        assert_eq!(encode(&encoder).unwrap(), "Message\n");
+        assert_eq!(*events.lock(), ["hide", "redraw"]);
+
+        set_suspend_hook(|_| {});
+        assert!(encode(&encoder).is_err());
+
+        clear_suspend_hook();
+        assert_eq!(encode(&encoder).unwrap(), "Message\n");
+        assert_eq!(events.lock().len(), 2);
+    }
+
+    fn width_encoder(overflow: LineOverflow) -> TerminalWidthEncoder {
+        TerminalWidthEncoder::new(Box::new(PatternEncoder::new("{h({m})}{n}")), overflow).width(8)
+    }
+
+    #[test]
+    fn wide_lines_are_truncated_or_wrapped_at_the_width() {
+        let truncate = width_encoder(LineOverflow::Truncate);
+        let wrap = width_encoder(LineOverflow::Wrap).marker("> ");
+        let full = width_encoder(LineOverflow::Full);
+
+        assert_eq!(encode_message(&truncate, "Short").unwrap(), "Short\n");
+        assert_eq!(encode_message(&truncate, "Exactly8").unwrap(), "Exactly8\n");
+        assert_eq!(
+            encode_message(&truncate, "Much too long").unwrap(),
+            format!("Much to{}\n", default::truncation_marker())
+        );
+        assert_eq!(
+            encode_message(&wrap, "Much too long").unwrap(),
+            "Much too\n>  long\n"
+        );
+        assert_eq!(
+            encode_message(&full, "Much too long").unwrap(),
+            "Much too long\n"
+        );
+    }
+
+    #[test]
+    fn styles_are_kept_around_truncated_text() {
+        let mut recorder = StyleRecorder::default();
+        width_encoder(LineOverflow::Truncate)
+            .encode(
+                &mut recorder,
+                &Record::builder()
+                    .level(lum_libs::log::Level::Error)
+                    .args(format_args!("Much too long"))
+                    .build(),
+            )
+            .unwrap();
+
+        let chunks = recorder
+            .chunks
+            .iter()
+            .map(|chunk| match chunk {
+                Chunk::Text(text) => String::from_utf8_lossy(text).into_owned(),
+                Chunk::Style(style) => format!("<{}>", style.text.is_some()),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            chunks,
+            [
+                "<true>".to_string(),
+                format!("Much to{}", default::truncation_marker()),
+                "<false>".to_string(),
+                "\n".to_string(),
+            ]
+        );
+    }
+}
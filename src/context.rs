@@ -0,0 +1,110 @@
+use std::fmt::{self, Display, Formatter};
+
+use log_mdc::InsertGuard;
+
+/// The MDC key under which [`error_panic!`](crate::error_panic) and [`error_unreachable!`](crate::error_unreachable)
+/// expose the location they were called at, as `<file>:<line>`, so the last record of a crash points to its source
+/// next to the MDC entries of the request or job that triggered it.
+/// Use it in patterns as `{X(lum_log.panic_location)}`, or use an encoder rendering the MDC, e.g. log4rs' `JsonEncoder`.
+pub const PANIC_LOCATION_MDC_KEY: &str = "lum_log.panic_location";
+
+/// Attaches the given location to the records logged on this thread until the returned guard is dropped.
+#[doc(hidden)]
+pub fn scope_location(file: &'static str, line: u32) -> InsertGuard {
+    log_mdc::insert_scoped(PANIC_LOCATION_MDC_KEY, format!("{file}:{line}"))
+}
+
+/// The diagnostic context of the current thread, i.e. its MDC entries, together with a source location.
+/// It is printed by [`error_panic!`](crate::error_panic) and [`error_unreachable!`](crate::error_unreachable)
+/// if the logger is not set up, so the crash still names the request or job that triggered it.
+///
+/// It is rendered as `at <file>:<line>` followed by the MDC entries, e.g.:
+/// ```text
+/// at src/jobs.rs:42 {correlation_id=0192f3c1-…, job=import}
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PanicContext {
+    pub file: &'static str,
+    pub line: u32,
+    pub fields: Vec<(String, String)>,
+}
+
+impl PanicContext {
+    /// Captures the MDC entries of the current thread, sorted by key, together with the given location.
+    pub fn capture(file: &'static str, line: u32) -> Self {
+        let mut fields = Vec::new();
+        log_mdc::iter(|key, value| fields.push((key.to_string(), value.to_string())));
+        fields.sort();
+
+        Self { file, line, fields }
+    }
+}
+
+impl Display for PanicContext {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "at {}:{}", self.file, self.line)?;
+
+        if self.fields.is_empty() {
+            return Ok(());
+        }
+
+        f.write_str(" {")?;
+        for (index, (key, value)) in self.fields.iter().enumerate() {
+            if index > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{key}={value}")?;
+        }
+        f.write_str("}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use lum_libs::log::LevelFilter;
+
+    use super::*;
+    use crate::{ConfigBuilder, testing};
+
+    #[test]
+    fn panic_records_carry_the_location_and_the_mdc_of_the_thread() {
+        let _global = testing::GLOBAL.lock();
+        let records = testing::capture(ConfigBuilder::new().root_log_level(LevelFilter::Info));
+
+        let result = thread::spawn(|| {
+            let _job = log_mdc::insert_scoped("job", "import");
+            let _correlation_id = log_mdc::insert_scoped("correlation_id", "7");
+            crate::error_panic!("Import failed: {}", 3);
+        })
+        .join();
+
+        assert!(result.is_err());
+        let record = records.try_recv().unwrap();
+        assert_eq!(record.message, "Import failed: 3");
+        let mut mdc = record.mdc;
+        let (key, location) = mdc.remove(2);
+        assert_eq!(key, PANIC_LOCATION_MDC_KEY);
+        assert!(location.starts_with(&format!("{}:", file!())), "{location}");
+        assert_eq!(
+            mdc,
+            [
+                ("correlation_id".to_string(), "7".to_string()),
+                ("job".to_string(), "import".to_string()),
+            ]
+        );
+        assert!(records.try_recv().is_err());
+    }
+
+    #[test]
+    fn contexts_without_mdc_entries_render_only_the_location() {
+        let context = PanicContext {
+            file: "src/jobs.rs",
+            line: 42,
+            fields: Vec::new(),
+        };
+
+        assert_eq!(context.to_string(), "at src/jobs.rs:42");
+    }
+}
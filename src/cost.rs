@@ -0,0 +1,214 @@
+use std::{
+    collections::HashMap,
+    fmt::{self, Display, Formatter},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+};
+
+use lum_libs::parking_lot::{RwLock, const_rwlock};
+
+static COST_ACCOUNTING: AtomicBool = AtomicBool::new(false);
+static COSTS: RwLock<Option<HashMap<String, Arc<Counters>>>> = const_rwlock(None);
+
+/// The counters of a single target.
+#[derive(Debug, Default)]
+struct Counters {
+    records: AtomicU64,
+    bytes: AtomicU64,
+}
+
+/// The records logged and bytes written for a single target, part of a [`CostReport`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TargetCost {
+    /// The target of the records.
+    pub target: String,
+    /// The number of records logged.
+    pub records: u64,
+    /// The number of bytes written by all appenders together, see [`set_cost_accounting`].
+    pub bytes: u64,
+}
+
+/// A snapshot of the records logged and bytes written per target since cost accounting was enabled, see [`cost_report`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CostReport {
+    /// The targets, sorted by bytes written in descending order, then by records logged and name.
+    pub targets: Vec<TargetCost>,
+}
+
+impl CostReport {
+    /// Returns the total number of records logged.
+    pub fn total_records(&self) -> u64 {
+        self.targets.iter().map(|target| target.records).sum()
+    }
+
+    /// Returns the total number of bytes written.
+    pub fn total_bytes(&self) -> u64 {
+        self.targets.iter().map(|target| target.bytes).sum()
+    }
+
+    /// Returns the cost of the given target, if it logged anything.
+    pub fn target(&self, target: &str) -> Option<&TargetCost> {
+        self.targets.iter().find(|cost| cost.target == target)
+    }
+}
+
+impl Display for CostReport {
+    /// Renders the report as a table with the share of each target in the total bytes, e.g.
+    /// ```text
+    /// bytes       share  records    target
+    /// 1048576     87.5%  9210       app::http
+    /// 149796      12.5%  1403       app::db
+    /// ```
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let total_bytes = self.total_bytes().max(1) as f64;
+        write!(
+            f,
+            "{:<10} {:>6}  {:<10} target",
+            "bytes", "share", "records"
+        )?;
+        for target in &self.targets {
+            write!(
+                f,
+                "\n{:<10} {:>5.1}%  {:<10} {}",
+                target.bytes,
+                target.bytes as f64 * 100.0 / total_bytes,
+                target.records,
+                target.target
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Sets whether the records logged and bytes written are counted per target, to find the subsystems responsible for log volume.
+/// Records are counted once they pass the level of their target, before any appender filters them.
+/// Bytes are counted when an encoder wrapped in a [`SafeEncoder`](crate::encode::SafeEncoder) writes a record, which the appenders of this crate do,
+/// summed over all appenders, so a record written to both the console and a file counts twice.
+/// Bytes written by other appenders, e.g. ones added by [`ConfigBuilder::appender`](crate::ConfigBuilder::appender), are not counted.
+/// Cost accounting is disabled by default, and counts are kept when it is disabled.
+/// See also [`ConfigBuilder::cost_accounting`](crate::ConfigBuilder::cost_accounting).
+pub fn set_cost_accounting(enabled: bool) {
+    COST_ACCOUNTING.store(enabled, Ordering::Relaxed);
+}
+
+/// Returns whether cost accounting is enabled, see [`set_cost_accounting`].
+pub fn cost_accounting() -> bool {
+    COST_ACCOUNTING.load(Ordering::Relaxed)
+}
+
+/// Returns a snapshot of the records logged and bytes written per target, see [`set_cost_accounting`].
+pub fn cost_report() -> CostReport {
+    let mut targets = COSTS
+        .read()
+        .iter()
+        .flatten()
+        .map(|(target, counters)| TargetCost {
+            target: target.clone(),
+            records: counters.records.load(Ordering::Relaxed),
+            bytes: counters.bytes.load(Ordering::Relaxed),
+        })
+        .collect::<Vec<_>>();
+    targets.sort_by(|a, b| {
+        (b.bytes, b.records)
+            .cmp(&(a.bytes, a.records))
+            .then_with(|| a.target.cmp(&b.target))
+    });
+
+    CostReport { targets }
+}
+
+/// Resets all counts to zero.
+pub fn reset_cost_report() {
+    *COSTS.write() = None;
+}
+
+/// Counts a record of the given target, if cost accounting is enabled.
+pub(crate) fn record_logged(target: &str) {
+    if cost_accounting() {
+        counters(target).records.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Counts the given number of bytes written for a record of the given target, if cost accounting is enabled.
+pub(crate) fn record_written(target: &str, bytes: usize) {
+    if cost_accounting() && bytes > 0 {
+        counters(target)
+            .bytes
+            .fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+}
+
+/// Returns the counters of the given target, creating them if needed.
+fn counters(target: &str) -> Arc<Counters> {
+    if let Some(counters) = COSTS.read().as_ref().and_then(|costs| costs.get(target)) {
+        return Arc::clone(counters);
+    }
+
+    let mut costs = COSTS.write();
+    let counters = costs
+        .get_or_insert_with(HashMap::new)
+        .entry(target.to_string())
+        .or_default();
+    Arc::clone(counters)
+}
+
+#[cfg(test)]
+mod tests {
+    use lum_libs::log::{self, LevelFilter};
+
+    use super::*;
+    use crate::{ConfigBuilder, testing};
+
+    #[test]
+    fn records_filtered_by_level_are_not_counted() {
+        let _global = testing::GLOBAL.lock();
+        let _records = testing::capture(
+            ConfigBuilder::new()
+                .root_log_level(LevelFilter::Info)
+                .log_level("cost_test::noisy", LevelFilter::Warn)
+                .cost_accounting(true),
+        );
+        reset_cost_report();
+
+        for _ in 0..10 {
+            log::debug!(target: "cost_test::noisy", "Filtered by the root level");
+            log::info!(target: "cost_test::noisy", "Filtered by the target level");
+        }
+        log::warn!(target: "cost_test::noisy", "Logged");
+        log::info!(target: "cost_test::quiet", "Logged");
+
+        let report = cost_report();
+        assert_eq!(report.target("cost_test::noisy").unwrap().records, 1);
+        assert_eq!(report.target("cost_test::quiet").unwrap().records, 1);
+        assert_eq!(report.total_records(), 2);
+    }
+
+    #[test]
+    fn report_is_sorted_by_bytes() {
+        let _global = testing::GLOBAL.lock();
+        set_cost_accounting(true);
+        reset_cost_report();
+
+        record_logged("cost_test::small");
+        record_written("cost_test::small", 10);
+        record_logged("cost_test::large");
+        record_written("cost_test::large", 100);
+        record_written("cost_test::large", 100);
+        set_cost_accounting(false);
+        record_logged("cost_test::small");
+
+        let report = cost_report();
+        let targets = report
+            .targets
+            .iter()
+            .map(|cost| (cost.target.as_str(), cost.records, cost.bytes))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            targets,
+            [("cost_test::large", 1, 200), ("cost_test::small", 1, 10)]
+        );
+        assert_eq!(report.total_bytes(), 210);
+    }
+}
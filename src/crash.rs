@@ -0,0 +1,196 @@
+use std::{
+    backtrace::Backtrace,
+    env,
+    fmt::Write as _,
+    fs,
+    io::{self, Write as _},
+    panic::{self, PanicHookInfo},
+    path::{Path, PathBuf},
+    thread,
+    time::SystemTime,
+};
+
+use lum_libs::humantime;
+
+use crate::{internal, subscribe};
+
+/// Creates a [`CrashReporter`] for the calling crate, using its `CARGO_PKG_NAME` and `CARGO_PKG_VERSION`.
+#[macro_export]
+macro_rules! crash_reporter {
+    () => {
+        $crate::crash::CrashReporter::new(
+            ::std::env!("CARGO_PKG_NAME"),
+            ::std::env!("CARGO_PKG_VERSION"),
+        )
+    };
+}
+
+/// A panic hook for end-user applications, e.g. CLI tools, in the spirit of `human-panic`.
+/// On panic, it writes a crash report to a file and prints a friendly message pointing to it to stderr,
+/// instead of the default panic output.
+/// The report contains the panic message and location, a backtrace, information about the environment,
+/// and the most recent records kept by [`subscribe::recent`], so add a [`BroadcastAppender`](crate::append::BroadcastAppender)
+/// to include them.
+#[derive(Debug, Clone)]
+pub struct CrashReporter {
+    name: String,
+    version: String,
+    directory: PathBuf,
+    support: Option<String>,
+}
+
+impl CrashReporter {
+    /// Creates a new `CrashReporter` for the application with the given name and version,
+    /// writing reports to the temporary directory. See also [`crash_reporter!`](crate::crash_reporter).
+    pub fn new(name: impl Into<String>, version: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            version: version.into(),
+            directory: env::temp_dir(),
+            support: None,
+        }
+    }
+
+    /// Sets the directory crash reports are written to. It is created if it does not exist.
+    pub fn directory(mut self, directory: impl Into<PathBuf>) -> Self {
+        self.directory = directory.into();
+        self
+    }
+
+    /// Sets where users should send crash reports, e.g. an issue tracker URL or an email address.
+    pub fn support(mut self, support: impl Into<String>) -> Self {
+        self.support = Some(support.into());
+        self
+    }
+
+    /// Installs this `CrashReporter` as the panic hook, replacing the previous one.
+    /// If the report cannot be written, the previous hook is called instead.
+    pub fn install(self) {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            // A panic while reporting must not abort the process through a double panic.
+            let path = internal::catch("Crash reporter", || self.write_report(info));
+            match path {
+                Some(Ok(path)) => self.print_message(&path),
+                Some(Err(error)) => {
+                    internal::report(format_args!("Failed to write crash report: {error}"));
+                    previous(info);
+                }
+                None => previous(info),
+            }
+        }));
+    }
+
+    /// Writes a crash report for the given panic, returning its path.
+    pub fn write_report(&self, info: &PanicHookInfo) -> io::Result<PathBuf> {
+        fs::create_dir_all(&self.directory)?;
+        let path =
+            self.directory
+                .join(format!("{}-crash-{}.txt", self.name, internal::unique_id()));
+        fs::write(&path, self.report(info))?;
+        Ok(path)
+    }
+
+    fn report(&self, info: &PanicHookInfo) -> String {
+        let message = internal::panic_message(info.payload());
+        let location = info
+            .location()
+            .map_or_else(|| "unknown".to_string(), ToString::to_string);
+        let thread = thread::current();
+
+        let mut report = String::new();
+        let _ = writeln!(report, "name: {}", self.name);
+        let _ = writeln!(report, "version: {}", self.version);
+        let _ = writeln!(
+            report,
+            "time: {}",
+            humantime::format_rfc3339_millis(SystemTime::now())
+        );
+        let _ = writeln!(report, "os: {} ({})", env::consts::OS, env::consts::ARCH);
+        let _ = writeln!(report, "arguments: {:?}", env::args().collect::<Vec<_>>());
+        let _ = writeln!(report, "thread: {}", thread.name().unwrap_or("<unnamed>"));
+        let _ = writeln!(report, "message: {message}");
+        let _ = writeln!(report, "location: {location}");
+
+        let _ = writeln!(report, "\nbacktrace:\n{}", Backtrace::force_capture());
+
+        let _ = writeln!(report, "recent records:");
+        for record in subscribe::recent() {
+            let _ = writeln!(
+                report,
+                "{} {:<5} {} {}",
+                humantime::format_rfc3339_millis(record.timestamp),
+                record.level,
+                record.target,
+                record.message
+            );
+        }
+
+        report
+    }
+
+    fn print_message(&self, path: &Path) {
+        let mut stderr = io::stderr().lock();
+        let _ = writeln!(
+            stderr,
+            "Well, this is embarrassing.\n\n{} {} had a problem and crashed. \
+             A report has been written to \"{}\".",
+            self.name,
+            self.version,
+            path.display()
+        );
+        if let Some(support) = &self.support {
+            let _ = writeln!(
+                stderr,
+                "To help us fix it, please send the report to {support}."
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lum_libs::log::Level;
+
+    use super::*;
+    use crate::testing;
+
+    #[test]
+    fn panics_write_a_report_with_the_context_and_recent_records() {
+        let _global = testing::GLOBAL.lock();
+        let directory = testing::temp_dir("crash");
+        subscribe::publish(testing::owned_record(
+            Level::Info,
+            "crash_test",
+            "Before the crash",
+        ));
+        crate::crash_reporter!()
+            .directory(&directory)
+            .support("https://example.com/issues")
+            .install();
+
+        let result = thread::Builder::new()
+            .name("crashing".to_string())
+            .spawn(|| panic!("Out of cheese"))
+            .unwrap()
+            .join();
+        // Restores the default panic hook.
+        drop(panic::take_hook());
+
+        assert!(result.is_err());
+        // Tests panicking on other threads meanwhile are reported as well, so the report is found by its thread.
+        let report = fs::read_dir(&directory)
+            .unwrap()
+            .map(|entry| fs::read_to_string(entry.unwrap().path()).unwrap())
+            .find(|report| report.contains("thread: crashing\n"))
+            .expect("A report is written");
+        assert!(report.starts_with(&format!(
+            "name: lum_log\nversion: {}\n",
+            env!("CARGO_PKG_VERSION")
+        )));
+        assert!(
+            report.contains("thread: crashing\nmessage: Out of cheese\nlocation: src/crash.rs:")
+        );
+        assert!(report.contains("INFO  crash_test Before the crash\n"));
+    }
+}
@@ -1,31 +1,249 @@
 use std::{
     io::{self},
-    path::Path,
+    path::{Path, PathBuf},
+    time::Duration,
 };
 
 use lum_libs::{
-    log::LevelFilter,
+    log::{Level, LevelFilter},
     log4rs::{
         append::{
-            console::ConsoleAppender,
-            rolling_file::{
-                RollingFileAppender,
-                policy::compound::{
-                    CompoundPolicy,
-                    roll::fixed_window::FixedWindowRoller,
-                    trigger::time::{TimeTrigger, TimeTriggerConfig, TimeTriggerInterval},
-                },
+            console::{ConsoleAppender, Target},
+            file::FileAppender,
+            rolling_file::policy::compound::{
+                CompoundPolicy,
+                roll::fixed_window::FixedWindowRoller,
+                trigger::time::{TimeTrigger, TimeTriggerConfig, TimeTriggerInterval},
             },
         },
-        encode::pattern::PatternEncoder,
+        encode::{Encode, pattern::PatternEncoder},
     },
 };
 
+use crate::{
+    console::SuspendingEncoder,
+    encode::{JsonEncoder, LevelNameEncoder, LogfmtEncoder, SafeEncoder, StripAnsiEncoder},
+    level::{LevelNames, LevelStyle},
+    rotate::{ManualRollingFileAppender, ManualTrigger, NotifyingRoller},
+    stdio::UncapturedEncoder,
+    timestamp::TimestampFormat,
+};
+
 /// Returns the log level [`LevelFilter::Info`].
 pub fn log_level() -> LevelFilter {
     LevelFilter::Info
 }
 
+/// Returns the log level of [`testing::try_init_quiet`](crate::testing::try_init_quiet), which is [`LevelFilter::Off`].
+pub fn test_log_level() -> LevelFilter {
+    LevelFilter::Off
+}
+
+/// Returns the log level [`LevelFilter::Warn`].
+/// This is the minimum level of records written to the errors file by [`errors_rolling_file_appender`].
+pub fn errors_log_level() -> LevelFilter {
+    LevelFilter::Warn
+}
+
+/// Returns the number of most recent records kept for subscribers, which is 1000.
+pub const fn history_capacity() -> usize {
+    1000
+}
+
+/// Returns the channel capacity of a subscription, which is 1024.
+pub const fn subscription_capacity() -> usize {
+    1024
+}
+
+/// Returns the interval between two free disk space checks of a [`DiskGuard`](crate::disk::DiskGuard), which is 30 seconds.
+#[cfg(feature = "disk-guard")]
+pub fn disk_check_interval() -> Duration {
+    Duration::from_secs(30)
+}
+
+/// Returns the interval in which a failed primary of a [`FailoverAppender`](crate::append::FailoverAppender) is retried, which is 10 seconds.
+pub fn failover_retry_interval() -> Duration {
+    Duration::from_secs(10)
+}
+
+/// Returns the maximum backoff of a [`RetryPolicy`](crate::retry::RetryPolicy), which is 30 seconds.
+pub fn max_backoff() -> Duration {
+    Duration::from_secs(30)
+}
+
+/// Returns the number of records a [`NetworkAppender`](crate::append::NetworkAppender) buffers, which is 10000.
+pub fn network_buffer_capacity() -> usize {
+    10_000
+}
+
+/// Returns the connect and write timeout of network transports, which is 5 seconds.
+pub fn network_timeout() -> Duration {
+    Duration::from_secs(5)
+}
+
+/// Returns the client-streaming method a [`GrpcTransport`](crate::append::network::grpc::GrpcTransport) calls,
+/// which is `"/lum_log.LogIngestion/Stream"`.
+#[cfg(feature = "grpc")]
+pub fn grpc_method() -> &'static str {
+    "/lum_log.LogIngestion/Stream"
+}
+
+/// Returns the number of records a [`GrpcTransport`](crate::append::network::grpc::GrpcTransport) buffers
+/// in its stream before sending blocks, which is 64.
+#[cfg(feature = "grpc")]
+pub fn grpc_stream_capacity() -> usize {
+    64
+}
+
+/// Returns the number of records a [`ZmqTransport`](crate::append::network::zeromq::ZmqTransport) queues per subscriber, which is 1000.
+#[cfg(feature = "zeromq")]
+pub fn zeromq_high_water_mark() -> i32 {
+    1000
+}
+
+/// Returns the subject template of a [`NatsTransport`](crate::append::network::nats::NatsTransport), which is `"logs.{hostname}"`.
+#[cfg(feature = "nats")]
+pub fn nats_subject() -> &'static str {
+    "logs.{hostname}"
+}
+
+/// Returns the topic template of an [`MqttTransport`](crate::append::network::mqtt::MqttTransport), which is `"devices/{device_id}/logs"`.
+#[cfg(feature = "mqtt")]
+pub fn mqtt_topic() -> &'static str {
+    "devices/{device_id}/logs"
+}
+
+/// Returns the number of requests an MQTT client created by an [`MqttTransport`](crate::append::network::mqtt::MqttTransport) queues, which is 100.
+#[cfg(feature = "mqtt")]
+pub fn mqtt_request_capacity() -> usize {
+    100
+}
+
+/// Returns the time an [`MqttTransport`](crate::append::network::mqtt::MqttTransport) waits before reconnecting after its connection failed, which is 1 second.
+#[cfg(feature = "mqtt")]
+pub fn mqtt_reconnect_interval() -> Duration {
+    Duration::from_secs(1)
+}
+
+/// Returns the maximum size of a single [`Spool`](crate::spool::Spool) segment file, which is 1 MiB.
+pub fn spool_segment_bytes() -> u64 {
+    1024 * 1024
+}
+
+/// Returns the maximum size of a single [`WriteAheadLog`](crate::wal::WriteAheadLog) segment file, which is 4 MiB.
+pub fn wal_segment_bytes() -> u64 {
+    4 * 1024 * 1024
+}
+
+/// Returns the number of records an [`AsyncAppender`](crate::append::AsyncAppender) buffers, which is 10000.
+pub fn async_buffer_capacity() -> usize {
+    10_000
+}
+
+/// Returns the number of consecutive records an appender must exceed its latency budget on before a warning is logged,
+/// see [`latency::set_latency_budget`](crate::latency::set_latency_budget), which is 10.
+pub fn slow_appender_records() -> u32 {
+    10
+}
+
+/// Returns the minimum interval between two warnings about the same slow appender, which is 1 minute.
+pub fn slow_appender_warning_interval() -> Duration {
+    Duration::from_secs(60)
+}
+
+/// Returns the window in which an [`AnomalyDetection`](crate::anomaly::AnomalyDetection) counts records, which is 10 seconds.
+pub fn anomaly_window() -> Duration {
+    Duration::from_secs(10)
+}
+
+/// Returns the factor by which the records of a window must exceed the baseline
+/// to be reported by an [`AnomalyDetection`](crate::anomaly::AnomalyDetection), which is 10.
+pub fn anomaly_factor() -> f64 {
+    10.0
+}
+
+/// Returns the minimum number of records of a window to be reported by an [`AnomalyDetection`](crate::anomaly::AnomalyDetection), which is 100.
+pub fn anomaly_min_records() -> u64 {
+    100
+}
+
+/// Returns the minimum interval between two warnings about the record rate of the same target, which is 1 minute.
+pub fn anomaly_warning_interval() -> Duration {
+    Duration::from_secs(60)
+}
+
+/// Returns the interval in which a file watched by [`watch_config`](crate::logger::watch_config) is checked for changes, which is 2 seconds.
+pub fn config_watch_interval() -> Duration {
+    Duration::from_secs(2)
+}
+
+/// Returns the interval in which replaying spilled records is retried after it failed, which is 5 seconds.
+pub fn spool_replay_interval() -> Duration {
+    Duration::from_secs(5)
+}
+
+/// Returns the maximum time flushing an appender waits for buffered records, which is 5 seconds.
+pub fn flush_timeout() -> Duration {
+    Duration::from_secs(5)
+}
+
+/// Returns the maximum time [`shutdown`](crate::shutdown) waits for the buffers of async appenders to drain, which is 10 seconds.
+pub fn shutdown_timeout() -> Duration {
+    Duration::from_secs(10)
+}
+
+/// Returns the size of each slot of a `ShmRingAppender` (feature `shm`) in bytes, which is 256.
+pub fn shm_slot_size() -> usize {
+    256
+}
+
+/// Returns the number of slots of a `ShmRingAppender` (feature `shm`), which is 4096.
+pub fn shm_slots() -> usize {
+    4096
+}
+
+/// Returns the number of bytes the `PwriteFileAppender` (feature `pwrite`) preallocates at once, which is 64 MiB.
+pub fn preallocate_bytes() -> u64 {
+    64 * 1024 * 1024
+}
+
+/// Returns the maximum number of bytes dumped by a [`HexDump`](crate::hex::HexDump), which is 4096.
+pub fn hex_dump_max_len() -> usize {
+    4096
+}
+
+/// Returns the prefix framing every line of a [`PrettyDebug`](crate::pretty::PrettyDebug), which is `"  | "`.
+pub fn pretty_prefix() -> &'static str {
+    "  | "
+}
+
+/// Returns the level at which the stdout lines of a [`ChildLogger`](crate::process::ChildLogger) are logged, which is [`Level::Info`].
+pub fn child_stdout_level() -> Level {
+    Level::Info
+}
+
+/// Returns the level at which the stderr lines of a [`ChildLogger`](crate::process::ChildLogger) are logged, which is [`Level::Warn`].
+pub fn child_stderr_level() -> Level {
+    Level::Warn
+}
+
+/// Returns the replacement of text matched by a [`RedactionRule`](crate::redaction::RedactionRule) without an explicit one,
+/// which is [`REDACTED`](crate::http::client::REDACTED).
+#[cfg(feature = "redaction")]
+pub fn redaction_replacement() -> &'static str {
+    crate::http::client::REDACTED
+}
+
+/// Returns the marker ending lines truncated by a [`TerminalWidthEncoder`](crate::console::TerminalWidthEncoder), which is `"…"`.
+pub fn truncation_marker() -> &'static str {
+    "…"
+}
+
+/// Returns the marker starting continuation lines wrapped by a [`TerminalWidthEncoder`](crate::console::TerminalWidthEncoder), which is `"  ↳ "`.
+pub fn wrap_marker() -> &'static str {
+    "  ↳ "
+}
+
 /// Returns a general-purpose log format string.
 /// The format resolves to the following:
 /// ```text
@@ -35,10 +253,89 @@ pub fn format() -> &'static str {
     "[{d(%Y-%m-%d %H:%M:%S%.3f)} {T:<-10.10} {t:<-40.40} {h({l:<5})}] {m}{n}"
 }
 
+/// Returns the format of [`testing::try_init_quiet`](crate::testing::try_init_quiet), which is the [`format()`] without colors.
+pub fn test_format() -> &'static str {
+    "[{d(%Y-%m-%d %H:%M:%S%.3f)} {T:<-10.10} {t:<-40.40} {l:<5}] {m}{n}"
+}
+
+/// Returns the format returned by [`format()`] with an RFC 3339 timestamp in UTC with millisecond precision.
+/// The format resolves to the following:
+/// ```text
+/// [2024-11-12T21:10:32.123Z main example::module::path INFO ] This is a log message
+/// ```
+pub fn format_rfc3339() -> String {
+    format_with_timestamp(&TimestampFormat::default())
+}
+
+/// Returns the format returned by [`format()`] with its timestamp rendered in the given [`TimestampFormat`].
+pub fn format_with_timestamp(timestamp_format: &TimestampFormat) -> String {
+    format_with_style(Some(timestamp_format), LevelStyle::Level)
+}
+
+/// Returns the format returned by [`format()`], coloring the parts of the line selected by the given [`LevelStyle`].
+/// If a [`TimestampFormat`] is given, the timestamp is rendered in it, like [`format_with_timestamp`].
+pub fn format_with_style(timestamp_format: Option<&TimestampFormat>, style: LevelStyle) -> String {
+    let timestamp = timestamp_format.map_or_else(
+        || "{d(%Y-%m-%d %H:%M:%S%.3f)}".to_string(),
+        TimestampFormat::pattern,
+    );
+
+    match style {
+        LevelStyle::Level => {
+            format!("[{timestamp} {{T:<-10.10}} {{t:<-40.40}} {{h({{l:<5}})}}] {{m}}{{n}}")
+        }
+        LevelStyle::LevelAndMessage => {
+            format!("[{timestamp} {{T:<-10.10}} {{t:<-40.40}} {{h({{l:<5}})}}] {{h({{m}})}}{{n}}")
+        }
+        LevelStyle::Line => {
+            format!("{{h([{timestamp} {{T:<-10.10}} {{t:<-40.40}} {{l:<5}}] {{m}})}}{{n}}")
+        }
+    }
+}
+
+/// Returns a [`LevelNameEncoder`] using the format returned by [`format()`],
+/// rendering levels with the given [`LevelNames`].
+pub fn level_name_encoder(level_names: LevelNames) -> LevelNameEncoder {
+    LevelNameEncoder::pattern(format(), level_names)
+}
+
+/// Returns a [`JsonEncoder`], rendering every record as a single line of JSON with its timestamp, level, target, thread, message,
+/// module path, file, line, and the MDC as key-values, e.g. the payload of [`log_json!`](crate::log_json).
+/// The format resolves to the following:
+/// ```text
+/// {"time":"2024-11-12T21:10:32.123456789+00:00","level":"INFO","message":"This is a log message","module_path":"example::module::path","file":"src/module/path.rs","line":42,"target":"example::module::path","thread":"main","thread_id":1,"mdc":{}}
+/// ```
+pub fn json_encoder() -> JsonEncoder {
+    JsonEncoder::new()
+}
+
+/// Returns a [`LogfmtEncoder`], writing every record as a single logfmt line with its timestamp, level, target, thread, message,
+/// and the MDC and JSON payload as key-values.
+/// The format resolves to the following:
+/// ```text
+/// time=2024-11-12T21:10:32.123Z level=info target=example::module::path thread=main msg="This is a log message"
+/// ```
+pub fn logfmt_encoder() -> LogfmtEncoder {
+    LogfmtEncoder::new()
+}
+
 /// Returns a [`ConsoleAppender`] with a [`PatternEncoder`] using the format returned by [`format()`].
 pub fn console_appender() -> ConsoleAppender {
+    console_appender_with_encoder(Box::new(PatternEncoder::new(format())))
+}
+
+/// Returns a [`ConsoleAppender`] using the given encoder, wrapped in an [`UncapturedEncoder`], a [`SuspendingEncoder`], and a [`SafeEncoder`].
+pub fn console_appender_with_encoder(encoder: Box<dyn Encode>) -> ConsoleAppender {
+    console_appender_with_target(encoder, Target::Stdout)
+}
+
+/// Returns a [`ConsoleAppender`] like [`console_appender_with_encoder`], writing to the given stream.
+pub fn console_appender_with_target(encoder: Box<dyn Encode>, target: Target) -> ConsoleAppender {
+    let encoder = Box::new(UncapturedEncoder::new(encoder, target));
+    let encoder = Box::new(SafeEncoder::new(Box::new(SuspendingEncoder::new(encoder))));
     ConsoleAppender::builder()
-        .encoder(Box::new(PatternEncoder::new(format())))
+        .encoder(encoder)
+        .target(target)
         .build()
 }
 
@@ -51,30 +348,156 @@ pub fn time_trigger_config() -> TimeTriggerConfig {
     }
 }
 
-/// Returns a [`RollingFileAppender`] with a [`PatternEncoder`]
+/// Returns a [`FileAppender`] using the given encoder, wrapped in a [`StripAnsiEncoder`] and a [`SafeEncoder`],
+/// appending to the given path without ever rolling it.
+pub fn file_appender_with_encoder(
+    path: impl AsRef<Path>,
+    encoder: Box<dyn Encode>,
+) -> io::Result<FileAppender> {
+    let encoder = Box::new(SafeEncoder::new(Box::new(StripAnsiEncoder::new(encoder))));
+    FileAppender::builder().encoder(encoder).build(path)
+}
+
+/// Returns the number of rolled files kept by rolling file appenders, which is 10.
+pub fn rolled_file_count() -> u32 {
+    10
+}
+
+/// Returns a [`ManualRollingFileAppender`] with a [`PatternEncoder`]
 /// using the format returned by [`format()`],
-/// and the [`TimeTriggerConfig`] provided by [`time_trigger_config()`],
+/// the [`TimeTriggerConfig`] provided by [`time_trigger_config()`] wrapped in a [`ManualTrigger`],
+/// and a [`NotifyingRoller`],
+/// writing to the given path.
+pub fn rolling_file_appender(path: impl AsRef<Path>) -> io::Result<ManualRollingFileAppender> {
+    rolling_file_appender_with_encoder(path, Box::new(PatternEncoder::new(format())))
+}
+
+/// Returns a [`ManualRollingFileAppender`] using the given encoder, wrapped in a [`StripAnsiEncoder`] and a [`SafeEncoder`],
+/// the [`TimeTriggerConfig`] provided by [`time_trigger_config()`] wrapped in a [`ManualTrigger`],
+/// and a [`NotifyingRoller`],
+/// writing to the given path.
+pub fn rolling_file_appender_with_encoder(
+    path: impl AsRef<Path>,
+    encoder: Box<dyn Encode>,
+) -> io::Result<ManualRollingFileAppender> {
+    time_rolling_file_appender(path, encoder, "{}.log")
+}
+
+/// Returns the path of the errors file belonging to the given log file path,
+/// e.g. `logs/app.errors.log` for `logs/app.log`.
+pub fn errors_file_path(path: impl AsRef<Path>) -> PathBuf {
+    let path = path.as_ref();
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let file_name = match path.extension() {
+        Some(extension) => format!("{stem}.errors.{}", extension.to_string_lossy()),
+        None => format!("{stem}.errors"),
+    };
+
+    path.with_file_name(file_name)
+}
+
+/// Returns the path of the file of the given shard of a [`ShardedAppender`](crate::append::ShardedAppender) belonging to the given log file path,
+/// e.g. `logs/tenants.3.log` for `logs/tenants.log` and shard 3.
+pub fn shard_file_path(path: impl AsRef<Path>, shard: usize) -> PathBuf {
+    let path = path.as_ref();
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let file_name = match path.extension() {
+        Some(extension) => format!("{stem}.{shard}.{}", extension.to_string_lossy()),
+        None => format!("{stem}.{shard}"),
+    };
+
+    path.with_file_name(file_name)
+}
+
+/// Returns the path of the file of the given time window of a [`PartitionedFileAppender`](crate::append::PartitionedFileAppender) belonging to the given log file path,
+/// e.g. `logs/app-2025-01-30-14.log` for `logs/app.log` and the window `2025-01-30-14`.
+pub fn partition_file_path(path: impl AsRef<Path>, window: &str) -> PathBuf {
+    let path = path.as_ref();
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let file_name = match path.extension() {
+        Some(extension) => format!("{stem}-{window}.{}", extension.to_string_lossy()),
+        None => format!("{stem}-{window}"),
+    };
+
+    path.with_file_name(file_name)
+}
+
+/// Returns the path of the checksum sidecar belonging to the given file path, see [`checksum::write_sidecar`](crate::checksum::write_sidecar),
+/// e.g. `logs/app.log.0.sha256` for `logs/app.log.0`.
+pub fn checksum_file_path(path: impl AsRef<Path>) -> PathBuf {
+    let mut path = path.as_ref().as_os_str().to_owned();
+    path.push(".sha256");
+    PathBuf::from(path)
+}
+
+/// Returns a [`ManualRollingFileAppender`] like [`rolling_file_appender`],
+/// writing to the errors file path returned by [`errors_file_path`] for the given path.
+/// Rolled files are named `{}.errors.log` so they do not collide with those of [`rolling_file_appender`].
+/// Note that the appender itself does not filter records; see [`errors_log_level`].
+pub fn errors_rolling_file_appender(
+    path: impl AsRef<Path>,
+) -> io::Result<ManualRollingFileAppender> {
+    errors_rolling_file_appender_with_encoder(path, Box::new(PatternEncoder::new(format())))
+}
+
+/// Returns a [`ManualRollingFileAppender`] like [`errors_rolling_file_appender`], using the given encoder.
+pub fn errors_rolling_file_appender_with_encoder(
+    path: impl AsRef<Path>,
+    encoder: Box<dyn Encode>,
+) -> io::Result<ManualRollingFileAppender> {
+    time_rolling_file_appender(errors_file_path(path), encoder, "{}.errors.log")
+}
+
+fn time_rolling_file_appender(
+    path: impl AsRef<Path>,
+    encoder: Box<dyn Encode>,
+    roller_pattern: &str,
+) -> io::Result<ManualRollingFileAppender> {
+    rolling_file_appender_with_policy(
+        path,
+        encoder,
+        time_trigger_config(),
+        roller_pattern,
+        rolled_file_count(),
+    )
+}
+
+/// Returns a [`ManualRollingFileAppender`] using the given encoder, wrapped in a [`StripAnsiEncoder`] and a [`SafeEncoder`],
+/// the given [`TimeTriggerConfig`] wrapped in a [`ManualTrigger`],
+/// and a [`NotifyingRoller`] keeping the given number of rolled files named by the given pattern, where `{}` is the index of a file,
 /// writing to the given path.
-pub fn rolling_file_appender(path: impl AsRef<Path>) -> io::Result<RollingFileAppender> {
-    RollingFileAppender::builder()
-        .encoder(Box::new(PatternEncoder::new(format())))
-        .build(
-            path,
-            Box::new(CompoundPolicy::new(
-                Box::new(TimeTrigger::new(time_trigger_config())),
-                Box::new(
-                    FixedWindowRoller::builder()
-                        .base(0)
-                        .build("{}.log", 10)
-                        .expect("Hard-coded example should always build successfully"),
-                ),
+/// Fails if the pattern does not contain `{}`.
+pub fn rolling_file_appender_with_policy(
+    path: impl AsRef<Path>,
+    encoder: Box<dyn Encode>,
+    trigger: TimeTriggerConfig,
+    roller_pattern: &str,
+    count: u32,
+) -> io::Result<ManualRollingFileAppender> {
+    let roller = FixedWindowRoller::builder()
+        .base(0)
+        .build(roller_pattern, count)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error.to_string()))?;
+
+    let encoder = Box::new(SafeEncoder::new(Box::new(StripAnsiEncoder::new(encoder))));
+    ManualRollingFileAppender::new(
+        path,
+        encoder,
+        Box::new(CompoundPolicy::new(
+            Box::new(ManualTrigger::new(Box::new(TimeTrigger::new(trigger)))),
+            Box::new(NotifyingRoller::new(
+                Box::new(roller),
+                roller_pattern.replace("{}", "0"),
             )),
-        )
+        )),
+    )
 }
 
-/// Returns a tuple of the [`ConsoleAppender`] and [`RollingFileAppender`]
+/// Returns a tuple of the [`ConsoleAppender`] and [`ManualRollingFileAppender`]
 /// returned by [`console_appender`] and [`rolling_file_appender`], respectively.
-pub fn appenders(path: impl AsRef<Path>) -> (ConsoleAppender, io::Result<RollingFileAppender>) {
+pub fn appenders(
+    path: impl AsRef<Path>,
+) -> (ConsoleAppender, io::Result<ManualRollingFileAppender>) {
     let console_appender = console_appender();
     let rolling_file_appender = rolling_file_appender(path);
 
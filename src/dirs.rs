@@ -0,0 +1,70 @@
+use std::{env, path::PathBuf};
+
+/// Returns the platform-appropriate directory for the log files of the application with the given name:
+/// - Linux and other Unix systems: `$XDG_STATE_HOME/<app>`, falling back to `~/.local/state/<app>`, as specified by the XDG Base Directory Specification.
+/// - macOS: `~/Library/Logs/<app>`.
+/// - Windows: `%LOCALAPPDATA%\<app>\logs`.
+///
+/// Returns `None` if the relevant environment variables are not set, e.g. for system services without a home directory.
+/// The directory is not created; appenders create it when opening their files.
+pub fn log_dir(app_name: &str) -> Option<PathBuf> {
+    platform_log_dir(app_name)
+}
+
+/// Returns the path of the main log file of the application with the given name, `<app>.log` in [`log_dir`].
+pub fn log_file(app_name: &str) -> Option<PathBuf> {
+    log_dir(app_name).map(|dir| dir.join(format!("{app_name}.log")))
+}
+
+#[cfg(target_os = "macos")]
+fn platform_log_dir(app_name: &str) -> Option<PathBuf> {
+    Some(home_dir()?.join("Library").join("Logs").join(app_name))
+}
+
+#[cfg(windows)]
+fn platform_log_dir(app_name: &str) -> Option<PathBuf> {
+    let local_app_data = env_path("LOCALAPPDATA")?;
+    Some(local_app_data.join(app_name).join("logs"))
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn platform_log_dir(app_name: &str) -> Option<PathBuf> {
+    let state_home = match env_path("XDG_STATE_HOME") {
+        Some(state_home) => state_home,
+        None => home_dir()?.join(".local").join("state"),
+    };
+    Some(state_home.join(app_name))
+}
+
+#[cfg(not(windows))]
+fn home_dir() -> Option<PathBuf> {
+    env_path("HOME")
+}
+
+/// Returns the value of the given environment variable as a path, if it is set to an absolute path.
+/// Relative paths are ignored, as the XDG Base Directory Specification requires.
+fn env_path(key: &str) -> Option<PathBuf> {
+    let path = PathBuf::from(env::var_os(key)?);
+    path.is_absolute().then_some(path)
+}
+
+#[cfg(all(test, not(any(target_os = "macos", windows))))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn log_files_are_placed_in_the_xdg_state_directory() {
+        // The environment is not modified, as other tests read it concurrently.
+        let state_home = env_path("XDG_STATE_HOME")
+            .or_else(|| Some(env_path("HOME")?.join(".local").join("state")));
+
+        assert_eq!(
+            log_dir("my-app"),
+            state_home.as_ref().map(|dir| dir.join("my-app"))
+        );
+        assert_eq!(
+            log_file("my-app"),
+            state_home.map(|dir| dir.join("my-app").join("my-app.log"))
+        );
+    }
+}
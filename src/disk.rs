@@ -0,0 +1,279 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, RecvTimeoutError, Sender},
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use lum_libs::{
+    log::{Level, Record},
+    log4rs::filter::{Filter, Response},
+};
+
+use crate::{default, info, warn};
+
+/// What file appenders guarded by a [`DiskGuard`] write while free disk space is low.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DegradedMode {
+    /// Only error records are written.
+    ErrorsOnly,
+    /// No records are written, leaving only the unguarded appenders, e.g. the console.
+    ConsoleOnly,
+}
+
+#[derive(Debug)]
+struct DiskGuardState {
+    mode: DegradedMode,
+    degraded: AtomicBool,
+}
+
+/// Protects the volume holding the log files from filling up.
+/// A watchdog thread started by [`DiskGuard::spawn`] periodically checks the free space of the volume
+/// containing the given path. While it is below the threshold, the appenders guarded by
+/// [`DiskGuard::filter`] switch to the [`DegradedMode`], and a warning is logged.
+/// Once enough space is available again, they switch back and an info record is logged.
+#[derive(Debug, Clone)]
+pub struct DiskGuard {
+    path: PathBuf,
+    min_free_bytes: u64,
+    interval: Duration,
+    state: Arc<DiskGuardState>,
+}
+
+impl DiskGuard {
+    /// Creates a new `DiskGuard` for the volume containing the given path,
+    /// degrading to [`DegradedMode::ErrorsOnly`] below the given number of free bytes.
+    /// The check interval defaults to [`default::disk_check_interval`].
+    pub fn new(path: impl Into<PathBuf>, min_free_bytes: u64) -> Self {
+        Self {
+            path: path.into(),
+            min_free_bytes,
+            interval: default::disk_check_interval(),
+            state: Arc::new(DiskGuardState {
+                mode: DegradedMode::ErrorsOnly,
+                degraded: AtomicBool::new(false),
+            }),
+        }
+    }
+
+    /// Sets the mode to switch to while free disk space is low.
+    /// This must be called before creating filters with [`DiskGuard::filter`].
+    pub fn mode(mut self, mode: DegradedMode) -> Self {
+        self.state = Arc::new(DiskGuardState {
+            mode,
+            degraded: AtomicBool::new(self.state.degraded.load(Ordering::Relaxed)),
+        });
+        self
+    }
+
+    /// Sets the interval between two checks.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Returns whether the guarded appenders are currently degraded.
+    pub fn is_degraded(&self) -> bool {
+        self.state.degraded.load(Ordering::Relaxed)
+    }
+
+    /// Returns a filter to attach to the appenders guarded by this `DiskGuard`.
+    pub fn filter(&self) -> DiskGuardFilter {
+        DiskGuardFilter {
+            state: Arc::clone(&self.state),
+        }
+    }
+
+    /// Checks the free space once and updates the degraded state, logging on changes.
+    /// Returns the number of free bytes.
+    pub fn check(&self) -> io::Result<u64> {
+        let free = free_space(&self.path)?;
+        let degraded = free < self.min_free_bytes;
+
+        if self.state.degraded.swap(degraded, Ordering::Relaxed) != degraded {
+            if degraded {
+                warn!(
+                    "Low disk space for {}: {free} bytes free, below {} bytes. Switching log files to {:?}",
+                    self.path.display(),
+                    self.min_free_bytes,
+                    self.state.mode
+                );
+            } else {
+                info!(
+                    "Disk space for {} recovered: {free} bytes free. Resuming normal logging to files",
+                    self.path.display()
+                );
+            }
+        }
+
+        Ok(free)
+    }
+
+    /// Spawns the watchdog thread, which calls [`DiskGuard::check`] periodically.
+    /// The thread stops when the returned handle is dropped.
+    pub fn spawn(&self) -> io::Result<DiskGuardHandle> {
+        let guard = self.clone();
+        let (stop, stopped) = mpsc::channel::<()>();
+
+        let thread = thread::Builder::new()
+            .name("lum_log-disk".to_string())
+            .spawn(move || {
+                loop {
+                    if let Err(error) = guard.check() {
+                        warn!(
+                            "Failed to check free disk space for {}: {error}",
+                            guard.path.display()
+                        );
+                    }
+
+                    match stopped.recv_timeout(guard.interval) {
+                        Err(RecvTimeoutError::Timeout) => continue,
+                        Ok(()) | Err(RecvTimeoutError::Disconnected) => break,
+                    }
+                }
+            })?;
+
+        Ok(DiskGuardHandle {
+            stop: Some(stop),
+            thread: Some(thread),
+        })
+    }
+}
+
+/// Handle to the watchdog thread spawned by [`DiskGuard::spawn`], stopping it when dropped.
+#[derive(Debug)]
+pub struct DiskGuardHandle {
+    stop: Option<Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Drop for DiskGuardHandle {
+    fn drop(&mut self) {
+        drop(self.stop.take());
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// A filter applying the [`DegradedMode`] of a [`DiskGuard`] while free disk space is low.
+#[derive(Debug, Clone)]
+pub struct DiskGuardFilter {
+    state: Arc<DiskGuardState>,
+}
+
+impl Filter for DiskGuardFilter {
+    fn filter(&self, record: &Record) -> Response {
+        if !self.state.degraded.load(Ordering::Relaxed) {
+            return Response::Neutral;
+        }
+
+        match self.state.mode {
+            DegradedMode::ErrorsOnly if record.level() == Level::Error => Response::Neutral,
+            DegradedMode::ErrorsOnly | DegradedMode::ConsoleOnly => Response::Reject,
+        }
+    }
+}
+
+/// Returns the number of bytes available to unprivileged users on the volume containing the given path.
+/// If the path does not exist yet, its closest existing ancestor is used.
+#[cfg(unix)]
+pub fn free_space(path: impl AsRef<Path>) -> io::Result<u64> {
+    use std::{ffi::CString, mem::MaybeUninit, os::unix::ffi::OsStrExt};
+
+    let path = path
+        .as_ref()
+        .ancestors()
+        .find(|path| path.exists())
+        .unwrap_or(Path::new("."));
+    let path = CString::new(path.as_os_str().as_bytes())
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+
+    let mut stat = MaybeUninit::<libc::statvfs>::uninit();
+    // SAFETY: `path` is a valid NUL-terminated string and `stat` points to writable memory for a `statvfs`.
+    let result = unsafe { libc::statvfs(path.as_ptr(), stat.as_mut_ptr()) };
+    if result != 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    // SAFETY: `statvfs` returned successfully, so `stat` is initialized.
+    let stat = unsafe { stat.assume_init() };
+    #[allow(clippy::unnecessary_cast)]
+    Ok(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+/// Returns the number of bytes available on the volume containing the given path.
+/// This is not supported on this platform and always returns an error.
+#[cfg(not(unix))]
+pub fn free_space(_path: impl AsRef<Path>) -> io::Result<u64> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Checking free disk space is not supported on this platform",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use lum_libs::log::LevelFilter;
+
+    use super::*;
+    use crate::{ConfigBuilder, testing};
+
+    fn response(filter: &DiskGuardFilter, level: Level) -> Response {
+        filter.filter(&Record::builder().level(level).build())
+    }
+
+    #[test]
+    fn guarded_appenders_degrade_while_free_space_is_below_the_threshold() {
+        let _global = testing::GLOBAL.lock();
+        let records = testing::capture(ConfigBuilder::new().root_log_level(LevelFilter::Info));
+        let directory = testing::temp_dir("disk_guard");
+        let free = free_space(&directory).unwrap();
+
+        let errors_only = DiskGuard::new(&directory, u64::MAX);
+        let errors_only_filter = errors_only.filter();
+        let console_only = DiskGuard::new(&directory, u64::MAX).mode(DegradedMode::ConsoleOnly);
+        let console_only_filter = console_only.filter();
+        assert!(matches!(
+            response(&errors_only_filter, Level::Warn),
+            Response::Neutral
+        ));
+
+        errors_only.check().unwrap();
+        console_only.check().unwrap();
+        assert!(errors_only.is_degraded());
+        assert!(matches!(
+            response(&errors_only_filter, Level::Warn),
+            Response::Reject
+        ));
+        assert!(matches!(
+            response(&errors_only_filter, Level::Error),
+            Response::Neutral
+        ));
+        assert!(matches!(
+            response(&console_only_filter, Level::Error),
+            Response::Reject
+        ));
+
+        let plenty = DiskGuard::new(&directory, free / 2);
+        plenty.check().unwrap();
+        assert!(!plenty.is_degraded());
+        assert!(matches!(
+            response(&plenty.filter(), Level::Warn),
+            Response::Neutral
+        ));
+
+        let messages = testing::messages(&records);
+        assert_eq!(messages.len(), 2);
+        assert!(
+            messages
+                .iter()
+                .all(|message| message.starts_with("Low disk space"))
+        );
+    }
+}
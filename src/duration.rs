@@ -0,0 +1,17 @@
+use std::time::Duration;
+
+use lum_libs::{
+    humantime,
+    serde::{Deserialize, Deserializer, Serializer, de::Error},
+};
+
+/// Serializes a [`Duration`] as a human-readable string like `1s 500ms`.
+pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(&humantime::format_duration(*duration))
+}
+
+/// Deserializes a [`Duration`] from a human-readable string like `1s 500ms` or `2min`.
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    humantime::parse_duration(&value).map_err(D::Error::custom)
+}
@@ -0,0 +1,188 @@
+//! The core logging macros for firmware built without the `std` feature, so firmware crates can share the macro surface of host applications.
+//!
+//! The macros log through [defmt](https://defmt.ferrous-systems.com), so their arguments must implement `defmt::Format`
+//! and their format strings follow defmt's syntax, which accepts the plain `{}` placeholders most messages use.
+//! Firmware crates must depend on `defmt` themselves, as its macros refer to it by name, and filter levels with `DEFMT_LOG`.
+//! With the `rtt` feature, records are written to RTT by `defmt-rtt`, ready for `probe-rs` or any other defmt decoder.
+//! Without it, the firmware provides its own `#[defmt::global_logger]`.
+//!
+//! Unlike on the host, records are always logged, as there is no logger to set up,
+//! targets are ignored, as defmt has none, and auxiliary levels map to their log levels.
+//!
+//! ```text
+//! use defmt_rtt as _; // Only without the rtt feature.
+//!
+//! lum_log::info!("Booted in {} ms", elapsed_ms);
+//! lum_log::warn_target!("sensor", "Reading out of range: {}", value);
+//! ```
+
+#[doc(hidden)]
+pub use defmt;
+#[cfg(feature = "rtt")]
+use defmt_rtt as _;
+
+/// Logs a message at the error level through defmt.
+#[macro_export]
+macro_rules! error {
+    ($($arg:tt)*) => {
+        $crate::embedded::defmt::error!($($arg)*)
+    };
+}
+
+/// Logs a message at the warn level through defmt.
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)*) => {
+        $crate::embedded::defmt::warn!($($arg)*)
+    };
+}
+
+/// Logs a message at the info level through defmt.
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)*) => {
+        $crate::embedded::defmt::info!($($arg)*)
+    };
+}
+
+/// Logs a message at the debug level through defmt.
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        $crate::embedded::defmt::debug!($($arg)*)
+    };
+}
+
+/// Logs a message at the trace level through defmt.
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        $crate::embedded::defmt::trace!($($arg)*)
+    };
+}
+
+/// Logs a message at the error level through defmt. The target is ignored.
+#[macro_export]
+macro_rules! error_target {
+    ($target:expr, $($arg:tt)*) => {
+        $crate::error!($($arg)*)
+    };
+}
+
+/// Logs a message at the warn level through defmt. The target is ignored.
+#[macro_export]
+macro_rules! warn_target {
+    ($target:expr, $($arg:tt)*) => {
+        $crate::warn!($($arg)*)
+    };
+}
+
+/// Logs a message at the info level through defmt. The target is ignored.
+#[macro_export]
+macro_rules! info_target {
+    ($target:expr, $($arg:tt)*) => {
+        $crate::info!($($arg)*)
+    };
+}
+
+/// Logs a message at the debug level through defmt. The target is ignored.
+#[macro_export]
+macro_rules! debug_target {
+    ($target:expr, $($arg:tt)*) => {
+        $crate::debug!($($arg)*)
+    };
+}
+
+/// Logs a message at the trace level through defmt. The target is ignored.
+#[macro_export]
+macro_rules! trace_target {
+    ($target:expr, $($arg:tt)*) => {
+        $crate::trace!($($arg)*)
+    };
+}
+
+/// Panics through `defmt::panic!`, which logs the message at the error level first.
+#[macro_export]
+macro_rules! error_panic {
+    ($($arg:tt)*) => {
+        $crate::embedded::defmt::panic!($($arg)*)
+    };
+}
+
+/// Panics through `defmt::unreachable!`, which logs the message at the error level first.
+#[macro_export]
+macro_rules! error_unreachable {
+    ($($arg:tt)*) => {
+        $crate::embedded::defmt::unreachable!($($arg)*)
+    };
+}
+
+/// Logs a message at the error level through defmt, which the auxiliary level fatal maps to.
+#[macro_export]
+macro_rules! fatal {
+    ($($arg:tt)*) => {
+        $crate::error!($($arg)*)
+    };
+}
+
+/// Logs a message at the info level through defmt, which the auxiliary level notice maps to.
+#[macro_export]
+macro_rules! notice {
+    ($($arg:tt)*) => {
+        $crate::info!($($arg)*)
+    };
+}
+
+/// Logs a message at the debug level through defmt, which the auxiliary level verbose maps to.
+#[macro_export]
+macro_rules! verbose {
+    ($($arg:tt)*) => {
+        $crate::debug!($($arg)*)
+    };
+}
+
+#[cfg(all(test, not(feature = "rtt")))]
+mod tests {
+    extern crate std;
+
+    use std::{sync::Mutex, vec::Vec};
+
+    static FRAMES: Mutex<Vec<Vec<u8>>> = Mutex::new(Vec::new());
+
+    defmt::timestamp!("");
+
+    /// Collects the raw frames written by defmt, as tests on the host have no RTT channel to read.
+    #[defmt::global_logger]
+    struct FrameCollector;
+
+    unsafe impl defmt::Logger for FrameCollector {
+        fn acquire() {
+            FRAMES.lock().unwrap().push(Vec::new());
+        }
+
+        unsafe fn flush() {}
+
+        unsafe fn release() {}
+
+        unsafe fn write(bytes: &[u8]) {
+            if let Some(frame) = FRAMES.lock().unwrap().last_mut() {
+                frame.extend_from_slice(bytes);
+            }
+        }
+    }
+
+    #[test]
+    fn error_macros_write_a_frame_with_their_arguments() {
+        // Levels below error are filtered by DEFMT_LOG at compile time, which defaults to errors only.
+        crate::error!("Sensor {=u16} failed", 0xbeef);
+        crate::error_target!("sensor", "Sensor {=u16} failed", 0xcafe);
+        crate::fatal!("Sensor {=u16} lost", 0xf00d);
+
+        let frames = FRAMES.lock().unwrap();
+        let arguments = frames
+            .iter()
+            .map(|frame| frame[frame.len() - 2..].to_vec())
+            .collect::<Vec<_>>();
+        assert_eq!(arguments, [[0xef, 0xbe], [0xfe, 0xca], [0x0d, 0xf0]]);
+    }
+}
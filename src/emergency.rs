@@ -0,0 +1,190 @@
+use std::{
+    cell::Cell,
+    fs::OpenOptions,
+    io,
+    path::PathBuf,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use lum_libs::{
+    log::{Level, Record},
+    log4rs::encode::writer::simple::SimpleWriter,
+    parking_lot::Mutex,
+    serde::{Deserialize, Serialize},
+};
+
+use crate::{encode, internal, stdio};
+
+/// Where error records are written when all appenders failed to append them.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde", rename_all = "snake_case")]
+pub enum EmergencyOutput {
+    /// Error records are lost when all appenders fail.
+    Disabled,
+    /// Error records are written to stderr.
+    #[default]
+    Stderr,
+    /// Error records are appended to the given file, e.g. on a tmpfs that is still writable when the log volume is not.
+    /// If writing to the file fails as well, they are written to stderr.
+    File(PathBuf),
+}
+
+static OUTPUT: Mutex<EmergencyOutput> = Mutex::new(EmergencyOutput::Stderr);
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    /// The number of attempted and failed appends of the record currently being logged on this thread.
+    static APPENDS: Cell<(u32, u32)> = const { Cell::new((0, 0)) };
+}
+
+/// Sets where error records are written when all appenders failed to append them.
+/// Defaults to [`EmergencyOutput::Stderr`]. See also [`ConfigBuilder::emergency_output`](crate::ConfigBuilder::emergency_output).
+/// Only appenders added by a [`ConfigBuilder`](crate::ConfigBuilder) are tracked,
+/// so this has no effect if the logger was set up with a raw [`Config`](lum_libs::log4rs::Config).
+pub fn set_emergency_output(output: EmergencyOutput) {
+    *OUTPUT.lock() = output;
+}
+
+/// Returns whether the emergency output is currently in use, i.e. whether all appenders failed to append the last record.
+pub fn is_active() -> bool {
+    ACTIVE.load(Ordering::Relaxed)
+}
+
+/// Records the outcome of an append of the record currently being logged on this thread.
+pub(crate) fn record_append(success: bool) {
+    APPENDS.with(|appends| {
+        let (attempted, failed) = appends.get();
+        appends.set((attempted + 1, failed + u32::from(!success)));
+    });
+}
+
+/// Logs the given record with the given function, writing it to the emergency output
+/// if it is an error record and all appenders failed to append it.
+pub(crate) fn guard(record: &Record, log: impl FnOnce()) {
+    // Appenders may log themselves, so the counts of an outer record are restored afterwards.
+    let outer = APPENDS.with(|appends| appends.replace((0, 0)));
+    log();
+    let (attempted, failed) = APPENDS.with(|appends| appends.replace(outer));
+
+    if attempted == 0 {
+        return;
+    }
+
+    if failed < attempted {
+        if ACTIVE.swap(false, Ordering::Relaxed) {
+            internal::report(format_args!(
+                "Appenders recovered, leaving emergency output"
+            ));
+        }
+        return;
+    }
+
+    if record.level() == Level::Error {
+        write(record);
+    }
+}
+
+fn write(record: &Record) {
+    let output = OUTPUT.lock().clone();
+    let first = !ACTIVE.swap(true, Ordering::Relaxed);
+
+    match output {
+        EmergencyOutput::Disabled => {}
+        EmergencyOutput::Stderr => {
+            if first {
+                internal::report(format_args!(
+                    "All appenders failed, writing error records to stderr"
+                ));
+            }
+            write_stderr(record);
+        }
+        EmergencyOutput::File(path) => {
+            if first {
+                internal::report(format_args!(
+                    "All appenders failed, writing error records to {}",
+                    path.display()
+                ));
+            }
+
+            let result = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|file| encode::encode_emergency(&mut SimpleWriter(file), record));
+            if let Err(error) = result {
+                internal::report(format_args!(
+                    "Failed to write to emergency output {}: {error}",
+                    path.display()
+                ));
+                write_stderr(record);
+            }
+        }
+    }
+}
+
+fn write_stderr(record: &Record) {
+    // While stdout and stderr are captured, the original stderr is used, so the record is not captured again.
+    let written = stdio::original_stderr(|stderr| {
+        let _ = encode::encode_emergency(&mut SimpleWriter(stderr), record);
+    });
+    if written.is_none() {
+        let _ = encode::encode_emergency(&mut SimpleWriter(io::stderr().lock()), record);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use lum_libs::{
+        log::{self, LevelFilter},
+        log4rs::append::Append,
+    };
+
+    use super::*;
+    use crate::{ConfigBuilder, testing};
+
+    /// An appender failing while `failing` is set.
+    #[derive(Debug)]
+    struct SwitchableAppender(Arc<AtomicBool>);
+
+    impl Append for SwitchableAppender {
+        fn append(&self, _record: &Record) -> anyhow::Result<()> {
+            if self.0.load(Ordering::SeqCst) {
+                anyhow::bail!("Disk full");
+            }
+            Ok(())
+        }
+
+        fn flush(&self) {}
+    }
+
+    #[test]
+    fn error_records_are_written_to_the_emergency_output_while_all_appenders_fail() {
+        let _global = testing::GLOBAL.lock();
+        let path = testing::temp_dir("emergency").join("emergency.log");
+        let failing = Arc::new(AtomicBool::new(true));
+        ConfigBuilder::new()
+            .root_log_level(LevelFilter::Info)
+            .appender(
+                "switchable",
+                Box::new(SwitchableAppender(Arc::clone(&failing))),
+            )
+            .emergency_output(EmergencyOutput::File(path.clone()))
+            .apply()
+            .expect("The logger can be set up");
+
+        log::warn!(target: "emergency_test", "Lost warning");
+        log::error!(target: "emergency_test", "Kept error");
+        assert!(is_active());
+        failing.store(false, Ordering::SeqCst);
+        log::error!(target: "emergency_test", "Appended error");
+        assert!(!is_active());
+
+        assert_eq!(
+            std::fs::read_to_string(path).expect("The emergency output is written"),
+            "ERROR emergency_test Kept error\n"
+        );
+    }
+}
@@ -0,0 +1,483 @@
+/// Defines the [`CborEncoder`], which encodes records as framed CBOR.
+#[cfg(feature = "cbor")]
+pub mod cbor;
+/// Defines the [`CombinedLogEncoder`], which encodes access log records in the Apache/NCSA Combined Log Format.
+pub mod combined;
+/// Defines the [`JsonEncoder`], which writes records as single lines of JSON.
+pub mod json;
+/// Defines the [`LogfmtEncoder`], which writes records as logfmt lines of `key=value` pairs.
+pub mod logfmt;
+/// Defines the [`MsgpackEncoder`], which encodes records as framed MessagePack.
+#[cfg(feature = "msgpack")]
+pub mod msgpack;
+/// Defines the [`PrettyJsonEncoder`], which renders records as colored JSON blocks for local development.
+pub mod pretty_json;
+/// Defines the [`ProtobufEncoder`], which encodes records as framed protobuf messages.
+#[cfg(feature = "protobuf")]
+pub mod protobuf;
+/// Defines the [`W3cEncoder`], which encodes access log records in the W3C Extended Log File Format.
+pub mod w3c;
+
+#[cfg(feature = "cbor")]
+pub use cbor::CborEncoder;
+pub use combined::CombinedLogEncoder;
+pub use json::JsonEncoder;
+pub use logfmt::LogfmtEncoder;
+#[cfg(feature = "msgpack")]
+pub use msgpack::MsgpackEncoder;
+pub use pretty_json::PrettyJsonEncoder;
+#[cfg(feature = "protobuf")]
+pub use protobuf::ProtobufEncoder;
+pub use w3c::W3cEncoder;
+
+use std::{
+    borrow::Cow,
+    cell::Cell,
+    fmt::{self, Arguments},
+    io,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use lum_libs::{
+    log::Record,
+    log4rs::encode::{Encode, Style, Write, pattern::PatternEncoder, writer::simple::SimpleWriter},
+};
+
+use crate::{
+    cost, internal,
+    level::{self, LevelNames},
+};
+
+/// The MDC key under which [`LevelNameEncoder`] exposes the display name of a record's level.
+/// Use it in patterns as `{X(lum_log.level)}`. It is prefixed so it does not replace an MDC entry named `level` set by the application.
+pub const LEVEL_MDC_KEY: &str = "lum_log.level";
+
+/// The MDC key under which [`SyslogSeverityEncoder`] exposes the numeric syslog severity of a record's level.
+/// Use it in patterns as `{X(syslog_severity)}`.
+pub const SYSLOG_SEVERITY_MDC_KEY: &str = "syslog_severity";
+
+static STRIP_ANSI: AtomicBool = AtomicBool::new(true);
+
+thread_local! {
+    /// Whether the encoder currently running on this thread writes binary output.
+    static BINARY_OUTPUT: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Sets whether [`StripAnsiEncoder`]s remove ANSI escape sequences. Stripping is enabled by default.
+/// See also [`ConfigBuilder::strip_ansi`](crate::ConfigBuilder::strip_ansi).
+pub fn set_strip_ansi(enabled: bool) {
+    STRIP_ANSI.store(enabled, Ordering::Relaxed);
+}
+
+/// Returns whether [`StripAnsiEncoder`]s remove ANSI escape sequences.
+pub fn strip_ansi() -> bool {
+    STRIP_ANSI.load(Ordering::Relaxed)
+}
+
+/// Marks the output of the record currently being encoded on this thread as binary, so [`StripAnsiEncoder`]s leave it untouched.
+/// Encoders writing binary data, e.g. the MessagePack encoder, must call this,
+/// as their output may contain bytes that look like the start of an escape sequence.
+pub fn mark_binary_output() {
+    BINARY_OUTPUT.with(|binary| binary.set(true));
+}
+
+/// An encoder that makes the display name of a record's level or auxiliary level, as configured by [`LevelNames`],
+/// available to the wrapped encoder through the MDC key [`LEVEL_MDC_KEY`].
+#[derive(Debug)]
+pub struct LevelNameEncoder {
+    inner: Box<dyn Encode>,
+    level_names: LevelNames,
+}
+
+impl LevelNameEncoder {
+    /// Creates a new `LevelNameEncoder` wrapping the given encoder.
+    pub fn new(inner: Box<dyn Encode>, level_names: LevelNames) -> Self {
+        Self { inner, level_names }
+    }
+
+    /// Creates a new `LevelNameEncoder` wrapping a [`PatternEncoder`] for the given pattern.
+    /// Level tokens (`{l}` and `{level}`) in the pattern are rewritten by [`level_name_pattern`].
+    pub fn pattern(pattern: &str, level_names: LevelNames) -> Self {
+        let encoder = PatternEncoder::new(&level_name_pattern(pattern));
+        Self::new(Box::new(encoder), level_names)
+    }
+}
+
+impl Encode for LevelNameEncoder {
+    fn encode(&self, w: &mut dyn Write, record: &Record) -> anyhow::Result<()> {
+        let name = self.level_names.get_current(record.level());
+        let _guard = log_mdc::insert_scoped(LEVEL_MDC_KEY, name);
+        self.inner.encode(w, record)
+    }
+}
+
+/// An encoder that makes the numeric syslog severity of a record's level, as returned by [`level::current_syslog_severity`],
+/// available to the wrapped encoder through the MDC key [`SYSLOG_SEVERITY_MDC_KEY`].
+#[derive(Debug)]
+pub struct SyslogSeverityEncoder {
+    inner: Box<dyn Encode>,
+}
+
+impl SyslogSeverityEncoder {
+    /// Creates a new `SyslogSeverityEncoder` wrapping the given encoder.
+    pub fn new(inner: Box<dyn Encode>) -> Self {
+        Self { inner }
+    }
+
+    /// Creates a new `SyslogSeverityEncoder` wrapping a [`PatternEncoder`] for the given pattern.
+    pub fn pattern(pattern: &str) -> Self {
+        Self::new(Box::new(PatternEncoder::new(pattern)))
+    }
+}
+
+impl Encode for SyslogSeverityEncoder {
+    fn encode(&self, w: &mut dyn Write, record: &Record) -> anyhow::Result<()> {
+        let severity = level::current_syslog_severity(record.level());
+        let _guard = log_mdc::insert_scoped(SYSLOG_SEVERITY_MDC_KEY, severity.to_string());
+        self.inner.encode(w, record)
+    }
+}
+
+/// An encoder that catches panics of the wrapped encoder, e.g. inside a user-provided format function.
+/// A panic is reported to stderr and the record is written by [`encode_emergency`] instead,
+/// so a panic inside formatting can neither take down the logging thread nor the process.
+/// The default appenders of this crate wrap their encoders in a `SafeEncoder`.
+/// It also counts the bytes written per target for [`cost::cost_report`].
+#[derive(Debug)]
+pub struct SafeEncoder {
+    inner: Box<dyn Encode>,
+}
+
+impl SafeEncoder {
+    /// Creates a new `SafeEncoder` wrapping the given encoder.
+    pub fn new(inner: Box<dyn Encode>) -> Self {
+        Self { inner }
+    }
+}
+
+impl Encode for SafeEncoder {
+    fn encode(&self, w: &mut dyn Write, record: &Record) -> anyhow::Result<()> {
+        let mut tracked = TrackingWriter {
+            inner: w,
+            written: 0,
+        };
+
+        let result = match internal::catch("Encoder", || self.inner.encode(&mut tracked, record)) {
+            Some(result) => result,
+            None => {
+                // Start a new line, so the emergency record is not glued to the partially written one.
+                if tracked.written > 0 {
+                    tracked.inner.write_all(b"\n")?;
+                }
+                tracked.inner.set_style(&Style::new())?;
+                encode_emergency(tracked.inner, record)
+            }
+        };
+        cost::record_written(record.target(), tracked.written);
+        result
+    }
+}
+
+/// A writer tracking the number of bytes written to the wrapped writer.
+struct TrackingWriter<'a> {
+    inner: &'a mut dyn Write,
+    written: usize,
+}
+
+impl io::Write for TrackingWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.written += written;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl Write for TrackingWriter<'_> {
+    fn set_style(&mut self, style: &Style) -> io::Result<()> {
+        self.inner.set_style(style)
+    }
+}
+
+/// An encoder removing ANSI escape sequences, e.g. colors, from the output of the wrapped encoder,
+/// so a colored format shared with the console does not fill files or remote sinks with escape sequences.
+/// Styles set by the wrapped encoder are dropped as well. Stripping can be disabled globally by [`set_strip_ansi`],
+/// and output marked as binary by [`mark_binary_output`] is never stripped.
+/// The file appenders created by [`crate::default`] and [`NetworkAppender`](crate::append::NetworkAppender)s
+/// wrap their encoders in a `StripAnsiEncoder`.
+#[derive(Debug)]
+pub struct StripAnsiEncoder {
+    inner: Box<dyn Encode>,
+}
+
+impl StripAnsiEncoder {
+    /// Creates a new `StripAnsiEncoder` wrapping the given encoder.
+    pub fn new(inner: Box<dyn Encode>) -> Self {
+        Self { inner }
+    }
+}
+
+impl Encode for StripAnsiEncoder {
+    fn encode(&self, w: &mut dyn Write, record: &Record) -> anyhow::Result<()> {
+        if !strip_ansi() {
+            return self.inner.encode(w, record);
+        }
+
+        // Encoders may be nested, so the mark of an outer record is restored afterwards.
+        let outer = BINARY_OUTPUT.with(|binary| binary.replace(false));
+        let mut buffer = SimpleWriter(Vec::new());
+        let result = self.inner.encode(&mut buffer, record);
+        let binary = BINARY_OUTPUT.with(|binary| binary.replace(outer));
+        result?;
+
+        match binary {
+            true => w.write_all(&buffer.0)?,
+            false => w.write_all(&strip_ansi_bytes(&buffer.0))?,
+        }
+        Ok(())
+    }
+}
+
+/// Removes ANSI escape sequences from the given text, e.g. `\u{1b}[32m`.
+/// Control sequences (CSI), operating system commands (OSC), and two-byte escapes are removed.
+pub fn strip_ansi_escapes(text: &str) -> Cow<'_, str> {
+    if !text.contains('\u{1b}') {
+        return Cow::Borrowed(text);
+    }
+
+    // Escape sequences consist of ASCII bytes only, so stripping them leaves valid UTF-8.
+    Cow::Owned(String::from_utf8_lossy(&strip_ansi_bytes(text.as_bytes())).into_owned())
+}
+
+fn strip_ansi_bytes(bytes: &[u8]) -> Vec<u8> {
+    const ESC: u8 = 0x1b;
+    const BEL: u8 = 0x07;
+
+    let mut result = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] != ESC {
+            result.push(bytes[index]);
+            index += 1;
+            continue;
+        }
+
+        index += 1;
+        match bytes.get(index) {
+            // CSI: parameter and intermediate bytes, terminated by a final byte in 0x40..=0x7e.
+            Some(b'[') => {
+                index += 1;
+                while index < bytes.len() && !(0x40..=0x7e).contains(&bytes[index]) {
+                    index += 1;
+                }
+                index += 1;
+            }
+            // OSC: terminated by BEL or ST (ESC \).
+            Some(b']') => {
+                index += 1;
+                while index < bytes.len() {
+                    if bytes[index] == BEL {
+                        index += 1;
+                        break;
+                    }
+                    if bytes[index] == ESC && bytes.get(index + 1) == Some(&b'\\') {
+                        index += 2;
+                        break;
+                    }
+                    index += 1;
+                }
+            }
+            Some(_) => index += 1,
+            None => {}
+        }
+    }
+
+    result
+}
+
+/// Writes the given record in a minimal emergency format, `LEVEL target message`, followed by a newline.
+/// If formatting the message panics, a placeholder is written instead.
+pub fn encode_emergency(w: &mut dyn Write, record: &Record) -> anyhow::Result<()> {
+    let message = internal::catch("Formatting a message", || fmt::format(*record.args()));
+    let message = message
+        .as_deref()
+        .unwrap_or("<message formatting panicked>");
+    write_line(
+        w,
+        format_args!("{} {} {message}", record.level(), record.target()),
+    )
+}
+
+fn write_line(w: &mut dyn Write, args: Arguments) -> anyhow::Result<()> {
+    w.write_fmt(args)?;
+    w.write_all(b"\n")?;
+    Ok(())
+}
+
+/// Rewrites the level tokens `{l}` and `{level}` of a [`PatternEncoder`] pattern
+/// to read the level's display name from the MDC key [`LEVEL_MDC_KEY`] instead.
+/// Formatting options like `{l:<5}` are preserved, escaped braces (`{{`) are left untouched.
+pub fn level_name_pattern(pattern: &str) -> String {
+    let mut result = String::with_capacity(pattern.len());
+    let mut rest = pattern;
+
+    while let Some(index) = rest.find('{') {
+        result.push_str(&rest[..index]);
+        rest = &rest[index..];
+
+        if rest.starts_with("{{") {
+            result.push_str("{{");
+            rest = &rest[2..];
+            continue;
+        }
+
+        let token = ["{level", "{l"].into_iter().find(|token| {
+            rest.strip_prefix(token)
+                .is_some_and(|after| after.starts_with('}') || after.starts_with(':'))
+        });
+
+        match token {
+            Some(token) => {
+                result.push_str("{X(");
+                result.push_str(LEVEL_MDC_KEY);
+                result.push(')');
+                rest = &rest[token.len()..];
+            }
+            None => {
+                result.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use lum_libs::log::Level;
+
+    use super::*;
+
+    #[test]
+    fn level_name_pattern_rewrites_level_tokens() {
+        assert_eq!(
+            level_name_pattern("{d} {l:<5} {h({level})} {{l}} {line}"),
+            "{d} {X(lum_log.level):<5} {h({X(lum_log.level)})} {{l}} {line}"
+        );
+    }
+
+    #[test]
+    fn level_name_encoder_keeps_the_mdc_entry_of_the_application() {
+        let _level = log_mdc::insert_scoped("level", "user");
+        let encoder = LevelNameEncoder::pattern("{l} {X(level)}", LevelNames::short());
+
+        let mut output = SimpleWriter(Vec::new());
+        encoder
+            .encode(
+                &mut output,
+                &Record::builder()
+                    .level(Level::Warn)
+                    .args(format_args!("Message"))
+                    .build(),
+            )
+            .unwrap();
+
+        assert_eq!(String::from_utf8(output.0).unwrap(), "WRN user");
+        assert_eq!(
+            log_mdc::get("level", |value| value.map(str::to_string)),
+            Some("user".to_string())
+        );
+    }
+
+    #[test]
+    fn syslog_severity_encoder_exposes_the_severity_of_the_level() {
+        let encoder = SyslogSeverityEncoder::pattern("<{X(syslog_severity)}>{m}");
+
+        let rendered: Vec<_> = [Level::Error, Level::Warn, Level::Info, Level::Trace]
+            .into_iter()
+            .map(|level| {
+                let mut output = SimpleWriter(Vec::new());
+                encoder
+                    .encode(
+                        &mut output,
+                        &Record::builder()
+                            .level(level)
+                            .args(format_args!("Message"))
+                            .build(),
+                    )
+                    .unwrap();
+                String::from_utf8(output.0).unwrap()
+            })
+            .collect();
+
+        assert_eq!(
+            rendered,
+            ["<3>Message", "<4>Message", "<6>Message", "<7>Message"]
+        );
+        assert!(log_mdc::get(SYSLOG_SEVERITY_MDC_KEY, |value| value.is_none()));
+    }
+
+    #[derive(Debug)]
+    struct PanickingEncoder;
+
+    impl Encode for PanickingEncoder {
+        fn encode(&self, w: &mut dyn Write, _record: &Record) -> anyhow::Result<()> {
+            w.write_all(b"Partial")?;
+            panic!("Broken format");
+        }
+    }
+
+    #[test]
+    fn safe_encoder_writes_an_emergency_line_if_the_encoder_panics() {
+        let encoder = SafeEncoder::new(Box::new(PanickingEncoder));
+
+        let mut output = SimpleWriter(Vec::new());
+        encoder
+            .encode(
+                &mut output,
+                &Record::builder()
+                    .level(Level::Warn)
+                    .target("encode_test")
+                    .args(format_args!("Message"))
+                    .build(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output.0).unwrap(),
+            "Partial\nWARN encode_test Message\n"
+        );
+    }
+
+    #[test]
+    fn strip_ansi_bytes_removes_csi_osc_and_two_byte_escapes() {
+        let colored = b"\x1b[1;32mINFO\x1b[0m \x1b]8;;https://example.com\x07link\x1b]8;;\x1b\\ \x1bMdone\x1b[";
+
+        assert_eq!(strip_ansi_bytes(colored), b"INFO link done");
+        assert_eq!(strip_ansi_escapes("plain"), Cow::Borrowed("plain"));
+        assert_eq!(strip_ansi_escapes("\u{1b}[31mÄrger\u{1b}[0m"), "Ärger");
+    }
+
+    #[test]
+    fn strip_ansi_encoder_removes_the_colors_of_the_wrapped_encoder() {
+        let encoder = StripAnsiEncoder::new(Box::new(PatternEncoder::new("{h({l})} {m}")));
+
+        let mut output = SimpleWriter(Vec::new());
+        encoder
+            .encode(
+                &mut output,
+                &Record::builder()
+                    .level(Level::Error)
+                    .args(format_args!("\u{1b}[4mMessage\u{1b}[0m"))
+                    .build(),
+            )
+            .unwrap();
+
+        assert_eq!(String::from_utf8(output.0).unwrap(), "ERROR Message");
+    }
+}
@@ -0,0 +1,91 @@
+use lum_libs::{
+    log::Record,
+    log4rs::encode::{Encode, Write},
+};
+
+use crate::{encode, level::LevelNames, record::OwnedRecord};
+
+/// An encoder writing each record as an [`OwnedRecord`] serialized to CBOR,
+/// framed by its length as a little-endian `u32`.
+/// CBOR is smaller than JSON and, unlike protobuf, needs no external schema, which suits constrained devices.
+/// Each call produces exactly one frame, so the encoder works for file appenders
+/// as well as for [`NetworkAppender`](crate::append::NetworkAppender)s sending one record per message.
+/// Use [`CborReader`](crate::reader::CborReader) to read the records back.
+#[derive(Debug, Default)]
+pub struct CborEncoder {
+    level_names: LevelNames,
+}
+
+impl CborEncoder {
+    /// Creates a new `CborEncoder` using the default [`LevelNames`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the display names written as the [`OwnedRecord::level_name`] of each record.
+    pub fn level_names(mut self, level_names: LevelNames) -> Self {
+        self.level_names = level_names;
+        self
+    }
+}
+
+impl Encode for CborEncoder {
+    fn encode(&self, w: &mut dyn Write, record: &Record) -> anyhow::Result<()> {
+        encode::mark_binary_output();
+        let mut payload = Vec::new();
+        ciborium::into_writer(
+            &OwnedRecord::from(record).with_level_name(&self.level_names),
+            &mut payload,
+        )?;
+        let length = u32::try_from(payload.len())?;
+        w.write_all(&length.to_le_bytes())?;
+        w.write_all(&payload)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::ErrorKind;
+
+    use lum_libs::{log::Level, log4rs::encode::writer::simple::SimpleWriter};
+
+    use super::*;
+    use crate::reader::CborReader;
+
+    fn encode(output: &mut SimpleWriter<Vec<u8>>, message: &str) {
+        CborEncoder::new()
+            .encode(
+                output,
+                &Record::builder()
+                    .level(Level::Info)
+                    .target("cbor_test")
+                    .args(format_args!("{message}"))
+                    .build(),
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn corrupt_frames_are_reported_and_skipped() {
+        let mut output = SimpleWriter(Vec::new());
+        encode(&mut output, "Before");
+        output.0.extend_from_slice(&2u32.to_le_bytes());
+        output.0.extend_from_slice(&[0xff, 0xff]);
+        encode(&mut output, "After");
+
+        let records = CborReader::new(output.0.as_slice()).collect::<Vec<_>>();
+
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].as_ref().unwrap().message, "Before");
+        assert_eq!(
+            records[0].as_ref().unwrap().level_name.as_deref(),
+            Some("INFO")
+        );
+        assert_eq!(
+            records[1].as_ref().unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+        assert_eq!(records[2].as_ref().unwrap().message, "After");
+    }
+}
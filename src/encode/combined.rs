@@ -0,0 +1,101 @@
+use lum_libs::{
+    log::Record,
+    log4rs::encode::{Encode, Write, pattern::PatternEncoder},
+};
+
+use crate::http::AccessEntry;
+
+/// The [`PatternEncoder`] pattern of the Apache/NCSA Combined Log Format, reading the fields of an [`AccessEntry`]
+/// from the [`access_mdc_keys`](crate::http::access_mdc_keys). Missing fields are rendered as `-`.
+pub const COMBINED_LOG_PATTERN: &str = concat!(
+    "{X(http_remote_addr)(-)} - {X(http_user)(-)} [{d(%d/%b/%Y:%H:%M:%S %z)}] ",
+    "\"{X(http_method)} {X(http_target)} {X(http_protocol)(-)}\" {X(http_status)} {X(http_response_bytes)(-)} ",
+    "\"{X(http_referer)(-)}\" \"{X(http_user_agent)(-)}\"{n}",
+);
+
+/// An encoder writing access log records in the Apache/NCSA Combined Log Format, e.g.
+/// ```text
+/// 127.0.0.1 - - [10/Oct/2025:13:55:36 +0200] "GET /orders?page=2 HTTP/1.1" 200 2326 "-" "curl/8.5.0"
+/// ```
+/// which analytics tools like GoAccess and AWStats consume directly.
+/// Only records with an [`AccessEntry`], i.e. those logged by [`log_access`](crate::http::log_access)
+/// and the request logging middleware, are written. Others, like the request start records, are skipped,
+/// so use it for an appender receiving [`ACCESS_TARGET`](crate::http::ACCESS_TARGET).
+#[derive(Debug)]
+pub struct CombinedLogEncoder {
+    inner: PatternEncoder,
+}
+
+impl Default for CombinedLogEncoder {
+    /// Creates a `CombinedLogEncoder` using [`COMBINED_LOG_PATTERN`].
+    fn default() -> Self {
+        Self {
+            inner: PatternEncoder::new(COMBINED_LOG_PATTERN),
+        }
+    }
+}
+
+impl CombinedLogEncoder {
+    /// Same as [`CombinedLogEncoder::default`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Encode for CombinedLogEncoder {
+    fn encode(&self, w: &mut dyn Write, record: &Record) -> anyhow::Result<()> {
+        if !AccessEntry::is_current() {
+            return Ok(());
+        }
+
+        self.inner.encode(w, record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use lum_libs::{log::Level, log4rs::encode::writer::simple::SimpleWriter};
+
+    use super::*;
+
+    fn encode(encoder: &CombinedLogEncoder) -> String {
+        let mut output = SimpleWriter(Vec::new());
+        encoder
+            .encode(
+                &mut output,
+                &Record::builder()
+                    .level(Level::Info)
+                    .args(format_args!("Request"))
+                    .build(),
+            )
+            .unwrap();
+        String::from_utf8(output.0).unwrap()
+    }
+
+    #[test]
+    fn access_entries_are_written_in_the_combined_log_format() {
+        let encoder = CombinedLogEncoder::new();
+        let mut entry = AccessEntry::new("GET", "/orders", 200, Duration::from_millis(5));
+        entry.query = Some("page=2".to_string());
+        entry.protocol = Some("HTTP/1.1".to_string());
+        entry.remote_addr = Some("127.0.0.1".to_string());
+        entry.response_bytes = Some(2326);
+        entry.user_agent = Some("curl/8.5.0".to_string());
+
+        let line = {
+            let _entry = entry.scope();
+            encode(&encoder)
+        };
+
+        let (client, rest) = line.split_once(" [").unwrap();
+        let (_time, request) = rest.split_once("] ").unwrap();
+        assert_eq!(client, "127.0.0.1 - -");
+        assert_eq!(
+            request,
+            "\"GET /orders?page=2 HTTP/1.1\" 200 2326 \"-\" \"curl/8.5.0\"\n"
+        );
+        assert_eq!(encode(&encoder), "");
+    }
+}
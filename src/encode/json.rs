@@ -0,0 +1,88 @@
+use lum_libs::{
+    log::Record,
+    log4rs::encode::{Encode, Write, json, writer::simple::SimpleWriter},
+    serde_json,
+};
+
+use crate::level::LevelNames;
+
+const LEVEL_FIELD: &[u8] = br#""level":""#;
+
+/// An encoder writing each record as a single line of JSON like log4rs' `JsonEncoder`,
+/// rendering the level with the configured [`LevelNames`], e.g.
+/// ```text
+/// {"time":"2024-11-12T21:10:32.123456789+00:00","level":"INFO","message":"Order created","module_path":"app::orders","file":"src/orders.rs","line":42,"target":"app::orders","thread":"main","thread_id":1,"mdc":{}}
+/// ```
+#[derive(Debug, Default)]
+pub struct JsonEncoder {
+    inner: json::JsonEncoder,
+    level_names: LevelNames,
+}
+
+impl JsonEncoder {
+    /// Creates a new `JsonEncoder` using the default [`LevelNames`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the display names used to render levels.
+    pub fn level_names(mut self, level_names: LevelNames) -> Self {
+        self.level_names = level_names;
+        self
+    }
+}
+
+impl Encode for JsonEncoder {
+    fn encode(&self, w: &mut dyn Write, record: &Record) -> anyhow::Result<()> {
+        let name = self.level_names.get_current(record.level());
+        if name == record.level().as_str() {
+            return self.inner.encode(w, record);
+        }
+
+        let mut buffer = SimpleWriter(Vec::new());
+        self.inner.encode(&mut buffer, record)?;
+        let line = buffer.0;
+
+        // The level follows the timestamp, which contains no quotes, so its field is the first one named `level`.
+        let Some(start) = line
+            .windows(LEVEL_FIELD.len())
+            .position(|window| window == LEVEL_FIELD)
+            .map(|index| index + LEVEL_FIELD.len() - 1)
+        else {
+            w.write_all(&line)?;
+            return Ok(());
+        };
+        let end = start + 1 + record.level().as_str().len() + 1;
+
+        w.write_all(&line[..start])?;
+        w.write_all(serde_json::to_string(name)?.as_bytes())?;
+        w.write_all(&line[end..])?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lum_libs::{log::Level, serde_json::Value};
+
+    use super::*;
+
+    #[test]
+    fn level_is_rendered_with_the_level_names() {
+        let mut output = SimpleWriter(Vec::new());
+        JsonEncoder::new()
+            .level_names(LevelNames::new().name(Level::Warn, "WARNING"))
+            .encode(
+                &mut output,
+                &Record::builder()
+                    .level(Level::Warn)
+                    .args(format_args!("Disk \"level\" low"))
+                    .build(),
+            )
+            .unwrap();
+
+        let line = serde_json::from_slice::<Value>(&output.0).unwrap();
+        assert_eq!(line["level"], "WARNING");
+        assert_eq!(line["message"], "Disk \"level\" low");
+    }
+}
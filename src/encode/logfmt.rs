@@ -0,0 +1,193 @@
+use std::collections::BTreeSet;
+
+use lum_libs::{
+    humantime,
+    log::Record,
+    log4rs::encode::{Encode, Write},
+    serde_json::{self, Value},
+};
+
+use crate::{encode::LEVEL_MDC_KEY, json::JSON_MDC_KEY, level::LevelNames, record::OwnedRecord};
+
+/// An encoder writing each record as a single logfmt line of `key=value` pairs, e.g.
+/// ```text
+/// time=2024-11-12T21:10:32.123Z level=info target=app::orders thread=main msg="Order created" correlation_id=4f1c order_id=17
+/// ```
+/// The timestamp, level, target, thread, and message come first, the level rendered in lowercase with the configured [`LevelNames`], followed by the MDC entries sorted by key,
+/// e.g. the event ID and correlation ID, and the fields of the JSON payload attached by [`log_json!`](crate::log_json).
+/// Values containing spaces, quotes, equals signs, or control characters are quoted and escaped, other values are written bare.
+/// Keys that already occurred are skipped, so every line has unique keys.
+#[derive(Debug, Default)]
+pub struct LogfmtEncoder {
+    level_names: LevelNames,
+}
+
+impl LogfmtEncoder {
+    /// Creates a new `LogfmtEncoder` using the default [`LevelNames`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the display names used to render levels.
+    pub fn level_names(mut self, level_names: LevelNames) -> Self {
+        self.level_names = level_names;
+        self
+    }
+}
+
+impl Encode for LogfmtEncoder {
+    fn encode(&self, w: &mut dyn Write, record: &Record) -> anyhow::Result<()> {
+        let record = OwnedRecord::from(record);
+        let mut line = Line::default();
+
+        line.push(
+            "time",
+            &humantime::format_rfc3339_millis(record.timestamp).to_string(),
+        );
+        line.push(
+            "level",
+            &self.level_names.get_current(record.level).to_lowercase(),
+        );
+        line.push("target", &record.target);
+        if let Some(thread) = &record.thread {
+            line.push("thread", thread);
+        }
+        line.push("msg", &record.message);
+
+        let mut mdc = Vec::new();
+        log_mdc::iter(|key, value| {
+            if key != LEVEL_MDC_KEY && key != JSON_MDC_KEY {
+                mdc.push((key.to_string(), value.to_string()));
+            }
+        });
+        mdc.sort();
+        for (key, value) in &mdc {
+            line.push(key, value);
+        }
+
+        if let Some(json) = &record.json {
+            match serde_json::from_str(json) {
+                Ok(Value::Object(fields)) => {
+                    for (key, value) in &fields {
+                        match value {
+                            Value::String(value) => line.push(key, value),
+                            value => line.push(key, &value.to_string()),
+                        }
+                    }
+                }
+                _ => line.push(JSON_MDC_KEY, json),
+            }
+        }
+
+        line.text.push('\n');
+        w.write_all(line.text.as_bytes())?;
+        Ok(())
+    }
+}
+
+/// A logfmt line being built, remembering its keys to skip duplicates.
+#[derive(Default)]
+struct Line {
+    text: String,
+    keys: BTreeSet<String>,
+}
+
+impl Line {
+    fn push(&mut self, key: &str, value: &str) {
+        let key = logfmt_key(key);
+        if !self.keys.insert(key.clone()) {
+            return;
+        }
+
+        if !self.text.is_empty() {
+            self.text.push(' ');
+        }
+        self.text.push_str(&key);
+        self.text.push('=');
+        match needs_quotes(value) {
+            true => self.text.push_str(&Value::from(value).to_string()),
+            false => self.text.push_str(value),
+        }
+    }
+}
+
+/// Returns the given key with characters that would break the line replaced by underscores.
+fn logfmt_key(key: &str) -> String {
+    let key = key
+        .chars()
+        .map(|char| match char {
+            '=' | '"' => '_',
+            char if char.is_whitespace() || char.is_control() => '_',
+            char => char,
+        })
+        .collect::<String>();
+
+    match key.is_empty() {
+        true => "_".to_string(),
+        false => key,
+    }
+}
+
+fn needs_quotes(value: &str) -> bool {
+    value.is_empty()
+        || value.chars().any(|char| {
+            matches!(char, '=' | '"' | '\\') || char.is_whitespace() || char.is_control()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use lum_libs::{log::Level, log4rs::encode::writer::simple::SimpleWriter};
+
+    use super::*;
+
+    #[test]
+    fn level_is_rendered_with_the_level_names_in_lowercase() {
+        let mut output = SimpleWriter(Vec::new());
+        LogfmtEncoder::new()
+            .level_names(LevelNames::short())
+            .encode(
+                &mut output,
+                &Record::builder()
+                    .level(Level::Warn)
+                    .target("app::orders")
+                    .args(format_args!("Order delayed"))
+                    .build(),
+            )
+            .unwrap();
+
+        let line = String::from_utf8(output.0).unwrap();
+        assert!(
+            line.contains(" level=wrn target=app::orders ")
+                && line.ends_with(" msg=\"Order delayed\"\n"),
+            "{line}"
+        );
+    }
+
+    #[test]
+    fn values_are_quoted_when_needed_and_followed_by_the_mdc_and_payload_fields() {
+        let _correlation_id = log_mdc::insert_scoped("correlation_id", "4f1c");
+        let _json =
+            crate::json::scope(r#"{"order_id":17,"note":"paid in full","msg":"Duplicate"}"#);
+        let mut output = SimpleWriter(Vec::new());
+        LogfmtEncoder::new()
+            .encode(
+                &mut output,
+                &Record::builder()
+                    .level(Level::Info)
+                    .target("app::orders")
+                    .args(format_args!("Order \"17\" created"))
+                    .build(),
+            )
+            .unwrap();
+
+        let line = String::from_utf8(output.0).unwrap();
+        assert!(line.starts_with("time="), "{line}");
+        assert!(
+            line.ends_with(
+                " msg=\"Order \\\"17\\\" created\" correlation_id=4f1c note=\"paid in full\" order_id=17\n"
+            ),
+            "{line}"
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use lum_libs::{
+    log::Record,
+    log4rs::encode::{Encode, Write},
+};
+
+use crate::{encode, level::LevelNames, record::OwnedRecord};
+
+/// An encoder writing each record as an [`OwnedRecord`] serialized to MessagePack,
+/// framed by its length as a little-endian `u32`.
+/// MessagePack is more compact and cheaper to encode than JSON, which matters for high-throughput services.
+/// Each call produces exactly one frame, so the encoder works for file appenders
+/// as well as for [`NetworkAppender`](crate::append::NetworkAppender)s sending one record per message.
+/// Use [`MsgpackReader`](crate::reader::MsgpackReader) to read the records back.
+#[derive(Debug, Default)]
+pub struct MsgpackEncoder {
+    level_names: LevelNames,
+}
+
+impl MsgpackEncoder {
+    /// Creates a new `MsgpackEncoder` using the default [`LevelNames`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the display names written as the [`OwnedRecord::level_name`] of each record.
+    pub fn level_names(mut self, level_names: LevelNames) -> Self {
+        self.level_names = level_names;
+        self
+    }
+}
+
+impl Encode for MsgpackEncoder {
+    fn encode(&self, w: &mut dyn Write, record: &Record) -> anyhow::Result<()> {
+        encode::mark_binary_output();
+        let payload =
+            rmp_serde::to_vec_named(&OwnedRecord::from(record).with_level_name(&self.level_names))?;
+        let length = u32::try_from(payload.len())?;
+        w.write_all(&length.to_le_bytes())?;
+        w.write_all(&payload)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lum_libs::{log::Level, log4rs::encode::writer::simple::SimpleWriter};
+
+    use super::*;
+    use crate::reader::MsgpackReader;
+
+    #[test]
+    fn records_are_read_back_until_a_truncated_frame() {
+        let encoder = MsgpackEncoder::new().level_names(LevelNames::short());
+        let mut output = SimpleWriter(Vec::new());
+        {
+            let _request = log_mdc::insert_scoped("request", "7");
+            for message in ["First", "Second"] {
+                encoder
+                    .encode(
+                        &mut output,
+                        &Record::builder()
+                            .level(Level::Warn)
+                            .target("msgpack_test")
+                            .args(format_args!("{message}"))
+                            .build(),
+                    )
+                    .unwrap();
+            }
+        }
+        // A frame cut short by a crash while writing.
+        output.0.extend_from_slice(&100u32.to_le_bytes());
+        output.0.extend_from_slice(b"partial");
+
+        let records = MsgpackReader::new(output.0.as_slice())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let messages = records
+            .iter()
+            .map(|record| record.message.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(messages, ["First", "Second"]);
+        assert_eq!(records[0].level, Level::Warn);
+        assert_eq!(records[0].level_name.as_deref(), Some("WRN"));
+        assert_eq!(records[0].target, "msgpack_test");
+        assert_eq!(records[0].mdc, [("request".to_string(), "7".to_string())]);
+    }
+}
@@ -0,0 +1,198 @@
+use std::io;
+
+use lum_libs::{
+    humantime,
+    log::{Level, Record},
+    log4rs::encode::{Color, Encode, Style, Write},
+    serde_json::{self, Value},
+};
+
+use crate::{level::LevelNames, record::OwnedRecord};
+
+const INDENT: &str = "  ";
+
+/// An encoder rendering each record as an indented, syntax-highlighted JSON block followed by a blank line, for local development.
+/// Keys, strings, numbers, and literals are colored, and the level, rendered with the configured [`LevelNames`], is colored by its severity.
+/// The JSON payload attached by [`log_json!`](crate::log_json) is rendered as nested JSON.
+/// Colors are only written if the appender's writer supports them, e.g. a console appender writing to a terminal.
+/// Use a line-based encoder like log4rs' `JsonEncoder` for files, so they stay NDJSON.
+#[derive(Debug, Default)]
+pub struct PrettyJsonEncoder {
+    level_names: LevelNames,
+}
+
+impl PrettyJsonEncoder {
+    /// Creates a new `PrettyJsonEncoder` using the default [`LevelNames`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the display names used to render levels.
+    pub fn level_names(mut self, level_names: LevelNames) -> Self {
+        self.level_names = level_names;
+        self
+    }
+}
+
+impl Encode for PrettyJsonEncoder {
+    fn encode(&self, w: &mut dyn Write, record: &Record) -> anyhow::Result<()> {
+        let record = OwnedRecord::from(record);
+
+        let mut fields = vec![
+            (
+                "timestamp",
+                Value::from(humantime::format_rfc3339_millis(record.timestamp).to_string()),
+            ),
+            (
+                "level",
+                Value::from(self.level_names.get_current(record.level)),
+            ),
+            ("target", Value::from(record.target)),
+            ("message", Value::from(record.message)),
+        ];
+        let optional = [
+            ("module_path", record.module_path.map(Value::from)),
+            ("file", record.file.map(Value::from)),
+            ("line", record.line.map(Value::from)),
+            ("thread", record.thread.map(Value::from)),
+            ("event_id", record.event_id.map(Value::from)),
+            (
+                "aux_level",
+                record
+                    .aux_level
+                    .map(|aux_level| Value::from(aux_level.as_str())),
+            ),
+            (
+                "json",
+                record
+                    .json
+                    .map(|json| serde_json::from_str(&json).unwrap_or(Value::String(json))),
+            ),
+        ];
+        fields.extend(
+            optional
+                .into_iter()
+                .filter_map(|(key, value)| value.map(|value| (key, value))),
+        );
+
+        w.write_all(b"{\n")?;
+        for (index, (key, value)) in fields.iter().enumerate() {
+            w.write_all(INDENT.as_bytes())?;
+            write_key(w, key)?;
+            match *key {
+                "level" => write_styled(w, &level_style(record.level), &value.to_string())?,
+                _ => write_value(w, value, 1)?,
+            }
+            if index + 1 < fields.len() {
+                w.write_all(b",")?;
+            }
+            w.write_all(b"\n")?;
+        }
+        w.write_all(b"}\n\n")?;
+        Ok(())
+    }
+}
+
+fn level_style(level: Level) -> Style {
+    let mut style = Style::new();
+    match level {
+        Level::Error => style.text(Color::Red).intense(true),
+        Level::Warn => style.text(Color::Yellow).intense(true),
+        Level::Info => style.text(Color::Green),
+        Level::Debug => style.text(Color::Blue),
+        Level::Trace => style.text(Color::White),
+    };
+    style
+}
+
+fn write_styled(w: &mut dyn Write, style: &Style, text: &str) -> io::Result<()> {
+    w.set_style(style)?;
+    w.write_all(text.as_bytes())?;
+    w.set_style(&Style::new())
+}
+
+fn write_key(w: &mut dyn Write, key: &str) -> io::Result<()> {
+    write_styled(
+        w,
+        Style::new().text(Color::Cyan),
+        &Value::from(key).to_string(),
+    )?;
+    w.write_all(b": ")
+}
+
+fn write_value(w: &mut dyn Write, value: &Value, depth: usize) -> io::Result<()> {
+    match value {
+        Value::Null | Value::Bool(_) => {
+            write_styled(w, Style::new().text(Color::Magenta), &value.to_string())
+        }
+        Value::Number(_) => write_styled(w, Style::new().text(Color::Yellow), &value.to_string()),
+        Value::String(_) => write_styled(w, Style::new().text(Color::Green), &value.to_string()),
+        Value::Array(values) if values.is_empty() => w.write_all(b"[]"),
+        Value::Object(map) if map.is_empty() => w.write_all(b"{}"),
+        Value::Array(values) => {
+            w.write_all(b"[\n")?;
+            for (index, value) in values.iter().enumerate() {
+                w.write_all(INDENT.repeat(depth + 1).as_bytes())?;
+                write_value(w, value, depth + 1)?;
+                if index + 1 < values.len() {
+                    w.write_all(b",")?;
+                }
+                w.write_all(b"\n")?;
+            }
+            w.write_all(INDENT.repeat(depth).as_bytes())?;
+            w.write_all(b"]")
+        }
+        Value::Object(map) => {
+            w.write_all(b"{\n")?;
+            for (index, (key, value)) in map.iter().enumerate() {
+                w.write_all(INDENT.repeat(depth + 1).as_bytes())?;
+                write_key(w, key)?;
+                write_value(w, value, depth + 1)?;
+                if index + 1 < map.len() {
+                    w.write_all(b",")?;
+                }
+                w.write_all(b"\n")?;
+            }
+            w.write_all(INDENT.repeat(depth).as_bytes())?;
+            w.write_all(b"}")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lum_libs::log4rs::encode::writer::simple::SimpleWriter;
+
+    use super::*;
+    use crate::json::JSON_MDC_KEY;
+
+    #[test]
+    fn records_are_rendered_as_indented_json_with_the_nested_payload() {
+        let mut output = SimpleWriter(Vec::new());
+        {
+            let _json = log_mdc::insert_scoped(JSON_MDC_KEY, r#"{"id":7,"tags":["new"]}"#);
+            PrettyJsonEncoder::new()
+                .level_names(LevelNames::short())
+                .encode(
+                    &mut output,
+                    &Record::builder()
+                        .level(Level::Warn)
+                        .target("pretty_json_test")
+                        .args(format_args!("Order created"))
+                        .build(),
+                )
+                .unwrap();
+        }
+
+        let output = String::from_utf8(output.0).unwrap();
+        assert!(output.ends_with("}\n\n"));
+        assert!(output.contains(
+            "\n  \"json\": {\n    \"id\": 7,\n    \"tags\": [\n      \"new\"\n    ]\n  }\n"
+        ));
+        let value = serde_json::from_str::<Value>(&output).unwrap();
+        assert_eq!(value["level"], "WRN");
+        assert_eq!(value["target"], "pretty_json_test");
+        assert_eq!(value["message"], "Order created");
+        assert_eq!(value["json"]["id"], 7);
+    }
+}
@@ -0,0 +1,84 @@
+use lum_libs::{
+    log::Record,
+    log4rs::encode::{Encode, Write},
+};
+use prost::Message;
+
+use crate::{encode, level::LevelNames, proto::LogRecord, record::OwnedRecord};
+
+/// An encoder writing each record as a protobuf [`LogRecord`], as defined by `proto/lum_log.proto`,
+/// framed by its length as a little-endian `u32`.
+/// Each call produces exactly one frame, so the encoder works for file appenders
+/// as well as for [`NetworkAppender`](crate::append::NetworkAppender)s sending one record per message.
+/// Use [`ProtobufReader`](crate::reader::ProtobufReader) to read the records back.
+#[derive(Debug, Default)]
+pub struct ProtobufEncoder {
+    level_names: LevelNames,
+}
+
+impl ProtobufEncoder {
+    /// Creates a new `ProtobufEncoder` using the default [`LevelNames`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the display names written as the `level_name` of each record.
+    pub fn level_names(mut self, level_names: LevelNames) -> Self {
+        self.level_names = level_names;
+        self
+    }
+}
+
+impl Encode for ProtobufEncoder {
+    fn encode(&self, w: &mut dyn Write, record: &Record) -> anyhow::Result<()> {
+        encode::mark_binary_output();
+        let payload =
+            LogRecord::from(&OwnedRecord::from(record).with_level_name(&self.level_names))
+                .encode_to_vec();
+        let length = u32::try_from(payload.len())?;
+        w.write_all(&length.to_le_bytes())?;
+        w.write_all(&payload)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lum_libs::{log::Level, log4rs::encode::writer::simple::SimpleWriter};
+
+    use super::*;
+    use crate::reader::ProtobufReader;
+
+    #[test]
+    fn records_are_read_back_until_a_truncated_frame() {
+        let encoder = ProtobufEncoder::new().level_names(LevelNames::short());
+        let mut output = SimpleWriter(Vec::new());
+        for message in ["First", "Second"] {
+            encoder
+                .encode(
+                    &mut output,
+                    &Record::builder()
+                        .level(Level::Error)
+                        .target("protobuf_test")
+                        .args(format_args!("{message}"))
+                        .build(),
+                )
+                .unwrap();
+        }
+        // A frame cut short by a crash while writing.
+        output.0.extend_from_slice(&100u32.to_le_bytes());
+        output.0.extend_from_slice(b"partial");
+
+        let records = ProtobufReader::new(output.0.as_slice())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let messages = records
+            .iter()
+            .map(|record| record.message.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(messages, ["First", "Second"]);
+        assert_eq!(records[1].level, Level::Error);
+        assert_eq!(records[1].level_name.as_deref(), Some("ERR"));
+        assert_eq!(records[1].target, "protobuf_test");
+    }
+}
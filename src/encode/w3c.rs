@@ -0,0 +1,296 @@
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, SystemTime},
+};
+
+use lum_libs::{
+    log::Record,
+    log4rs::encode::{Encode, Write},
+    parking_lot::Mutex,
+    serde::{Deserialize, Serialize},
+};
+
+use crate::{
+    http::{AccessEntry, access_mdc_keys},
+    rotate, timestamp,
+};
+
+/// A field of the W3C Extended Log File Format, rendered from an [`AccessEntry`] by the [`W3cEncoder`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde", rename_all = "snake_case")]
+pub enum W3cField {
+    /// `date`, the UTC date the record was logged.
+    Date,
+    /// `time`, the UTC time the record was logged.
+    Time,
+    /// `c-ip`, the address of the client.
+    ClientIp,
+    /// `cs-username`, the authenticated user.
+    Username,
+    /// `cs-method`, the request method.
+    Method,
+    /// `cs-uri-stem`, the request path.
+    UriStem,
+    /// `cs-uri-query`, the query string.
+    UriQuery,
+    /// `cs-version`, the protocol.
+    Protocol,
+    /// `sc-status`, the response status code.
+    Status,
+    /// `sc-bytes`, the size of the response body in bytes.
+    Bytes,
+    /// `time-taken`, the latency in milliseconds.
+    TimeTaken,
+    /// `cs(User-Agent)`, the `User-Agent` header.
+    UserAgent,
+    /// `cs(Referer)`, the `Referer` header.
+    Referer,
+}
+
+impl W3cField {
+    /// Returns the identifier of this field in the `#Fields` directive, e.g. `cs-method`.
+    pub fn identifier(self) -> &'static str {
+        match self {
+            W3cField::Date => "date",
+            W3cField::Time => "time",
+            W3cField::ClientIp => "c-ip",
+            W3cField::Username => "cs-username",
+            W3cField::Method => "cs-method",
+            W3cField::UriStem => "cs-uri-stem",
+            W3cField::UriQuery => "cs-uri-query",
+            W3cField::Protocol => "cs-version",
+            W3cField::Status => "sc-status",
+            W3cField::Bytes => "sc-bytes",
+            W3cField::TimeTaken => "time-taken",
+            W3cField::UserAgent => "cs(User-Agent)",
+            W3cField::Referer => "cs(Referer)",
+        }
+    }
+
+    /// Returns the fields written by IIS by default, in their order.
+    pub fn defaults() -> Vec<Self> {
+        vec![
+            W3cField::Date,
+            W3cField::Time,
+            W3cField::Method,
+            W3cField::UriStem,
+            W3cField::UriQuery,
+            W3cField::Username,
+            W3cField::ClientIp,
+            W3cField::UserAgent,
+            W3cField::Referer,
+            W3cField::Status,
+            W3cField::TimeTaken,
+        ]
+    }
+}
+
+/// When the [`W3cEncoder`] writes its directives (`#Version`, `#Date`, and `#Fields`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde", rename_all = "snake_case")]
+pub enum W3cDirectives {
+    /// Directives are never written, e.g. when a log shipper adds them.
+    Never,
+    /// Directives are written before the first record only.
+    Once,
+    /// Directives are written before the first record and again after each rotation, see [`rotate::rotations`],
+    /// so every file of a rolling file appender starts with them.
+    #[default]
+    AfterRotation,
+}
+
+/// An encoder writing access log records in the W3C Extended Log File Format, e.g.
+/// ```text
+/// #Version: 1.0
+/// #Date: 2025-10-10 13:55:36
+/// #Fields: date time cs-method cs-uri-stem sc-status
+/// 2025-10-10 13:55:36 GET /orders 200
+/// ```
+/// Missing values are written as `-`, and spaces in values are replaced by `+`.
+/// Only records with an [`AccessEntry`], i.e. those logged by [`log_access`](crate::http::log_access)
+/// and the request logging middleware, are written. Others, like the request start records, are skipped,
+/// so use it for an appender receiving [`ACCESS_TARGET`](crate::http::ACCESS_TARGET).
+#[derive(Debug)]
+pub struct W3cEncoder {
+    fields: Vec<W3cField>,
+    directives: W3cDirectives,
+    /// The value of [`rotate::rotations`] when the directives were last written, plus one. Zero if they were never written.
+    written_at: AtomicU64,
+    lock: Mutex<()>,
+}
+
+impl Default for W3cEncoder {
+    /// Creates a `W3cEncoder` writing the [`W3cField::defaults`] and [`W3cDirectives::AfterRotation`].
+    fn default() -> Self {
+        Self::new(W3cField::defaults())
+    }
+}
+
+impl W3cEncoder {
+    /// Creates a new `W3cEncoder` writing the given fields in the given order, using [`W3cDirectives::AfterRotation`].
+    pub fn new(fields: Vec<W3cField>) -> Self {
+        Self {
+            fields,
+            directives: W3cDirectives::default(),
+            written_at: AtomicU64::new(0),
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// Sets when the directives are written.
+    pub fn directives(mut self, directives: W3cDirectives) -> Self {
+        self.directives = directives;
+        self
+    }
+
+    fn needs_directives(&self) -> bool {
+        let written_at = self.written_at.load(Ordering::SeqCst);
+        match self.directives {
+            W3cDirectives::Never => false,
+            W3cDirectives::Once => written_at == 0,
+            W3cDirectives::AfterRotation => written_at != rotate::rotations() + 1,
+        }
+    }
+
+    fn write_directives(&self, w: &mut dyn Write, now: SystemTime) -> anyhow::Result<()> {
+        let (date, time) = timestamp::utc_date_time(now);
+        let identifiers = self
+            .fields
+            .iter()
+            .map(|field| field.identifier())
+            .collect::<Vec<_>>();
+
+        writeln!(w, "#Software: lum_log {}", env!("CARGO_PKG_VERSION"))?;
+        writeln!(w, "#Version: 1.0")?;
+        writeln!(w, "#Date: {date} {time}")?;
+        writeln!(w, "#Fields: {}", identifiers.join(" "))?;
+        Ok(())
+    }
+
+    fn value(field: W3cField, date: &str, time: &str) -> String {
+        let mdc = |key: &str| log_mdc::get(key, |value| value.map(str::to_string));
+        let value = match field {
+            W3cField::Date => Some(date.to_string()),
+            W3cField::Time => Some(time.to_string()),
+            W3cField::ClientIp => mdc(access_mdc_keys::REMOTE_ADDR),
+            W3cField::Username => mdc(access_mdc_keys::USER),
+            W3cField::Method => mdc(access_mdc_keys::METHOD),
+            W3cField::UriStem => mdc(access_mdc_keys::TARGET)
+                .map(|target| target.split('?').next().unwrap_or_default().to_string()),
+            W3cField::UriQuery => mdc(access_mdc_keys::TARGET)
+                .and_then(|target| target.split_once('?').map(|(_, query)| query.to_string())),
+            W3cField::Protocol => mdc(access_mdc_keys::PROTOCOL),
+            W3cField::Status => mdc(access_mdc_keys::STATUS),
+            W3cField::Bytes => mdc(access_mdc_keys::RESPONSE_BYTES),
+            W3cField::TimeTaken => mdc(access_mdc_keys::LATENCY_MICROS)
+                .and_then(|micros| micros.parse().ok())
+                .map(|micros| Duration::from_micros(micros).as_millis().to_string()),
+            W3cField::UserAgent => mdc(access_mdc_keys::USER_AGENT),
+            W3cField::Referer => mdc(access_mdc_keys::REFERER),
+        };
+
+        match value {
+            Some(value) if !value.is_empty() => value.replace(' ', "+"),
+            _ => "-".to_string(),
+        }
+    }
+}
+
+impl Encode for W3cEncoder {
+    fn encode(&self, w: &mut dyn Write, _record: &Record) -> anyhow::Result<()> {
+        if !AccessEntry::is_current() {
+            return Ok(());
+        }
+
+        let now = SystemTime::now();
+        if self.needs_directives() {
+            // Appenders encode concurrently, so only one of them writes the directives.
+            let _lock = self.lock.lock();
+            if self.needs_directives() {
+                self.write_directives(w, now)?;
+                self.written_at
+                    .store(rotate::rotations() + 1, Ordering::SeqCst);
+            }
+        }
+
+        let (date, time) = timestamp::utc_date_time(now);
+        let values = self
+            .fields
+            .iter()
+            .map(|&field| Self::value(field, &date, &time))
+            .collect::<Vec<_>>();
+        writeln!(w, "{}", values.join(" "))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lum_libs::{log::Level, log4rs::encode::writer::simple::SimpleWriter};
+
+    use super::*;
+    use crate::{rotate::Rotation, testing};
+
+    fn encode(encoder: &W3cEncoder, output: &mut SimpleWriter<Vec<u8>>, entry: &AccessEntry) {
+        let _entry = entry.scope();
+        encoder
+            .encode(
+                output,
+                &Record::builder()
+                    .level(Level::Info)
+                    .args(format_args!("Request"))
+                    .build(),
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn directives_are_written_again_after_a_rotation() {
+        let _global = testing::GLOBAL.lock();
+        let encoder = W3cEncoder::new(vec![
+            W3cField::Method,
+            W3cField::UriStem,
+            W3cField::UriQuery,
+            W3cField::Status,
+            W3cField::TimeTaken,
+            W3cField::UserAgent,
+        ]);
+        let mut entry = AccessEntry::new("GET", "/orders", 200, Duration::from_millis(12));
+        entry.query = Some("page=2".to_string());
+        entry.user_agent = Some("Mozilla/5.0 (X11)".to_string());
+        let mut output = SimpleWriter(Vec::new());
+
+        encode(&encoder, &mut output, &entry);
+        encode(
+            &encoder,
+            &mut output,
+            &AccessEntry::new("POST", "/orders", 201, Duration::ZERO),
+        );
+        rotate::notify(Rotation {
+            file: "w3c_test.log".into(),
+            rotated: "w3c_test.0.log".into(),
+        })
+        .unwrap();
+        encode(&encoder, &mut output, &entry);
+
+        let output = String::from_utf8(output.0).unwrap();
+        let lines = output
+            .lines()
+            .filter(|line| !line.starts_with("#Software") && !line.starts_with("#Date"))
+            .collect::<Vec<_>>();
+        let fields =
+            "#Fields: cs-method cs-uri-stem cs-uri-query sc-status time-taken cs(User-Agent)";
+        assert_eq!(
+            lines,
+            [
+                "#Version: 1.0",
+                fields,
+                "GET /orders page=2 200 12 Mozilla/5.0+(X11)",
+                "POST /orders - 201 0 -",
+                "#Version: 1.0",
+                fields,
+                "GET /orders page=2 200 12 Mozilla/5.0+(X11)",
+            ]
+        );
+    }
+}
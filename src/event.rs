@@ -0,0 +1,69 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use log_mdc::InsertGuard;
+use uuid::Uuid;
+
+/// The MDC key under which the event ID of the record currently being logged is exposed.
+/// Use it in patterns as `{X(event_id)}`.
+pub const EVENT_ID_MDC_KEY: &str = "event_id";
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Sets whether a unique event ID, a UUIDv7, is attached to every record.
+/// Event IDs are disabled by default. See also [`ConfigBuilder::event_ids`](crate::ConfigBuilder::event_ids).
+/// This only has an effect if the logger has been set up by this crate.
+pub fn set_event_ids(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Returns whether event IDs are attached to records.
+pub fn event_ids() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Returns the event ID of the record currently being logged on this thread, if any.
+/// This is meant for appenders and encoders that render records themselves.
+pub fn current() -> Option<Uuid> {
+    log_mdc::get(EVENT_ID_MDC_KEY, |id| {
+        id.and_then(|id| Uuid::parse_str(id).ok())
+    })
+}
+
+/// Attaches a new event ID to the record about to be logged on this thread, if event IDs are enabled.
+/// The event ID is removed when the returned guard is dropped.
+pub(crate) fn scope() -> Option<InsertGuard> {
+    event_ids().then(|| log_mdc::insert_scoped(EVENT_ID_MDC_KEY, Uuid::now_v7().to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use lum_libs::log::{self, LevelFilter};
+
+    use super::*;
+    use crate::{ConfigBuilder, testing};
+
+    #[test]
+    fn every_record_gets_its_own_event_id_if_enabled() {
+        let _global = testing::GLOBAL.lock();
+        let records = testing::capture(
+            ConfigBuilder::new()
+                .root_log_level(LevelFilter::Info)
+                .event_ids(true),
+        );
+
+        log::info!("First");
+        log::info!("Second");
+        set_event_ids(false);
+        log::info!("Without");
+
+        let ids: Vec<_> = records.try_iter().map(|record| record.event_id).collect();
+        let first = Uuid::parse_str(ids[0].as_deref().expect("The first record has an ID"))
+            .expect("The ID is a UUID");
+        let second = Uuid::parse_str(ids[1].as_deref().expect("The second record has an ID"))
+            .expect("The ID is a UUID");
+        assert_eq!(first.get_version_num(), 7);
+        assert_ne!(first, second);
+        assert_eq!(ids[2], None);
+        assert_eq!(current(), None);
+    }
+}
@@ -0,0 +1,112 @@
+use std::fmt::{Arguments, Display};
+
+use lum_libs::log::Level;
+
+use crate::{debug, error, info, trace, warn};
+
+/// Extension methods for logging the error of a [`Result`] while passing it on unchanged.
+/// Logging goes through lum_log's macros, so it falls back to stdout/stderr if the logger is not set up yet.
+pub trait LogResultExt<T, E> {
+    /// Logs the error, if any, at the error level as `{context}: {error}`.
+    fn log_err(self, context: &str) -> Self;
+
+    /// Logs the error, if any, at the warn level as `{context}: {error}`.
+    fn log_err_warn(self, context: &str) -> Self;
+
+    /// Logs the error, if any, at the given level as `{context}: {error}`.
+    fn log_err_at(self, level: Level, context: &str) -> Self;
+}
+
+impl<T, E: Display> LogResultExt<T, E> for Result<T, E> {
+    fn log_err(self, context: &str) -> Self {
+        self.log_err_at(Level::Error, context)
+    }
+
+    fn log_err_warn(self, context: &str) -> Self {
+        self.log_err_at(Level::Warn, context)
+    }
+
+    fn log_err_at(self, level: Level, context: &str) -> Self {
+        if let Err(error) = &self {
+            log(level, format_args!("{context}: {error}"));
+        }
+        self
+    }
+}
+
+/// Extension methods for logging a [`None`] while passing the [`Option`] on unchanged.
+/// Logging goes through lum_log's macros, so it falls back to stdout/stderr if the logger is not set up yet.
+pub trait LogOptionExt<T> {
+    /// Logs the message at the error level if the option is [`None`].
+    fn log_none(self, message: &str) -> Self;
+
+    /// Logs the message at the warn level if the option is [`None`].
+    fn log_none_warn(self, message: &str) -> Self;
+
+    /// Logs the message at the given level if the option is [`None`].
+    fn log_none_at(self, level: Level, message: &str) -> Self;
+}
+
+impl<T> LogOptionExt<T> for Option<T> {
+    fn log_none(self, message: &str) -> Self {
+        self.log_none_at(Level::Error, message)
+    }
+
+    fn log_none_warn(self, message: &str) -> Self {
+        self.log_none_at(Level::Warn, message)
+    }
+
+    fn log_none_at(self, level: Level, message: &str) -> Self {
+        if self.is_none() {
+            log(level, format_args!("{message}"));
+        }
+        self
+    }
+}
+
+fn log(level: Level, message: Arguments<'_>) {
+    match level {
+        Level::Error => error!("{message}"),
+        Level::Warn => warn!("{message}"),
+        Level::Info => info!("{message}"),
+        Level::Debug => debug!("{message}"),
+        Level::Trace => trace!("{message}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lum_libs::log::LevelFilter;
+
+    use super::*;
+    use crate::{ConfigBuilder, testing};
+
+    #[test]
+    fn errors_and_nones_are_logged_and_passed_on_unchanged() {
+        let _global = testing::GLOBAL.lock();
+        let records = testing::capture(ConfigBuilder::new().root_log_level(LevelFilter::Info));
+
+        let ok: Result<u8, &str> = Ok(1);
+        assert_eq!(ok.log_err("Parsing the port"), Ok(1));
+        let failed: Result<u8, &str> = Err("invalid digit");
+        assert_eq!(
+            failed.log_err_warn("Parsing the port"),
+            Err("invalid digit")
+        );
+        assert_eq!(Some(2).log_none("Missing user"), Some(2));
+        assert_eq!(None::<u8>.log_none_at(Level::Debug, "Filtered"), None);
+        assert_eq!(None::<u8>.log_none("Missing user"), None);
+
+        let logged = records
+            .try_iter()
+            .map(|record| (record.level, record.message))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            logged,
+            [
+                (Level::Warn, "Parsing the port: invalid digit".to_string()),
+                (Level::Error, "Missing user".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+
+use lum_libs::{
+    parking_lot::{RwLock, const_rwlock},
+    serde::Serialize,
+};
+
+use crate::{logger, toggle};
+
+/// The consecutive failures of appenders, by appender name. Appenders without failures have no entry.
+static FAILURES: RwLock<Option<HashMap<String, Failures>>> = const_rwlock(None);
+
+#[derive(Debug, Clone)]
+struct Failures {
+    count: u64,
+    last_error: String,
+}
+
+/// The status of an appender, see [`appender_health`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(crate = "lum_libs::serde", rename_all = "snake_case", tag = "status")]
+pub enum AppenderStatus {
+    /// The last record was appended successfully, or no record has been appended yet.
+    Ok,
+    /// The last records failed to append.
+    Degraded {
+        /// The error of the last failed record.
+        last_error: String,
+        /// The number of records that failed to append since the last successful one.
+        consecutive_failures: u64,
+    },
+    /// The appender has been disabled by [`AppenderHandle::set_enabled`](crate::toggle::AppenderHandle::set_enabled).
+    Disabled,
+}
+
+/// The health of a single appender, see [`appender_health`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct AppenderHealth {
+    pub name: String,
+    #[serde(flatten)]
+    pub status: AppenderStatus,
+}
+
+impl AppenderHealth {
+    /// Returns whether the appender is [`AppenderStatus::Ok`].
+    pub fn is_ok(&self) -> bool {
+        self.status == AppenderStatus::Ok
+    }
+}
+
+/// Returns the health of every appender of the configuration built by the [`ConfigBuilder`](crate::ConfigBuilder)
+/// the logger was set up with, sorted by name, e.g. for the health endpoint of the application.
+/// An appender is degraded while its records fail to append, e.g. because the log shipping endpoint is down.
+/// For appenders wrapped by [`ConfigBuilder::asynchronous`](crate::ConfigBuilder::asynchronous), this reflects the failures of the wrapped appender.
+/// Returns no appenders if the logger has not been set up with a `ConfigBuilder`.
+///
+/// ```text
+/// for health in lum_log::appender_health().iter().filter(|health| !health.is_ok()) {
+///     report_unhealthy(format!("logging to {} is down: {:?}", health.name, health.status));
+/// }
+/// ```
+pub fn appender_health() -> Vec<AppenderHealth> {
+    let mut names = logger::with_builder(|builder| builder.appender_names()).unwrap_or_default();
+    names.sort();
+
+    let failures = FAILURES.read();
+    names
+        .into_iter()
+        .map(|name| {
+            let status = match failures.as_ref().and_then(|failures| failures.get(&name)) {
+                _ if !toggle::appender(name.as_str()).is_enabled() => AppenderStatus::Disabled,
+                Some(failures) => AppenderStatus::Degraded {
+                    last_error: failures.last_error.clone(),
+                    consecutive_failures: failures.count,
+                },
+                None => AppenderStatus::Ok,
+            };
+            AppenderHealth { name, status }
+        })
+        .collect()
+}
+
+/// Records the result of appending a record to the appender with the given name.
+pub(crate) fn record_append(name: &str, result: &anyhow::Result<()>) {
+    match result {
+        Ok(()) => {
+            let failing = FAILURES
+                .read()
+                .as_ref()
+                .is_some_and(|failures| failures.contains_key(name));
+            if failing && let Some(failures) = FAILURES.write().as_mut() {
+                failures.remove(name);
+            }
+        }
+        Err(error) => {
+            let mut failures = FAILURES.write();
+            let failures = failures
+                .get_or_insert_with(HashMap::new)
+                .entry(name.to_string())
+                .or_insert_with(|| Failures {
+                    count: 0,
+                    last_error: String::new(),
+                });
+            failures.count += 1;
+            failures.last_error = format!("{error:#}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    };
+
+    use lum_libs::{
+        log::{self, LevelFilter, Record},
+        log4rs::append::Append,
+    };
+
+    use super::*;
+    use crate::{ConfigBuilder, testing};
+
+    /// An appender failing while its flag is set.
+    #[derive(Debug)]
+    struct Flaky(Arc<AtomicBool>);
+
+    impl Append for Flaky {
+        fn append(&self, record: &Record) -> anyhow::Result<()> {
+            match self.0.load(Ordering::SeqCst) {
+                true => Err(anyhow::anyhow!(
+                    "Endpoint down while sending {}",
+                    record.args()
+                )),
+                false => Ok(()),
+            }
+        }
+
+        fn flush(&self) {}
+    }
+
+    fn status(name: &str) -> AppenderStatus {
+        appender_health()
+            .into_iter()
+            .find(|health| health.name == name)
+            .expect("The appender is reported")
+            .status
+    }
+
+    #[test]
+    fn appenders_are_degraded_while_their_records_fail() {
+        let _global = testing::GLOBAL.lock();
+        let down = Arc::new(AtomicBool::new(true));
+        let _records = testing::capture(
+            ConfigBuilder::new()
+                .root_log_level(LevelFilter::Info)
+                .appender("health_test", Box::new(Flaky(Arc::clone(&down)))),
+        );
+
+        log::info!(target: "health_test", "First");
+        log::info!(target: "health_test", "Second");
+        assert_eq!(
+            status("health_test"),
+            AppenderStatus::Degraded {
+                last_error: "Endpoint down while sending Second".to_string(),
+                consecutive_failures: 2,
+            }
+        );
+        assert_eq!(status("capture"), AppenderStatus::Ok);
+
+        down.store(false, Ordering::SeqCst);
+        log::info!(target: "health_test", "Third");
+        assert_eq!(status("health_test"), AppenderStatus::Ok);
+
+        let handle = toggle::appender("health_test");
+        handle.set_enabled(false).unwrap();
+        assert_eq!(status("health_test"), AppenderStatus::Disabled);
+        handle.set_enabled(true).unwrap();
+    }
+}
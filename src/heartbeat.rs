@@ -0,0 +1,159 @@
+use std::{
+    io,
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+    time::Duration,
+};
+
+use lum_libs::{
+    log::Level,
+    parking_lot::{Condvar, Mutex},
+    serde::Serialize,
+};
+
+use crate::{
+    log_json, logger,
+    stats::{self, Stats},
+};
+
+/// The target of the heartbeat records, see [`set_heartbeat`].
+pub const HEARTBEAT_TARGET: &str = "lum_log::heartbeat";
+
+static INTERVAL: Mutex<Option<Duration>> = Mutex::new(None);
+static CHANGED: Condvar = Condvar::new();
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// The JSON payload of a heartbeat record: the uptime, and the records logged per level,
+/// records dropped, and failed appends since the last heartbeat.
+#[derive(Debug, Serialize)]
+#[serde(crate = "lum_libs::serde")]
+struct Payload {
+    uptime_secs: u64,
+    error: u64,
+    warn: u64,
+    info: u64,
+    debug: u64,
+    trace: u64,
+    dropped: u64,
+    appender_errors: u64,
+}
+
+impl Payload {
+    fn new(stats: &Stats, last: &Stats) -> Self {
+        let since_last = |level| stats.records(level) - last.records(level);
+        Self {
+            uptime_secs: stats.uptime.as_secs(),
+            error: since_last(Level::Error),
+            warn: since_last(Level::Warn),
+            info: since_last(Level::Info),
+            debug: since_last(Level::Debug),
+            trace: since_last(Level::Trace),
+            dropped: stats.dropped - last.dropped,
+            appender_errors: stats.appender_errors - last.appender_errors,
+        }
+    }
+}
+
+/// Sets the interval in which an info record is logged to [`HEARTBEAT_TARGET`], or `None` to stop logging them.
+/// The record carries the uptime and the record counts since the previous heartbeat, whose own record is included, as JSON payload, see [`log_json!`],
+/// so downstream pipelines can tell a quiet service from broken log shipping.
+/// The first heartbeat is logged one interval after this call. No heartbeat is logged by default.
+/// See also [`ConfigBuilder::heartbeat`](crate::ConfigBuilder::heartbeat).
+/// This spawns a background thread, which stops once the heartbeat is disabled.
+pub fn set_heartbeat(interval: Option<Duration>) -> io::Result<()> {
+    let mut current = INTERVAL.lock();
+    *current = interval;
+    CHANGED.notify_all();
+
+    if interval.is_some() && !RUNNING.swap(true, Ordering::Relaxed) {
+        let since = stats::stats();
+        let spawned = thread::Builder::new()
+            .name("lum_log-heartbeat".to_string())
+            .spawn(move || run(since));
+        if let Err(error) = spawned {
+            RUNNING.store(false, Ordering::Relaxed);
+            *current = None;
+            return Err(error);
+        }
+    }
+    Ok(())
+}
+
+/// Returns the interval set by [`set_heartbeat`], if any.
+pub fn heartbeat() -> Option<Duration> {
+    *INTERVAL.lock()
+}
+
+fn run(mut last: Stats) {
+    let mut interval = INTERVAL.lock();
+
+    loop {
+        let Some(duration) = *interval else {
+            RUNNING.store(false, Ordering::Relaxed);
+            return;
+        };
+
+        // A changed interval restarts the wait.
+        if !CHANGED.wait_for(&mut interval, duration).timed_out() {
+            continue;
+        }
+
+        let stats = stats::stats();
+        let payload = Payload::new(&stats, &last);
+        last = stats;
+
+        if logger::is_set_up() {
+            drop(interval);
+            log_json!(target: HEARTBEAT_TARGET, Level::Info, "Heartbeat", &payload);
+            interval = INTERVAL.lock();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lum_libs::{
+        log::{self, LevelFilter},
+        serde_json::{self, Value},
+    };
+
+    use super::*;
+    use crate::{ConfigBuilder, testing};
+
+    #[test]
+    fn heartbeats_carry_the_record_counts_since_the_previous_one() {
+        let _global = testing::GLOBAL.lock();
+        let records = testing::capture(
+            ConfigBuilder::new()
+                .root_log_level(LevelFilter::Info)
+                .heartbeat(Duration::from_millis(50)),
+        );
+
+        log::warn!(target: "heartbeat_test", "Disk almost full");
+        log::warn!(target: "heartbeat_test", "Disk full");
+        let heartbeats = (0..2)
+            .map(|_| {
+                loop {
+                    let record = records.recv_timeout(Duration::from_secs(10)).unwrap();
+                    if record.target == HEARTBEAT_TARGET {
+                        break record;
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+        set_heartbeat(None).unwrap();
+
+        let payloads = heartbeats
+            .iter()
+            .map(|record| {
+                assert_eq!(record.level, Level::Info);
+                serde_json::from_str::<Value>(record.json.as_deref().unwrap()).unwrap()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(payloads[0]["warn"], 2);
+        // The second heartbeat counts the record of the first one.
+        assert_eq!(payloads[1]["warn"], 0);
+        assert_eq!(payloads[1]["info"], 1);
+        assert_eq!(heartbeat(), None);
+    }
+}
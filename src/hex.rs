@@ -0,0 +1,97 @@
+use std::fmt::{self, Display, Formatter};
+
+use crate::default;
+
+/// A hex and ASCII dump of bytes, rendered like `hexdump -C`:
+/// ```text
+/// 00000000  48 65 6c 6c 6f 2c 20 77  6f 72 6c 64 21 0a 00 ff  |Hello, world!...|
+/// ```
+/// Dumps are capped at a maximum length, noting the number of omitted bytes.
+#[derive(Debug, Clone, Copy)]
+pub struct HexDump<'a> {
+    bytes: &'a [u8],
+    max_len: usize,
+}
+
+impl<'a> HexDump<'a> {
+    /// Creates a new `HexDump` of the given bytes, capped at [`default::hex_dump_max_len`].
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self {
+            bytes,
+            max_len: default::hex_dump_max_len(),
+        }
+    }
+
+    /// Sets the maximum number of bytes dumped.
+    pub fn max_len(mut self, max_len: usize) -> Self {
+        self.max_len = max_len;
+        self
+    }
+}
+
+impl Display for HexDump<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let shown = &self.bytes[..self.bytes.len().min(self.max_len)];
+
+        for (index, line) in shown.chunks(16).enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{:08x} ", index * 16)?;
+
+            for column in 0..16 {
+                if column == 8 {
+                    write!(f, " ")?;
+                }
+                match line.get(column) {
+                    Some(byte) => write!(f, " {byte:02x}")?,
+                    None => write!(f, "   ")?,
+                }
+            }
+
+            write!(f, "  |")?;
+            for &byte in line {
+                let char = match byte.is_ascii_graphic() || byte == b' ' {
+                    true => byte as char,
+                    false => '.',
+                };
+                write!(f, "{char}")?;
+            }
+            write!(f, "|")?;
+        }
+
+        let omitted = self.bytes.len() - shown.len();
+        if omitted > 0 {
+            if !shown.is_empty() {
+                writeln!(f)?;
+            }
+            write!(f, "... {omitted} more bytes")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dumps_are_rendered_like_hexdump_and_capped() {
+        let bytes = b"Hello, world!\n\x00\xffRest";
+
+        assert_eq!(
+            HexDump::new(bytes).to_string(),
+            "00000000  48 65 6c 6c 6f 2c 20 77  6f 72 6c 64 21 0a 00 ff  |Hello, world!...|\n\
+             00000010  52 65 73 74                                       |Rest|"
+        );
+        assert_eq!(
+            HexDump::new(bytes).max_len(2).to_string(),
+            "00000000  48 65                                             |He|\n... 18 more bytes"
+        );
+        assert_eq!(
+            HexDump::new(bytes).max_len(0).to_string(),
+            "... 20 more bytes"
+        );
+    }
+}
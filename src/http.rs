@@ -0,0 +1,261 @@
+/// Defines the actix-web [`AccessLog`](actix::AccessLog) middleware for logging HTTP requests.
+#[cfg(feature = "actix")]
+pub mod actix;
+/// Defines helpers for logging outgoing HTTP requests.
+pub mod client;
+/// Defines the reqwest [`ClientLogMiddleware`](reqwest::ClientLogMiddleware) for logging outgoing HTTP requests.
+#[cfg(feature = "reqwest")]
+pub mod reqwest;
+/// Defines the tower [`AccessLogLayer`](tower::AccessLogLayer) for logging HTTP requests.
+#[cfg(feature = "tower")]
+pub mod tower;
+
+use std::{
+    fmt::{self, Display, Formatter},
+    time::Duration,
+};
+
+use log_mdc::InsertGuard;
+use lum_libs::log::{self, Level};
+
+use crate::internal;
+
+/// The target of the access log records of HTTP requests.
+pub const ACCESS_TARGET: &str = "lum_log::access";
+
+/// The MDC key under which the [`CorrelationId`] of a request is exposed while its access log records are logged.
+/// Use it in patterns as `{X(correlation_id)}`.
+pub const CORRELATION_ID_MDC_KEY: &str = "correlation_id";
+
+/// The MDC keys under which the fields of an [`AccessEntry`] are exposed while its access log record is logged,
+/// e.g. for the [`CombinedLogEncoder`](crate::encode::CombinedLogEncoder).
+/// Use them in patterns as `{X(http_status)}`. Missing optional fields are not inserted.
+pub mod access_mdc_keys {
+    /// The request method, e.g. `GET`.
+    pub const METHOD: &str = "http_method";
+    /// The request target, i.e. the path including the query, e.g. `/orders?page=2`.
+    pub const TARGET: &str = "http_target";
+    /// The protocol, e.g. `HTTP/1.1`.
+    pub const PROTOCOL: &str = "http_protocol";
+    /// The response status code, e.g. `200`.
+    pub const STATUS: &str = "http_status";
+    /// The latency in microseconds.
+    pub const LATENCY_MICROS: &str = "http_latency_micros";
+    /// The address of the client.
+    pub const REMOTE_ADDR: &str = "http_remote_addr";
+    /// The authenticated user.
+    pub const USER: &str = "http_user";
+    /// The `Referer` header.
+    pub const REFERER: &str = "http_referer";
+    /// The `User-Agent` header.
+    pub const USER_AGENT: &str = "http_user_agent";
+    /// The size of the response body in bytes.
+    pub const RESPONSE_BYTES: &str = "http_response_bytes";
+}
+
+/// The header a [`CorrelationId`] is read from and written to.
+pub const CORRELATION_ID_HEADER: &str = "x-correlation-id";
+
+/// The header a [`CorrelationId`] is read from if [`CORRELATION_ID_HEADER`] is missing.
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// An ID correlating all records belonging to one HTTP request, across services.
+/// Request logging middleware inserts it into the request's extensions, so handlers can access it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CorrelationId(String);
+
+impl Default for CorrelationId {
+    /// Creates a new random `CorrelationId` in the layout of a UUIDv7.
+    fn default() -> Self {
+        Self(internal::unique_id())
+    }
+}
+
+impl CorrelationId {
+    /// Same as [`CorrelationId::default`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the given header value as `CorrelationId`,
+    /// or a new one if the value is missing, empty, or longer than 128 characters.
+    pub fn from_header(value: Option<&str>) -> Self {
+        match value.map(str::trim) {
+            Some(value) if !value.is_empty() && value.len() <= 128 => Self(value.to_string()),
+            _ => Self::new(),
+        }
+    }
+
+    /// Returns the ID as string.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Exposes this ID under [`CORRELATION_ID_MDC_KEY`] to the records logged on this thread until the returned guard is dropped.
+    pub fn scope(&self) -> InsertGuard {
+        log_mdc::insert_scoped(CORRELATION_ID_MDC_KEY, self.0.as_str())
+    }
+}
+
+impl Display for CorrelationId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// The details of a finished HTTP request, logged by [`log_access`].
+/// The request logging middleware fill in as many fields as their framework provides.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccessEntry {
+    /// The request method, e.g. `GET`.
+    pub method: String,
+    /// The request path, e.g. `/orders`.
+    pub path: String,
+    /// The query string without the leading `?`, if any.
+    pub query: Option<String>,
+    /// The protocol, e.g. `HTTP/1.1`.
+    pub protocol: Option<String>,
+    /// The response status code.
+    pub status: u16,
+    /// The time from receiving the request to finishing the response.
+    pub latency: Duration,
+    /// The address of the client.
+    pub remote_addr: Option<String>,
+    /// The authenticated user.
+    pub user: Option<String>,
+    /// The `Referer` header.
+    pub referer: Option<String>,
+    /// The `User-Agent` header.
+    pub user_agent: Option<String>,
+    /// The size of the response body in bytes.
+    pub response_bytes: Option<u64>,
+}
+
+impl AccessEntry {
+    /// Creates a new `AccessEntry` with the given required fields and no optional fields.
+    pub fn new(
+        method: impl Into<String>,
+        path: impl Into<String>,
+        status: u16,
+        latency: Duration,
+    ) -> Self {
+        Self {
+            method: method.into(),
+            path: path.into(),
+            query: None,
+            protocol: None,
+            status,
+            latency,
+            remote_addr: None,
+            user: None,
+            referer: None,
+            user_agent: None,
+            response_bytes: None,
+        }
+    }
+
+    /// Returns the request target, i.e. the path including the query, e.g. `/orders?page=2`.
+    pub fn target(&self) -> String {
+        match &self.query {
+            Some(query) => format!("{}?{query}", self.path),
+            None => self.path.clone(),
+        }
+    }
+
+    /// Exposes this entry under the [`access_mdc_keys`] to the records logged on this thread until the returned guards are dropped.
+    pub fn scope(&self) -> Vec<InsertGuard> {
+        let mut guards = vec![
+            log_mdc::insert_scoped(access_mdc_keys::METHOD, self.method.as_str()),
+            log_mdc::insert_scoped(access_mdc_keys::TARGET, self.target()),
+            log_mdc::insert_scoped(access_mdc_keys::STATUS, self.status.to_string()),
+            log_mdc::insert_scoped(
+                access_mdc_keys::LATENCY_MICROS,
+                self.latency.as_micros().to_string(),
+            ),
+        ];
+
+        let optional = [
+            (access_mdc_keys::PROTOCOL, self.protocol.clone()),
+            (access_mdc_keys::REMOTE_ADDR, self.remote_addr.clone()),
+            (access_mdc_keys::USER, self.user.clone()),
+            (access_mdc_keys::REFERER, self.referer.clone()),
+            (access_mdc_keys::USER_AGENT, self.user_agent.clone()),
+            (
+                access_mdc_keys::RESPONSE_BYTES,
+                self.response_bytes.map(|bytes| bytes.to_string()),
+            ),
+        ];
+        for (key, value) in optional {
+            if let Some(value) = value {
+                guards.push(log_mdc::insert_scoped(key, value));
+            }
+        }
+
+        guards
+    }
+
+    /// Returns whether the record currently being logged on this thread has an access entry exposed by [`AccessEntry::scope`].
+    pub fn is_current() -> bool {
+        log_mdc::get(access_mdc_keys::STATUS, |status| status.is_some())
+    }
+}
+
+/// Logs the start of an HTTP request at [`Level::Info`] to [`ACCESS_TARGET`].
+pub fn log_request_start(method: &str, path: &str, correlation_id: &CorrelationId) {
+    let _guard = correlation_id.scope();
+    log::info!(target: ACCESS_TARGET, "--> {method} {path} correlation_id={correlation_id}");
+}
+
+/// Logs the end of an HTTP request to [`ACCESS_TARGET`], see [`log_access`].
+pub fn log_request_end(
+    method: &str,
+    path: &str,
+    status: u16,
+    latency: Duration,
+    correlation_id: &CorrelationId,
+) {
+    log_access(
+        &AccessEntry::new(method, path, status, latency),
+        correlation_id,
+    );
+}
+
+/// Logs the end of an HTTP request described by the given [`AccessEntry`] to [`ACCESS_TARGET`],
+/// exposing the entry under the [`access_mdc_keys`] while the record is logged.
+/// Server errors are logged at [`Level::Error`], client errors at [`Level::Warn`], and everything else at [`Level::Info`].
+pub fn log_access(entry: &AccessEntry, correlation_id: &CorrelationId) {
+    let level = match entry.status {
+        500.. => Level::Error,
+        400..500 => Level::Warn,
+        _ => Level::Info,
+    };
+
+    let _guard = correlation_id.scope();
+    let _entry = entry.scope();
+    let AccessEntry {
+        method,
+        path,
+        status,
+        latency,
+        ..
+    } = entry;
+    log::log!(
+        target: ACCESS_TARGET,
+        level,
+        "<-- {method} {path} {status} {latency:.3?} correlation_id={correlation_id}"
+    );
+}
+
+/// Logs an HTTP request that failed without a response at [`Level::Error`] to [`ACCESS_TARGET`].
+pub fn log_request_failed(
+    method: &str,
+    path: &str,
+    latency: Duration,
+    correlation_id: &CorrelationId,
+) {
+    let _guard = correlation_id.scope();
+    log::error!(
+        target: ACCESS_TARGET,
+        "<-- {method} {path} failed {latency:.3?} correlation_id={correlation_id}"
+    );
+}
@@ -0,0 +1,176 @@
+use std::{
+    future::{Future, Ready, ready},
+    pin::Pin,
+    time::{Duration, Instant},
+};
+
+use actix_web::{
+    Error, HttpMessage,
+    body::{BodySize, MessageBody},
+    dev::{Service, ServiceRequest, ServiceResponse, Transform, forward_ready},
+    http::header::{self, HeaderMap, HeaderName, HeaderValue},
+};
+
+use crate::http::{
+    AccessEntry, CORRELATION_ID_HEADER, CorrelationId, REQUEST_ID_HEADER, log_access,
+    log_request_start,
+};
+
+/// An actix-web middleware logging the start and end of every request with its method, path, status, latency, and [`CorrelationId`].
+/// The correlation ID is taken from the request's headers or generated,
+/// inserted into the request's extensions, and returned in the [`CORRELATION_ID_HEADER`] of the response.
+/// The records are identical to those of the tower `AccessLogLayer`, as both use [`log_request_start`] and [`log_access`].
+/// The [`AccessEntry`] includes the query, protocol, peer address, `Referer` and `User-Agent` headers, and the size of sized response bodies.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AccessLog;
+
+impl AccessLog {
+    /// Same as [`AccessLog::default`].
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for AccessLog
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = AccessLogMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(AccessLogMiddleware { service }))
+    }
+}
+
+/// The middleware service created by [`AccessLog`].
+#[derive(Debug)]
+pub struct AccessLogMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for AccessLogMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    forward_ready!(service);
+
+    fn call(&self, request: ServiceRequest) -> Self::Future {
+        let method = request.method().to_string();
+        let path = request.path().to_string();
+        let header = request
+            .headers()
+            .get(CORRELATION_ID_HEADER)
+            .or_else(|| request.headers().get(REQUEST_ID_HEADER))
+            .and_then(|value| value.to_str().ok());
+        let correlation_id = CorrelationId::from_header(header);
+
+        let mut entry = AccessEntry::new(method.as_str(), path.as_str(), 0, Duration::ZERO);
+        entry.query = Some(request.query_string())
+            .filter(|query| !query.is_empty())
+            .map(str::to_string);
+        entry.protocol = Some(format!("{:?}", request.version()));
+        entry.remote_addr = request.peer_addr().map(|address| address.ip().to_string());
+        entry.referer = header_value(request.headers(), header::REFERER);
+        entry.user_agent = header_value(request.headers(), header::USER_AGENT);
+
+        request.extensions_mut().insert(correlation_id.clone());
+        log_request_start(&method, &path, &correlation_id);
+
+        let start = Instant::now();
+        let future = self.service.call(request);
+        Box::pin(async move {
+            let mut result = future.await;
+            let latency = start.elapsed();
+
+            let status = match &mut result {
+                Ok(response) => {
+                    if let Ok(value) = HeaderValue::from_str(correlation_id.as_str()) {
+                        let name = HeaderName::from_static(CORRELATION_ID_HEADER);
+                        response.headers_mut().insert(name, value);
+                    }
+                    if let BodySize::Sized(size) = response.response().body().size() {
+                        entry.response_bytes = Some(size);
+                    }
+                    response.status()
+                }
+                Err(error) => error.as_response_error().status_code(),
+            };
+            entry.status = status.as_u16();
+            entry.latency = latency;
+            log_access(&entry, &correlation_id);
+
+            result
+        })
+    }
+}
+
+fn header_value(headers: &HeaderMap, name: HeaderName) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{App, HttpResponse, rt::System, test, web};
+    use lum_libs::log::LevelFilter;
+
+    use super::*;
+    use crate::{ConfigBuilder, http::access_mdc_keys, testing};
+
+    #[test]
+    fn requests_are_logged_with_their_access_entry_and_correlation_id() {
+        let _global = testing::GLOBAL.lock();
+        let records = testing::capture(ConfigBuilder::new().root_log_level(LevelFilter::Info));
+
+        let response = System::new().block_on(async {
+            let app = test::init_service(App::new().wrap(AccessLog::new()).route(
+                "/orders",
+                web::get().to(|| async { HttpResponse::Ok().body("Orders") }),
+            ))
+            .await;
+            let request = test::TestRequest::get()
+                .uri("/orders?page=2")
+                .insert_header((REQUEST_ID_HEADER, "request-7"))
+                .peer_addr("192.0.2.1:443".parse().expect("The address is valid"))
+                .to_request();
+            test::call_service(&app, request).await
+        });
+
+        assert_eq!(
+            header_value(
+                response.headers(),
+                HeaderName::from_static(CORRELATION_ID_HEADER)
+            )
+            .as_deref(),
+            Some("request-7")
+        );
+        let records: Vec<_> = records.try_iter().collect();
+        assert_eq!(records.len(), 2);
+        let mdc = |key: &str| {
+            records[1]
+                .mdc
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value.as_str())
+        };
+        assert_eq!(mdc(crate::http::CORRELATION_ID_MDC_KEY), Some("request-7"));
+        assert_eq!(mdc(access_mdc_keys::TARGET), Some("/orders?page=2"));
+        assert_eq!(mdc(access_mdc_keys::STATUS), Some("200"));
+        assert_eq!(mdc(access_mdc_keys::REMOTE_ADDR), Some("192.0.2.1"));
+        assert_eq!(mdc(access_mdc_keys::RESPONSE_BYTES), Some("6"));
+    }
+}
@@ -0,0 +1,97 @@
+use std::time::Instant;
+
+use ::http::{Extensions, HeaderValue};
+use async_trait::async_trait;
+use reqwest::{Request, Response};
+use reqwest_middleware::{Middleware, Next, Result};
+
+use crate::http::{
+    CORRELATION_ID_HEADER, CorrelationId,
+    client::{
+        log_client_error, log_client_request, log_client_response, redact_headers, redact_url,
+    },
+};
+
+/// The number of attempts of the current call, shared through the extensions between retries.
+#[derive(Debug, Clone, Copy)]
+struct Attempt(u32);
+
+/// A reqwest middleware logging every outgoing request and its response or error
+/// with redacted headers, duration, and retry count.
+/// If a [`CorrelationId`] is present in the extensions or the request's [`CORRELATION_ID_HEADER`],
+/// it is logged and sent in the request's [`CORRELATION_ID_HEADER`].
+/// Add it after a retry middleware, so every attempt is logged.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ClientLogMiddleware;
+
+impl ClientLogMiddleware {
+    /// Same as [`ClientLogMiddleware::default`].
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl Middleware for ClientLogMiddleware {
+    async fn handle(
+        &self,
+        mut request: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> Result<Response> {
+        let attempt = extensions
+            .get::<Attempt>()
+            .map_or(1, |attempt| attempt.0 + 1);
+        extensions.insert(Attempt(attempt));
+
+        let header = request
+            .headers()
+            .get(CORRELATION_ID_HEADER)
+            .and_then(|value| value.to_str().ok());
+        let correlation_id = match extensions.get::<CorrelationId>() {
+            Some(correlation_id) => Some(correlation_id.clone()),
+            None => header.map(|header| CorrelationId::from_header(Some(header))),
+        };
+        if let Some(correlation_id) = &correlation_id
+            && let Ok(value) = HeaderValue::from_str(correlation_id.as_str())
+        {
+            request.headers_mut().insert(CORRELATION_ID_HEADER, value);
+        }
+
+        let method = request.method().to_string();
+        let url = redact_url(request.url().as_str());
+        let headers =
+            redact_headers(request.headers().iter().filter_map(|(name, value)| {
+                value.to_str().ok().map(|value| (name.as_str(), value))
+            }));
+        log_client_request(&method, &url, &headers, attempt, correlation_id.as_ref());
+
+        let start = Instant::now();
+        let result = next.run(request, extensions).await;
+        let duration = start.elapsed();
+
+        match &result {
+            Ok(response) => {
+                let status = response.status().as_u16();
+                log_client_response(
+                    &method,
+                    &url,
+                    status,
+                    duration,
+                    attempt,
+                    correlation_id.as_ref(),
+                );
+            }
+            Err(error) => log_client_error(
+                &method,
+                &url,
+                error,
+                duration,
+                attempt,
+                correlation_id.as_ref(),
+            ),
+        }
+
+        result
+    }
+}
@@ -0,0 +1,177 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use ::http::{HeaderMap, HeaderValue, Request, Response, header};
+use tower_layer::Layer;
+use tower_service::Service;
+
+use crate::http::{
+    AccessEntry, CORRELATION_ID_HEADER, CorrelationId, REQUEST_ID_HEADER, log_access,
+    log_request_failed, log_request_start,
+};
+
+/// A tower [`Layer`] logging the start and end of every request with its method, path, status, latency, and [`CorrelationId`].
+/// The end is logged by [`log_access`], with the query, protocol, `Referer` and `User-Agent` headers,
+/// and the response's `Content-Length` as [`AccessEntry`]. tower does not expose the client address.
+/// The correlation ID is taken from the request's headers or generated,
+/// inserted into the request's extensions, and returned in the [`CORRELATION_ID_HEADER`] of the response.
+/// Works with every tower-based framework, like axum.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AccessLogLayer;
+
+impl AccessLogLayer {
+    /// Same as [`AccessLogLayer::default`].
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<S> Layer<S> for AccessLogLayer {
+    type Service = AccessLogService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        AccessLogService { inner }
+    }
+}
+
+/// The [`Service`] created by the [`AccessLogLayer`].
+#[derive(Debug, Clone)]
+pub struct AccessLogService<S> {
+    inner: S,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for AccessLogService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<ReqBody>) -> Self::Future {
+        let method = request.method().to_string();
+        let path = request.uri().path().to_string();
+        let header = request
+            .headers()
+            .get(CORRELATION_ID_HEADER)
+            .or_else(|| request.headers().get(REQUEST_ID_HEADER))
+            .and_then(|value| value.to_str().ok());
+        let correlation_id = CorrelationId::from_header(header);
+
+        let mut entry = AccessEntry::new(method.as_str(), path.as_str(), 0, Duration::ZERO);
+        entry.query = request.uri().query().map(str::to_string);
+        entry.protocol = Some(format!("{:?}", request.version()));
+        entry.referer = header_value(request.headers(), header::REFERER);
+        entry.user_agent = header_value(request.headers(), header::USER_AGENT);
+
+        request.extensions_mut().insert(correlation_id.clone());
+        log_request_start(&method, &path, &correlation_id);
+
+        let start = Instant::now();
+        let future = self.inner.call(request);
+        Box::pin(async move {
+            let mut result = future.await;
+            let latency = start.elapsed();
+
+            match &mut result {
+                Ok(response) => {
+                    if let Ok(value) = HeaderValue::from_str(correlation_id.as_str()) {
+                        response.headers_mut().insert(CORRELATION_ID_HEADER, value);
+                    }
+                    entry.status = response.status().as_u16();
+                    entry.latency = latency;
+                    entry.response_bytes = header_value(response.headers(), header::CONTENT_LENGTH)
+                        .and_then(|length| length.parse().ok());
+                    log_access(&entry, &correlation_id);
+                }
+                Err(_) => log_request_failed(&method, &path, latency, &correlation_id),
+            }
+
+            result
+        })
+    }
+}
+
+fn header_value(headers: &HeaderMap, name: header::HeaderName) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{convert::Infallible, future, task::Waker};
+
+    use lum_libs::log::{Level, LevelFilter};
+
+    use super::*;
+    use crate::{ConfigBuilder, http::access_mdc_keys, testing};
+
+    struct Respond(u16);
+
+    impl Service<Request<()>> for Respond {
+        type Response = Response<()>;
+        type Error = Infallible;
+        type Future = future::Ready<Result<Response<()>, Infallible>>;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, request: Request<()>) -> Self::Future {
+            assert!(request.extensions().get::<CorrelationId>().is_some());
+            let response = Response::builder()
+                .status(self.0)
+                .header(header::CONTENT_LENGTH, "42")
+                .body(())
+                .expect("The response is valid");
+            future::ready(Ok(response))
+        }
+    }
+
+    #[test]
+    fn requests_are_logged_with_their_access_entry_and_correlation_id() {
+        let _global = testing::GLOBAL.lock();
+        let records = testing::capture(ConfigBuilder::new().root_log_level(LevelFilter::Info));
+        let mut service = AccessLogLayer::new().layer(Respond(404));
+        let request = Request::get("/orders?page=2")
+            .header(REQUEST_ID_HEADER, "request-7")
+            .body(())
+            .expect("The request is valid");
+
+        let mut future = service.call(request);
+        let Poll::Ready(Ok(response)) = future
+            .as_mut()
+            .poll(&mut Context::from_waker(Waker::noop()))
+        else {
+            panic!("The response is ready");
+        };
+
+        assert_eq!(response.headers()[CORRELATION_ID_HEADER], "request-7");
+        let records: Vec<_> = records.try_iter().collect();
+        assert_eq!(records.len(), 2);
+        assert!(records[0].message.starts_with("--> GET /orders"));
+        assert_eq!(records[1].level, Level::Warn);
+        let mdc = |key: &str| {
+            records[1]
+                .mdc
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value.as_str())
+        };
+        assert_eq!(mdc(crate::http::CORRELATION_ID_MDC_KEY), Some("request-7"));
+        assert_eq!(mdc(access_mdc_keys::TARGET), Some("/orders?page=2"));
+        assert_eq!(mdc(access_mdc_keys::STATUS), Some("404"));
+        assert_eq!(mdc(access_mdc_keys::RESPONSE_BYTES), Some("42"));
+    }
+}
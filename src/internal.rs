@@ -0,0 +1,113 @@
+use std::{
+    any::Any,
+    collections::hash_map::RandomState,
+    fmt,
+    hash::{BuildHasher, Hasher},
+    panic::{self, AssertUnwindSafe},
+    sync::atomic::{AtomicU64, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::stdio;
+
+/// Reports an error of lum_log itself to stderr, as logging it could fail the same way.
+/// While stdout and stderr are captured, the original stderr is used, so the report is not captured again.
+pub(crate) fn report(args: fmt::Arguments) {
+    let reported = stdio::original_stderr(|stderr| {
+        let _ = writeln!(stderr, "lum_log: {args}");
+    });
+    if reported.is_none() {
+        eprintln!("lum_log: {args}");
+    }
+}
+
+/// Calls the given function, catching and reporting a panic instead of unwinding.
+/// Returns `None` if the function panicked.
+pub(crate) fn catch<T>(context: &str, f: impl FnOnce() -> T) -> Option<T> {
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(value) => Some(value),
+        Err(payload) => {
+            report(format_args!(
+                "{context} panicked: {}",
+                panic_message(&*payload)
+            ));
+            None
+        }
+    }
+}
+
+/// Returns the message of the given panic payload.
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "<non-string panic payload>"
+    }
+}
+
+/// Returns a new random ID in the textual layout of a UUIDv7, without depending on `uuid`.
+/// IDs created later sort after earlier ones, to the millisecond.
+pub(crate) fn unique_id() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as u64);
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(COUNTER.fetch_add(1, Ordering::Relaxed));
+    let random = hasher.finish();
+
+    let high = (millis << 16) | 0x7000 | (random >> 52);
+    let low = (random & 0x3fff_ffff_ffff_ffff) | 0x8000_0000_0000_0000;
+    format!(
+        "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+        high >> 32,
+        (high >> 16) & 0xffff,
+        high & 0xffff,
+        low >> 48,
+        low & 0xffff_ffff_ffff
+    )
+}
+
+/// Returns the host name of this machine, or `unknown` if it cannot be determined.
+#[cfg(all(unix, any(feature = "mqtt", feature = "nats", feature = "s3")))]
+pub(crate) fn hostname() -> String {
+    let mut buffer = [0u8; 256];
+    // SAFETY: `buffer` points to writable memory of the given length.
+    let result = unsafe { libc::gethostname(buffer.as_mut_ptr().cast(), buffer.len()) };
+    if result != 0 {
+        return "unknown".to_string();
+    }
+
+    let length = buffer
+        .iter()
+        .position(|&byte| byte == 0)
+        .unwrap_or(buffer.len());
+    String::from_utf8_lossy(&buffer[..length]).into_owned()
+}
+
+/// Returns the host name of this machine, or `unknown` if it cannot be determined.
+#[cfg(all(not(unix), any(feature = "mqtt", feature = "nats", feature = "s3")))]
+pub(crate) fn hostname() -> String {
+    std::env::var("COMPUTERNAME").unwrap_or_else(|_| "unknown".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unique_ids_have_the_layout_of_a_uuid_v7() {
+        let first = unique_id();
+        let second = unique_id();
+
+        let groups: Vec<_> = first.split('-').map(str::len).collect();
+        assert_eq!(groups, [8, 4, 4, 4, 12]);
+        assert!(first.chars().all(|c| c == '-' || c.is_ascii_hexdigit()));
+        assert_eq!(&first[14..15], "7");
+        assert!(matches!(&first[19..20], "8" | "9" | "a" | "b"));
+        assert_ne!(first, second);
+    }
+}
@@ -0,0 +1,51 @@
+use log_mdc::InsertGuard;
+use lum_libs::{
+    serde::Serialize,
+    serde_json::{self, json},
+};
+
+/// The MDC key under which the JSON payload of a record logged by [`log_json!`](crate::log_json) is exposed.
+/// Use it in patterns as `{X(json)}`, or use an encoder rendering the MDC, e.g. log4rs' `JsonEncoder`.
+pub const JSON_MDC_KEY: &str = "json";
+
+/// Returns the JSON payload of the record currently being logged on this thread, if any.
+/// This is meant for appenders and encoders that render records themselves.
+pub fn current() -> Option<String> {
+    log_mdc::get(JSON_MDC_KEY, |payload| payload.map(str::to_string))
+}
+
+/// Serializes the given value to a JSON payload.
+/// If serialization fails, the payload is an object describing the error, so the record is still logged.
+#[doc(hidden)]
+pub fn payload<T: Serialize + ?Sized>(value: &T) -> String {
+    serde_json::to_string(value)
+        .unwrap_or_else(|error| json!({ "serialization_error": error.to_string() }).to_string())
+}
+
+/// Attaches the given JSON payload to the record about to be logged on this thread.
+/// The payload is removed when the returned guard is dropped.
+#[doc(hidden)]
+pub fn scope(payload: &str) -> InsertGuard {
+    log_mdc::insert_scoped(JSON_MDC_KEY, payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use lum_libs::log::{Level, LevelFilter};
+
+    use super::*;
+    use crate::{ConfigBuilder, log_json, testing};
+
+    #[test]
+    fn log_json_attaches_the_payload_without_changing_the_message() {
+        let _global = testing::GLOBAL.lock();
+        let records = testing::capture(ConfigBuilder::new().root_log_level(LevelFilter::Info));
+
+        log_json!(target: "json_test", Level::Info, "Order created", &json!({ "id": 17 }));
+
+        let record = records.try_recv().unwrap();
+        assert_eq!(record.message, "Order created");
+        assert_eq!(record.json.as_deref(), Some(r#"{"id":17}"#));
+        assert_eq!(current(), None);
+    }
+}
@@ -0,0 +1,258 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc,
+        atomic::{AtomicU32, AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use lum_libs::{
+    humantime, log,
+    parking_lot::{Mutex, RwLock, const_rwlock},
+};
+
+use crate::{default, logger};
+
+/// The target of the warnings logged for slow appenders, see [`set_latency_budget`].
+pub const LATENCY_TARGET: &str = "lum_log::latency";
+
+/// The number of histogram buckets. Bucket `i` counts appends taking less than `2^i` microseconds.
+const BUCKETS: usize = 40;
+
+static TRACKERS: RwLock<Option<HashMap<String, Arc<LatencyTracker>>>> = const_rwlock(None);
+/// The latency budget in microseconds, or 0 if there is none.
+static BUDGET_MICROS: AtomicU64 = AtomicU64::new(0);
+
+/// Percentiles of the time an appender takes to append a record, see [`appender_latencies`].
+/// Percentiles are rounded up to the next power of two microseconds, but never exceed the maximum.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppenderLatency {
+    pub name: String,
+    /// The number of records appended.
+    pub count: u64,
+    pub p50: Duration,
+    pub p90: Duration,
+    pub p99: Duration,
+    pub max: Duration,
+}
+
+/// Records the time an appender takes to append a record.
+#[derive(Debug)]
+pub(crate) struct LatencyTracker {
+    buckets: [AtomicU64; BUCKETS],
+    max_micros: AtomicU64,
+    consecutive_slow: AtomicU32,
+    last_warning: Mutex<Option<Instant>>,
+}
+
+impl LatencyTracker {
+    fn new() -> Self {
+        Self {
+            buckets: [const { AtomicU64::new(0) }; BUCKETS],
+            max_micros: AtomicU64::new(0),
+            consecutive_slow: AtomicU32::new(0),
+            last_warning: Mutex::new(None),
+        }
+    }
+
+    /// Records that the appender with the given name took the given time to append a record,
+    /// warning if it exceeded the latency budget on too many consecutive records.
+    pub(crate) fn record(&self, name: &str, elapsed: Duration) {
+        let micros = u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX);
+        let bucket = (u64::BITS - micros.leading_zeros()) as usize;
+        self.buckets[bucket.min(BUCKETS - 1)].fetch_add(1, Ordering::Relaxed);
+        self.max_micros.fetch_max(micros, Ordering::Relaxed);
+
+        let budget = BUDGET_MICROS.load(Ordering::Relaxed);
+        if budget == 0 || micros <= budget {
+            self.consecutive_slow.store(0, Ordering::Relaxed);
+            return;
+        }
+
+        let consecutive = self.consecutive_slow.fetch_add(1, Ordering::Relaxed) + 1;
+        if consecutive < default::slow_appender_records() {
+            return;
+        }
+
+        {
+            let mut last_warning = self.last_warning.lock();
+            if last_warning
+                .is_some_and(|last| last.elapsed() < default::slow_appender_warning_interval())
+            {
+                return;
+            }
+            *last_warning = Some(Instant::now());
+        }
+
+        log::warn!(
+            target: LATENCY_TARGET,
+            "Appender {name} exceeded the latency budget of {} on {consecutive} consecutive records, the last one took {}, p99: {}",
+            humantime::format_duration(Duration::from_micros(budget)),
+            humantime::format_duration(Duration::from_micros(micros)),
+            humantime::format_duration(self.latency(name.to_string()).p99),
+        );
+    }
+
+    fn latency(&self, name: String) -> AppenderLatency {
+        let buckets = self
+            .buckets
+            .each_ref()
+            .map(|bucket| bucket.load(Ordering::Relaxed));
+        let count = buckets.iter().sum::<u64>();
+        let max = Duration::from_micros(self.max_micros.load(Ordering::Relaxed));
+
+        let percentile = |percentile: u64| {
+            let rank = (count * percentile).div_ceil(100).max(1);
+            let mut seen = 0;
+            for (bucket, bucket_count) in buckets.iter().enumerate() {
+                seen += bucket_count;
+                if seen >= rank {
+                    return Duration::from_micros(1 << bucket).min(max);
+                }
+            }
+            Duration::ZERO
+        };
+
+        AppenderLatency {
+            name,
+            count,
+            p50: percentile(50),
+            p90: percentile(90),
+            p99: percentile(99),
+            max,
+        }
+    }
+}
+
+/// Returns the latency tracker of the appender with the given name, creating it if needed,
+/// so its histogram survives reconfiguring the logger.
+pub(crate) fn tracker(name: &str) -> Arc<LatencyTracker> {
+    if let Some(tracker) = TRACKERS
+        .read()
+        .as_ref()
+        .and_then(|trackers| trackers.get(name))
+    {
+        return Arc::clone(tracker);
+    }
+
+    let mut trackers = TRACKERS.write();
+    let tracker = trackers
+        .get_or_insert_with(HashMap::new)
+        .entry(name.to_string())
+        .or_insert_with(|| Arc::new(LatencyTracker::new()));
+    Arc::clone(tracker)
+}
+
+/// Returns the latency percentiles of every appender of the configuration built by the [`ConfigBuilder`](crate::ConfigBuilder)
+/// the logger was set up with, sorted by name, e.g. to export them as metrics.
+/// For appenders wrapped by [`ConfigBuilder::asynchronous`](crate::ConfigBuilder::asynchronous), this is the latency of the wrapped appender.
+/// Returns no appenders if the logger has not been set up with a `ConfigBuilder`.
+pub fn appender_latencies() -> Vec<AppenderLatency> {
+    let mut names = logger::with_builder(|builder| builder.appender_names()).unwrap_or_default();
+    names.sort();
+
+    names
+        .into_iter()
+        .map(|name| tracker(&name).latency(name))
+        .collect()
+}
+
+/// Sets the time appending a record may take, or `None` for no budget.
+/// If an appender exceeds it on [`default::slow_appender_records`] consecutive records, a warning is logged to [`LATENCY_TARGET`],
+/// at most once per [`default::slow_appender_warning_interval`] per appender, e.g. to notice a stalling network filesystem.
+/// No budget is set by default. See also [`ConfigBuilder::latency_budget`](crate::ConfigBuilder::latency_budget).
+pub fn set_latency_budget(budget: Option<Duration>) {
+    let micros = budget.map_or(0, |budget| {
+        u64::try_from(budget.as_micros()).unwrap_or(u64::MAX).max(1)
+    });
+    BUDGET_MICROS.store(micros, Ordering::Relaxed);
+}
+
+/// Returns the budget set by [`set_latency_budget`], if any.
+pub fn latency_budget() -> Option<Duration> {
+    match BUDGET_MICROS.load(Ordering::Relaxed) {
+        0 => None,
+        micros => Some(Duration::from_micros(micros)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use lum_libs::{
+        log::{LevelFilter, Record},
+        log4rs::append::Append,
+    };
+
+    use super::*;
+    use crate::{ConfigBuilder, testing};
+
+    #[test]
+    fn percentiles_are_rounded_up_to_powers_of_two_but_not_beyond_the_maximum() {
+        let tracker = LatencyTracker::new();
+        for _ in 0..100 {
+            tracker.record("percentiles_test", Duration::from_micros(10));
+        }
+        tracker.record("percentiles_test", Duration::from_millis(5));
+
+        assert_eq!(
+            tracker.latency("percentiles_test".to_string()),
+            AppenderLatency {
+                name: "percentiles_test".to_string(),
+                count: 101,
+                p50: Duration::from_micros(16),
+                p90: Duration::from_micros(16),
+                p99: Duration::from_micros(16),
+                max: Duration::from_millis(5),
+            }
+        );
+    }
+
+    /// An appender taking longer than the budget of the test.
+    #[derive(Debug)]
+    struct Slow;
+
+    impl Append for Slow {
+        fn append(&self, _record: &Record) -> anyhow::Result<()> {
+            thread::sleep(Duration::from_millis(2));
+            Ok(())
+        }
+
+        fn flush(&self) {}
+    }
+
+    #[test]
+    fn appenders_exceeding_the_budget_on_consecutive_records_are_reported_once() {
+        let _global = testing::GLOBAL.lock();
+        let records = testing::capture(
+            ConfigBuilder::new()
+                .root_log_level(LevelFilter::Info)
+                .appender("latency_test", Box::new(Slow))
+                .latency_budget(Duration::from_millis(1)),
+        );
+
+        for _ in 0..default::slow_appender_records() * 2 {
+            log::info!(target: "latency_test", "Appended");
+        }
+        set_latency_budget(None);
+
+        let warnings = records
+            .try_iter()
+            .filter(|record| record.target == LATENCY_TARGET)
+            .map(|record| record.message)
+            .collect::<Vec<_>>();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].starts_with(&format!(
+            "Appender latency_test exceeded the latency budget of 1ms on {} consecutive records",
+            default::slow_appender_records()
+        )));
+        let latency = appender_latencies()
+            .into_iter()
+            .find(|latency| latency.name == "latency_test")
+            .unwrap();
+        assert!(latency.count >= u64::from(default::slow_appender_records() * 2));
+        assert!(latency.max >= Duration::from_millis(2));
+    }
+}
@@ -0,0 +1,175 @@
+use std::{
+    fmt::{self, Debug, Formatter},
+    sync::Arc,
+};
+
+use lum_libs::parking_lot::{RwLock, const_rwlock};
+
+#[cfg(feature = "redaction")]
+use crate::redaction::RedactionRules;
+use crate::{internal, record::OwnedRecord};
+
+/// The layers every record passes, in order.
+type Layers = Arc<[Arc<dyn Layer>]>;
+
+static LAYERS: RwLock<Option<Layers>> = const_rwlock(None);
+
+/// A step of the pipeline every record passes before it reaches the appenders, added by [`ConfigBuilder::layer`](crate::ConfigBuilder::layer).
+/// A layer receives each record and passes on any number of records to the next layer by calling `next`:
+/// the record itself, possibly mutated or enriched, none to drop it, or several to duplicate it,
+/// e.g. with another target to route the copy to other appenders.
+/// This unifies redaction, enrichment, sampling, and routing into one composable pipeline.
+///
+/// A panicking layer is reported, and the record it was processing is dropped.
+pub trait Layer: Debug + Send + Sync + 'static {
+    /// Processes the given record, passing the resulting records on to `next`.
+    fn process(&self, record: OwnedRecord, next: &mut dyn FnMut(OwnedRecord));
+}
+
+/// Redacts the message of every record with these rules, see [`redaction`](crate::redaction).
+#[cfg(feature = "redaction")]
+impl Layer for RedactionRules {
+    fn process(&self, mut record: OwnedRecord, next: &mut dyn FnMut(OwnedRecord)) {
+        if let std::borrow::Cow::Owned(redacted) = self.redact(&record.message) {
+            record.message = redacted;
+        }
+        next(record);
+    }
+}
+
+/// A [`Layer`] calling a function, see [`from_fn`].
+pub struct FnLayer<F> {
+    name: String,
+    f: F,
+}
+
+impl<F> Debug for FnLayer<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FnLayer")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<F> Layer for FnLayer<F>
+where
+    F: Fn(OwnedRecord, &mut dyn FnMut(OwnedRecord)) + Send + Sync + 'static,
+{
+    fn process(&self, record: OwnedRecord, next: &mut dyn FnMut(OwnedRecord)) {
+        (self.f)(record, next);
+    }
+}
+
+/// Creates a [`Layer`] calling the given function with every record, named for its [`Debug`] output.
+///
+/// ```text
+/// let sample = layer::from_fn("sample_debug", |record, next| {
+///     if record.level < Level::Debug || rand::random::<u8>() < 26 {
+///         next(record);
+///     }
+/// });
+/// ConfigBuilder::new().layer(sample);
+/// ```
+pub fn from_fn<F>(name: impl Into<String>, f: F) -> FnLayer<F>
+where
+    F: Fn(OwnedRecord, &mut dyn FnMut(OwnedRecord)) + Send + Sync + 'static,
+{
+    FnLayer {
+        name: name.into(),
+        f,
+    }
+}
+
+/// Sets the layers every record passes, in order, before it reaches the appenders.
+/// No layers are set by default. See also [`ConfigBuilder::layer`](crate::ConfigBuilder::layer).
+/// This only has an effect if the logger has been set up by this crate.
+pub fn set_layers(layers: Vec<Arc<dyn Layer>>) {
+    *LAYERS.write() = match layers.is_empty() {
+        true => None,
+        false => Some(layers.into()),
+    };
+}
+
+/// Returns the layers set by [`set_layers`], if any.
+pub(crate) fn layers() -> Option<Layers> {
+    LAYERS.read().clone()
+}
+
+/// Passes the given record through the given layers, calling `sink` with every resulting record.
+pub(crate) fn run(
+    layers: &[Arc<dyn Layer>],
+    record: OwnedRecord,
+    sink: &mut dyn FnMut(OwnedRecord),
+) {
+    match layers.split_first() {
+        None => sink(record),
+        Some((layer, rest)) => {
+            internal::catch("Layer", || {
+                layer.process(record, &mut |record| run(rest, record, sink))
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lum_libs::log::{self, Level, LevelFilter};
+
+    use super::*;
+    use crate::{ConfigBuilder, testing};
+
+    #[test]
+    fn layers_mutate_drop_and_duplicate_records_in_order() {
+        let _global = testing::GLOBAL.lock();
+        let records = testing::capture(
+            ConfigBuilder::new()
+                .root_log_level(LevelFilter::Info)
+                .layer(from_fn("drop_noise", |record, next| {
+                    if record.message != "Noise" {
+                        next(record);
+                    }
+                }))
+                .layer(from_fn("audit_copy", |record, next| {
+                    if record.level == Level::Warn {
+                        let mut copy = record.clone();
+                        copy.target = "audit".to_string();
+                        next(copy);
+                    }
+                    next(record);
+                }))
+                .layer(from_fn("enrich", |mut record, next| {
+                    record.message.push_str(" [enriched]");
+                    next(record);
+                }))
+                .layer(from_fn("panicking", |record, next| {
+                    if record.message.starts_with("Panic") {
+                        panic!("Layer failed");
+                    }
+                    next(record);
+                })),
+        );
+
+        log::info!(target: "layer_test", "Noise");
+        log::warn!(target: "layer_test", "Login failed");
+        log::info!(target: "layer_test", "Panic");
+        log::info!(target: "layer_test", "Started");
+
+        let records = records
+            .try_iter()
+            .map(|record| (record.target, record.message))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            records,
+            [
+                ("audit".to_string(), "Login failed [enriched]".to_string()),
+                (
+                    "layer_test".to_string(),
+                    "Login failed [enriched]".to_string()
+                ),
+                ("layer_test".to_string(), "Started [enriched]".to_string()),
+            ]
+        );
+        // Only configurations applied by a ConfigBuilder replace the layers, unlike those set up by other tests.
+        set_layers(Vec::new());
+    }
+}
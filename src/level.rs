@@ -0,0 +1,277 @@
+use lum_libs::{
+    log::Level,
+    serde::{Deserialize, Serialize},
+};
+
+use crate::severity::AuxLevel;
+
+/// Returns the numeric syslog severity (RFC 5424) of the given level.
+/// [`Level::Error`] maps to 3 (error), [`Level::Warn`] to 4 (warning), [`Level::Info`] to 6 (informational),
+/// and both [`Level::Debug`] and [`Level::Trace`] to 7 (debug).
+/// See [`current_syslog_severity`] for also considering auxiliary levels.
+pub fn syslog_severity(level: Level) -> u8 {
+    match level {
+        Level::Error => 3,
+        Level::Warn => 4,
+        Level::Info => 6,
+        Level::Debug | Level::Trace => 7,
+    }
+}
+
+/// Returns the numeric syslog severity of a record at the given level,
+/// using [`AuxLevel::syslog_severity`] if the record currently being logged has an auxiliary level.
+pub fn current_syslog_severity(level: Level) -> u8 {
+    AuxLevel::current().map_or_else(|| syslog_severity(level), AuxLevel::syslog_severity)
+}
+
+/// Which part of a rendered line the default formats color by its level, see [`default::format_with_style`](crate::default::format_with_style).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde", rename_all = "snake_case")]
+pub enum LevelStyle {
+    /// Only the level token is colored.
+    #[default]
+    Level,
+    /// The level token and the message are colored.
+    LevelAndMessage,
+    /// The whole line is colored.
+    Line,
+}
+
+/// Display names used when rendering log levels.
+/// By default, the names match [`Level::as_str`], e.g. `WARN` for [`Level::Warn`].
+/// It is (de)serialized as a table of names by lowercase level, where missing levels keep their default names, e.g.
+/// ```toml
+/// [level_names]
+/// warn = "WARNING"
+/// fatal = "CRITICAL"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(
+    crate = "lum_libs::serde",
+    from = "LevelNamesTable",
+    into = "LevelNamesTable"
+)]
+pub struct LevelNames {
+    names: [String; 5],
+    aux_names: [String; 3],
+}
+
+impl Default for LevelNames {
+    /// Creates `LevelNames` using the names returned by [`Level::as_str`] and [`AuxLevel::as_str`].
+    fn default() -> Self {
+        Self {
+            names: Level::iter()
+                .map(|level| level.as_str().to_string())
+                .collect::<Vec<_>>()
+                .try_into()
+                .expect("There are exactly five log levels"),
+            aux_names: AuxLevel::iter()
+                .map(|aux_level| aux_level.as_str().to_string())
+                .collect::<Vec<_>>()
+                .try_into()
+                .expect("There are exactly three auxiliary levels"),
+        }
+    }
+}
+
+impl LevelNames {
+    /// Same as [`LevelNames::default`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns three-letter level names: `ERR`, `WRN`, `INF`, `DBG`, and `TRC`,
+    /// and `FTL`, `NTC`, and `VRB` for the auxiliary levels.
+    pub fn short() -> Self {
+        Self::new()
+            .name(Level::Error, "ERR")
+            .name(Level::Warn, "WRN")
+            .name(Level::Info, "INF")
+            .name(Level::Debug, "DBG")
+            .name(Level::Trace, "TRC")
+            .aux_name(AuxLevel::Fatal, "FTL")
+            .aux_name(AuxLevel::Notice, "NTC")
+            .aux_name(AuxLevel::Verbose, "VRB")
+    }
+
+    /// Sets the display name of the given level.
+    pub fn name(mut self, level: Level, name: impl Into<String>) -> Self {
+        self.names[Self::index(level)] = name.into();
+        self
+    }
+
+    /// Returns the display name of the given level.
+    pub fn get(&self, level: Level) -> &str {
+        &self.names[Self::index(level)]
+    }
+
+    /// Sets the display name of the given auxiliary level.
+    pub fn aux_name(mut self, aux_level: AuxLevel, name: impl Into<String>) -> Self {
+        self.aux_names[aux_level as usize] = name.into();
+        self
+    }
+
+    /// Returns the display name of the given auxiliary level.
+    pub fn get_aux(&self, aux_level: AuxLevel) -> &str {
+        &self.aux_names[aux_level as usize]
+    }
+
+    /// Returns the display name of a record at the given level,
+    /// using the name of the auxiliary level of the record currently being logged, if any.
+    pub fn get_current(&self, level: Level) -> &str {
+        match AuxLevel::current() {
+            Some(aux_level) => self.get_aux(aux_level),
+            None => self.get(level),
+        }
+    }
+
+    fn index(level: Level) -> usize {
+        level as usize - 1
+    }
+}
+
+/// The serialized form of [`LevelNames`].
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde", default, deny_unknown_fields)]
+struct LevelNamesTable {
+    error: String,
+    warn: String,
+    info: String,
+    debug: String,
+    trace: String,
+    fatal: String,
+    notice: String,
+    verbose: String,
+}
+
+impl Default for LevelNamesTable {
+    fn default() -> Self {
+        LevelNames::default().into()
+    }
+}
+
+impl From<LevelNames> for LevelNamesTable {
+    fn from(level_names: LevelNames) -> Self {
+        let [error, warn, info, debug, trace] = level_names.names;
+        let [fatal, notice, verbose] = level_names.aux_names;
+        Self {
+            error,
+            warn,
+            info,
+            debug,
+            trace,
+            fatal,
+            notice,
+            verbose,
+        }
+    }
+}
+
+impl From<LevelNamesTable> for LevelNames {
+    fn from(table: LevelNamesTable) -> Self {
+        Self {
+            names: [
+                table.error,
+                table.warn,
+                table.info,
+                table.debug,
+                table.trace,
+            ],
+            aux_names: [table.fatal, table.notice, table.verbose],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use lum_libs::{
+        log::Record,
+        log4rs::encode::{self, Encode, Style, pattern::PatternEncoder},
+        serde_json,
+    };
+
+    use super::*;
+    use crate::default;
+
+    /// Collects the colored parts of the output.
+    #[derive(Default)]
+    struct StyleWriter {
+        colored: Vec<String>,
+        coloring: bool,
+    }
+
+    impl io::Write for StyleWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if self.coloring {
+                let part = self.colored.last_mut().expect("A part was started");
+                part.push_str(std::str::from_utf8(buf).expect("The output is UTF-8"));
+            }
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl encode::Write for StyleWriter {
+        fn set_style(&mut self, style: &Style) -> io::Result<()> {
+            self.coloring = style.text.is_some();
+            if self.coloring {
+                self.colored.push(String::new());
+            }
+            Ok(())
+        }
+    }
+
+    fn colored_parts(style: LevelStyle) -> Vec<String> {
+        let mut writer = StyleWriter::default();
+        PatternEncoder::new(&default::format_with_style(None, style))
+            .encode(
+                &mut writer,
+                &Record::builder()
+                    .level(Level::Warn)
+                    .target("style_test")
+                    .args(format_args!("Message"))
+                    .build(),
+            )
+            .expect("The record can be encoded");
+        writer.colored
+    }
+
+    #[test]
+    fn level_styles_color_the_selected_parts_of_the_line() {
+        assert_eq!(colored_parts(LevelStyle::Level), ["WARN "]);
+        assert_eq!(
+            colored_parts(LevelStyle::LevelAndMessage),
+            ["WARN ", "Message"]
+        );
+
+        let line = colored_parts(LevelStyle::Line);
+        assert_eq!(line.len(), 1);
+        assert!(line[0].starts_with('['));
+        assert!(line[0].ends_with("style_test                               WARN ] Message"));
+    }
+
+    #[test]
+    fn level_names_deserialize_missing_levels_to_their_defaults() {
+        let level_names =
+            serde_json::from_str::<LevelNames>(r#"{"warn":"WARNING","fatal":"CRITICAL"}"#).unwrap();
+
+        assert_eq!(
+            level_names,
+            LevelNames::new()
+                .name(Level::Warn, "WARNING")
+                .aux_name(AuxLevel::Fatal, "CRITICAL")
+        );
+        assert_eq!(
+            serde_json::from_value::<LevelNames>(
+                serde_json::to_value(LevelNames::short()).unwrap()
+            )
+            .unwrap(),
+            LevelNames::short()
+        );
+    }
+}
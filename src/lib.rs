@@ -1,20 +1,221 @@
 //! lum_log is a simple wrapper around log4rs offering easy setup and convenience macros.
 //! It provides a simplified builder for log4rs configurations.
 //! Furthermore, it provides logging macros that fall back to stdout/stderr if the logger is not set up yet.
+//!
+//! Everything but the core logging macros requires the default `std` feature.
+//! Without it, the `defmt` feature provides those macros for firmware, see the `embedded` module.
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+/// Defines the [`AnomalyDetection`](anomaly::AnomalyDetection) warning about sudden jumps of a target's record rate.
+#[cfg(feature = "std")]
+pub mod anomaly;
+/// Defines custom appenders.
+#[cfg(feature = "std")]
+pub mod append;
+/// Defines the [`S3Archiver`](archive::S3Archiver) uploading rotated log files.
+#[cfg(feature = "s3")]
+pub mod archive;
+/// Defines the [`Backpressure`](backpressure::Backpressure) strategies of buffering appenders.
+#[cfg(feature = "std")]
+pub mod backpressure;
 /// Defines the [`ConfigBuilder`] for building log4rs configurations.
+#[cfg(feature = "std")]
 pub mod builder;
+/// Defines the `.sha256` checksum sidecars written next to rotated log files.
+#[cfg(feature = "checksum")]
+pub mod checksum;
+/// Defines bridges between lum_log and other logging libraries, e.g. fern and slog.
+#[cfg(any(feature = "fern", feature = "slog"))]
+pub mod compat;
+/// Defines the serializable [`Config`](config::Config) describing levels, format, and outputs.
+#[cfg(feature = "std")]
+pub mod config;
+/// Defines hooks for console output, e.g. printing log lines above progress bars.
+#[cfg(feature = "std")]
+pub mod console;
+/// Defines the location and [`PanicContext`](context::PanicContext) attached to records logged before panicking.
+#[cfg(feature = "std")]
+pub mod context;
+/// Defines the per-target [`CostReport`](cost::CostReport) of records logged and bytes written.
+#[cfg(feature = "std")]
+pub mod cost;
+/// Defines the [`CrashReporter`](crash::CrashReporter), which writes crash reports for end-user applications.
+#[cfg(feature = "std")]
+pub mod crash;
 /// Defines some defaults that help setting up logging.
+#[cfg(feature = "std")]
 pub mod default;
+/// Defines platform-appropriate log directories.
+#[cfg(feature = "std")]
+pub mod dirs;
+/// Defines the [`DiskGuard`](disk::DiskGuard) protecting the log volume from filling up.
+#[cfg(feature = "disk-guard")]
+pub mod disk;
+/// Defines serde helpers for human-readable durations.
+#[cfg(feature = "std")]
+mod duration;
+/// Defines the core logging macros for firmware, which log through defmt.
+#[cfg(all(feature = "defmt", not(feature = "std")))]
+pub mod embedded;
+/// Defines the [`EmergencyOutput`](emergency::EmergencyOutput) used when all appenders fail.
+#[cfg(feature = "std")]
+pub mod emergency;
+/// Defines custom encoders.
+#[cfg(feature = "std")]
+pub mod encode;
+/// Defines unique event IDs attached to records.
+#[cfg(feature = "event-id")]
+pub mod event;
+/// Defines extension traits for logging [`Result`]s and [`Option`]s.
+#[cfg(feature = "std")]
+pub mod ext;
+/// Defines the [`appender_health`] of appenders, e.g. for health endpoints.
+#[cfg(feature = "std")]
+pub mod health;
+/// Defines the heartbeat records distinguishing a quiet service from broken log shipping.
+#[cfg(feature = "std")]
+pub mod heartbeat;
+/// Defines [`HexDump`](hex::HexDump) for logging binary data.
+#[cfg(feature = "std")]
+pub mod hex;
+/// Defines helpers and middleware for logging HTTP requests.
+#[cfg(feature = "std")]
+pub mod http;
+/// Defines internal error reporting.
+#[cfg(feature = "std")]
+mod internal;
+/// Defines the JSON payloads attached by [`log_json!`].
+#[cfg(feature = "std")]
+pub mod json;
+/// Defines the [`appender_latencies`](latency::appender_latencies) and the latency budget of appenders.
+#[cfg(feature = "std")]
+pub mod latency;
+/// Defines the [`Layer`](layer::Layer) pipeline records pass before they reach the appenders.
+#[cfg(feature = "std")]
+pub mod layer;
+/// Defines [`LevelNames`] and [`LevelStyle`] for customizing how log levels are rendered, and their syslog severities.
+#[cfg(feature = "std")]
+pub mod level;
+/// Defines [`setup_from_log4rs_file`] for setting up the logger from standard log4rs configuration files.
+#[cfg(feature = "std")]
+pub mod log4rs_file;
 /// Defines functions to set up the logger.
+#[cfg(feature = "std")]
 pub mod logger;
 /// Defines convenience logging macros.
+#[cfg(feature = "std")]
 pub mod macros;
+/// Defines the memory budget of internal buffers.
+#[cfg(feature = "std")]
+pub mod memory;
+/// Defines [`PrettyDebug`](pretty::PrettyDebug) for logging framed multi-line debug output.
+#[cfg(feature = "std")]
+pub mod pretty;
+/// Defines the [`ChildLogger`](process::ChildLogger) re-emitting the output of child processes.
+#[cfg(feature = "std")]
+pub mod process;
+/// Defines [`Profiles`](profile::Profiles) of log levels, which can be switched at runtime.
+#[cfg(feature = "std")]
+pub mod profile;
+/// Defines the types of the protobuf schema in `proto/lum_log.proto`.
+#[cfg(feature = "protobuf")]
+pub mod proto;
+/// Defines readers for records written by framing encoders, based on the [`FrameReader`](reader::FrameReader).
+#[cfg(feature = "std")]
+pub mod reader;
+/// Defines [`OwnedRecord`], an owned copy of a log record.
+#[cfg(feature = "std")]
+pub mod record;
+/// Defines [`RedactionRules`](redaction::RedactionRules) removing sensitive data from log messages.
+#[cfg(feature = "redaction")]
+pub mod redaction;
+/// Defines the [`RetryPolicy`](retry::RetryPolicy) shared by network appenders.
+#[cfg(feature = "std")]
+pub mod retry;
+/// Defines [`rotate_now`] for rolling log files on request, and [`on_rotation`] callbacks.
+#[cfg(feature = "std")]
+pub mod rotate;
+/// Defines target-based routing of records to appenders.
+#[cfg(feature = "std")]
+pub mod route;
+/// Defines auxiliary levels layered on top of the five log levels.
+#[cfg(feature = "std")]
+pub mod severity;
+/// Defines [`flush_on_signal`](signal::flush_on_signal) for flushing the logger when the process is stopped.
+#[cfg(all(feature = "signals", unix))]
+pub mod signal;
+/// Defines the [`Spool`](spool::Spool) persisting undeliverable records for later replay.
+#[cfg(feature = "std")]
+pub mod spool;
+/// Defines the logger [`Stats`](stats::Stats) reported on shutdown.
+#[cfg(feature = "std")]
+pub mod stats;
+/// Defines the `StdioCapture` re-injecting the process's own stdout and stderr as records, which is only available on unix.
+#[cfg(feature = "std")]
+pub mod stdio;
+/// Defines the subscription API for live log streaming.
+#[cfg(feature = "std")]
+pub mod subscribe;
+/// Defines the default prefix of targets of records logged without an explicit target.
+#[cfg(feature = "std")]
+pub mod target;
+/// Defines [`try_init_quiet`](testing::try_init_quiet) for setting up the logger in tests.
+#[cfg(feature = "std")]
+pub mod testing;
+/// Defines the [`TimestampFormat`](timestamp::TimestampFormat) presets for RFC 3339 timestamps.
+#[cfg(feature = "std")]
+pub mod timestamp;
+/// Defines [`AppenderHandle`](toggle::AppenderHandle)s enabling and disabling appenders at runtime.
+#[cfg(feature = "std")]
+pub mod toggle;
+/// Defines an embedded ratatui log viewer.
+#[cfg(feature = "tui")]
+pub mod tui;
+/// Defines [`verbose_scope`] for temporarily raising the log level.
+#[cfg(feature = "std")]
+pub mod verbosity;
+/// Defines the [`WriteAheadLog`](wal::WriteAheadLog) for at-least-once delivery.
+#[cfg(feature = "std")]
+pub mod wal;
+/// Defines the [`watchdog()`] guard reporting slow operations.
+#[cfg(feature = "std")]
+pub mod watchdog;
 
 /// Re-exports of external crates.
+#[cfg(feature = "std")]
 pub use lum_libs::log;
+#[cfg(feature = "std")]
 pub use lum_libs::log4rs;
 
 // Re-exports of internal modules.
+#[cfg(feature = "std")]
 pub use builder::{ConfigBuilder, ConfigBuilderError};
-pub use logger::{is_set_up, setup};
+#[cfg(feature = "std")]
+pub use ext::{LogOptionExt, LogResultExt};
+#[cfg(feature = "std")]
+pub use health::appender_health;
+#[cfg(feature = "std")]
+pub use level::{LevelNames, LevelStyle};
+#[cfg(feature = "std")]
+pub use log4rs_file::setup_from_log4rs_file;
+#[cfg(feature = "std")]
+pub use logger::{
+    flush, is_set_up, set_level, set_module_level, setup, shutdown, shutdown_with_timeout,
+};
+#[cfg(feature = "std")]
+pub use profile::{activate_profile, active_profile, deactivate_profile};
+#[cfg(feature = "std")]
+pub use record::OwnedRecord;
+#[cfg(feature = "std")]
+pub use rotate::{on_rotation, rotate_now};
+#[cfg(feature = "std")]
+pub use route::{Route, RouteRule};
+#[cfg(feature = "std")]
+pub use subscribe::{recent, subscribe};
+#[cfg(feature = "std")]
+pub use toggle::appender;
+#[cfg(feature = "std")]
+pub use verbosity::{verbose_scope, verbose_scope_for};
+#[cfg(feature = "std")]
+pub use watchdog::watchdog;
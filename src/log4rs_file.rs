@@ -0,0 +1,473 @@
+#[cfg(feature = "yaml")]
+use std::{collections::BTreeMap, fmt::Write as _};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use lum_libs::{
+    log::{LevelFilter, SetLoggerError},
+    log4rs::{
+        Config,
+        config::{Deserializers, RawConfig, runtime::ConfigErrors},
+    },
+    serde::Serialize,
+};
+use thiserror::Error;
+
+use crate::{default, logger};
+
+/// Errors that can occur when setting up the logger from a log4rs configuration file.
+#[derive(Debug, Error)]
+pub enum Log4rsFileError {
+    #[error("I/O error while reading log4rs configuration file: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("Unsupported log4rs configuration file format: {0}")]
+    UnsupportedFormat(String),
+
+    #[cfg(feature = "yaml")]
+    #[error("Error while parsing log4rs YAML configuration: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+
+    #[error("Error while parsing log4rs JSON configuration: {0}")]
+    Json(#[from] lum_libs::serde_json::Error),
+
+    #[cfg(feature = "toml")]
+    #[error("Error while parsing log4rs TOML configuration: {0}")]
+    Toml(#[from] toml::de::Error),
+
+    #[error("Error while deserializing log4rs appenders: {0}")]
+    Appenders(#[source] anyhow::Error),
+
+    #[error("Error while building log4rs configuration: {0}")]
+    Log4rs(#[from] ConfigErrors),
+
+    #[error("Error while setting the global logger: {0}")]
+    SetLogger(#[from] SetLoggerError),
+}
+
+/// Errors that can occur when exporting the applied configuration as a log4rs configuration file.
+#[cfg(feature = "yaml")]
+#[derive(Debug, Error)]
+pub enum Log4rsExportError {
+    #[error("The logger has not been set up with a ConfigBuilder")]
+    NotSetUp,
+
+    #[error("Error while serializing log4rs YAML configuration: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+}
+
+/// Sets up the logger from a standard log4rs configuration file, like [`setup`](crate::setup) does with a [`Config`],
+/// so projects can migrate from raw log4rs without rewriting their configuration.
+/// The format is chosen by the file extension: `.json`, with the `yaml` feature `.yaml` or `.yml`, and with the `toml` feature `.toml`.
+/// All appenders, encoders, and filters built into log4rs are supported.
+///
+/// Unlike [`log4rs::init_file`](lum_libs::log4rs::init_file), invalid appenders fail the setup instead of being skipped,
+/// and the `refresh_rate` of the file is ignored.
+/// The logger's handle is stored, so it can be reconfigured later by calling this or [`setup`](crate::setup) again.
+pub fn setup_from_log4rs_file(path: impl AsRef<Path>) -> Result<(), Log4rsFileError> {
+    let config = load(path.as_ref())?;
+    logger::setup(config)?;
+    Ok(())
+}
+
+/// Loads the log4rs configuration file at the given path.
+pub(crate) fn load(path: &Path) -> Result<Config, Log4rsFileError> {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default();
+    let parse: fn(&str) -> Result<RawConfig, Log4rsFileError> = match extension {
+        #[cfg(feature = "yaml")]
+        "yaml" | "yml" => |source| Ok(serde_yaml::from_str(source)?),
+        #[cfg(not(feature = "yaml"))]
+        "yaml" | "yml" => {
+            return Err(Log4rsFileError::UnsupportedFormat(
+                "YAML requires the yaml feature".to_string(),
+            ));
+        }
+        "json" => |source| Ok(lum_libs::serde_json::from_str(source)?),
+        #[cfg(feature = "toml")]
+        "toml" => |source| Ok(toml::from_str(source)?),
+        #[cfg(not(feature = "toml"))]
+        "toml" => {
+            return Err(Log4rsFileError::UnsupportedFormat(
+                "TOML requires the toml feature".to_string(),
+            ));
+        }
+        extension => {
+            return Err(Log4rsFileError::UnsupportedFormat(format!(
+                "unknown extension {extension:?}"
+            )));
+        }
+    };
+    let raw = parse(&fs::read_to_string(path)?)?;
+
+    let (appenders, errors) = raw.appenders_lossy(&Deserializers::default());
+    if !errors.is_empty() {
+        return Err(Log4rsFileError::Appenders(errors.into()));
+    }
+
+    let config = Config::builder()
+        .appenders(appenders)
+        .loggers(raw.loggers())
+        .build(raw.root())?;
+    Ok(config)
+}
+
+/// Returns the configuration applied by [`ConfigBuilder::apply`](crate::ConfigBuilder::apply) as a log4rs YAML configuration file,
+/// with the active profile, verbosity overrides, and disabled appenders taken into account,
+/// so deployments can snapshot their programmatic setup and move to [`setup_from_log4rs_file`] incrementally.
+/// See [`ConfigBuilder::to_log4rs_yaml`](crate::ConfigBuilder::to_log4rs_yaml) for what can be exported.
+#[cfg(feature = "yaml")]
+pub fn export_log4rs_yaml() -> Result<String, Log4rsExportError> {
+    let yaml = logger::with_builder(|builder| logger::effective_builder(builder).to_log4rs_yaml())
+        .ok_or(Log4rsExportError::NotSetUp)??;
+    Ok(yaml)
+}
+
+/// The log4rs configuration of an appender created by a [`ConfigBuilder`](crate::ConfigBuilder), used to export it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(crate = "lum_libs::serde")]
+pub(crate) struct AppenderSpec {
+    kind: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    target: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<PathBuf>,
+    encoder: EncoderSpec,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    policy: Option<PolicySpec>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    filters: Vec<FilterSpec>,
+}
+
+/// The log4rs configuration of an encoder, part of an [`AppenderSpec`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(crate = "lum_libs::serde")]
+pub(crate) struct EncoderSpec {
+    kind: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pattern: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(crate = "lum_libs::serde")]
+struct PolicySpec {
+    kind: &'static str,
+    trigger: TriggerSpec,
+    roller: RollerSpec,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(crate = "lum_libs::serde")]
+struct TriggerSpec {
+    kind: &'static str,
+    interval: &'static str,
+    modulate: bool,
+    max_random_delay: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(crate = "lum_libs::serde")]
+struct RollerSpec {
+    kind: &'static str,
+    pattern: String,
+    base: u32,
+    count: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(crate = "lum_libs::serde")]
+struct FilterSpec {
+    kind: &'static str,
+    level: LevelFilter,
+}
+
+impl EncoderSpec {
+    /// Describes a pattern encoder with the given pattern.
+    pub(crate) fn pattern(pattern: impl Into<String>) -> Self {
+        Self {
+            kind: "pattern",
+            pattern: Some(pattern.into()),
+        }
+    }
+
+    /// Describes a JSON encoder.
+    pub(crate) fn json() -> Self {
+        Self {
+            kind: "json",
+            pattern: None,
+        }
+    }
+}
+
+impl AppenderSpec {
+    /// Describes a console appender using the given encoder.
+    pub(crate) fn console(encoder: EncoderSpec) -> Self {
+        Self {
+            kind: "console",
+            target: None,
+            path: None,
+            encoder,
+            policy: None,
+            filters: Vec::new(),
+        }
+    }
+
+    /// Describes a file appender like [`default::file_appender_with_encoder`], writing to the given path with the given encoder.
+    pub(crate) fn file(path: impl Into<PathBuf>, encoder: EncoderSpec) -> Self {
+        Self {
+            kind: "file",
+            target: None,
+            path: Some(path.into()),
+            encoder,
+            policy: None,
+            filters: Vec::new(),
+        }
+    }
+
+    /// Describes a rolling file appender like [`default::rolling_file_appender`],
+    /// writing to the given path with the given encoder and rolling to the given roller pattern.
+    pub(crate) fn rolling_file(
+        path: impl Into<PathBuf>,
+        encoder: EncoderSpec,
+        roller_pattern: impl Into<String>,
+    ) -> Self {
+        Self::rolling_file_with_policy(
+            path,
+            encoder,
+            "1 day",
+            roller_pattern,
+            default::rolled_file_count(),
+        )
+    }
+
+    /// Describes a rolling file appender like [`default::rolling_file_appender_with_policy`],
+    /// rolling in the given interval, e.g. `1 hour`, and keeping the given number of rolled files.
+    pub(crate) fn rolling_file_with_policy(
+        path: impl Into<PathBuf>,
+        encoder: EncoderSpec,
+        interval: &'static str,
+        roller_pattern: impl Into<String>,
+        count: u32,
+    ) -> Self {
+        let time_trigger = default::time_trigger_config();
+        Self {
+            kind: "rolling_file",
+            target: None,
+            path: Some(path.into()),
+            encoder,
+            policy: Some(PolicySpec {
+                kind: "compound",
+                trigger: TriggerSpec {
+                    kind: "time",
+                    interval,
+                    modulate: time_trigger.modulate,
+                    max_random_delay: time_trigger.max_random_delay,
+                },
+                roller: RollerSpec {
+                    kind: "fixed_window",
+                    pattern: roller_pattern.into(),
+                    base: 0,
+                    count,
+                },
+            }),
+            filters: Vec::new(),
+        }
+    }
+
+    /// Makes a console appender write to stderr instead of stdout.
+    pub(crate) fn stderr(mut self) -> Self {
+        self.target = Some("stderr");
+        self
+    }
+
+    /// Adds a threshold filter rejecting records below the given level.
+    pub(crate) fn threshold(mut self, level: LevelFilter) -> Self {
+        self.filters.push(FilterSpec {
+            kind: "threshold",
+            level,
+        });
+        self
+    }
+
+    /// Returns the number of filters described.
+    #[cfg(feature = "yaml")]
+    pub(crate) fn filter_count(&self) -> usize {
+        self.filters.len()
+    }
+}
+
+/// A log4rs configuration file, built by [`ConfigBuilder::to_log4rs_yaml`](crate::ConfigBuilder::to_log4rs_yaml).
+#[cfg(feature = "yaml")]
+#[derive(Debug, Serialize)]
+#[serde(crate = "lum_libs::serde")]
+pub(crate) struct Document<'a> {
+    pub(crate) root: RootSpec,
+    pub(crate) appenders: BTreeMap<&'a str, &'a AppenderSpec>,
+    pub(crate) loggers: BTreeMap<&'a str, LoggerSpec<'a>>,
+}
+
+#[cfg(feature = "yaml")]
+#[derive(Debug, Serialize)]
+#[serde(crate = "lum_libs::serde")]
+pub(crate) struct RootSpec {
+    pub(crate) level: LevelFilter,
+    pub(crate) appenders: Vec<String>,
+}
+
+#[cfg(feature = "yaml")]
+#[derive(Debug, Serialize)]
+#[serde(crate = "lum_libs::serde")]
+pub(crate) struct LoggerSpec<'a> {
+    pub(crate) level: LevelFilter,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) appenders: Vec<&'a str>,
+    #[serde(skip_serializing_if = "is_additive")]
+    pub(crate) additive: bool,
+}
+
+#[cfg(feature = "yaml")]
+impl LoggerSpec<'_> {
+    /// Describes a logger with the given level, no appenders of its own, and additivity.
+    pub(crate) fn new(level: LevelFilter) -> Self {
+        Self {
+            level,
+            appenders: Vec::new(),
+            additive: true,
+        }
+    }
+}
+
+/// Returns whether a logger is additive, which is the default in log4rs configuration files.
+#[cfg(feature = "yaml")]
+fn is_additive(additive: &bool) -> bool {
+    *additive
+}
+
+#[cfg(feature = "yaml")]
+impl Document<'_> {
+    /// Serializes the document to YAML, preceded by a comment for each of the given notes on what was not exported.
+    pub(crate) fn to_yaml(&self, notes: &[String]) -> Result<String, serde_yaml::Error> {
+        let mut yaml = String::from("# log4rs configuration exported by lum_log\n");
+        for note in notes {
+            let _ = writeln!(yaml, "# Not exported: {note}");
+        }
+        yaml.push_str(&serde_yaml::to_string(self)?);
+        Ok(yaml)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lum_libs::log;
+
+    use super::*;
+    use crate::testing;
+
+    #[test]
+    fn log4rs_files_set_up_their_appenders_and_loggers() {
+        let _global = testing::GLOBAL.lock();
+        let directory = testing::temp_dir("log4rs_file");
+        let log_file = directory.join("app.log");
+        let config_file = directory.join("log4rs.json");
+        let config = lum_libs::serde_json::json!({
+            "appenders": {
+                "file": {
+                    "kind": "file",
+                    "path": log_file,
+                    "encoder": { "pattern": "{l} {t} {m}{n}" },
+                },
+            },
+            "root": { "level": "info", "appenders": ["file"] },
+            "loggers": { "log4rs_file_test::noisy": { "level": "error" } },
+        });
+        fs::write(&config_file, config.to_string()).unwrap();
+
+        setup_from_log4rs_file(&config_file).unwrap();
+        log::info!(target: "log4rs_file_test", "Kept");
+        log::debug!(target: "log4rs_file_test", "Below the root level");
+        log::warn!(target: "log4rs_file_test::noisy", "Below the logger level");
+        log::logger().flush();
+
+        assert_eq!(
+            fs::read_to_string(&log_file).unwrap(),
+            "INFO log4rs_file_test Kept\n"
+        );
+    }
+
+    #[test]
+    fn unsupported_files_and_invalid_appenders_are_rejected() {
+        let directory = testing::temp_dir("log4rs_file_invalid");
+        let ini = directory.join("log4rs.ini");
+        fs::write(&ini, "").unwrap();
+        let invalid = directory.join("log4rs.json");
+        fs::write(
+            &invalid,
+            r#"{ "appenders": { "unknown": { "kind": "unknown" } }, "root": { "level": "info" } }"#,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            load(&ini),
+            Err(Log4rsFileError::UnsupportedFormat(_))
+        ));
+        assert!(matches!(load(&invalid), Err(Log4rsFileError::Appenders(_))));
+        assert!(matches!(
+            load(&directory.join("missing.json")),
+            Err(Log4rsFileError::Io(_))
+        ));
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn yaml_files_are_loaded() {
+        let path = testing::temp_dir("log4rs_file_yaml").join("log4rs.yaml");
+        fs::write(
+            &path,
+            "appenders:\n  stdout:\n    kind: console\nroot:\n  level: warn\n  appenders:\n    - stdout\nloggers:\n  app:\n    level: trace\n",
+        )
+        .unwrap();
+
+        let config = load(&path).unwrap();
+
+        assert_eq!(config.root().level(), LevelFilter::Warn);
+        assert_eq!(config.root().appenders(), ["stdout"]);
+        assert_eq!(config.loggers()[0].name(), "app");
+        assert_eq!(config.loggers()[0].level(), LevelFilter::Trace);
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn exported_files_set_up_the_applied_levels_and_appenders() {
+        let _global = testing::GLOBAL.lock();
+        let directory = testing::temp_dir("log4rs_file_export");
+        let _records = testing::capture(
+            crate::ConfigBuilder::new()
+                .root_log_level(LevelFilter::Info)
+                .log_level("log4rs_file_test::noisy", LevelFilter::Error)
+                .file_rolling_appender(directory.join("app.log"))
+                .unwrap(),
+        );
+
+        let yaml = export_log4rs_yaml().unwrap();
+        assert!(yaml.contains("# Not exported: appender capture"));
+        let path = directory.join("log4rs.yaml");
+        fs::write(&path, yaml).unwrap();
+        let config = load(&path).unwrap();
+
+        assert_eq!(config.root().level(), LevelFilter::Info);
+        assert_eq!(config.root().appenders(), ["file"]);
+        assert_eq!(config.appenders()[0].name(), "file");
+        assert_eq!(config.loggers()[0].name(), "log4rs_file_test::noisy");
+        assert_eq!(config.loggers()[0].level(), LevelFilter::Error);
+
+        setup_from_log4rs_file(&path).unwrap();
+        assert!(matches!(
+            export_log4rs_yaml(),
+            Err(Log4rsExportError::NotSetUp)
+        ));
+    }
+}
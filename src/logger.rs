@@ -1,10 +1,40 @@
+use std::{
+    borrow::Cow,
+    fmt::{self, Display, Formatter, Write as _},
+    fs, io,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, RecvTimeoutError, Sender},
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
 use lum_libs::{
-    log::SetLoggerError,
-    log4rs::{self, Config, Handle},
+    log::{self, LevelFilter, Log, Metadata, Record, SetLoggerError},
+    log4rs::{self, Config, Handle, config::runtime::ConfigErrors},
     parking_lot::Mutex,
 };
+use thiserror::Error;
+
+use crate::{
+    ConfigBuilder, ConfigBuilderError, OwnedRecord, anomaly,
+    append::asynchronous,
+    cost, default, emergency, internal, layer, log4rs_file, profile,
+    stats::{self, SUMMARY_TARGET},
+    target, toggle, verbosity,
+};
+
+/// Errors that can occur when changing log levels at runtime.
+#[derive(Debug, Error)]
+pub enum SetLevelError {
+    #[error("The logger has not been set up with a ConfigBuilder")]
+    NotSetUp,
+
+    #[error("Error while building log4rs configuration: {0}")]
+    Log4rs(#[from] ConfigErrors),
+}
 
 static LOGGER_HANDLE: Mutex<Option<Handle>> = Mutex::new(None);
+static LOGGER_BUILDER: Mutex<Option<ConfigBuilder>> = Mutex::new(None);
 
 /// Returns whether the logger has been set up.
 /// This uses an atomic boolean under the hood, so it is safe for concurrent use.
@@ -14,16 +44,502 @@ pub fn is_set_up() -> bool {
 
 /// Sets up the logger with the given [`Config`] and applies it as the global logger.
 /// This uses [`log4rs`] under the hood.
-/// You can call this multiple times to overwrite an existing logger's config.
+/// You can call this multiple times to overwrite an existing logger's config, which flushes its appenders first.
+/// Fails if a logger not set up by this crate is already the global logger.
+/// Runtime reconfiguration, like [`verbosity::verbose_scope`], requires setting up the logger with [`ConfigBuilder::apply`] instead.
 pub fn setup(config: Config) -> Result<(), SetLoggerError> {
+    *LOGGER_BUILDER.lock() = None;
+    set_config(config)
+}
+
+/// Sets up the logger with the configuration built by the given [`ConfigBuilder`],
+/// keeping the builder so the configuration can be rebuilt at runtime.
+pub(crate) fn setup_builder(builder: ConfigBuilder) -> Result<(), ConfigBuilderError> {
+    let mut lock = LOGGER_BUILDER.lock();
+
+    let config = effective_builder(&builder).build_config()?;
+    set_config(config).map_err(ConfigBuilderError::ForeignLoggerInstalled)?;
+    *lock = Some(builder);
+
+    Ok(())
+}
+
+/// Rebuilds the configuration from the [`ConfigBuilder`] kept by [`setup_builder`] and applies it.
+/// Returns `false` if the logger was not set up with a [`ConfigBuilder`].
+pub(crate) fn reconfigure() -> Result<bool, ConfigErrors> {
+    let lock = LOGGER_BUILDER.lock();
+    let Some(builder) = lock.as_ref() else {
+        return Ok(false);
+    };
+
+    let config = effective_builder(builder).build_config()?;
+    if let Some(handle) = LOGGER_HANDLE.lock().as_ref() {
+        handle.set_config(config);
+    }
+
+    Ok(true)
+}
+
+/// Replaces the [`ConfigBuilder`] kept by [`setup_builder`] with the one returned by the given function and applies its configuration.
+/// The kept builder is only replaced if the configuration could be built.
+/// Returns `false` if the logger was not set up with a [`ConfigBuilder`].
+fn update_builder(f: impl FnOnce(ConfigBuilder) -> ConfigBuilder) -> Result<bool, ConfigErrors> {
+    let mut lock = LOGGER_BUILDER.lock();
+    let Some(builder) = lock.as_mut() else {
+        return Ok(false);
+    };
+
+    let updated = f(builder.clone());
+    let config = effective_builder(&updated).build_config()?;
+    *builder = updated;
+    if let Some(handle) = LOGGER_HANDLE.lock().as_ref() {
+        handle.set_config(config);
+    }
+
+    Ok(true)
+}
+
+/// Sets the level of the root logger at runtime, rebuilding the configuration from the [`ConfigBuilder`] the logger was set up with
+/// and applying it atomically, like [`ConfigBuilder::root_log_level`] followed by [`ConfigBuilder::apply`] would.
+/// The change is kept when the configuration is rebuilt later, e.g. by [`activate_profile`](crate::activate_profile).
+/// The active profile and verbosity scopes still apply on top, see [`verbose_scope`](crate::verbose_scope).
+pub fn set_level(level: LevelFilter) -> Result<(), SetLevelError> {
+    match update_builder(|builder| builder.root_log_level(level))? {
+        true => Ok(()),
+        false => Err(SetLevelError::NotSetUp),
+    }
+}
+
+/// Sets the level of the given module or target at runtime, like [`set_level`] does for the root logger.
+/// This replaces the level set by [`ConfigBuilder::log_level`], or by [`ConfigBuilder::logger`] if a logger was added for the target.
+pub fn set_module_level(module: &str, level: LevelFilter) -> Result<(), SetLevelError> {
+    match update_builder(|builder| builder.target_level(module, level))? {
+        true => Ok(()),
+        false => Err(SetLevelError::NotSetUp),
+    }
+}
+
+/// Calls the given function with the [`ConfigBuilder`] kept by [`setup_builder`].
+/// Returns `None` if the logger was not set up with a [`ConfigBuilder`].
+pub(crate) fn with_builder<T>(f: impl FnOnce(&ConfigBuilder) -> T) -> Option<T> {
+    LOGGER_BUILDER.lock().as_ref().map(f)
+}
+
+/// Returns a copy of the given builder with the active profile, verbosity overrides, and disabled appenders applied.
+pub(crate) fn effective_builder(builder: &ConfigBuilder) -> ConfigBuilder {
+    toggle::apply_disabled(verbosity::apply_overrides(profile::apply_active(
+        builder.clone(),
+    )))
+}
+
+/// Watches the log4rs configuration file at the given path and applies it whenever its contents change,
+/// so levels and appenders of a running service can be changed without redeploying it.
+/// The file is read in the formats of [`setup_from_log4rs_file`](crate::setup_from_log4rs_file), e.g. YAML or, with the `toml` feature, TOML,
+/// and checked every [`default::config_watch_interval`], reading it only if its modification time changed.
+/// Its contents at the time of this call are not applied,
+/// so set up the logger from the same file first, e.g. with [`setup_from_log4rs_file`](crate::setup_from_log4rs_file).
+///
+/// If the logger was set up with a [`ConfigBuilder`], only the levels of the root logger and the loggers in the file are applied on top of it,
+/// like [`set_level`] and [`set_module_level`] do, and its appenders are ignored.
+/// Otherwise, applying the file replaces the configuration like [`setup`] does.
+/// If the file cannot be read or is invalid, a warning is logged and the current configuration is kept.
+/// The watcher thread stops when the returned handle is dropped.
+pub fn watch_config(path: impl Into<PathBuf>) -> io::Result<ConfigWatcher> {
+    let path = path.into();
+    let modified = |path: &Path| {
+        fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    };
+    let mut last_modified = modified(&path);
+    let mut contents = fs::read(&path).ok();
+    let (stop, stopped) = mpsc::channel::<()>();
+
+    let thread = thread::Builder::new()
+        .name("lum_log-config".to_string())
+        .spawn(move || {
+            while let Err(RecvTimeoutError::Timeout) =
+                stopped.recv_timeout(default::config_watch_interval())
+            {
+                let current_modified = modified(&path);
+                if current_modified.is_none() || current_modified == last_modified {
+                    continue;
+                }
+                last_modified = current_modified;
+
+                let current = fs::read(&path).ok();
+                if current.is_none() || current == contents {
+                    continue;
+                }
+                contents = current;
+
+                apply_config_file(&path);
+            }
+        })?;
+
+    Ok(ConfigWatcher {
+        stop: Some(stop),
+        thread: Some(thread),
+    })
+}
+
+/// Applies the changed log4rs configuration file at the given path, see [`watch_config`].
+fn apply_config_file(path: &Path) {
+    let config = match log4rs_file::load(path) {
+        Ok(config) => config,
+        Err(error) => {
+            crate::warn!(
+                "Failed to load changed logging configuration from {}: {error}",
+                path.display()
+            );
+            return;
+        }
+    };
+
+    let levels = |builder: ConfigBuilder| {
+        config.loggers().iter().fold(
+            builder.root_log_level(config.root().level()),
+            |builder, logger| builder.target_level(logger.name(), logger.level()),
+        )
+    };
+    match update_builder(levels) {
+        Ok(true) => crate::info!("Applied changed log levels from {}", path.display()),
+        Ok(false) => match setup(config) {
+            Ok(()) => crate::info!(
+                "Applied changed logging configuration from {}",
+                path.display()
+            ),
+            Err(error) => crate::warn!(
+                "Failed to apply changed logging configuration from {}: {error}",
+                path.display()
+            ),
+        },
+        Err(error) => crate::warn!(
+            "Failed to apply changed log levels from {}: {error}",
+            path.display()
+        ),
+    }
+}
+
+/// Handle to the watcher thread spawned by [`watch_config`], stopping it when dropped.
+#[derive(Debug)]
+pub struct ConfigWatcher {
+    stop: Option<Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Drop for ConfigWatcher {
+    fn drop(&mut self) {
+        drop(self.stop.take());
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// The outcome of draining the buffers of [`AsyncAppender`](crate::append::AsyncAppender)s by [`shutdown_with_timeout`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ShutdownReport {
+    /// The number of buffered records appended while draining.
+    pub flushed: u64,
+    /// The number of buffered records discarded, as the timeout was reached before they were appended.
+    pub dropped: u64,
+}
+
+impl Display for ShutdownReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "flushed: {}, dropped: {}", self.flushed, self.dropped)
+    }
+}
+
+/// Shuts down the logger like [`shutdown_with_timeout`], waiting for at most [`default::shutdown_timeout`].
+/// Call this at the end of the program, as buffered records may be lost otherwise.
+pub fn shutdown() -> ShutdownReport {
+    shutdown_with_timeout(default::shutdown_timeout())
+}
+
+/// Shuts down the logger by draining the buffers of all [`AsyncAppender`](crate::append::AsyncAppender)s and flushing all appenders,
+/// waiting for at most the given timeout to drain the buffers, e.g. to fit the termination grace period of an orchestrator.
+/// All buffers are closed first, so records logged to them afterwards are discarded, then drained concurrently by their worker threads,
+/// each in the order its records were buffered. Spilled records stay in the spool and are replayed by the next process.
+/// Records still buffered when the timeout is reached are discarded, so all appenders are flushed in time.
+/// Returns how many buffered records were flushed and dropped, e.g. to tune the timeout and buffer sizes.
+/// If enabled by [`stats::set_shutdown_summary`], a summary of the [`stats::stats`] is logged before.
+/// Does nothing if the logger is not set up.
+pub fn shutdown_with_timeout(timeout: Duration) -> ShutdownReport {
+    if !is_set_up() {
+        return ShutdownReport::default();
+    }
+
+    if stats::shutdown_summary() {
+        log::info!(target: SUMMARY_TARGET, "Shutting down, {}", stats::stats());
+    }
+    let (flushed, dropped) = asynchronous::drain_all(Instant::now() + timeout);
+    flush();
+
+    ShutdownReport { flushed, dropped }
+}
+
+/// Flushes all appenders of the current configuration, which is the same as `log::logger().flush()`.
+/// Buffering appenders, like the [`AsyncAppender`](crate::append::AsyncAppender), wait for at most [`default::flush_timeout`] each.
+/// Does nothing if the logger is not set up.
+pub fn flush() {
+    log::logger().flush();
+}
+
+fn set_config(config: Config) -> Result<(), SetLoggerError> {
     let mut lock = LOGGER_HANDLE.lock();
 
     if let Some(handle) = lock.as_ref() {
+        flush();
         handle.set_config(config);
         return Ok(());
     }
 
-    let handle = log4rs::init_config(config)?;
+    stats::start();
+    let logger = log4rs::Logger::new(config);
+    let handle = logger.handle();
+    let max_level = logger.max_log_level();
+    log::set_boxed_logger(Box::new(GlobalLogger(logger)))?;
+    log::set_max_level(max_level);
+
     *lock = Some(handle);
     Ok(())
 }
+
+/// The global logger, wrapping the [`log4rs::Logger`] to enrich records before they are appended.
+struct GlobalLogger(log4rs::Logger);
+
+impl GlobalLogger {
+    /// Passes the given record through the layers set by [`layer::set_layers`], if any, and dispatches the resulting records.
+    fn process(&self, record: &Record) {
+        let Some(layers) = layer::layers() else {
+            return self.dispatch(record);
+        };
+
+        layer::run(&layers, OwnedRecord::from(record), &mut |record| {
+            record.with_record(|record| self.dispatch(record))
+        });
+    }
+
+    /// Passes the given record to the appenders of the current configuration.
+    fn dispatch(&self, record: &Record) {
+        stats::record_logged(record.level());
+        anomaly::observe(record.target());
+        cost::record_logged(record.target());
+        #[cfg(feature = "event-id")]
+        let _event_id = crate::event::scope();
+        emergency::guard(record, || self.0.log(record));
+    }
+}
+
+impl Log for GlobalLogger {
+    /// Returns whether the configured level of the record's target, including active verbosity scopes, enables the record,
+    /// so `log::log_enabled!` checks in dependencies are accurate.
+    /// As metadata does not tell whether its target is explicit, a target with the default target prefix enables the record as well,
+    /// see [`target::set_default_target_prefix`].
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        if self.0.enabled(metadata) {
+            return true;
+        }
+
+        target::prefixed(metadata.target()).is_some_and(|target| {
+            let metadata = Metadata::builder()
+                .level(metadata.level())
+                .target(&target)
+                .build();
+            self.0.enabled(&metadata)
+        })
+    }
+
+    /// Logs the record to all appenders of the current configuration.
+    ///
+    /// Records not [`enabled`](Log::enabled) for their target are discarded before anything else is done,
+    /// as the `log` macros only check the global maximum level.
+    /// The message is formatted once upfront and the formatted record is shared by all appenders,
+    /// so only their encoders differ, instead of every appender running the formatting of the message again,
+    /// see `benches/shared_formatting.rs`.
+    /// With the `redaction` feature, the formatted message is redacted by the rules set by [`redaction::set_redaction_rules`](crate::redaction::set_redaction_rules), if any,
+    /// before the record passes the layers set by [`layer::set_layers`].
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let prefixed = match record.module_path() == Some(record.target()) {
+            true => target::prefixed(record.target()),
+            false => None,
+        };
+
+        let message = match record.args().as_str() {
+            Some(message) => Cow::Borrowed(message),
+            None => {
+                // A failing `Display` implementation keeps the message formatted so far, like an encoder would.
+                let mut message = String::new();
+                let _ = message.write_fmt(*record.args());
+                Cow::Owned(message)
+            }
+        };
+        #[cfg(feature = "redaction")]
+        let message = crate::redaction::redact(message);
+
+        self.process(
+            &Record::builder()
+                .level(record.level())
+                .target(prefixed.as_deref().unwrap_or(record.target()))
+                .args(format_args!("{message}"))
+                .module_path(record.module_path())
+                .file(record.file())
+                .line(record.line())
+                .build(),
+        );
+    }
+
+    /// Flushes all appenders of the current configuration.
+    /// A panic of an appender is reported instead of unwinding into the caller, e.g. a dependency calling `log::logger().flush()`.
+    fn flush(&self) {
+        internal::catch("Flushing the logger", || self.0.flush());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    use lum_libs::log4rs::append::Append;
+
+    use super::*;
+    use crate::testing;
+
+    #[test]
+    fn changed_config_file_applies_levels_on_top_of_the_builder() {
+        let _global = testing::GLOBAL.lock();
+        let records = testing::capture(ConfigBuilder::new().root_log_level(LevelFilter::Info));
+        let path = testing::temp_dir("watch_config").join("log4rs.json");
+        fs::write(
+            &path,
+            r#"{"root": {"level": "warn"}, "loggers": {"watch_test::db": {"level": "trace"}}}"#,
+        )
+        .unwrap();
+
+        apply_config_file(&path);
+        while records.try_recv().is_ok() {}
+
+        log::info!(target: "watch_test", "Filtered");
+        log::warn!(target: "watch_test", "Logged");
+        log::trace!(target: "watch_test::db", "Logged");
+
+        let messages = records
+            .try_iter()
+            .map(|record| record.message)
+            .collect::<Vec<_>>();
+        assert_eq!(messages, ["Logged", "Logged"]);
+        assert!(with_builder(|_| ()).is_some());
+    }
+
+    #[derive(Debug)]
+    struct FlushCounter(Arc<AtomicUsize>);
+
+    impl Append for FlushCounter {
+        fn append(&self, _record: &Record) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        fn flush(&self) {
+            if self.0.fetch_add(1, Ordering::Relaxed) == 1 {
+                panic!("Flushing failed");
+            }
+        }
+    }
+
+    #[test]
+    fn flushing_reaches_every_appender_and_reports_panics() {
+        let _global = testing::GLOBAL.lock();
+        let flushes = Arc::new(AtomicUsize::new(0));
+        ConfigBuilder::new()
+            .root_log_level(LevelFilter::Info)
+            .appender("counter", Box::new(FlushCounter(flushes.clone())))
+            .apply()
+            .unwrap();
+
+        flush();
+        log::logger().flush();
+
+        assert_eq!(flushes.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn enabled_follows_the_levels_of_targets_and_verbosity_scopes() {
+        let _global = testing::GLOBAL.lock();
+        let _records = testing::capture(
+            ConfigBuilder::new()
+                .root_log_level(LevelFilter::Info)
+                .log_level("enabled_test::db", LevelFilter::Trace),
+        );
+
+        assert!(log::log_enabled!(target: "enabled_test", log::Level::Info));
+        assert!(!log::log_enabled!(target: "enabled_test", log::Level::Debug));
+        assert!(log::log_enabled!(target: "enabled_test::db::pool", log::Level::Trace));
+
+        let _guard = verbosity::verbose_scope(LevelFilter::Debug);
+        assert!(log::log_enabled!(target: "enabled_test", log::Level::Debug));
+    }
+
+    /// Counts how often it is formatted.
+    struct Counted<'a>(&'a AtomicUsize);
+
+    impl Display for Counted<'_> {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            self.0.fetch_add(1, Ordering::Relaxed);
+            f.write_str("Formatted")
+        }
+    }
+
+    #[test]
+    fn messages_are_formatted_once_for_all_appenders() {
+        let _global = testing::GLOBAL.lock();
+        let (second, second_records) = testing::channel();
+        let records = testing::capture(
+            ConfigBuilder::new()
+                .root_log_level(LevelFilter::Info)
+                .appender("second", second),
+        );
+        let formatted = AtomicUsize::new(0);
+
+        log::info!(target: "formatting_test", "{}", Counted(&formatted));
+        log::debug!(target: "formatting_test", "{}", Counted(&formatted));
+
+        assert_eq!(formatted.load(Ordering::Relaxed), 1);
+        assert_eq!(testing::messages(&records), ["Formatted"]);
+        assert_eq!(testing::messages(&second_records), ["Formatted"]);
+    }
+
+    #[test]
+    fn levels_set_at_runtime_are_kept_when_the_configuration_is_rebuilt() {
+        let _global = testing::GLOBAL.lock();
+        let records = testing::capture(ConfigBuilder::new().root_log_level(LevelFilter::Info));
+
+        set_level(LevelFilter::Warn).unwrap();
+        set_module_level("set_level_test::db", LevelFilter::Trace).unwrap();
+        reconfigure().unwrap();
+        log::info!(target: "set_level_test", "Filtered");
+        log::warn!(target: "set_level_test", "Warned");
+        log::trace!(target: "set_level_test::db", "Traced");
+
+        assert_eq!(testing::messages(&records), ["Warned", "Traced"]);
+
+        setup(
+            Config::builder()
+                .build(lum_libs::log4rs::config::Root::builder().build(LevelFilter::Info))
+                .unwrap(),
+        )
+        .unwrap();
+        assert!(matches!(
+            set_level(LevelFilter::Debug),
+            Err(SetLevelError::NotSetUp)
+        ));
+    }
+}
@@ -68,22 +68,427 @@ macro_rules! trace {
     };
 }
 
+/// Logs a message at the error level with the given target, e.g. to match a [`RouteRule`](crate::RouteRule),
+/// like `error_target!("audit", "user {} deleted item", id)`.
+/// If the logger is not set up, the message is printed to stderr.
+/// **This macro uses a Mutex under the hood, so do not use it in performance-critical code.**
+#[macro_export]
+macro_rules! error_target {
+    ($target:expr, $($arg:tt)*) => {
+        if $crate::is_set_up() {
+            $crate::log::error!(target: $target, $($arg)*);
+        } else {
+            std::eprintln!($($arg)*);
+        }
+    };
+}
+
+/// Logs a message at the warn level with the given target, e.g. to match a [`RouteRule`](crate::RouteRule),
+/// like `warn_target!("audit", "user {} deleted item", id)`.
+/// If the logger is not set up, the message is printed to stdout.
+/// **This macro uses a Mutex under the hood, so do not use it in performance-critical code.**
+#[macro_export]
+macro_rules! warn_target {
+    ($target:expr, $($arg:tt)*) => {
+        if $crate::is_set_up() {
+            $crate::log::warn!(target: $target, $($arg)*);
+        } else {
+            std::println!($($arg)*);
+        }
+    };
+}
+
+/// Logs a message at the info level with the given target, e.g. to match a [`RouteRule`](crate::RouteRule),
+/// like `info_target!("audit", "user {} deleted item", id)`.
+/// If the logger is not set up, the message is printed to stdout.
+/// **This macro uses a Mutex under the hood, so do not use it in performance-critical code.**
+#[macro_export]
+macro_rules! info_target {
+    ($target:expr, $($arg:tt)*) => {
+        if $crate::is_set_up() {
+            $crate::log::info!(target: $target, $($arg)*);
+        } else {
+            std::println!($($arg)*);
+        }
+    };
+}
+
+/// Logs a message at the debug level with the given target, e.g. to match a [`RouteRule`](crate::RouteRule),
+/// like `debug_target!("audit", "user {} deleted item", id)`.
+/// If the logger is not set up, the message is printed to stdout.
+/// **This macro uses a Mutex under the hood, so do not use it in performance-critical code.**
+#[macro_export]
+macro_rules! debug_target {
+    ($target:expr, $($arg:tt)*) => {
+        if $crate::is_set_up() {
+            $crate::log::debug!(target: $target, $($arg)*);
+        } else {
+            std::println!($($arg)*);
+        }
+    };
+}
+
+/// Logs a message at the trace level with the given target, e.g. to match a [`RouteRule`](crate::RouteRule),
+/// like `trace_target!("audit", "user {} deleted item", id)`.
+/// If the logger is not set up, the message is printed to stdout.
+/// **This macro uses a Mutex under the hood, so do not use it in performance-critical code.**
+#[macro_export]
+macro_rules! trace_target {
+    ($target:expr, $($arg:tt)*) => {
+        if $crate::is_set_up() {
+            $crate::log::trace!(target: $target, $($arg)*);
+        } else {
+            std::println!($($arg)*);
+        }
+    };
+}
+
 /// Calls the `error!` macro and then panics by using the `panic!` macro with the same message.
+/// The message is logged as written, with the location exposed under [`PANIC_LOCATION_MDC_KEY`](crate::context::PANIC_LOCATION_MDC_KEY),
+/// so the record carries it next to the current MDC entries.
+/// If the logger is not set up, the message is printed to stderr followed by the [`PanicContext`](crate::context::PanicContext).
 /// **This macro uses a Mutex under the hood, so do not use it in performance-critical code.**
 #[macro_export]
 macro_rules! error_panic {
     ($($arg:tt)*) => {
-        $crate::error!($($arg)*);
+        if $crate::is_set_up() {
+            let _location = $crate::context::scope_location(std::file!(), std::line!());
+            $crate::log::error!($($arg)*);
+        } else {
+            std::eprintln!(
+                "{} ({})",
+                std::format_args!($($arg)*),
+                $crate::context::PanicContext::capture(std::file!(), std::line!())
+            );
+        }
         std::panic!($($arg)*);
     };
 }
 
 /// Calls the `error!` macro and then panics by using the `unreachable!` macro with the same message.
+/// The message is logged as written, with the location exposed under [`PANIC_LOCATION_MDC_KEY`](crate::context::PANIC_LOCATION_MDC_KEY),
+/// so the record carries it next to the current MDC entries.
+/// If the logger is not set up, the message is printed to stderr followed by the [`PanicContext`](crate::context::PanicContext).
 /// **This macro uses a Mutex under the hood, so do not use it in performance-critical code.**
 #[macro_export]
 macro_rules! error_unreachable {
     ($($arg:tt)*) => {
-        $crate::error!($($arg)*);
+        if $crate::is_set_up() {
+            let _location = $crate::context::scope_location(std::file!(), std::line!());
+            $crate::log::error!($($arg)*);
+        } else {
+            std::eprintln!(
+                "{} ({})",
+                std::format_args!($($arg)*),
+                $crate::context::PanicContext::capture(std::file!(), std::line!())
+            );
+        }
         std::unreachable!($($arg)*);
     };
 }
+
+/// Logs the message returned by the given closure at the given level.
+/// The closure is only called if the level is enabled for the target, so expensive messages are not built needlessly.
+/// If the logger is not set up, the message is printed to stderr for errors and to stdout otherwise,
+/// but only if the level is at least as severe as [`default::log_level`](crate::default::log_level).
+/// The closure may return anything implementing [`Display`](std::fmt::Display).
+/// **This macro uses a Mutex under the hood, so do not use it in performance-critical code.**
+#[macro_export]
+macro_rules! log_lazy {
+    (target: $target:expr, $level:expr, $message:expr) => {{
+        let level: $crate::log::Level = $level;
+        if $crate::is_set_up() {
+            if $crate::log::log_enabled!(target: $target, level) {
+                $crate::log::log!(target: $target, level, "{}", ($message)());
+            }
+        } else if level <= $crate::default::log_level() {
+            if level == $crate::log::Level::Error {
+                std::eprintln!("{}", ($message)());
+            } else {
+                std::println!("{}", ($message)());
+            }
+        }
+    }};
+    ($level:expr, $message:expr) => {
+        $crate::log_lazy!(target: std::module_path!(), $level, $message)
+    };
+}
+
+/// Logs the message returned by the given closure at the error level. See [`log_lazy!`].
+/// **This macro uses a Mutex under the hood, so do not use it in performance-critical code.**
+#[macro_export]
+macro_rules! error_lazy {
+    (target: $target:expr, $message:expr) => {
+        $crate::log_lazy!(target: $target, $crate::log::Level::Error, $message)
+    };
+    ($message:expr) => {
+        $crate::log_lazy!($crate::log::Level::Error, $message)
+    };
+}
+
+/// Logs the message returned by the given closure at the warn level. See [`log_lazy!`].
+/// **This macro uses a Mutex under the hood, so do not use it in performance-critical code.**
+#[macro_export]
+macro_rules! warn_lazy {
+    (target: $target:expr, $message:expr) => {
+        $crate::log_lazy!(target: $target, $crate::log::Level::Warn, $message)
+    };
+    ($message:expr) => {
+        $crate::log_lazy!($crate::log::Level::Warn, $message)
+    };
+}
+
+/// Logs the message returned by the given closure at the info level. See [`log_lazy!`].
+/// **This macro uses a Mutex under the hood, so do not use it in performance-critical code.**
+#[macro_export]
+macro_rules! info_lazy {
+    (target: $target:expr, $message:expr) => {
+        $crate::log_lazy!(target: $target, $crate::log::Level::Info, $message)
+    };
+    ($message:expr) => {
+        $crate::log_lazy!($crate::log::Level::Info, $message)
+    };
+}
+
+/// Logs the message returned by the given closure at the debug level. See [`log_lazy!`].
+/// **This macro uses a Mutex under the hood, so do not use it in performance-critical code.**
+#[macro_export]
+macro_rules! debug_lazy {
+    (target: $target:expr, $message:expr) => {
+        $crate::log_lazy!(target: $target, $crate::log::Level::Debug, $message)
+    };
+    ($message:expr) => {
+        $crate::log_lazy!($crate::log::Level::Debug, $message)
+    };
+}
+
+/// Logs the message returned by the given closure at the trace level. See [`log_lazy!`].
+/// **This macro uses a Mutex under the hood, so do not use it in performance-critical code.**
+#[macro_export]
+macro_rules! trace_lazy {
+    (target: $target:expr, $message:expr) => {
+        $crate::log_lazy!(target: $target, $crate::log::Level::Trace, $message)
+    };
+    ($message:expr) => {
+        $crate::log_lazy!($crate::log::Level::Trace, $message)
+    };
+}
+
+/// Logs a message at the auxiliary level [`AuxLevel::Fatal`](crate::severity::AuxLevel::Fatal), which maps to the error level.
+/// If the logger is not set up, the message is printed to stderr.
+/// **This macro uses a Mutex under the hood, so do not use it in performance-critical code.**
+#[macro_export]
+macro_rules! fatal {
+    ($($arg:tt)*) => {{
+        let _severity = $crate::severity::AuxLevel::Fatal.scope();
+        $crate::error!($($arg)*);
+    }};
+}
+
+/// Logs a message at the auxiliary level [`AuxLevel::Notice`](crate::severity::AuxLevel::Notice), which maps to the info level.
+/// If the logger is not set up, the message is printed to stdout.
+/// **This macro uses a Mutex under the hood, so do not use it in performance-critical code.**
+#[macro_export]
+macro_rules! notice {
+    ($($arg:tt)*) => {{
+        let _severity = $crate::severity::AuxLevel::Notice.scope();
+        $crate::info!($($arg)*);
+    }};
+}
+
+/// Logs a message at the auxiliary level [`AuxLevel::Verbose`](crate::severity::AuxLevel::Verbose), which maps to the debug level.
+/// If the logger is not set up, the message is printed to stdout.
+/// **This macro uses a Mutex under the hood, so do not use it in performance-critical code.**
+#[macro_export]
+macro_rules! verbose {
+    ($($arg:tt)*) => {{
+        let _severity = $crate::severity::AuxLevel::Verbose.scope();
+        $crate::debug!($($arg)*);
+    }};
+}
+
+/// Logs a hex and ASCII dump of the given bytes at the trace level, headed by the given label.
+/// The dump is capped at [`default::hex_dump_max_len`](crate::default::hex_dump_max_len) bytes, see [`HexDump`](crate::hex::HexDump).
+/// Like [`log_lazy!`], the dump is only formatted if the trace level is enabled.
+/// **This macro uses a Mutex under the hood, so do not use it in performance-critical code.**
+#[macro_export]
+macro_rules! trace_hex {
+    (target: $target:expr, $label:expr, $bytes:expr) => {
+        $crate::log_lazy!(target: $target, $crate::log::Level::Trace, || {
+            let bytes: &[u8] = ::core::convert::AsRef::as_ref($bytes);
+            ::std::format!(
+                "{} ({} bytes):\n{}",
+                $label,
+                bytes.len(),
+                $crate::hex::HexDump::new(bytes)
+            )
+        })
+    };
+    ($label:expr, $bytes:expr) => {
+        $crate::trace_hex!(target: std::module_path!(), $label, $bytes)
+    };
+}
+
+/// Logs the given header followed by the pretty-printed debug output (`{:#?}`) of the given value at the given level,
+/// with every line of the output framed by [`default::pretty_prefix`](crate::default::pretty_prefix), see [`PrettyDebug`](crate::pretty::PrettyDebug).
+/// Like [`log_lazy!`], the value is only formatted if the level is enabled for the target.
+/// **This macro uses a Mutex under the hood, so do not use it in performance-critical code.**
+#[macro_export]
+macro_rules! log_pretty {
+    (target: $target:expr, $level:expr, $header:expr, $value:expr) => {
+        $crate::log_lazy!(target: $target, $level, || {
+            ::std::format!("{}\n{}", $header, $crate::pretty::PrettyDebug::new($value))
+        })
+    };
+    ($level:expr, $header:expr, $value:expr) => {
+        $crate::log_pretty!(target: std::module_path!(), $level, $header, $value)
+    };
+}
+
+/// Logs the given header followed by the framed, pretty-printed debug output of the given value at the error level. See [`log_pretty!`].
+/// **This macro uses a Mutex under the hood, so do not use it in performance-critical code.**
+#[macro_export]
+macro_rules! error_pretty {
+    (target: $target:expr, $header:expr, $value:expr) => {
+        $crate::log_pretty!(target: $target, $crate::log::Level::Error, $header, $value)
+    };
+    ($header:expr, $value:expr) => {
+        $crate::log_pretty!($crate::log::Level::Error, $header, $value)
+    };
+}
+
+/// Logs the given header followed by the framed, pretty-printed debug output of the given value at the warn level. See [`log_pretty!`].
+/// **This macro uses a Mutex under the hood, so do not use it in performance-critical code.**
+#[macro_export]
+macro_rules! warn_pretty {
+    (target: $target:expr, $header:expr, $value:expr) => {
+        $crate::log_pretty!(target: $target, $crate::log::Level::Warn, $header, $value)
+    };
+    ($header:expr, $value:expr) => {
+        $crate::log_pretty!($crate::log::Level::Warn, $header, $value)
+    };
+}
+
+/// Logs the given header followed by the framed, pretty-printed debug output of the given value at the info level. See [`log_pretty!`].
+/// **This macro uses a Mutex under the hood, so do not use it in performance-critical code.**
+#[macro_export]
+macro_rules! info_pretty {
+    (target: $target:expr, $header:expr, $value:expr) => {
+        $crate::log_pretty!(target: $target, $crate::log::Level::Info, $header, $value)
+    };
+    ($header:expr, $value:expr) => {
+        $crate::log_pretty!($crate::log::Level::Info, $header, $value)
+    };
+}
+
+/// Logs the given header followed by the framed, pretty-printed debug output of the given value at the debug level. See [`log_pretty!`].
+/// **This macro uses a Mutex under the hood, so do not use it in performance-critical code.**
+#[macro_export]
+macro_rules! debug_pretty {
+    (target: $target:expr, $header:expr, $value:expr) => {
+        $crate::log_pretty!(target: $target, $crate::log::Level::Debug, $header, $value)
+    };
+    ($header:expr, $value:expr) => {
+        $crate::log_pretty!($crate::log::Level::Debug, $header, $value)
+    };
+}
+
+/// Logs the given header followed by the framed, pretty-printed debug output of the given value at the trace level. See [`log_pretty!`].
+/// **This macro uses a Mutex under the hood, so do not use it in performance-critical code.**
+#[macro_export]
+macro_rules! trace_pretty {
+    (target: $target:expr, $header:expr, $value:expr) => {
+        $crate::log_pretty!(target: $target, $crate::log::Level::Trace, $header, $value)
+    };
+    ($header:expr, $value:expr) => {
+        $crate::log_pretty!($crate::log::Level::Trace, $header, $value)
+    };
+}
+
+/// Logs the given message at the given level with the given [`Serialize`](lum_libs::serde::Serialize) value serialized to JSON attached as payload,
+/// e.g. `log_json!(Level::Info, "Order created", &order)`.
+/// The message is logged unchanged, and the payload is exposed under [`JSON_MDC_KEY`](crate::json::JSON_MDC_KEY), so encoders render it as a separate field.
+/// Patterns show it with `{X(json)}`.
+/// The value is only serialized if the level is enabled for the target. If serialization fails, the error is attached as the payload instead.
+/// If the logger is not set up, the message is printed followed by the payload, like [`log_lazy!`] does.
+/// **This macro uses a Mutex under the hood, so do not use it in performance-critical code.**
+#[macro_export]
+macro_rules! log_json {
+    (target: $target:expr, $level:expr, $message:expr, $value:expr) => {{
+        let level: $crate::log::Level = $level;
+        let enabled = match $crate::is_set_up() {
+            true => $crate::log::log_enabled!(target: $target, level),
+            false => level <= $crate::default::log_level(),
+        };
+        if enabled {
+            let payload = $crate::json::payload($value);
+            if $crate::is_set_up() {
+                let _json = $crate::json::scope(&payload);
+                $crate::log::log!(target: $target, level, "{}", $message);
+            } else {
+                // Without a logger, there is no encoder to render the payload as a separate field.
+                $crate::log_lazy!(target: $target, level, || ::std::format!("{} {}", $message, payload));
+            }
+        }
+    }};
+    ($level:expr, $message:expr, $value:expr) => {
+        $crate::log_json!(target: std::module_path!(), $level, $message, $value)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use lum_libs::log::{Level, LevelFilter};
+
+    use crate::{ConfigBuilder, testing};
+
+    #[test]
+    fn lazy_messages_are_only_built_if_the_level_is_enabled() {
+        let _global = testing::GLOBAL.lock();
+        let records = testing::capture(ConfigBuilder::new().root_log_level(LevelFilter::Info));
+        let built = Cell::new(0);
+        let message = || {
+            built.set(built.get() + 1);
+            "Built"
+        };
+
+        crate::debug_lazy!(message);
+        crate::info_lazy!(message);
+        crate::error_lazy!(target: "macros_test", message);
+
+        assert_eq!(built.get(), 2);
+        assert_eq!(testing::messages(&records), ["Built", "Built"]);
+    }
+
+    #[test]
+    fn target_macros_log_with_the_given_target() {
+        let _global = testing::GLOBAL.lock();
+        let records = testing::capture(ConfigBuilder::new().root_log_level(LevelFilter::Info));
+
+        crate::error_target!("audit", "User {} deleted item", 7);
+        crate::info_target!("audit", "User {} logged in", 7);
+        crate::debug_target!("audit", "Hidden");
+
+        let records = records
+            .try_iter()
+            .map(|record| (record.target, record.level, record.message))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            records,
+            [
+                (
+                    "audit".to_string(),
+                    Level::Error,
+                    "User 7 deleted item".to_string()
+                ),
+                (
+                    "audit".to_string(),
+                    Level::Info,
+                    "User 7 logged in".to_string()
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,110 @@
+use std::{
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use lum_libs::serde::{Deserialize, Serialize};
+
+static BUDGET: AtomicUsize = AtomicUsize::new(usize::MAX);
+static USED: AtomicUsize = AtomicUsize::new(0);
+static BACKPRESSURE: AtomicBool = AtomicBool::new(false);
+
+/// The interval in which buffers blocked by an exhausted budget check for released memory.
+pub(crate) const RETRY_INTERVAL: Duration = Duration::from_millis(10);
+
+/// What a buffer does with a new record when the memory budget is exhausted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde", rename_all = "snake_case")]
+pub enum MemoryPolicy {
+    /// The new record is dropped.
+    #[default]
+    DropNewest,
+    /// The buffer applies its [`Backpressure`](crate::backpressure::Backpressure) strategy, as if it was full.
+    Backpressure,
+}
+
+/// Sets the maximum number of bytes held by the buffers of the [`AsyncAppender`](crate::append::AsyncAppender),
+/// the [`NetworkAppender`](crate::append::NetworkAppender), and the subscription history, or `None` for no limit.
+/// The memory of buffered records is estimated, so the actual footprint may deviate slightly.
+/// Spools are on disk and capped by their own size limit. See also [`ConfigBuilder::memory_budget`](crate::ConfigBuilder::memory_budget).
+pub fn set_memory_budget(bytes: Option<usize>) {
+    BUDGET.store(bytes.unwrap_or(usize::MAX), Ordering::Relaxed);
+}
+
+/// Returns the memory budget set by [`set_memory_budget`].
+pub fn memory_budget() -> Option<usize> {
+    match BUDGET.load(Ordering::Relaxed) {
+        usize::MAX => None,
+        bytes => Some(bytes),
+    }
+}
+
+/// Returns the estimated number of bytes currently held by buffers.
+pub fn memory_used() -> usize {
+    USED.load(Ordering::Relaxed)
+}
+
+/// Sets what buffers do with new records when the memory budget is exhausted. Defaults to [`MemoryPolicy::DropNewest`].
+/// The subscription history always evicts its oldest records instead.
+pub fn set_memory_policy(policy: MemoryPolicy) {
+    BACKPRESSURE.store(policy == MemoryPolicy::Backpressure, Ordering::Relaxed);
+}
+
+/// Returns the policy set by [`set_memory_policy`].
+pub fn memory_policy() -> MemoryPolicy {
+    match BACKPRESSURE.load(Ordering::Relaxed) {
+        true => MemoryPolicy::Backpressure,
+        false => MemoryPolicy::DropNewest,
+    }
+}
+
+/// Reserves the given number of bytes of the budget, returning whether they fit.
+pub(crate) fn try_reserve(bytes: usize) -> bool {
+    let budget = BUDGET.load(Ordering::Relaxed);
+    USED.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |used| {
+        used.checked_add(bytes).filter(|&total| total <= budget)
+    })
+    .is_ok()
+}
+
+/// Releases the given number of bytes previously reserved by [`try_reserve`].
+pub(crate) fn release(bytes: usize) {
+    USED.fetch_sub(bytes, Ordering::Relaxed);
+}
+
+#[cfg(test)]
+mod tests {
+    use lum_libs::log::Level;
+
+    use super::*;
+    use crate::{default, subscribe, testing};
+
+    fn publish_message(message: &str) {
+        subscribe::publish(testing::owned_record(Level::Info, "memory_test", message));
+    }
+
+    #[test]
+    fn the_history_evicts_its_oldest_records_to_stay_within_the_memory_budget() {
+        let _global = testing::GLOBAL.lock();
+        subscribe::set_history_capacity(0);
+        subscribe::set_history_capacity(100);
+        let size = testing::owned_record(Level::Info, "memory_test", "Record 1").estimated_size();
+        let budget = memory_used() + 2 * size;
+        set_memory_budget(Some(budget));
+
+        for message in ["Record 1", "Record 2", "Record 3", "Record 4"] {
+            publish_message(message);
+        }
+
+        let recent = subscribe::recent()
+            .into_iter()
+            .map(|record| record.message)
+            .collect::<Vec<_>>();
+        assert_eq!(recent, ["Record 3", "Record 4"]);
+        assert_eq!(memory_used(), budget);
+        assert!(!try_reserve(1));
+
+        set_memory_budget(None);
+        subscribe::set_history_capacity(default::history_capacity());
+    }
+}
@@ -0,0 +1,84 @@
+use std::fmt::{self, Debug, Display, Formatter};
+
+use crate::default;
+
+/// The pretty-printed debug output (`{:#?}`) of a value, with every line framed by a prefix:
+/// ```text
+///   | Order {
+///   |     id: 42,
+///   | }
+/// ```
+/// The framed lines are easily told apart from the header line of a record,
+/// so multi-line dumps stay readable and do not confuse line-based parsers.
+#[derive(Clone, Copy)]
+pub struct PrettyDebug<'a, T: ?Sized> {
+    value: &'a T,
+    prefix: &'a str,
+}
+
+impl<'a, T: Debug + ?Sized> PrettyDebug<'a, T> {
+    /// Creates a new `PrettyDebug` of the given value, framed by [`default::pretty_prefix`].
+    pub fn new(value: &'a T) -> Self {
+        Self {
+            value,
+            prefix: default::pretty_prefix(),
+        }
+    }
+
+    /// Sets the prefix of every line.
+    pub fn prefix(mut self, prefix: &'a str) -> Self {
+        self.prefix = prefix;
+        self
+    }
+}
+
+impl<T: Debug + ?Sized> Debug for PrettyDebug<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PrettyDebug")
+            .field("value", &self.value)
+            .field("prefix", &self.prefix)
+            .finish()
+    }
+}
+
+impl<T: Debug + ?Sized> Display for PrettyDebug<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let debug = format!("{:#?}", self.value);
+        for (index, line) in debug.lines().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}{line}", self.prefix)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lum_libs::log::LevelFilter;
+
+    use super::*;
+    use crate::{ConfigBuilder, testing};
+
+    #[test]
+    fn pretty_values_are_logged_below_the_header_with_framed_lines() {
+        let _global = testing::GLOBAL.lock();
+        let records = testing::capture(ConfigBuilder::new().root_log_level(LevelFilter::Info));
+
+        crate::info_pretty!("Order created", &Some(42));
+
+        assert_eq!(
+            testing::messages(&records),
+            [format!(
+                "Order created\n{prefix}Some(\n{prefix}    42,\n{prefix})",
+                prefix = default::pretty_prefix()
+            )]
+        );
+        assert_eq!(
+            PrettyDebug::new(&[1]).prefix("> ").to_string(),
+            "> [\n>     1,\n> ]"
+        );
+    }
+}
@@ -0,0 +1,187 @@
+use std::{
+    io::{self, BufRead, BufReader, Read},
+    process::{Child, ChildStdin, Command, ExitStatus, Stdio},
+    thread::{self, JoinHandle},
+};
+
+use lum_libs::log::{self, Level};
+
+use crate::default;
+
+/// Spawns child processes and re-emits their stdout and stderr line by line through the logger,
+/// so their output lands in the configured appenders with proper timestamps.
+///
+/// Lines are logged to the configured target, stdout lines at [`default::child_stdout_level`]
+/// and stderr lines at [`default::child_stderr_level`] unless configured otherwise.
+/// Invalid UTF-8 is replaced, and trailing line breaks are removed.
+///
+/// ```text
+/// let status = ChildLogger::new("ffmpeg").run(Command::new("ffmpeg").args(["-i", "in.mkv", "out.mp4"]))?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct ChildLogger {
+    target: String,
+    stdout_level: Level,
+    stderr_level: Level,
+}
+
+impl ChildLogger {
+    /// Creates a `ChildLogger` logging to the given target.
+    pub fn new(target: impl Into<String>) -> Self {
+        Self {
+            target: target.into(),
+            stdout_level: default::child_stdout_level(),
+            stderr_level: default::child_stderr_level(),
+        }
+    }
+
+    /// Sets the level at which stdout lines are logged.
+    pub fn stdout_level(mut self, level: Level) -> Self {
+        self.stdout_level = level;
+        self
+    }
+
+    /// Sets the level at which stderr lines are logged.
+    pub fn stderr_level(mut self, level: Level) -> Self {
+        self.stderr_level = level;
+        self
+    }
+
+    /// Spawns the given command with piped stdout and stderr, and starts re-emitting their lines on background threads.
+    /// The stdin of the command is left as configured.
+    pub fn spawn(&self, command: &mut Command) -> io::Result<CapturedChild> {
+        let mut child = command
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        let mut readers = Vec::with_capacity(2);
+        if let Some(stdout) = child.stdout.take() {
+            readers.push(forward_lines(
+                "lum_log-child-stdout".to_string(),
+                self.target.clone(),
+                self.stdout_level,
+                stdout,
+            )?);
+        }
+        if let Some(stderr) = child.stderr.take() {
+            readers.push(forward_lines(
+                "lum_log-child-stderr".to_string(),
+                self.target.clone(),
+                self.stderr_level,
+                stderr,
+            )?);
+        }
+
+        Ok(CapturedChild { child, readers })
+    }
+
+    /// Spawns the given command like [`spawn`](Self::spawn) and waits for it to exit
+    /// and for all of its output to be logged.
+    pub fn run(&self, command: &mut Command) -> io::Result<ExitStatus> {
+        self.spawn(command)?.wait()
+    }
+}
+
+/// Reads the given output line by line on a background thread with the given name, logging each line to the given target at the given level.
+/// Invalid UTF-8 is replaced, and trailing line breaks are removed.
+pub(crate) fn forward_lines(
+    thread_name: String,
+    target: String,
+    level: Level,
+    output: impl Read + Send + 'static,
+) -> io::Result<JoinHandle<()>> {
+    thread::Builder::new().name(thread_name).spawn(move || {
+        let mut reader = BufReader::new(output);
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            match reader.read_until(b'\n', &mut line) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {
+                    while line
+                        .last()
+                        .is_some_and(|byte| matches!(byte, b'\n' | b'\r'))
+                    {
+                        line.pop();
+                    }
+                    log::log!(target: &target, level, "{}", String::from_utf8_lossy(&line));
+                }
+            }
+        }
+    })
+}
+
+/// A child process whose output is re-emitted by a [`ChildLogger`]. Returned by [`ChildLogger::spawn`].
+#[derive(Debug)]
+pub struct CapturedChild {
+    child: Child,
+    readers: Vec<JoinHandle<()>>,
+}
+
+impl CapturedChild {
+    /// Returns the OS-assigned process identifier of the child.
+    pub fn id(&self) -> u32 {
+        self.child.id()
+    }
+
+    /// Returns the stdin of the child, if it was piped.
+    pub fn stdin(&mut self) -> Option<&mut ChildStdin> {
+        self.child.stdin.as_mut()
+    }
+
+    /// Kills the child.
+    pub fn kill(&mut self) -> io::Result<()> {
+        self.child.kill()
+    }
+
+    /// Waits for the child to exit and for all of its output to be logged.
+    pub fn wait(mut self) -> io::Result<ExitStatus> {
+        drop(self.child.stdin.take());
+        let status = self.child.wait()?;
+        for reader in self.readers.drain(..) {
+            let _ = reader.join();
+        }
+        Ok(status)
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use lum_libs::log::LevelFilter;
+
+    use super::*;
+    use crate::{ConfigBuilder, testing};
+
+    #[test]
+    fn child_output_is_logged_line_by_line_at_the_configured_levels() {
+        let _global = testing::GLOBAL.lock();
+        let records = testing::capture(ConfigBuilder::new().root_log_level(LevelFilter::Info));
+
+        let status = ChildLogger::new("process_test::child")
+            .stdout_level(Level::Info)
+            .stderr_level(Level::Warn)
+            .run(Command::new("sh").args([
+                "-c",
+                "printf 'first\\r\\nsecond\\n'; printf 'failed\\n' >&2; exit 3",
+            ]))
+            .unwrap();
+
+        assert_eq!(status.code(), Some(3));
+        let mut records = records
+            .try_iter()
+            .map(|record| (record.target, record.level, record.message))
+            .collect::<Vec<_>>();
+        // Stdout and stderr are read concurrently, so only the order of each output is kept.
+        records.sort_by_key(|(_, level, _)| *level);
+        let target = "process_test::child".to_string();
+        assert_eq!(
+            records,
+            [
+                (target.clone(), Level::Warn, "failed".to_string()),
+                (target.clone(), Level::Info, "first".to_string()),
+                (target, Level::Info, "second".to_string()),
+            ]
+        );
+    }
+}
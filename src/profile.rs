@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+
+use lum_libs::{
+    log::LevelFilter,
+    log4rs::config::runtime::ConfigErrors,
+    parking_lot::Mutex,
+    serde::{Deserialize, Serialize},
+};
+use thiserror::Error;
+
+use crate::{ConfigBuilder, logger};
+
+static ACTIVE_PROFILE: Mutex<Option<String>> = Mutex::new(None);
+
+/// Errors that can occur when switching profiles.
+#[derive(Debug, Error)]
+pub enum ProfileError {
+    #[error("The logger has not been set up with a ConfigBuilder")]
+    NotSetUp,
+
+    #[error("No profile named {0} has been added")]
+    UnknownProfile(String),
+
+    #[error("Error while building log4rs configuration: {0}")]
+    Log4rs(#[from] ConfigErrors),
+}
+
+/// A named set of log levels layered on top of the configuration built by a [`ConfigBuilder`] while it is active,
+/// e.g. a `debugging` profile raising the levels of the crates under investigation.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde", default)]
+pub struct Profile {
+    /// The log level of the root logger, if it differs from the builder's.
+    pub root_level: Option<LevelFilter>,
+    /// Log levels for specific logger names, replacing those of the builder.
+    pub levels: HashMap<String, LevelFilter>,
+}
+
+/// Multiple named [`Profile`]s, e.g. as deserialized from a configuration file.
+/// In TOML, they look like this:
+/// ```toml
+/// active = "normal"
+///
+/// [profiles.normal]
+/// root_level = "info"
+///
+/// [profiles.debugging]
+/// root_level = "debug"
+/// levels = { "my_crate::db" = "trace" }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde", default)]
+pub struct Profiles {
+    /// The profile that is active once the configuration is applied, if any.
+    pub active: Option<String>,
+    pub profiles: HashMap<String, Profile>,
+}
+
+impl Profiles {
+    /// Parses profiles from a TOML document, see [`Profiles`] for the layout.
+    #[cfg(feature = "toml")]
+    pub fn from_toml(document: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(document)
+    }
+}
+
+/// Activates the profile with the given name, swapping the active configuration atomically.
+/// The profile must have been added by [`ConfigBuilder::profiles`] to the builder the logger was set up with.
+/// Verbosity scopes, see [`verbose_scope`](crate::verbose_scope), still apply on top of the profile.
+pub fn activate_profile(name: impl Into<String>) -> Result<(), ProfileError> {
+    let name = name.into();
+    match logger::with_builder(|builder| builder.profile(&name).is_some()) {
+        None => return Err(ProfileError::NotSetUp),
+        Some(false) => return Err(ProfileError::UnknownProfile(name)),
+        Some(true) => {}
+    }
+
+    *ACTIVE_PROFILE.lock() = Some(name);
+    logger::reconfigure()?;
+    Ok(())
+}
+
+/// Deactivates the active profile, if any, returning to the configuration built by the [`ConfigBuilder`] alone.
+pub fn deactivate_profile() -> Result<(), ProfileError> {
+    *ACTIVE_PROFILE.lock() = None;
+    match logger::reconfigure()? {
+        true => Ok(()),
+        false => Err(ProfileError::NotSetUp),
+    }
+}
+
+/// Returns the name of the active profile, if any.
+pub fn active_profile() -> Option<String> {
+    ACTIVE_PROFILE.lock().clone()
+}
+
+/// Sets the active profile without reconfiguring the logger.
+pub(crate) fn set_active(name: Option<String>) {
+    *ACTIVE_PROFILE.lock() = name;
+}
+
+/// Applies the active profile, if the given builder has it, to the given builder.
+pub(crate) fn apply_active(mut builder: ConfigBuilder) -> ConfigBuilder {
+    let active = ACTIVE_PROFILE.lock();
+    if let Some(profile) = active.as_deref().and_then(|name| builder.profile(name)) {
+        let profile = profile.clone();
+        builder.apply_profile(&profile);
+    }
+    builder
+}
+
+#[cfg(test)]
+mod tests {
+    use lum_libs::{log, serde_json};
+
+    use super::*;
+    use crate::testing;
+
+    #[test]
+    fn activating_profiles_swaps_the_levels_at_runtime() {
+        let _global = testing::GLOBAL.lock();
+        let profiles = serde_json::from_str::<Profiles>(
+            r#"{"profiles": {"debugging": {"root_level": "debug", "levels": {"profile_test::db": "trace"}}}}"#,
+        )
+        .unwrap();
+        let records = testing::capture(
+            ConfigBuilder::new()
+                .root_log_level(LevelFilter::Info)
+                .profiles(profiles),
+        );
+
+        log::debug!(target: "profile_test", "Hidden");
+        activate_profile("debugging").unwrap();
+        log::debug!(target: "profile_test", "Debugging");
+        log::trace!(target: "profile_test::db", "Query");
+        assert_eq!(active_profile().as_deref(), Some("debugging"));
+        assert!(matches!(
+            activate_profile("unknown"),
+            Err(ProfileError::UnknownProfile(_))
+        ));
+
+        deactivate_profile().unwrap();
+        log::debug!(target: "profile_test", "Hidden again");
+
+        assert_eq!(testing::messages(&records), ["Debugging", "Query"]);
+        assert_eq!(active_profile(), None);
+    }
+
+    #[test]
+    fn the_active_profile_of_the_builder_applies_on_setup() {
+        let _global = testing::GLOBAL.lock();
+        let profiles = Profiles {
+            active: Some("quiet".to_string()),
+            profiles: HashMap::from([(
+                "quiet".to_string(),
+                Profile {
+                    root_level: Some(LevelFilter::Error),
+                    levels: HashMap::new(),
+                },
+            )]),
+        };
+        let records = testing::capture(
+            ConfigBuilder::new()
+                .root_log_level(LevelFilter::Info)
+                .profiles(profiles),
+        );
+
+        log::warn!(target: "profile_test", "Hidden");
+        log::error!(target: "profile_test", "Failed");
+        deactivate_profile().unwrap();
+
+        assert_eq!(testing::messages(&records), ["Failed"]);
+    }
+}
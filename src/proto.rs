@@ -0,0 +1,206 @@
+//! The Rust types of the protobuf schema in `proto/lum_log.proto`, as generated by prost.
+
+use std::{
+    collections::HashMap,
+    time::{Duration, UNIX_EPOCH},
+};
+
+use lum_libs::log;
+
+use crate::{record::OwnedRecord, severity};
+
+/// A log record, see `proto/lum_log.proto`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct LogRecord {
+    /// The time the record was logged, as seconds since the Unix epoch.
+    #[prost(int64, tag = "1")]
+    pub timestamp_seconds: i64,
+    /// The nanoseconds part of the time the record was logged.
+    #[prost(uint32, tag = "2")]
+    pub timestamp_nanos: u32,
+    #[prost(enumeration = "Level", tag = "3")]
+    pub level: i32,
+    #[prost(string, tag = "4")]
+    pub target: String,
+    #[prost(string, tag = "5")]
+    pub message: String,
+    #[prost(string, optional, tag = "6")]
+    pub module_path: Option<String>,
+    #[prost(string, optional, tag = "7")]
+    pub file: Option<String>,
+    #[prost(uint32, optional, tag = "8")]
+    pub line: Option<u32>,
+    #[prost(string, optional, tag = "9")]
+    pub thread: Option<String>,
+    #[prost(string, optional, tag = "10")]
+    pub event_id: Option<String>,
+    #[prost(enumeration = "AuxLevel", optional, tag = "11")]
+    pub aux_level: Option<i32>,
+    #[prost(string, optional, tag = "12")]
+    pub json: Option<String>,
+    /// The display name of the level, see [`LevelNames`](crate::LevelNames).
+    #[prost(string, optional, tag = "13")]
+    pub level_name: Option<String>,
+    /// The MDC entries of the thread that logged the record.
+    #[prost(map = "string, string", tag = "14")]
+    pub mdc: HashMap<String, String>,
+}
+
+/// The response of the `LogIngestion.Stream` method, see `proto/lum_log.proto`.
+#[derive(Clone, Copy, PartialEq, Eq, prost::Message)]
+pub struct StreamResponse {
+    /// The number of records the server received on the stream.
+    #[prost(uint64, tag = "1")]
+    pub received: u64,
+}
+
+/// The level of a [`LogRecord`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+#[repr(i32)]
+pub enum Level {
+    Unspecified = 0,
+    Error = 1,
+    Warn = 2,
+    Info = 3,
+    Debug = 4,
+    Trace = 5,
+}
+
+/// The auxiliary level of a [`LogRecord`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+#[repr(i32)]
+pub enum AuxLevel {
+    Unspecified = 0,
+    Fatal = 1,
+    Notice = 2,
+    Verbose = 3,
+}
+
+impl From<&OwnedRecord> for LogRecord {
+    fn from(record: &OwnedRecord) -> Self {
+        let since_epoch = record
+            .timestamp
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        let level = match record.level {
+            log::Level::Error => Level::Error,
+            log::Level::Warn => Level::Warn,
+            log::Level::Info => Level::Info,
+            log::Level::Debug => Level::Debug,
+            log::Level::Trace => Level::Trace,
+        };
+        let aux_level = record.aux_level.map(|aux_level| match aux_level {
+            severity::AuxLevel::Fatal => AuxLevel::Fatal,
+            severity::AuxLevel::Notice => AuxLevel::Notice,
+            severity::AuxLevel::Verbose => AuxLevel::Verbose,
+        });
+
+        Self {
+            timestamp_seconds: since_epoch.as_secs() as i64,
+            timestamp_nanos: since_epoch.subsec_nanos(),
+            level: level.into(),
+            target: record.target.clone(),
+            message: record.message.clone(),
+            module_path: record.module_path.clone(),
+            file: record.file.clone(),
+            line: record.line,
+            thread: record.thread.clone(),
+            event_id: record.event_id.clone(),
+            aux_level: aux_level.map(Into::into),
+            json: record.json.clone(),
+            level_name: record.level_name.clone(),
+            mdc: record.mdc.iter().cloned().collect(),
+        }
+    }
+}
+
+impl From<LogRecord> for OwnedRecord {
+    /// Converts a decoded [`LogRecord`] into an [`OwnedRecord`].
+    /// Unknown or unspecified levels are read as [`log::Level::Info`], unknown auxiliary levels are dropped.
+    fn from(record: LogRecord) -> Self {
+        let level = match Level::try_from(record.level) {
+            Ok(Level::Error) => log::Level::Error,
+            Ok(Level::Warn) => log::Level::Warn,
+            Ok(Level::Debug) => log::Level::Debug,
+            Ok(Level::Trace) => log::Level::Trace,
+            Ok(Level::Info | Level::Unspecified) | Err(_) => log::Level::Info,
+        };
+        let aux_level =
+            record
+                .aux_level
+                .and_then(|aux_level| match AuxLevel::try_from(aux_level) {
+                    Ok(AuxLevel::Fatal) => Some(severity::AuxLevel::Fatal),
+                    Ok(AuxLevel::Notice) => Some(severity::AuxLevel::Notice),
+                    Ok(AuxLevel::Verbose) => Some(severity::AuxLevel::Verbose),
+                    Ok(AuxLevel::Unspecified) | Err(_) => None,
+                });
+        let mut mdc = record.mdc.into_iter().collect::<Vec<_>>();
+        mdc.sort();
+        let since_epoch = Duration::new(
+            record.timestamp_seconds.max(0) as u64,
+            record.timestamp_nanos.min(999_999_999),
+        );
+
+        Self {
+            timestamp: UNIX_EPOCH + since_epoch,
+            level,
+            target: record.target,
+            message: record.message,
+            module_path: record.module_path,
+            file: record.file,
+            line: record.line,
+            thread: record.thread,
+            event_id: record.event_id,
+            aux_level,
+            json: record.json,
+            level_name: record.level_name,
+            mdc,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use prost::Message;
+
+    use super::*;
+
+    #[test]
+    fn records_survive_the_conversion_to_protobuf_and_back() {
+        let record = OwnedRecord {
+            timestamp: UNIX_EPOCH + Duration::new(1_731_445_832, 123_456_789),
+            level: log::Level::Debug,
+            target: "proto_test".to_string(),
+            message: "Message".to_string(),
+            module_path: Some("proto_test::module".to_string()),
+            file: Some("src/proto_test.rs".to_string()),
+            line: Some(42),
+            thread: Some("worker".to_string()),
+            event_id: Some("01931f2a-0000-7000-8000-000000000000".to_string()),
+            aux_level: Some(severity::AuxLevel::Verbose),
+            json: Some("{\"id\":7}".to_string()),
+            level_name: Some("DBG".to_string()),
+            mdc: vec![
+                ("a".to_string(), "1".to_string()),
+                ("b".to_string(), "2".to_string()),
+            ],
+        };
+
+        let decoded = LogRecord::decode(LogRecord::from(&record).encode_to_vec().as_slice())
+            .expect("The record can be decoded");
+
+        assert_eq!(OwnedRecord::from(decoded), record);
+    }
+
+    #[test]
+    fn unknown_levels_are_read_as_info() {
+        let record = OwnedRecord::from(LogRecord {
+            level: 42,
+            aux_level: Some(42),
+            ..LogRecord::default()
+        });
+
+        assert_eq!(record.level, log::Level::Info);
+        assert_eq!(record.aux_level, None);
+    }
+}
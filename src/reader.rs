@@ -0,0 +1,158 @@
+use std::io::{self, ErrorKind, Read};
+
+#[cfg(feature = "protobuf")]
+use prost::Message;
+
+#[cfg(feature = "protobuf")]
+use crate::proto::LogRecord;
+#[cfg(any(feature = "cbor", feature = "msgpack", feature = "protobuf"))]
+use crate::record::OwnedRecord;
+
+/// An iterator over the frames of a stream written by a framing encoder, e.g. the CBOR, MessagePack, or protobuf encoder.
+/// Each frame is prefixed by its length as a little-endian `u32`.
+/// A truncated frame at the end of the stream, e.g. left over from a crash while writing, ends the iteration.
+#[derive(Debug)]
+pub struct FrameReader<R> {
+    inner: R,
+}
+
+impl<R: Read> FrameReader<R> {
+    /// Creates a new `FrameReader` reading from the given reader.
+    pub fn new(inner: R) -> Self {
+        Self { inner }
+    }
+
+    /// Returns the wrapped reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Iterator for FrameReader<R> {
+    type Item = io::Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut length = [0; 4];
+        match self.inner.read_exact(&mut length) {
+            Ok(()) => {}
+            Err(error) if error.kind() == ErrorKind::UnexpectedEof => return None,
+            Err(error) => return Some(Err(error)),
+        }
+
+        let mut frame = vec![0; u32::from_le_bytes(length) as usize];
+        match self.inner.read_exact(&mut frame) {
+            Ok(()) => Some(Ok(frame)),
+            Err(error) if error.kind() == ErrorKind::UnexpectedEof => None,
+            Err(error) => Some(Err(error)),
+        }
+    }
+}
+
+/// An iterator over the records of a stream written by a [`CborEncoder`](crate::encode::CborEncoder), e.g. a log file.
+/// Wrap files in a [`BufReader`](std::io::BufReader) for efficient reading.
+#[cfg(feature = "cbor")]
+#[derive(Debug)]
+pub struct CborReader<R> {
+    frames: FrameReader<R>,
+}
+
+#[cfg(feature = "cbor")]
+impl<R: Read> CborReader<R> {
+    /// Creates a new `CborReader` reading from the given reader.
+    pub fn new(inner: R) -> Self {
+        Self {
+            frames: FrameReader::new(inner),
+        }
+    }
+
+    /// Returns the wrapped reader.
+    pub fn into_inner(self) -> R {
+        self.frames.into_inner()
+    }
+}
+
+#[cfg(feature = "cbor")]
+impl<R: Read> Iterator for CborReader<R> {
+    type Item = io::Result<OwnedRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let frame = self.frames.next()?;
+        Some(frame.and_then(|frame| {
+            ciborium::from_reader(frame.as_slice())
+                .map_err(|error| io::Error::new(ErrorKind::InvalidData, error))
+        }))
+    }
+}
+
+/// An iterator over the records of a stream written by a [`MsgpackEncoder`](crate::encode::MsgpackEncoder), e.g. a log file.
+/// Wrap files in a [`BufReader`](std::io::BufReader) for efficient reading.
+#[cfg(feature = "msgpack")]
+#[derive(Debug)]
+pub struct MsgpackReader<R> {
+    frames: FrameReader<R>,
+}
+
+#[cfg(feature = "msgpack")]
+impl<R: Read> MsgpackReader<R> {
+    /// Creates a new `MsgpackReader` reading from the given reader.
+    pub fn new(inner: R) -> Self {
+        Self {
+            frames: FrameReader::new(inner),
+        }
+    }
+
+    /// Returns the wrapped reader.
+    pub fn into_inner(self) -> R {
+        self.frames.into_inner()
+    }
+}
+
+#[cfg(feature = "msgpack")]
+impl<R: Read> Iterator for MsgpackReader<R> {
+    type Item = io::Result<OwnedRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let frame = self.frames.next()?;
+        Some(frame.and_then(|frame| {
+            rmp_serde::from_slice(&frame)
+                .map_err(|error| io::Error::new(ErrorKind::InvalidData, error))
+        }))
+    }
+}
+
+/// An iterator over the records of a stream written by a [`ProtobufEncoder`](crate::encode::ProtobufEncoder), e.g. a log file.
+/// Wrap files in a [`BufReader`](std::io::BufReader) for efficient reading.
+#[cfg(feature = "protobuf")]
+#[derive(Debug)]
+pub struct ProtobufReader<R> {
+    frames: FrameReader<R>,
+}
+
+#[cfg(feature = "protobuf")]
+impl<R: Read> ProtobufReader<R> {
+    /// Creates a new `ProtobufReader` reading from the given reader.
+    pub fn new(inner: R) -> Self {
+        Self {
+            frames: FrameReader::new(inner),
+        }
+    }
+
+    /// Returns the wrapped reader.
+    pub fn into_inner(self) -> R {
+        self.frames.into_inner()
+    }
+}
+
+#[cfg(feature = "protobuf")]
+impl<R: Read> Iterator for ProtobufReader<R> {
+    type Item = io::Result<OwnedRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let frame = self.frames.next()?;
+        Some(frame.and_then(|frame| {
+            LogRecord::decode(frame.as_slice())
+                .map(OwnedRecord::from)
+                .map_err(|error| io::Error::new(ErrorKind::InvalidData, error))
+        }))
+    }
+}
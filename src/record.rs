@@ -0,0 +1,176 @@
+use std::{cell::RefCell, thread, time::SystemTime};
+
+use lum_libs::{
+    log::{Level, Record},
+    serde::{Deserialize, Serialize},
+};
+
+#[cfg(feature = "event-id")]
+use crate::event;
+use crate::{
+    json::{self, JSON_MDC_KEY},
+    level::{self, LevelNames},
+    severity::{AuxLevel, SEVERITY_MDC_KEY},
+};
+
+thread_local! {
+    /// The timestamp and thread of the record [`OwnedRecord::with_record`] is currently passing on on this thread, if any.
+    static REPLAYED: RefCell<Option<(SystemTime, Option<String>)>> = const { RefCell::new(None) };
+}
+
+/// An owned copy of a [`Record`], which can be sent to other threads or stored for later use.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct OwnedRecord {
+    pub timestamp: SystemTime,
+    pub level: Level,
+    pub target: String,
+    pub message: String,
+    pub module_path: Option<String>,
+    pub file: Option<String>,
+    pub line: Option<u32>,
+    pub thread: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aux_level: Option<AuxLevel>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub json: Option<String>,
+    /// The display name of the level, as rendered by the binary encoders with the configured [`LevelNames`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level_name: Option<String>,
+    /// The MDC entries of the thread that logged the record, sorted by key,
+    /// without those kept in [`event_id`](Self::event_id), [`aux_level`](Self::aux_level), and [`json`](Self::json).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mdc: Vec<(String, String)>,
+}
+
+impl From<&Record<'_>> for OwnedRecord {
+    /// Creates an `OwnedRecord` from the given [`Record`], using the current time, thread name, MDC entries, event ID, auxiliary level, and JSON payload.
+    /// While [`OwnedRecord::with_record`] passes on a record, the time and thread name of that record are used instead,
+    /// so records keep them when they are copied again, e.g. by an appender behind an [`AsyncAppender`](crate::append::AsyncAppender).
+    fn from(record: &Record<'_>) -> Self {
+        let (timestamp, thread) = REPLAYED.with_borrow(|replayed| match replayed {
+            Some((timestamp, thread)) => (*timestamp, thread.clone()),
+            None => (
+                SystemTime::now(),
+                thread::current().name().map(str::to_string),
+            ),
+        });
+
+        let mut mdc = Vec::new();
+        log_mdc::iter(|key, value| {
+            #[cfg(feature = "event-id")]
+            if key == event::EVENT_ID_MDC_KEY {
+                return;
+            }
+            if key != SEVERITY_MDC_KEY && key != JSON_MDC_KEY {
+                mdc.push((key.to_string(), value.to_string()));
+            }
+        });
+        mdc.sort();
+
+        Self {
+            timestamp,
+            level: record.level(),
+            target: record.target().to_string(),
+            message: record.args().to_string(),
+            module_path: record.module_path().map(str::to_string),
+            file: record.file().map(str::to_string),
+            line: record.line(),
+            thread,
+            #[cfg(feature = "event-id")]
+            event_id: event::current().map(|id| id.to_string()),
+            #[cfg(not(feature = "event-id"))]
+            event_id: None,
+            aux_level: AuxLevel::current(),
+            json: json::current(),
+            level_name: None,
+            mdc,
+        }
+    }
+}
+
+impl OwnedRecord {
+    /// Sets the [`level_name`](Self::level_name) of this record to its display name in the given level names,
+    /// using the auxiliary level of the record currently being logged, if any.
+    pub fn with_level_name(mut self, level_names: &LevelNames) -> Self {
+        self.level_name = Some(level_names.get_current(self.level).to_string());
+        self
+    }
+
+    /// Returns an estimate of the number of bytes this record occupies in memory.
+    pub fn estimated_size(&self) -> usize {
+        let optional = |value: &Option<String>| value.as_ref().map_or(0, String::len);
+
+        size_of::<Self>()
+            + self.target.len()
+            + self.message.len()
+            + optional(&self.module_path)
+            + optional(&self.file)
+            + optional(&self.thread)
+            + optional(&self.event_id)
+            + optional(&self.json)
+            + self
+                .mdc
+                .iter()
+                .map(|(key, value)| key.len() + value.len())
+                .sum::<usize>()
+    }
+
+    /// Returns the numeric syslog severity of this record's auxiliary level or level,
+    /// see [`AuxLevel::syslog_severity`] and [`level::syslog_severity`].
+    pub fn syslog_severity(&self) -> u8 {
+        self.aux_level.map_or_else(
+            || level::syslog_severity(self.level),
+            AuxLevel::syslog_severity,
+        )
+    }
+
+    /// Calls the given function with a [`Record`] borrowing from this `OwnedRecord`,
+    /// e.g. to pass it to an appender on another thread.
+    /// The MDC entries are restored while the function runs, and so are the event ID, auxiliary level, and JSON payload
+    /// under [`EVENT_ID_MDC_KEY`](crate::event::EVENT_ID_MDC_KEY), [`SEVERITY_MDC_KEY`], and [`JSON_MDC_KEY`].
+    /// The timestamp and thread are not part of a [`Record`], so they are restored for [`OwnedRecord::from`],
+    /// which the encoders of this crate use, e.g. [`LogfmtEncoder`](crate::encode::LogfmtEncoder).
+    /// The encoders of log4rs, e.g. `{d}` and `{T}` of a pattern, still render the time and thread they run at.
+    pub fn with_record<T>(&self, f: impl FnOnce(&Record<'_>) -> T) -> T {
+        let _mdc = self
+            .mdc
+            .iter()
+            .map(|(key, value)| log_mdc::insert_scoped(key.as_str(), value.as_str()))
+            .collect::<Vec<_>>();
+        #[cfg(feature = "event-id")]
+        let _event_id = self
+            .event_id
+            .as_ref()
+            .map(|id| log_mdc::insert_scoped(event::EVENT_ID_MDC_KEY, id.as_str()));
+        let _severity = self.aux_level.map(|aux_level| aux_level.scope());
+        let _json = self.json.as_deref().map(json::scope);
+        let _replayed = ReplayedGuard::new(self.timestamp, self.thread.clone());
+
+        f(&Record::builder()
+            .level(self.level)
+            .target(&self.target)
+            .args(format_args!("{}", self.message))
+            .module_path(self.module_path.as_deref())
+            .file(self.file.as_deref())
+            .line(self.line)
+            .build())
+    }
+}
+
+/// Restores the previously replayed timestamp and thread of this thread when dropped, see [`OwnedRecord::with_record`].
+struct ReplayedGuard(Option<(SystemTime, Option<String>)>);
+
+impl ReplayedGuard {
+    fn new(timestamp: SystemTime, thread: Option<String>) -> Self {
+        Self(REPLAYED.replace(Some((timestamp, thread))))
+    }
+}
+
+impl Drop for ReplayedGuard {
+    fn drop(&mut self) {
+        REPLAYED.set(self.0.take());
+    }
+}
@@ -0,0 +1,336 @@
+use std::{
+    borrow::Cow,
+    fmt::{self, Display, Formatter},
+    sync::Arc,
+};
+
+use lum_libs::{
+    parking_lot::{RwLock, const_rwlock},
+    serde::{Deserialize, Serialize},
+};
+use regex_lite::Regex;
+use thiserror::Error;
+
+use crate::default;
+
+static REDACTION_RULES: RwLock<Option<Arc<RedactionRules>>> = const_rwlock(None);
+
+/// Errors that can occur when compiling [`RedactionRule`]s.
+#[derive(Debug, Error)]
+pub enum RedactionError {
+    #[error("Invalid pattern of redaction rule {name}: {source}")]
+    InvalidPattern {
+        name: String,
+        source: regex_lite::Error,
+    },
+}
+
+/// A named rule replacing all matches of a regular expression in log messages, e.g. to remove PII.
+/// The replacement may refer to capture groups, e.g. `$1`.
+/// In TOML, a rule looks like this:
+/// ```toml
+/// name = "email"
+/// pattern = "[\\w.+-]+@[\\w-]+\\.[\\w.]+"
+/// replacement = "[EMAIL]"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct RedactionRule {
+    pub name: String,
+    pub pattern: String,
+    /// The replacement of the matches, [`default::redaction_replacement`] if omitted.
+    #[serde(default = "default_replacement")]
+    pub replacement: String,
+}
+
+fn default_replacement() -> String {
+    default::redaction_replacement().to_string()
+}
+
+impl RedactionRule {
+    /// Creates a new `RedactionRule` replacing the matches of the given pattern with [`default::redaction_replacement`].
+    pub fn new(name: impl Into<String>, pattern: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            pattern: pattern.into(),
+            replacement: default_replacement(),
+        }
+    }
+
+    /// Sets the replacement of the matches.
+    pub fn replacement(mut self, replacement: impl Into<String>) -> Self {
+        self.replacement = replacement.into();
+        self
+    }
+}
+
+/// Compiled [`RedactionRule`]s, applied one after another in the order they were given.
+#[derive(Debug, Clone)]
+pub struct RedactionRules {
+    rules: Vec<(RedactionRule, Regex)>,
+}
+
+impl RedactionRules {
+    /// Compiles the given rules, failing on the first rule with an invalid pattern.
+    pub fn new(rules: impl IntoIterator<Item = RedactionRule>) -> Result<Self, RedactionError> {
+        let rules = rules
+            .into_iter()
+            .map(|rule| match Regex::new(&rule.pattern) {
+                Ok(regex) => Ok((rule, regex)),
+                Err(source) => Err(RedactionError::InvalidPattern {
+                    name: rule.name,
+                    source,
+                }),
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { rules })
+    }
+
+    /// Returns the rules in the order they are applied.
+    pub fn rules(&self) -> impl Iterator<Item = &RedactionRule> {
+        self.rules.iter().map(|(rule, _)| rule)
+    }
+
+    /// Applies all rules to the given text.
+    pub fn redact<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        self.redact_matching(text).0
+    }
+
+    /// Applies all rules to the given text, also returning the names of the rules that matched.
+    fn redact_matching<'a>(&self, text: &'a str) -> (Cow<'a, str>, Vec<&str>) {
+        let mut text = Cow::Borrowed(text);
+        let mut matched = Vec::new();
+        for (rule, regex) in &self.rules {
+            if let Cow::Owned(redacted) = regex.replace_all(&text, rule.replacement.as_str()) {
+                text = Cow::Owned(redacted);
+                matched.push(rule.name.as_str());
+            }
+        }
+        (text, matched)
+    }
+}
+
+/// Sets the rules applied to the message of every record before it reaches the appenders, or `None` to disable redaction.
+/// Only the message is redacted, not the target, MDC values, or JSON payloads. No rules are set by default.
+/// See also [`ConfigBuilder::redaction_rules`](crate::ConfigBuilder::redaction_rules).
+/// This only has an effect if the logger has been set up by this crate.
+pub fn set_redaction_rules(rules: Option<RedactionRules>) {
+    *REDACTION_RULES.write() = rules.map(Arc::new);
+}
+
+/// Returns the rules set by [`set_redaction_rules`], if any, e.g. to [`check`] them.
+pub fn redaction_rules() -> Option<Arc<RedactionRules>> {
+    REDACTION_RULES.read().clone()
+}
+
+/// Applies the rules set by [`set_redaction_rules`], if any, to the given message.
+pub(crate) fn redact(message: Cow<'_, str>) -> Cow<'_, str> {
+    let rules = REDACTION_RULES.read();
+    let Some(rules) = rules.as_deref() else {
+        return message;
+    };
+
+    match rules.redact(&message) {
+        Cow::Borrowed(_) => message,
+        Cow::Owned(redacted) => Cow::Owned(redacted),
+    }
+}
+
+/// The outcome of applying [`RedactionRules`] to a single sample line, see [`check`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineReport {
+    pub input: String,
+    pub output: String,
+    /// The names of the rules that matched, in the order they were applied.
+    pub matched_rules: Vec<String>,
+}
+
+/// The outcome of applying [`RedactionRules`] to sample lines, see [`check`].
+/// Its [`Display`] output lists every line before and after redaction with the matching rules,
+/// followed by the rules that matched no line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub lines: Vec<LineReport>,
+    /// The names of the rules that matched no line, which may indicate a broken pattern.
+    pub unmatched_rules: Vec<String>,
+}
+
+impl Report {
+    /// Returns the number of lines the rule with the given name matched.
+    pub fn match_count(&self, rule: &str) -> usize {
+        self.lines
+            .iter()
+            .filter(|line| line.matched_rules.iter().any(|matched| matched == rule))
+            .count()
+    }
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for line in &self.lines {
+            writeln!(f, "{}", line.input)?;
+            match line.matched_rules.is_empty() {
+                true => writeln!(f, "  unchanged, no rule matched")?,
+                false => writeln!(
+                    f,
+                    "  -> {} ({})",
+                    line.output,
+                    line.matched_rules.join(", ")
+                )?,
+            }
+        }
+
+        match self.unmatched_rules.is_empty() {
+            true => write!(f, "Every rule matched at least one line"),
+            false => write!(
+                f,
+                "Rules matching no line: {}",
+                self.unmatched_rules.join(", ")
+            ),
+        }
+    }
+}
+
+/// Applies the given rules to the given sample lines and reports which rules matched which lines,
+/// so PII rules can be validated before deploying them, e.g. in a test or a CLI subcommand.
+/// The configured rules are available from [`redaction_rules`].
+///
+/// ```text
+/// let rules = RedactionRules::new([RedactionRule::new("email", r"[\w.+-]+@[\w-]+\.[\w.]+")])?;
+/// let report = redaction::check(&rules, ["login of jane@example.com", "login of 42"]);
+/// assert_eq!(report.match_count("email"), 1);
+/// println!("{report}");
+/// ```
+pub fn check<I, S>(rules: &RedactionRules, sample_lines: I) -> Report
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let lines = sample_lines
+        .into_iter()
+        .map(|line| {
+            let input = line.as_ref();
+            let (output, matched_rules) = rules.redact_matching(input);
+            LineReport {
+                input: input.to_string(),
+                output: output.into_owned(),
+                matched_rules: matched_rules.into_iter().map(str::to_string).collect(),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let unmatched_rules = rules
+        .rules()
+        .filter(|rule| {
+            !lines
+                .iter()
+                .any(|line| line.matched_rules.contains(&rule.name))
+        })
+        .map(|rule| rule.name.clone())
+        .collect();
+
+    Report {
+        lines,
+        unmatched_rules,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::atomic::{AtomicBool, Ordering},
+        thread,
+    };
+
+    use lum_libs::log::{self, LevelFilter};
+
+    use super::*;
+    use crate::{ConfigBuilder, testing};
+
+    fn rules() -> RedactionRules {
+        RedactionRules::new([
+            RedactionRule::new("email", r"[\w.+-]+@[\w-]+\.[\w.]+"),
+            RedactionRule::new("token", r"token=(\w{2})\w*").replacement("token=$1…"),
+            RedactionRule::new("iban", r"[A-Z]{2}\d{20}"),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn messages_are_redacted_before_they_reach_the_appenders() {
+        let _global = testing::GLOBAL.lock();
+        let records = testing::capture(
+            ConfigBuilder::new()
+                .root_log_level(LevelFilter::Info)
+                .redaction_rules(rules()),
+        );
+
+        log::info!(target: "redaction_test", "Login of {} with token=abcdef", "jane@example.com");
+        log::info!(target: "redaction_test", "Nothing to hide");
+
+        assert_eq!(
+            testing::messages(&records),
+            [
+                format!(
+                    "Login of {} with token=ab…",
+                    default::redaction_replacement()
+                ),
+                "Nothing to hide".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn records_logged_while_applying_reach_the_new_appenders_redacted() {
+        let _global = testing::GLOBAL.lock();
+        let _records = testing::capture(ConfigBuilder::new().root_log_level(LevelFilter::Info));
+        let stop = AtomicBool::new(false);
+
+        let records = thread::scope(|scope| {
+            scope.spawn(|| {
+                while !stop.load(Ordering::Relaxed) {
+                    log::info!(target: "redaction_test", "Login of jane@example.com");
+                }
+            });
+            let records = testing::capture(
+                ConfigBuilder::new()
+                    .root_log_level(LevelFilter::Info)
+                    .redaction_rules(rules()),
+            );
+            stop.store(true, Ordering::Relaxed);
+            records
+        });
+
+        let messages = testing::messages(&records);
+        assert!(
+            messages
+                .iter()
+                .all(|message| !message.contains("jane@example.com")),
+            "{messages:?}"
+        );
+    }
+
+    #[test]
+    fn check_reports_the_rules_matching_each_line() {
+        let report = check(
+            &rules(),
+            [
+                "mail jane@example.com token=secret",
+                "mail john@example.com",
+                "no match",
+            ],
+        );
+
+        assert_eq!(report.match_count("email"), 2);
+        assert_eq!(report.match_count("token"), 1);
+        assert_eq!(report.unmatched_rules, ["iban"]);
+        assert_eq!(report.lines[0].matched_rules, ["email", "token"]);
+        assert_eq!(report.lines[2].output, "no match");
+        assert!(report.to_string().ends_with("Rules matching no line: iban"));
+        assert!(matches!(
+            RedactionRules::new([RedactionRule::new("broken", "(")]),
+            Err(RedactionError::InvalidPattern { name, .. }) if name == "broken"
+        ));
+    }
+}
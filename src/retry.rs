@@ -0,0 +1,111 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    time::Duration,
+};
+
+use lum_libs::serde::{Deserialize, Serialize};
+
+use crate::default;
+
+/// A retry policy with exponential backoff and jitter, shared by all network appenders.
+/// The backoff before retry `n` (starting at 1) is `initial_backoff * multiplier^(n - 1)`,
+/// capped at `max_backoff`, and randomly reduced by up to `jitter` (a fraction between 0 and 1).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde", default)]
+pub struct RetryPolicy {
+    /// The maximum number of attempts per record, including the first one.
+    pub max_attempts: u32,
+    /// The backoff before the first retry.
+    #[serde(with = "crate::duration")]
+    pub initial_backoff: Duration,
+    /// The maximum backoff between two attempts.
+    #[serde(with = "crate::duration")]
+    pub max_backoff: Duration,
+    /// The factor by which the backoff grows with each retry.
+    pub multiplier: f64,
+    /// The maximum fraction by which the backoff is randomly reduced.
+    pub jitter: f64,
+}
+
+impl Default for RetryPolicy {
+    /// Creates a `RetryPolicy` with 5 attempts, an initial backoff of 100 milliseconds doubling up to
+    /// [`default::max_backoff`], and a jitter of 0.2.
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: default::max_backoff(),
+            multiplier: 2.0,
+            jitter: 0.2,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Same as [`RetryPolicy::default`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a `RetryPolicy` that never retries.
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Returns whether another attempt should be made after the given number of failed attempts.
+    pub fn should_retry(&self, failed_attempts: u32) -> bool {
+        failed_attempts < self.max_attempts
+    }
+
+    /// Returns the backoff to wait before the given retry, starting at 1.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let exponent = retry.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let backoff = self.initial_backoff.as_secs_f64() * self.multiplier.max(1.0).powi(exponent);
+        let backoff = backoff.min(self.max_backoff.as_secs_f64());
+
+        let jitter = self.jitter.clamp(0.0, 1.0) * random_fraction();
+        Duration::from_secs_f64(backoff * (1.0 - jitter))
+    }
+}
+
+/// Returns a random number between 0 and 1. This is not cryptographically secure.
+fn random_fraction() -> f64 {
+    let random = RandomState::new().build_hasher().finish();
+    (random >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_grows_exponentially_up_to_the_maximum_reduced_by_the_jitter() {
+        let policy = RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(350),
+            multiplier: 2.0,
+            jitter: 0.0,
+        };
+        let backoffs = (1..=4)
+            .map(|retry| policy.backoff(retry))
+            .collect::<Vec<_>>();
+        assert_eq!(backoffs, [100, 200, 350, 350].map(Duration::from_millis));
+        assert!(policy.should_retry(2));
+        assert!(!policy.should_retry(3));
+        assert!(!RetryPolicy::none().should_retry(1));
+
+        let jittered = RetryPolicy {
+            jitter: 0.5,
+            ..policy
+        };
+        for _ in 0..100 {
+            let backoff = jittered.backoff(2);
+            assert!(backoff >= Duration::from_millis(100) && backoff <= Duration::from_millis(200));
+        }
+    }
+}
@@ -0,0 +1,458 @@
+use std::{
+    cell::Cell,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{
+        Arc, Weak,
+        atomic::{AtomicU64, Ordering},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use lum_libs::{
+    log::{self, Level, Record},
+    log4rs::{
+        append::{
+            Append,
+            rolling_file::{
+                LogFile, RollingFileAppender,
+                policy::{
+                    Policy,
+                    compound::{roll::Roll, trigger::Trigger},
+                },
+            },
+        },
+        encode::{Encode, Write},
+    },
+    parking_lot::Mutex,
+};
+use thiserror::Error;
+
+use crate::{default, internal, logger};
+
+/// The target of the record logged by [`rotate_now`].
+pub const ROTATE_TARGET: &str = "lum_log::rotate";
+
+static ROTATION_GENERATION: AtomicU64 = AtomicU64::new(0);
+static ROTATIONS: AtomicU64 = AtomicU64::new(0);
+static ROTATION_CALLBACKS: Mutex<Vec<Arc<RotationCallback>>> = Mutex::new(Vec::new());
+/// The appenders of all live [`ManualRollingFileAppender`]s, rolled by [`rotate_now`].
+static ROLLING_APPENDERS: Mutex<Vec<Weak<RollingAppender>>> = Mutex::new(Vec::new());
+
+thread_local! {
+    /// Whether [`rotate_now`] is currently rolling an appender on this thread, so the record it rolls with is not written.
+    static ROLLING: Cell<bool> = const { Cell::new(false) };
+    /// Whether a [`ManualTrigger`] triggered for the record [`rotate_now`] rolls with on this thread.
+    static TRIGGERED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// The error returned by [`rotate_now`] when a log file could not be rolled.
+#[derive(Debug, Error)]
+#[error("Failed to roll {}: {source:#}", path.display())]
+pub struct RotateError {
+    /// The path of the log file that could not be rolled.
+    pub path: PathBuf,
+    /// The error returned by the appender while rolling.
+    pub source: anyhow::Error,
+}
+
+/// Information about a finished rotation, passed to the callbacks registered by [`on_rotation`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rotation {
+    /// The path of the active log file, which has been rotated.
+    pub file: PathBuf,
+    /// The path the closed log file has been rotated to.
+    pub rotated: PathBuf,
+}
+
+/// A callback invoked after a rotation.
+pub type RotationCallback = dyn Fn(&Rotation) + Send + Sync;
+
+/// Registers a callback invoked after each rotation of a rolling file appender using a [`NotifyingRoller`],
+/// e.g. to archive, checksum, or clean up the rotated file.
+/// Callbacks are invoked on a background thread, so they may log and take their time.
+/// All rolling file appenders created by [`crate::default`] use a [`NotifyingRoller`].
+/// [`PartitionedFileAppender`](crate::append::PartitionedFileAppender)s report each file they close as rotated to itself.
+pub fn on_rotation(callback: impl Fn(&Rotation) + Send + Sync + 'static) {
+    ROTATION_CALLBACKS.lock().push(Arc::new(callback));
+}
+
+/// Returns the number of rotations performed by rolling file appenders using a [`NotifyingRoller`] and [`PartitionedFileAppender`](crate::append::PartitionedFileAppender)s
+/// since the start of the process.
+/// Encoders writing file headers, e.g. the [`W3cEncoder`](crate::encode::W3cEncoder), compare it to detect new files.
+pub fn rotations() -> u64 {
+    ROTATIONS.load(Ordering::SeqCst)
+}
+
+/// Rolls all [`ManualRollingFileAppender`]s immediately, e.g. to ship the closed file right after this returns,
+/// and logs an info record with the target [`ROTATE_TARGET`] afterwards.
+/// The logger is flushed first, so records buffered by [`AsyncAppender`](crate::append::AsyncAppender)s end up in the closed files.
+/// Appenders are rolled on the calling thread regardless of levels and filters, and files are rolled even if nothing was written to them.
+/// All rolling file appenders created by [`crate::default`] are [`ManualRollingFileAppender`]s.
+///
+/// Returns the number of appenders rolled, not counting those whose policy does not use a [`ManualTrigger`].
+/// If an appender fails to roll, the remaining ones are still rolled, and the error of the first failure is returned.
+/// Other rolling file appenders using a [`ManualTrigger`] roll when they process their next record.
+pub fn rotate_now() -> Result<usize, RotateError> {
+    ROTATION_GENERATION.fetch_add(1, Ordering::SeqCst);
+    logger::flush();
+
+    let appenders = {
+        let mut appenders = ROLLING_APPENDERS.lock();
+        appenders.retain(|appender| appender.strong_count() > 0);
+        appenders
+            .iter()
+            .filter_map(Weak::upgrade)
+            .collect::<Vec<_>>()
+    };
+
+    let mut rolled = 0;
+    let mut first_error = None;
+    for appender in appenders {
+        let result = roll(&appender);
+        match result {
+            Ok(true) => rolled += 1,
+            Ok(false) => {}
+            Err(source) => {
+                first_error.get_or_insert(RotateError {
+                    path: appender.path.clone(),
+                    source,
+                });
+            }
+        }
+    }
+
+    log::info!(target: ROTATE_TARGET, "Rotated {rolled} log file(s) on request");
+    match first_error {
+        Some(error) => Err(error),
+        None => Ok(rolled),
+    }
+}
+
+/// Makes the given appender process a record that is not written, so its [`ManualTrigger`] rolls the file.
+/// Returns whether a [`ManualTrigger`] triggered, i.e. whether the file was rolled.
+fn roll(appender: &RollingAppender) -> anyhow::Result<bool> {
+    let outer = ROLLING.with(|rolling| rolling.replace(true));
+    TRIGGERED.with(|triggered| triggered.set(false));
+    let result = appender.appender.append(
+        &Record::builder()
+            .level(Level::Info)
+            .target(ROTATE_TARGET)
+            .args(format_args!("Rolling on request"))
+            .build(),
+    );
+    ROLLING.with(|rolling| rolling.set(outer));
+    result.map(|()| TRIGGERED.with(|triggered| triggered.replace(false)))
+}
+
+/// A [`RollingFileAppender`] rolled by [`rotate_now`] before it returns.
+/// Its policy should use a [`ManualTrigger`], otherwise [`rotate_now`] only writes nothing to it.
+#[derive(Debug)]
+pub struct ManualRollingFileAppender {
+    inner: Arc<RollingAppender>,
+}
+
+#[derive(Debug)]
+struct RollingAppender {
+    appender: RollingFileAppender,
+    path: PathBuf,
+}
+
+impl ManualRollingFileAppender {
+    /// Creates a new `ManualRollingFileAppender` writing to the given path with the given encoder and policy.
+    pub fn new(
+        path: impl AsRef<Path>,
+        encoder: Box<dyn Encode>,
+        policy: Box<dyn Policy>,
+    ) -> io::Result<Self> {
+        let path = path.as_ref();
+        let appender = RollingFileAppender::builder()
+            .encoder(Box::new(SkipWhileRollingEncoder(encoder)))
+            .build(path, policy)?;
+        let inner = Arc::new(RollingAppender {
+            appender,
+            path: path.to_path_buf(),
+        });
+        ROLLING_APPENDERS.lock().push(Arc::downgrade(&inner));
+
+        Ok(Self { inner })
+    }
+
+    /// Returns the path of the active log file.
+    pub fn path(&self) -> &Path {
+        &self.inner.path
+    }
+}
+
+impl Append for ManualRollingFileAppender {
+    fn append(&self, record: &Record) -> anyhow::Result<()> {
+        self.inner.appender.append(record)
+    }
+
+    fn flush(&self) {
+        self.inner.appender.flush();
+    }
+}
+
+/// An [`Encode`] wrapping another encoder, which writes nothing for the record [`rotate_now`] rolls with.
+#[derive(Debug)]
+struct SkipWhileRollingEncoder(Box<dyn Encode>);
+
+impl Encode for SkipWhileRollingEncoder {
+    fn encode(&self, w: &mut dyn Write, record: &Record) -> anyhow::Result<()> {
+        if ROLLING.with(Cell::get) {
+            return Ok(());
+        }
+
+        self.0.encode(w, record)
+    }
+}
+
+/// A [`Trigger`] wrapping another trigger, which additionally triggers after [`rotate_now`] has been called.
+#[derive(Debug)]
+pub struct ManualTrigger {
+    inner: Box<dyn Trigger>,
+    generation: AtomicU64,
+}
+
+impl ManualTrigger {
+    /// Creates a new `ManualTrigger` wrapping the given trigger.
+    pub fn new(inner: Box<dyn Trigger>) -> Self {
+        Self {
+            inner,
+            generation: AtomicU64::new(ROTATION_GENERATION.load(Ordering::SeqCst)),
+        }
+    }
+}
+
+impl Trigger for ManualTrigger {
+    fn trigger(&self, file: &LogFile) -> anyhow::Result<bool> {
+        let generation = ROTATION_GENERATION.load(Ordering::SeqCst);
+        let triggered = self.generation.swap(generation, Ordering::SeqCst) != generation
+            || self.inner.trigger(file)?;
+        if triggered && ROLLING.with(Cell::get) {
+            TRIGGERED.with(|triggered| triggered.set(true));
+        }
+
+        Ok(triggered)
+    }
+
+    fn is_pre_process(&self) -> bool {
+        self.inner.is_pre_process()
+    }
+}
+
+/// A [`Roll`] wrapping another roller, which invokes the callbacks registered by [`on_rotation`] after each roll.
+#[derive(Debug)]
+pub struct NotifyingRoller {
+    inner: Box<dyn Roll>,
+    rotated: PathBuf,
+}
+
+impl NotifyingRoller {
+    /// Creates a new `NotifyingRoller` wrapping the given roller,
+    /// which moves the closed log file to the given path, e.g. the base index of a fixed window roller.
+    pub fn new(inner: Box<dyn Roll>, rotated: impl Into<PathBuf>) -> Self {
+        Self {
+            inner,
+            rotated: rotated.into(),
+        }
+    }
+}
+
+impl Roll for NotifyingRoller {
+    fn roll(&self, file: &Path) -> anyhow::Result<()> {
+        self.inner.roll(file)?;
+        wait_for_background_rotation(file);
+        notify(Rotation {
+            file: file.to_path_buf(),
+            rotated: self.rotated.clone(),
+        })?;
+        Ok(())
+    }
+}
+
+/// Waits until the closed log file has been moved to its rotated path, for at most [`default::flush_timeout`].
+/// The [`FixedWindowRoller`](lum_libs::log4rs::append::rolling_file::policy::compound::roll::fixed_window::FixedWindowRoller)
+/// moves the closed file to a temporary file named after the active one, with a timestamp as its extension,
+/// and rotates and compresses it on a background thread, so the rotation has finished once no such file is left.
+fn wait_for_background_rotation(file: &Path) {
+    let deadline = Instant::now() + default::flush_timeout();
+    while is_rotating(file) && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(1));
+    }
+}
+
+fn is_rotating(file: &Path) -> bool {
+    let directory = match file.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let Ok(entries) = fs::read_dir(directory) else {
+        return false;
+    };
+
+    // The timestamp in seconds has at least 10 digits, unlike the indexes of rotated files named like `app.0`.
+    entries.flatten().any(|entry| {
+        let path = entry.path();
+        path.file_stem() == file.file_stem()
+            && path
+                .extension()
+                .and_then(|extension| extension.to_str())
+                .is_some_and(|extension| {
+                    extension.len() >= 10 && extension.bytes().all(|byte| byte.is_ascii_digit())
+                })
+    })
+}
+
+/// Counts the given rotation and invokes the callbacks registered by [`on_rotation`] for it on a background thread.
+pub(crate) fn notify(rotation: Rotation) -> io::Result<()> {
+    ROTATIONS.fetch_add(1, Ordering::SeqCst);
+
+    let callbacks = ROTATION_CALLBACKS.lock().clone();
+    if callbacks.is_empty() {
+        return Ok(());
+    }
+
+    // The appender is locked while rolling, so callbacks logging to it would deadlock if invoked here.
+    thread::Builder::new()
+        .name("lum_log-rotation".to_string())
+        .spawn(move || {
+            for callback in callbacks {
+                internal::catch("Rotation callback", || callback(&rotation));
+            }
+        })?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use lum_libs::log4rs::{
+        append::rolling_file::policy::compound::{
+            CompoundPolicy, roll::delete::DeleteRoller, trigger::size::SizeTrigger,
+        },
+        encode::pattern::PatternEncoder,
+    };
+
+    use super::*;
+    use crate::{default, testing};
+
+    #[test]
+    fn rotate_now_rolls_before_returning() {
+        let _global = testing::GLOBAL.lock();
+        let dir = testing::temp_dir("rotate_now");
+        let path = dir.join("app.log");
+        let appender = default::rolling_file_appender_with_policy(
+            &path,
+            Box::new(PatternEncoder::new("{m}{n}")),
+            default::time_trigger_config(),
+            &dir.join("app.{}.log").to_string_lossy(),
+            3,
+        )
+        .unwrap();
+
+        appender
+            .append(
+                &Record::builder()
+                    .level(Level::Info)
+                    .args(format_args!("Before rotation"))
+                    .build(),
+            )
+            .unwrap();
+        let before = rotations();
+        assert!(rotate_now().unwrap() >= 1);
+
+        assert_eq!(
+            fs::read_to_string(dir.join("app.0.log")).unwrap(),
+            "Before rotation\n"
+        );
+        assert_eq!(fs::read_to_string(&path).unwrap(), "");
+        assert!(rotations() > before);
+
+        drop(appender);
+        assert!(ROLLING_APPENDERS.lock().iter().all(|appender| {
+            appender
+                .upgrade()
+                .is_none_or(|appender| appender.path != path)
+        }));
+    }
+
+    #[test]
+    fn rotation_callbacks_receive_the_rotated_file() {
+        let _global = testing::GLOBAL.lock();
+        let dir = testing::temp_dir("rotation_callback");
+        let path = dir.join("app.log");
+        let rotated = dir.join("app.0.log");
+        let appender = default::rolling_file_appender_with_policy(
+            &path,
+            Box::new(PatternEncoder::new("{m}{n}")),
+            default::time_trigger_config(),
+            &dir.join("app.{}.log").to_string_lossy(),
+            3,
+        )
+        .unwrap();
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let sender = Mutex::new(sender);
+        let callback_path = path.clone();
+        on_rotation(move |rotation| {
+            if rotation.file == callback_path {
+                let content = fs::read_to_string(&rotation.rotated).unwrap_or_default();
+                let _ = sender.lock().send((rotation.clone(), content));
+            }
+        });
+
+        appender
+            .append(
+                &Record::builder()
+                    .level(Level::Info)
+                    .args(format_args!("Before rotation"))
+                    .build(),
+            )
+            .unwrap();
+        rotate_now().unwrap();
+
+        let (rotation, content) = receiver
+            .recv_timeout(Duration::from_secs(5))
+            .expect("The callback is invoked");
+        assert_eq!(
+            rotation,
+            Rotation {
+                file: path,
+                rotated
+            }
+        );
+        assert_eq!(content, "Before rotation\n");
+    }
+
+    #[test]
+    fn appenders_without_a_manual_trigger_are_not_counted_as_rolled() {
+        let _global = testing::GLOBAL.lock();
+        let dir = testing::temp_dir("rotate_without_manual_trigger");
+        let manual = default::rolling_file_appender_with_policy(
+            dir.join("manual.log"),
+            Box::new(PatternEncoder::new("{m}{n}")),
+            default::time_trigger_config(),
+            &dir.join("manual.{}.log").to_string_lossy(),
+            3,
+        )
+        .unwrap();
+        let sized = ManualRollingFileAppender::new(
+            dir.join("sized.log"),
+            Box::new(PatternEncoder::new("{m}{n}")),
+            Box::new(CompoundPolicy::new(
+                Box::new(SizeTrigger::new(u64::MAX)),
+                Box::new(DeleteRoller::new()),
+            )),
+        )
+        .unwrap();
+
+        ROTATION_GENERATION.fetch_add(1, Ordering::SeqCst);
+        assert!(roll(&manual.inner).unwrap());
+        assert!(!roll(&sized.inner).unwrap());
+        assert!(!roll(&manual.inner).unwrap());
+    }
+}
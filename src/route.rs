@@ -0,0 +1,150 @@
+use lum_libs::{
+    log::Record,
+    log4rs::filter::{Filter, Response},
+    serde::{Deserialize, Serialize},
+};
+
+/// A rule matching the target of a record.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde", rename_all = "snake_case")]
+pub enum RouteRule {
+    /// Matches targets that are equal to the given string.
+    Equals(String),
+    /// Matches targets that start with the given string.
+    StartsWith(String),
+    /// Matches targets that contain the given string.
+    Contains(String),
+}
+
+impl RouteRule {
+    /// Returns whether the given target matches this rule.
+    pub fn matches(&self, target: &str) -> bool {
+        match self {
+            RouteRule::Equals(value) => target == value,
+            RouteRule::StartsWith(value) => target.starts_with(value.as_str()),
+            RouteRule::Contains(value) => target.contains(value.as_str()),
+        }
+    }
+}
+
+/// Routes records whose target matches a [`RouteRule`] to an appender.
+/// An appender with routes only receives records matching at least one of them.
+/// If a route is exclusive, matching records are not written to appenders without a matching route.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct Route {
+    pub rule: RouteRule,
+    pub appender: String,
+    #[serde(default)]
+    pub exclusive: bool,
+}
+
+impl Route {
+    /// Creates a new non-exclusive `Route`.
+    pub fn new(rule: RouteRule, appender: impl Into<String>) -> Self {
+        Self {
+            rule,
+            appender: appender.into(),
+            exclusive: false,
+        }
+    }
+
+    /// Creates a new exclusive `Route`.
+    pub fn exclusive(rule: RouteRule, appender: impl Into<String>) -> Self {
+        Self {
+            exclusive: true,
+            ..Self::new(rule, appender)
+        }
+    }
+}
+
+/// A filter implementing [`Route`]s for a single appender.
+/// Records are rejected if they match none of the included rules (if any),
+/// or if they match one of the excluded rules without matching an included one.
+/// Otherwise, the filter is neutral so that subsequent filters still apply.
+#[derive(Debug, Clone, Default)]
+pub struct RouteFilter {
+    include: Vec<RouteRule>,
+    exclude: Vec<RouteRule>,
+}
+
+impl RouteFilter {
+    /// Creates a new `RouteFilter` from the rules routed to the appender
+    /// and the exclusive rules routed to other appenders.
+    pub fn new(include: Vec<RouteRule>, exclude: Vec<RouteRule>) -> Self {
+        Self { include, exclude }
+    }
+
+    /// Creates the `RouteFilter` of the appender with the given name from all routes of a configuration,
+    /// or `None` if no route affects the appender.
+    pub(crate) fn for_appender(routes: &[Route], appender: &str) -> Option<Self> {
+        let include = routes
+            .iter()
+            .filter(|route| route.appender == appender)
+            .map(|route| route.rule.clone())
+            .collect::<Vec<_>>();
+        let exclude = routes
+            .iter()
+            .filter(|route| route.exclusive && route.appender != appender)
+            .map(|route| route.rule.clone())
+            .collect::<Vec<_>>();
+
+        if include.is_empty() && exclude.is_empty() {
+            return None;
+        }
+
+        Some(Self::new(include, exclude))
+    }
+}
+
+impl Filter for RouteFilter {
+    fn filter(&self, record: &Record) -> Response {
+        let target = record.target();
+
+        if self.include.iter().any(|rule| rule.matches(target)) {
+            return Response::Neutral;
+        }
+
+        if !self.include.is_empty() || self.exclude.iter().any(|rule| rule.matches(target)) {
+            return Response::Reject;
+        }
+
+        Response::Neutral
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lum_libs::log::{self, LevelFilter};
+
+    use super::*;
+    use crate::{ConfigBuilder, testing};
+
+    #[test]
+    fn exclusive_routes_keep_matching_records_away_from_other_appenders() {
+        let _global = testing::GLOBAL.lock();
+        let (audit, audit_records) = testing::channel();
+        let (errors, error_records) = testing::channel();
+        let records = testing::capture(
+            ConfigBuilder::new()
+                .root_log_level(LevelFilter::Info)
+                .appender("audit", audit)
+                .appender("errors", errors)
+                .routes([
+                    Route::exclusive(
+                        RouteRule::StartsWith("route_test::audit".to_string()),
+                        "audit",
+                    ),
+                    Route::new(RouteRule::Contains("payment".to_string()), "errors"),
+                ]),
+        );
+
+        log::info!(target: "route_test::audit::login", "Login");
+        log::info!(target: "route_test::payment", "Charged");
+        log::info!(target: "route_test", "Started");
+
+        assert_eq!(testing::messages(&audit_records), ["Login"]);
+        assert_eq!(testing::messages(&error_records), ["Charged"]);
+        assert_eq!(testing::messages(&records), ["Charged", "Started"]);
+    }
+}
@@ -0,0 +1,135 @@
+use std::fmt::{self, Display, Formatter};
+
+use log_mdc::InsertGuard;
+use lum_libs::{
+    log::Level,
+    serde::{Deserialize, Serialize},
+};
+
+/// The MDC key under which the auxiliary level of the record currently being logged is exposed,
+/// e.g. `notice`. Use it in patterns as `{X(lum_log.severity)}`.
+pub const SEVERITY_MDC_KEY: &str = "lum_log.severity";
+
+/// An auxiliary severity layered on top of one of the five [`Level`]s.
+/// Records logged at an auxiliary level are logged at its [`AuxLevel::level`],
+/// with the auxiliary level attached under [`SEVERITY_MDC_KEY`].
+/// Use the [`fatal!`](crate::fatal), [`notice!`](crate::notice), and [`verbose!`](crate::verbose) macros to log at them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde", rename_all = "snake_case")]
+pub enum AuxLevel {
+    /// An unrecoverable error, logged at [`Level::Error`].
+    Fatal,
+    /// A normal but significant event, logged at [`Level::Info`].
+    Notice,
+    /// Detailed information, logged at [`Level::Debug`].
+    Verbose,
+}
+
+impl AuxLevel {
+    /// Returns all auxiliary levels.
+    pub fn iter() -> impl Iterator<Item = AuxLevel> {
+        [AuxLevel::Fatal, AuxLevel::Notice, AuxLevel::Verbose].into_iter()
+    }
+
+    /// Returns the [`Level`] records at this auxiliary level are logged at.
+    pub fn level(self) -> Level {
+        match self {
+            AuxLevel::Fatal => Level::Error,
+            AuxLevel::Notice => Level::Info,
+            AuxLevel::Verbose => Level::Debug,
+        }
+    }
+
+    /// Returns the upper-case name of this auxiliary level, e.g. `NOTICE`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AuxLevel::Fatal => "FATAL",
+            AuxLevel::Notice => "NOTICE",
+            AuxLevel::Verbose => "VERBOSE",
+        }
+    }
+
+    /// Returns the numeric syslog severity (RFC 5424) of this auxiliary level.
+    /// [`AuxLevel::Fatal`] maps to 2 (critical), [`AuxLevel::Notice`] to 5 (notice), and [`AuxLevel::Verbose`] to 7 (debug).
+    pub fn syslog_severity(self) -> u8 {
+        match self {
+            AuxLevel::Fatal => 2,
+            AuxLevel::Notice => 5,
+            AuxLevel::Verbose => 7,
+        }
+    }
+
+    /// Returns the auxiliary level of the record currently being logged on this thread, if any.
+    /// This is meant for appenders and encoders that render records themselves.
+    pub fn current() -> Option<AuxLevel> {
+        log_mdc::get(SEVERITY_MDC_KEY, |name| name.and_then(Self::from_key))
+    }
+
+    /// Attaches this auxiliary level to the records logged on this thread until the returned guard is dropped.
+    #[doc(hidden)]
+    pub fn scope(self) -> InsertGuard {
+        log_mdc::insert_scoped(SEVERITY_MDC_KEY, self.key())
+    }
+
+    pub(crate) fn key(self) -> &'static str {
+        match self {
+            AuxLevel::Fatal => "fatal",
+            AuxLevel::Notice => "notice",
+            AuxLevel::Verbose => "verbose",
+        }
+    }
+
+    pub(crate) fn from_key(key: &str) -> Option<AuxLevel> {
+        Self::iter().find(|aux_level| aux_level.key() == key)
+    }
+}
+
+impl Display for AuxLevel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lum_libs::log::LevelFilter;
+
+    use super::*;
+    use crate::{ConfigBuilder, level, testing};
+
+    #[test]
+    fn auxiliary_levels_are_logged_at_their_level_and_attached_to_the_record() {
+        let _global = testing::GLOBAL.lock();
+        let records = testing::capture(ConfigBuilder::new().root_log_level(LevelFilter::Info));
+
+        crate::fatal!("Fatal");
+        crate::notice!("Notice");
+        crate::verbose!("Verbose");
+        crate::info!("Info");
+
+        let records: Vec<_> = records
+            .try_iter()
+            .map(|record| (record.message, record.level, record.aux_level))
+            .collect();
+        assert_eq!(
+            records,
+            [
+                ("Fatal".to_string(), Level::Error, Some(AuxLevel::Fatal)),
+                ("Notice".to_string(), Level::Info, Some(AuxLevel::Notice)),
+                ("Info".to_string(), Level::Info, None),
+            ]
+        );
+        assert_eq!(AuxLevel::current(), None);
+    }
+
+    #[test]
+    fn auxiliary_levels_override_the_syslog_severity_while_in_scope() {
+        assert_eq!(level::current_syslog_severity(Level::Error), 3);
+        {
+            let _severity = AuxLevel::Fatal.scope();
+            assert_eq!(level::current_syslog_severity(Level::Error), 2);
+        }
+        assert_eq!(AuxLevel::from_key("notice"), Some(AuxLevel::Notice));
+        assert_eq!(format!("{:<8}|", AuxLevel::Notice), "NOTICE  |");
+    }
+}
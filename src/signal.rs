@@ -0,0 +1,71 @@
+use std::{
+    io,
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+};
+
+use lum_libs::log;
+use signal_hook::{
+    consts::{SIGINT, SIGTERM},
+    iterator::Signals,
+    low_level,
+};
+
+use crate::logger;
+
+/// The target of the record logged when a signal is received, see [`flush_on_signal`].
+pub const SIGNAL_TARGET: &str = "lum_log::signal";
+
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+/// Installs handlers for SIGINT (Ctrl-C) and SIGTERM which [shut down](logger::shutdown) the logger and then re-raise the signal,
+/// so buffered records are not lost when the process is stopped, e.g. by an orchestrator.
+/// The re-raised signal terminates the process as if no handler had been installed.
+/// Also flushes the logger when the process exits normally, e.g. by returning from `main` or calling [`std::process::exit`].
+/// Installing the handlers more than once has no effect.
+/// See also [`ConfigBuilder::flush_on_signal`](crate::ConfigBuilder::flush_on_signal).
+///
+/// Note that handlers installed for the same signals by the application, e.g. for a graceful shutdown, run as well,
+/// but the process terminates once the logger has been shut down.
+pub fn flush_on_signal() -> io::Result<()> {
+    if INSTALLED.swap(true, Ordering::Relaxed) {
+        return Ok(());
+    }
+
+    let installed = Signals::new([SIGINT, SIGTERM]).and_then(|mut signals| {
+        thread::Builder::new()
+            .name("lum_log-signals".to_string())
+            .spawn(move || {
+                if let Some(signal) = signals.forever().next() {
+                    shutdown(signal);
+                }
+            })
+    });
+    if let Err(error) = installed {
+        INSTALLED.store(false, Ordering::Relaxed);
+        return Err(error);
+    }
+
+    // SAFETY: `flush_at_exit` does not unwind, as flushing catches panics of appenders.
+    unsafe { libc::atexit(flush_at_exit) };
+    Ok(())
+}
+
+/// Returns whether the handlers have been installed by [`flush_on_signal`].
+pub fn is_flushing_on_signal() -> bool {
+    INSTALLED.load(Ordering::Relaxed)
+}
+
+fn shutdown(signal: i32) {
+    log::info!(
+        target: SIGNAL_TARGET,
+        "Received {}, shutting down",
+        low_level::signal_name(signal).unwrap_or("signal")
+    );
+    logger::shutdown();
+    let _ = low_level::emulate_default_handler(signal);
+}
+
+extern "C" fn flush_at_exit() {
+    logger::flush();
+}
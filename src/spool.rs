@@ -0,0 +1,387 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, BufReader, ErrorKind, Read, Write},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use lum_libs::parking_lot::Mutex;
+
+use crate::default;
+
+const SEGMENT_PREFIX: &str = "spool-";
+const SEGMENT_EXTENSION: &str = "bin";
+const QUARANTINE_EXTENSION: &str = "corrupt";
+
+#[derive(Debug)]
+struct SpoolState {
+    segments: Vec<(u64, PathBuf)>,
+    next_segment: u64,
+    current_len: u64,
+}
+
+/// A directory persisting undeliverable records, so they can be replayed once the remote sink recovers.
+/// Records are appended to segment files as length-prefixed frames.
+/// If the total size exceeds the cap, the oldest segments are deleted.
+/// Spooled records survive restarts, as existing segments are picked up when the spool is opened.
+/// Segments that cannot be read are renamed to the extension `corrupt` and skipped, so they can be inspected.
+#[derive(Debug)]
+pub struct Spool {
+    directory: PathBuf,
+    max_bytes: u64,
+    segment_bytes: u64,
+    state: Mutex<SpoolState>,
+    /// Held while replaying, so records are not replayed twice by concurrent calls.
+    replaying: Mutex<()>,
+    dropped_segments: AtomicU64,
+    quarantined_segments: AtomicU64,
+}
+
+impl Spool {
+    /// Opens the spool in the given directory, creating it if necessary,
+    /// capping its total size at the given number of bytes.
+    pub fn open(directory: impl Into<PathBuf>, max_bytes: u64) -> io::Result<Self> {
+        let directory = directory.into();
+        fs::create_dir_all(&directory)?;
+
+        let mut segments = Vec::new();
+        for entry in fs::read_dir(&directory)? {
+            let path = entry?.path();
+            if let Some(sequence) = segment_sequence(&path) {
+                segments.push((sequence, path));
+            }
+        }
+        segments.sort();
+
+        let next_segment = segments.last().map_or(0, |(sequence, _)| sequence + 1);
+        Ok(Self {
+            directory,
+            max_bytes,
+            segment_bytes: (max_bytes / 8).clamp(1, default::spool_segment_bytes()),
+            state: Mutex::new(SpoolState {
+                segments,
+                next_segment,
+                current_len: u64::MAX,
+            }),
+            replaying: Mutex::new(()),
+            dropped_segments: AtomicU64::new(0),
+            quarantined_segments: AtomicU64::new(0),
+        })
+    }
+
+    /// Returns the directory of the spool.
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Returns whether the spool holds no records.
+    pub fn is_empty(&self) -> bool {
+        self.state.lock().segments.is_empty()
+    }
+
+    /// Returns the total size of all segments in bytes.
+    pub fn size(&self) -> u64 {
+        let state = self.state.lock();
+        state
+            .segments
+            .iter()
+            .filter_map(|(_, path)| fs::metadata(path).ok())
+            .map(|metadata| metadata.len())
+            .sum()
+    }
+
+    /// Returns the number of segments deleted because the size cap was exceeded.
+    pub fn dropped_segments(&self) -> u64 {
+        self.dropped_segments.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of segments skipped by [`replay`](Self::replay) because they could not be read.
+    pub fn quarantined_segments(&self) -> u64 {
+        self.quarantined_segments.load(Ordering::Relaxed)
+    }
+
+    /// Persists the given encoded record.
+    pub fn push(&self, payload: &[u8]) -> io::Result<()> {
+        let length = u32::try_from(payload.len())
+            .map_err(|_| io::Error::new(ErrorKind::InvalidInput, "Record too large to spool"))?;
+
+        let mut state = self.state.lock();
+        if state.current_len >= self.segment_bytes || state.segments.is_empty() {
+            let sequence = state.next_segment;
+            state.next_segment += 1;
+            state.segments.push((sequence, self.segment_path(sequence)));
+            state.current_len = 0;
+        }
+
+        let (_, path) = state.segments.last().expect("A segment was just ensured");
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        file.write_all(&length.to_le_bytes())?;
+        file.write_all(payload)?;
+        file.sync_data()?;
+        state.current_len += 4 + u64::from(length);
+
+        self.enforce_cap(&mut state);
+        Ok(())
+    }
+
+    /// Replays spooled records oldest first by passing them to the given function,
+    /// removing each record once the function succeeded.
+    /// Stops at the first error, keeping the failed record and all newer ones.
+    /// The spool is not locked while the function runs, so records can be pushed meanwhile.
+    /// Returns the number of replayed records, or the error if none could be replayed.
+    pub fn replay(&self, mut send: impl FnMut(&[u8]) -> io::Result<()>) -> io::Result<usize> {
+        let _replaying = self.replaying.lock();
+        let mut replayed = 0;
+
+        loop {
+            let path = {
+                let mut state = self.state.lock();
+                let Some((_, path)) = state.segments.first().cloned() else {
+                    break;
+                };
+                if state.segments.len() == 1 {
+                    // Records pushed while the segment is replayed go to a new one, so they are not lost when it is removed.
+                    state.current_len = u64::MAX;
+                }
+                path
+            };
+
+            let frames = match read_frames(&path) {
+                Ok(frames) => frames,
+                Err(_) => {
+                    self.quarantine(&path);
+                    continue;
+                }
+            };
+
+            let mut sent = 0;
+            let mut failure = None;
+            for frame in &frames {
+                if let Err(error) = send(frame) {
+                    failure = Some(error);
+                    break;
+                }
+                sent += 1;
+            }
+            replayed += sent;
+
+            let mut state = self.state.lock();
+            // The segment may have been deleted by the size cap while it was replayed.
+            let position = state
+                .segments
+                .iter()
+                .position(|(_, segment)| *segment == path);
+            if let Some(error) = failure {
+                if position.is_some() && sent > 0 {
+                    // If this fails, the replayed records are replayed again next time.
+                    let _ = rewrite_frames(&path, &frames[sent..]);
+                }
+                return if replayed > 0 {
+                    Ok(replayed)
+                } else {
+                    Err(error)
+                };
+            }
+
+            if let Some(position) = position {
+                // A segment that cannot be removed is picked up again after a restart, but not replayed twice by this process.
+                let _ = fs::remove_file(&path);
+                state.segments.remove(position);
+            }
+        }
+
+        Ok(replayed)
+    }
+
+    /// Renames the given unreadable segment to the extension [`QUARANTINE_EXTENSION`], or removes it if that fails,
+    /// and stops tracking it.
+    fn quarantine(&self, path: &Path) {
+        let mut state = self.state.lock();
+        state.segments.retain(|(_, segment)| segment != path);
+        if fs::rename(path, path.with_extension(QUARANTINE_EXTENSION)).is_err() {
+            let _ = fs::remove_file(path);
+        }
+        self.quarantined_segments.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn segment_path(&self, sequence: u64) -> PathBuf {
+        self.directory.join(format!(
+            "{SEGMENT_PREFIX}{sequence:020}.{SEGMENT_EXTENSION}"
+        ))
+    }
+
+    fn enforce_cap(&self, state: &mut SpoolState) {
+        let mut total = state
+            .segments
+            .iter()
+            .filter_map(|(_, path)| fs::metadata(path).ok())
+            .map(|metadata| metadata.len())
+            .sum::<u64>();
+
+        while total > self.max_bytes && state.segments.len() > 1 {
+            let (_, path) = state.segments.remove(0);
+            total -= fs::metadata(&path).map_or(0, |metadata| metadata.len());
+            let _ = fs::remove_file(&path);
+            self.dropped_segments.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+fn segment_sequence(path: &Path) -> Option<u64> {
+    if path.extension()? != SEGMENT_EXTENSION {
+        return None;
+    }
+
+    path.file_stem()?
+        .to_str()?
+        .strip_prefix(SEGMENT_PREFIX)?
+        .parse()
+        .ok()
+}
+
+fn read_frames(path: &Path) -> io::Result<Vec<Vec<u8>>> {
+    let file = File::open(path)?;
+    let mut remaining = file.metadata()?.len();
+    let mut reader = BufReader::new(file);
+    let mut frames = Vec::new();
+
+    loop {
+        let mut length = [0; 4];
+        match reader.read_exact(&mut length) {
+            Ok(()) => {}
+            Err(error) if error.kind() == ErrorKind::UnexpectedEof => break,
+            Err(error) => return Err(error),
+        }
+
+        // A truncated frame is left over from a crash while writing, so it is skipped.
+        // Its length is checked first, so a garbled one does not allocate up to 4 GiB.
+        let length = u64::from(u32::from_le_bytes(length));
+        remaining = remaining.saturating_sub(4);
+        if length > remaining {
+            break;
+        }
+        remaining -= length;
+
+        let mut frame = vec![0; length as usize];
+        reader.read_exact(&mut frame)?;
+        frames.push(frame);
+    }
+
+    Ok(frames)
+}
+
+fn rewrite_frames(path: &Path, frames: &[Vec<u8>]) -> io::Result<()> {
+    let temporary = path.with_extension("tmp");
+
+    let mut file = File::create(&temporary)?;
+    for frame in frames {
+        file.write_all(&(frame.len() as u32).to_le_bytes())?;
+        file.write_all(frame)?;
+    }
+    file.sync_data()?;
+
+    fs::rename(temporary, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    #[test]
+    fn replay_keeps_failed_and_newer_records() {
+        let spool = Spool::open(testing::temp_dir("spool_replay"), 1024 * 1024).unwrap();
+        for payload in [b"one", b"two", b"six"] {
+            spool.push(payload).unwrap();
+        }
+
+        let mut sent = Vec::new();
+        let replayed = spool
+            .replay(|payload| {
+                if payload == b"two" {
+                    return Err(io::Error::other("Sink down"));
+                }
+                sent.push(payload.to_vec());
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!((replayed, sent), (1, vec![b"one".to_vec()]));
+
+        let mut sent = Vec::new();
+        let replayed = spool
+            .replay(|payload| {
+                sent.push(payload.to_vec());
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(
+            (replayed, sent),
+            (2, vec![b"two".to_vec(), b"six".to_vec()])
+        );
+        assert!(spool.is_empty());
+    }
+
+    #[test]
+    fn replay_does_not_lock_the_spool_while_sending() {
+        let spool =
+            Spool::open(testing::temp_dir("spool_push_while_replaying"), 1024 * 1024).unwrap();
+        spool.push(b"old").unwrap();
+
+        let mut sent = Vec::new();
+        let replayed = spool
+            .replay(|payload| {
+                if sent.is_empty() {
+                    spool.push(b"new")?;
+                }
+                sent.push(payload.to_vec());
+                Ok(())
+            })
+            .unwrap();
+
+        // The record pushed while sending went to a new segment, which is replayed afterwards.
+        assert_eq!(replayed, 2);
+        assert_eq!(sent, [b"old".to_vec(), b"new".to_vec()]);
+        assert!(spool.is_empty());
+    }
+
+    #[test]
+    fn replay_skips_unreadable_segments() {
+        let directory = testing::temp_dir("spool_corrupt");
+        fs::create_dir(directory.join("spool-00000000000000000000.bin")).unwrap();
+        let spool = Spool::open(&directory, 1024 * 1024).unwrap();
+        spool.push(b"readable").unwrap();
+
+        let replayed = spool.replay(|_| Ok(())).unwrap();
+
+        assert_eq!(replayed, 1);
+        assert_eq!(spool.quarantined_segments(), 1);
+        assert!(spool.is_empty());
+        assert!(
+            directory
+                .join("spool-00000000000000000000.corrupt")
+                .exists()
+        );
+    }
+
+    #[test]
+    fn truncated_frames_are_skipped() {
+        let directory = testing::temp_dir("spool_truncated");
+        let spool = Spool::open(&directory, 1024 * 1024).unwrap();
+        spool.push(b"complete").unwrap();
+        let mut segment = OpenOptions::new()
+            .append(true)
+            .open(directory.join("spool-00000000000000000000.bin"))
+            .unwrap();
+        segment.write_all(&u32::MAX.to_le_bytes()).unwrap();
+        segment.write_all(b"cut").unwrap();
+
+        let mut sent = Vec::new();
+        spool
+            .replay(|payload| {
+                sent.push(payload.to_vec());
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(sent, [b"complete".to_vec()]);
+    }
+}
@@ -0,0 +1,143 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    sync::{
+        LazyLock,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use lum_libs::{humantime, log::Level};
+
+/// The target of the summary record logged by [`shutdown`](crate::logger::shutdown).
+pub const SUMMARY_TARGET: &str = "lum_log::summary";
+
+static RECORDS: [AtomicU64; 5] = [const { AtomicU64::new(0) }; 5];
+static DROPPED: AtomicU64 = AtomicU64::new(0);
+static APPENDER_ERRORS: AtomicU64 = AtomicU64::new(0);
+static STARTED: LazyLock<Instant> = LazyLock::new(Instant::now);
+static SHUTDOWN_SUMMARY: AtomicBool = AtomicBool::new(false);
+
+/// A snapshot of the statistics of the logger since it was set up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    records: [u64; 5],
+    /// The number of records dropped by buffering appenders.
+    pub dropped: u64,
+    /// The number of failed appends of appenders added by a [`ConfigBuilder`](crate::ConfigBuilder).
+    pub appender_errors: u64,
+    /// The time since the logger was first set up.
+    pub uptime: Duration,
+}
+
+impl Stats {
+    /// Returns the number of records logged at the given level.
+    pub fn records(&self, level: Level) -> u64 {
+        self.records[level as usize - 1]
+    }
+
+    /// Returns the total number of records logged.
+    pub fn total_records(&self) -> u64 {
+        self.records.iter().sum()
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "records:")?;
+        for level in Level::iter() {
+            write!(
+                f,
+                " {}={}",
+                level.as_str().to_lowercase(),
+                self.records(level)
+            )?;
+        }
+
+        write!(
+            f,
+            ", dropped: {}, appender errors: {}, uptime: {}",
+            self.dropped,
+            self.appender_errors,
+            humantime::format_duration(Duration::from_secs(self.uptime.as_secs()))
+        )
+    }
+}
+
+/// Returns a snapshot of the statistics of the logger.
+pub fn stats() -> Stats {
+    Stats {
+        records: RECORDS
+            .each_ref()
+            .map(|count| count.load(Ordering::Relaxed)),
+        dropped: DROPPED.load(Ordering::Relaxed),
+        appender_errors: APPENDER_ERRORS.load(Ordering::Relaxed),
+        uptime: STARTED.elapsed(),
+    }
+}
+
+/// Sets whether [`shutdown`](crate::logger::shutdown) logs a summary of the [`Stats`] to [`SUMMARY_TARGET`].
+/// The summary is disabled by default. See also [`ConfigBuilder::shutdown_summary`](crate::ConfigBuilder::shutdown_summary).
+pub fn set_shutdown_summary(enabled: bool) {
+    SHUTDOWN_SUMMARY.store(enabled, Ordering::Relaxed);
+}
+
+/// Returns whether [`shutdown`](crate::logger::shutdown) logs a summary.
+pub fn shutdown_summary() -> bool {
+    SHUTDOWN_SUMMARY.load(Ordering::Relaxed)
+}
+
+/// Starts the uptime clock, if it has not been started yet.
+pub(crate) fn start() {
+    LazyLock::force(&STARTED);
+}
+
+pub(crate) fn record_logged(level: Level) {
+    RECORDS[level as usize - 1].fetch_add(1, Ordering::Relaxed);
+}
+
+pub(crate) fn record_dropped() {
+    DROPPED.fetch_add(1, Ordering::Relaxed);
+}
+
+pub(crate) fn appender_error() {
+    APPENDER_ERRORS.fetch_add(1, Ordering::Relaxed);
+}
+
+#[cfg(test)]
+mod tests {
+    use lum_libs::log::{self, LevelFilter};
+
+    use super::*;
+    use crate::{ConfigBuilder, logger, testing};
+
+    #[test]
+    fn records_are_counted_per_level_and_summarized_on_shutdown() {
+        let _global = testing::GLOBAL.lock();
+        let records = testing::capture(
+            ConfigBuilder::new()
+                .root_log_level(LevelFilter::Info)
+                .shutdown_summary(true),
+        );
+        let before = stats();
+
+        log::info!("First");
+        log::info!("Second");
+        log::warn!("Third");
+        log::debug!("Disabled");
+        let after = stats();
+        logger::shutdown();
+
+        assert_eq!(after.records(Level::Info) - before.records(Level::Info), 2);
+        assert_eq!(after.records(Level::Warn) - before.records(Level::Warn), 1);
+        assert_eq!(after.total_records() - before.total_records(), 3);
+        let summary = records.try_iter().last().expect("A summary is logged");
+        assert_eq!(summary.target, SUMMARY_TARGET);
+        assert!(
+            summary
+                .message
+                .starts_with("Shutting down, records: error=")
+        );
+        assert!(summary.message.contains(", dropped: "));
+    }
+}
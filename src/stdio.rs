@@ -0,0 +1,216 @@
+use std::{
+    fs::File,
+    io::{self, Write as _},
+};
+
+use lum_libs::{
+    log::Record,
+    log4rs::{
+        append::console::Target,
+        encode::{Encode, Write, writer::simple::SimpleWriter},
+    },
+    parking_lot::{RwLock, const_rwlock},
+};
+
+#[cfg(unix)]
+use std::{
+    os::fd::{AsRawFd, FromRawFd, RawFd},
+    thread::JoinHandle,
+};
+
+#[cfg(unix)]
+use crate::{default, process};
+
+/// The target of the records re-injected from the captured stdout of the process.
+pub const STDOUT_TARGET: &str = "stdout";
+
+/// The target of the records re-injected from the captured stderr of the process.
+pub const STDERR_TARGET: &str = "stderr";
+
+#[derive(Debug)]
+struct Originals {
+    stdout: File,
+    stderr: File,
+}
+
+/// The original stdout and stderr of the process while a `StdioCapture` is active.
+static ORIGINALS: RwLock<Option<Originals>> = const_rwlock(None);
+
+/// Returns whether the stdout and stderr of the process are currently captured.
+pub fn is_capturing() -> bool {
+    ORIGINALS.read().is_some()
+}
+
+/// Calls the given function with the original stderr of the process if it is currently captured.
+/// Returns `None` if it is not captured, in which case the regular stderr can be used.
+pub(crate) fn original_stderr<T>(f: impl FnOnce(&mut dyn io::Write) -> T) -> Option<T> {
+    let originals = ORIGINALS.read();
+    let mut stderr = &originals.as_ref()?.stderr;
+    Some(f(&mut stderr))
+}
+
+/// Redirects the raw stdout and stderr of the process into pipes and re-injects every line written to them as a record,
+/// so nothing bypasses the log files in daemon deployments, e.g. prints of C libraries.
+/// Stdout lines are logged to [`STDOUT_TARGET`] at [`default::child_stdout_level`],
+/// stderr lines to [`STDERR_TARGET`] at [`default::child_stderr_level`].
+///
+/// Capturing is opt-in and lasts until the returned guard is dropped, which restores the original descriptors,
+/// so keep it alive for the lifetime of the program, e.g. in `main`.
+/// Console appenders created by this crate write to the original stdout or stderr while capturing, without styles,
+/// so their output is not captured again. Other appenders writing to stdout or stderr would feed back into the capture.
+/// Child processes inheriting stdout or stderr keep the pipes open, so dropping the guard waits for them to exit.
+///
+/// ```text
+/// let _capture = StdioCapture::start()?;
+/// ```
+#[cfg(unix)]
+#[derive(Debug)]
+#[must_use = "Stdout and stderr are only captured until the guard is dropped"]
+pub struct StdioCapture {
+    readers: Vec<JoinHandle<()>>,
+}
+
+#[cfg(unix)]
+impl StdioCapture {
+    /// Starts capturing the stdout and stderr of the process.
+    /// Fails with [`io::ErrorKind::AlreadyExists`] if they are already captured.
+    pub fn start() -> io::Result<Self> {
+        let mut originals = ORIGINALS.write();
+        if originals.is_some() {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                "stdout and stderr are already captured",
+            ));
+        }
+
+        io::stdout().flush()?;
+        io::stderr().flush()?;
+
+        let (stdout, stdout_reader) = redirect(libc::STDOUT_FILENO)?;
+        let (stderr, stderr_reader) = match redirect(libc::STDERR_FILENO) {
+            Ok(redirected) => redirected,
+            Err(error) => {
+                restore(libc::STDOUT_FILENO, &stdout);
+                return Err(error);
+            }
+        };
+        *originals = Some(Originals { stdout, stderr });
+        drop(originals);
+
+        // If spawning a reader fails, dropping the capture restores the original descriptors.
+        let mut capture = Self {
+            readers: Vec::with_capacity(2),
+        };
+        capture.readers.push(process::forward_lines(
+            "lum_log-stdout".to_string(),
+            STDOUT_TARGET.to_string(),
+            default::child_stdout_level(),
+            stdout_reader,
+        )?);
+        capture.readers.push(process::forward_lines(
+            "lum_log-stderr".to_string(),
+            STDERR_TARGET.to_string(),
+            default::child_stderr_level(),
+            stderr_reader,
+        )?);
+
+        Ok(capture)
+    }
+}
+
+#[cfg(unix)]
+impl Drop for StdioCapture {
+    fn drop(&mut self) {
+        let _ = io::stdout().flush();
+        let _ = io::stderr().flush();
+
+        if let Some(originals) = ORIGINALS.write().take() {
+            restore(libc::STDOUT_FILENO, &originals.stdout);
+            restore(libc::STDERR_FILENO, &originals.stderr);
+        }
+
+        // Restoring closed the last write ends of the pipes, so the readers finish once they are drained.
+        for reader in self.readers.drain(..) {
+            let _ = reader.join();
+        }
+    }
+}
+
+/// Redirects the given descriptor into a new pipe.
+/// Returns a duplicate of the original descriptor and the read end of the pipe.
+#[cfg(unix)]
+fn redirect(fd: RawFd) -> io::Result<(File, File)> {
+    let mut pipe = [0; 2];
+    // SAFETY: `pipe` points to two writable descriptors.
+    if unsafe { libc::pipe(pipe.as_mut_ptr()) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // SAFETY: `pipe` just returned these descriptors, so they are open and owned by nobody else.
+    let (read, write) = unsafe { (File::from_raw_fd(pipe[0]), File::from_raw_fd(pipe[1])) };
+    set_cloexec(&read)?;
+    set_cloexec(&write)?;
+
+    // SAFETY: Duplicating a descriptor has no memory safety requirements.
+    let original = unsafe { libc::fcntl(fd, libc::F_DUPFD_CLOEXEC, 0) };
+    if original < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // SAFETY: `fcntl` just returned this descriptor, so it is open and owned by nobody else.
+    let original = unsafe { File::from_raw_fd(original) };
+
+    // SAFETY: Both descriptors are open; `dup2` atomically replaces `fd`.
+    if unsafe { libc::dup2(write.as_raw_fd(), fd) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok((original, read))
+}
+
+/// Points the given descriptor back to the given original.
+#[cfg(unix)]
+fn restore(fd: RawFd, original: &File) {
+    // SAFETY: Both descriptors are open; `dup2` atomically replaces `fd`.
+    unsafe { libc::dup2(original.as_raw_fd(), fd) };
+}
+
+#[cfg(unix)]
+fn set_cloexec(file: &File) -> io::Result<()> {
+    // SAFETY: Setting descriptor flags has no memory safety requirements.
+    if unsafe { libc::fcntl(file.as_raw_fd(), libc::F_SETFD, libc::FD_CLOEXEC) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// An encoder that writes to the original stdout or stderr of the process while a `StdioCapture` is active,
+/// so console output is not captured again. Otherwise, it delegates to the wrapped encoder.
+/// Console appenders created by this crate are wrapped in it. Capturing is only supported on unix.
+#[derive(Debug)]
+pub struct UncapturedEncoder {
+    inner: Box<dyn Encode>,
+    target: Target,
+}
+
+impl UncapturedEncoder {
+    /// Creates a new `UncapturedEncoder` wrapping the given encoder of a console appender writing to the given stream.
+    pub fn new(inner: Box<dyn Encode>, target: Target) -> Self {
+        Self { inner, target }
+    }
+}
+
+impl Encode for UncapturedEncoder {
+    fn encode(&self, w: &mut dyn Write, record: &Record) -> anyhow::Result<()> {
+        if !is_capturing() {
+            return self.inner.encode(w, record);
+        }
+
+        let mut buffer = SimpleWriter(Vec::new());
+        self.inner.encode(&mut buffer, record)?;
+
+        match (&*ORIGINALS.read(), self.target) {
+            (Some(originals), Target::Stdout) => (&originals.stdout).write_all(&buffer.0)?,
+            (Some(originals), Target::Stderr) => (&originals.stderr).write_all(&buffer.0)?,
+            (None, _) => w.write_all(&buffer.0)?,
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,141 @@
+use std::{
+    collections::VecDeque,
+    sync::mpsc::{self, Receiver, SyncSender, TrySendError},
+};
+
+use lum_libs::parking_lot::Mutex;
+
+use crate::{default, memory, record::OwnedRecord};
+
+struct Hub {
+    history: VecDeque<OwnedRecord>,
+    history_capacity: usize,
+    subscribers: Vec<SyncSender<OwnedRecord>>,
+}
+
+static HUB: Mutex<Hub> = Mutex::new(Hub {
+    history: VecDeque::new(),
+    history_capacity: default::history_capacity(),
+    subscribers: Vec::new(),
+});
+
+/// Subscribes to the records published by the [`BroadcastAppender`](crate::append::BroadcastAppender),
+/// using [`default::subscription_capacity`] as the capacity of the returned channel.
+/// See [`subscribe_with_capacity`].
+pub fn subscribe() -> Receiver<OwnedRecord> {
+    subscribe_with_capacity(default::subscription_capacity())
+}
+
+/// Subscribes to the records published by the [`BroadcastAppender`](crate::append::BroadcastAppender).
+/// The returned receiver first yields the most recent records kept in the history (up to the capacity),
+/// followed by all records published from now on.
+/// Any number of subscribers may exist at the same time.
+/// If a subscriber does not keep up and its channel is full, records are dropped for that subscriber only.
+/// Subscriptions end when their receiver is dropped.
+pub fn subscribe_with_capacity(capacity: usize) -> Receiver<OwnedRecord> {
+    let capacity = capacity.max(1);
+    let (sender, receiver) = mpsc::sync_channel(capacity);
+
+    let mut hub = HUB.lock();
+    let skip = hub.history.len().saturating_sub(capacity);
+    for record in hub.history.iter().skip(skip) {
+        let _ = sender.try_send(record.clone());
+    }
+    hub.subscribers.push(sender);
+
+    receiver
+}
+
+/// Returns the most recent records kept in the history, oldest first.
+pub fn recent() -> Vec<OwnedRecord> {
+    HUB.lock().history.iter().cloned().collect()
+}
+
+/// Sets the number of most recent records kept in the history.
+/// Defaults to [`default::history_capacity`]. A capacity of 0 disables the history.
+pub fn set_history_capacity(capacity: usize) {
+    let mut hub = HUB.lock();
+    hub.history_capacity = capacity;
+
+    let excess = hub.history.len().saturating_sub(capacity);
+    for record in hub.history.drain(..excess) {
+        memory::release(record.estimated_size());
+    }
+}
+
+/// Returns whether publishing a record would have any effect.
+pub(crate) fn is_active() -> bool {
+    let hub = HUB.lock();
+    hub.history_capacity > 0 || !hub.subscribers.is_empty()
+}
+
+/// Publishes the given record to the history and all subscribers.
+pub(crate) fn publish(record: OwnedRecord) {
+    let mut hub = HUB.lock();
+
+    hub.subscribers
+        .retain(|subscriber| match subscriber.try_send(record.clone()) {
+            Ok(()) | Err(TrySendError::Full(_)) => true,
+            Err(TrySendError::Disconnected(_)) => false,
+        });
+
+    if hub.history_capacity == 0 {
+        return;
+    }
+    if hub.history.len() >= hub.history_capacity
+        && let Some(oldest) = hub.history.pop_front()
+    {
+        memory::release(oldest.estimated_size());
+    }
+
+    // The history is best-effort, so it makes room by evicting its oldest records if the memory budget is exhausted.
+    let size = record.estimated_size();
+    while !memory::try_reserve(size) {
+        let Some(oldest) = hub.history.pop_front() else {
+            return;
+        };
+        memory::release(oldest.estimated_size());
+    }
+    hub.history.push_back(record);
+}
+
+#[cfg(test)]
+mod tests {
+    use lum_libs::log::Level;
+
+    use super::*;
+    use crate::testing;
+
+    fn publish_message(message: &str) {
+        publish(testing::owned_record(
+            Level::Info,
+            "subscribe_test",
+            message,
+        ));
+    }
+
+    #[test]
+    fn subscribers_receive_the_recent_history_followed_by_new_records() {
+        let _global = testing::GLOBAL.lock();
+        set_history_capacity(0);
+        set_history_capacity(2);
+        for message in ["First", "Second", "Third"] {
+            publish_message(message);
+        }
+
+        let all = subscribe_with_capacity(8);
+        let latest = subscribe_with_capacity(1);
+        publish_message("Fourth");
+
+        assert_eq!(testing::messages(&all), ["Second", "Third", "Fourth"]);
+        // The channel was full, so the new record was dropped for this subscriber only.
+        assert_eq!(testing::messages(&latest), ["Third"]);
+        let recent = recent()
+            .into_iter()
+            .map(|record| record.message)
+            .collect::<Vec<_>>();
+        assert_eq!(recent, ["Third", "Fourth"]);
+
+        set_history_capacity(default::history_capacity());
+    }
+}
@@ -0,0 +1,78 @@
+use lum_libs::parking_lot::{RwLock, const_rwlock};
+
+static DEFAULT_TARGET_PREFIX: RwLock<Option<String>> = const_rwlock(None);
+
+/// Sets the prefix prepended to the target of records logged without an explicit target, e.g. `myapp` turns
+/// `my_crate::module` into `myapp::my_crate::module`, making allow/deny and routing rules reliable across workspace crates.
+/// A record has no explicit target if its target equals its module path, which is the default of the logging macros.
+/// Targets already starting with the prefix are left untouched. No prefix is set by default.
+/// See also [`ConfigBuilder::default_target_prefix`](crate::ConfigBuilder::default_target_prefix).
+/// This only has an effect if the logger has been set up by this crate.
+pub fn set_default_target_prefix(prefix: Option<String>) {
+    *DEFAULT_TARGET_PREFIX.write() = prefix;
+}
+
+/// Returns the prefix set by [`set_default_target_prefix`], if any.
+pub fn default_target_prefix() -> Option<String> {
+    DEFAULT_TARGET_PREFIX.read().clone()
+}
+
+/// Returns the given target with the default target prefix prepended,
+/// or `None` if no prefix is set or the target already starts with it.
+pub(crate) fn prefixed(target: &str) -> Option<String> {
+    let prefix = DEFAULT_TARGET_PREFIX.read();
+    let prefix = prefix.as_deref()?;
+
+    let already_prefixed = target
+        .strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"));
+    (!already_prefixed).then(|| format!("{prefix}::{target}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use lum_libs::log::{self, LevelFilter};
+
+    use super::*;
+    use crate::{ConfigBuilder, testing};
+
+    #[test]
+    fn records_without_an_explicit_target_are_prefixed() {
+        let _global = testing::GLOBAL.lock();
+        let records = testing::capture(
+            ConfigBuilder::new()
+                .root_log_level(LevelFilter::Info)
+                .log_level("prefix_test", LevelFilter::Debug)
+                .default_target_prefix("prefix_test"),
+        );
+
+        assert!(log::log_enabled!(log::Level::Debug));
+        log::debug!("Implicit");
+        log::debug!(target: "explicit", "Filtered");
+        log::info!(target: "explicit", "Explicit");
+        log::info!(target: "prefix_test::db", "Already prefixed");
+
+        let targets = records
+            .try_iter()
+            .map(|record| (record.target, record.message))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            targets,
+            [
+                (
+                    format!("prefix_test::{}", module_path!()),
+                    "Implicit".to_string()
+                ),
+                ("explicit".to_string(), "Explicit".to_string()),
+                (
+                    "prefix_test::db".to_string(),
+                    "Already prefixed".to_string()
+                ),
+            ]
+        );
+        assert_eq!(
+            prefixed("prefix_testing"),
+            Some("prefix_test::prefix_testing".to_string())
+        );
+    }
+}
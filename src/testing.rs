@@ -0,0 +1,129 @@
+use std::{io::Write as _, sync::Once};
+
+use lum_libs::{
+    log::{LevelFilter, Record},
+    log4rs::{
+        append::Append,
+        encode::{Encode, pattern::PatternEncoder, writer::simple::SimpleWriter},
+    },
+};
+
+use crate::{ConfigBuilder, default, is_set_up};
+
+/// The environment variable setting the root log level of [`try_init_quiet`], e.g. `LUM_LOG_TEST_LEVEL=debug`.
+pub const LEVEL_ENV_VAR: &str = "LUM_LOG_TEST_LEVEL";
+
+static INIT: Once = Once::new();
+
+/// Sets up the logger for tests, so library crates can call this at the start of every test without coordination.
+/// It is safe to call from many parallel tests: the first call sets up the logger, all other calls do nothing.
+/// It also does nothing if a logger has already been set up, by this crate or another one.
+///
+/// The root log level is read from [`LEVEL_ENV_VAR`], falling back to [`default::test_log_level`] if it is unset or invalid,
+/// so tests are quiet unless asked otherwise.
+/// Records are printed without colors through the standard test output capturing, so only the output of failing tests is shown.
+pub fn try_init_quiet() {
+    INIT.call_once(|| {
+        if is_set_up() {
+            return;
+        }
+
+        let level = std::env::var(LEVEL_ENV_VAR)
+            .ok()
+            .and_then(|level| level.trim().parse::<LevelFilter>().ok())
+            .unwrap_or_else(default::test_log_level);
+
+        // Another logger may have been set as the global logger, in which case this does nothing.
+        let _ = ConfigBuilder::new()
+            .root_log_level(level)
+            .appender("test", Box::new(TestAppender::new()))
+            .apply();
+    });
+}
+
+/// An appender printing records with `print!`, so the test harness captures them per test.
+#[derive(Debug)]
+struct TestAppender {
+    encoder: PatternEncoder,
+}
+
+impl TestAppender {
+    fn new() -> Self {
+        Self {
+            encoder: PatternEncoder::new(default::test_format()),
+        }
+    }
+}
+
+impl Append for TestAppender {
+    fn append(&self, record: &Record) -> anyhow::Result<()> {
+        let mut buffer = SimpleWriter(Vec::new());
+        self.encoder.encode(&mut buffer, record)?;
+        print!("{}", String::from_utf8_lossy(&buffer.0));
+        Ok(())
+    }
+
+    fn flush(&self) {
+        let _ = std::io::stdout().flush();
+    }
+}
+
+/// Serializes the tests of this crate depending on global state, e.g. the global logger or the buffers closed on shutdown.
+#[cfg(test)]
+pub(crate) static GLOBAL: lum_libs::parking_lot::Mutex<()> = lum_libs::parking_lot::Mutex::new(());
+
+/// Applies the given builder with an additional appender named "capture" sending the records it receives into the returned channel.
+/// Hold the lock of [`GLOBAL`] while using the logger set up by this.
+#[cfg(test)]
+pub(crate) fn capture(builder: ConfigBuilder) -> std::sync::mpsc::Receiver<crate::OwnedRecord> {
+    let (appender, receiver) = channel();
+    builder
+        .appender("capture", appender)
+        .apply()
+        .expect("The logger can be set up");
+    receiver
+}
+
+/// Returns an appender sending the records it receives into the returned channel.
+#[cfg(test)]
+pub(crate) fn channel() -> (
+    Box<dyn Append>,
+    std::sync::mpsc::Receiver<crate::OwnedRecord>,
+) {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    (
+        Box::new(crate::append::ChannelAppender::new(sender)),
+        receiver,
+    )
+}
+
+/// Returns the messages of the records received so far.
+#[cfg(test)]
+pub(crate) fn messages(records: &std::sync::mpsc::Receiver<crate::OwnedRecord>) -> Vec<String> {
+    records.try_iter().map(|record| record.message).collect()
+}
+
+/// Returns an empty directory for the files of the test with the given name, removing any left over by a previous run.
+#[cfg(test)]
+pub(crate) fn temp_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("lum_log-{}-{name}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).expect("The test directory can be created");
+    dir
+}
+
+/// Returns an [`OwnedRecord`](crate::OwnedRecord) with the given level, target, and message.
+#[cfg(test)]
+pub(crate) fn owned_record(
+    level: lum_libs::log::Level,
+    target: &str,
+    message: &str,
+) -> crate::OwnedRecord {
+    crate::OwnedRecord::from(
+        &Record::builder()
+            .level(level)
+            .target(target)
+            .args(format_args!("{message}"))
+            .build(),
+    )
+}
@@ -0,0 +1,159 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use lum_libs::serde::{Deserialize, Serialize};
+
+/// The number of fractional second digits of a timestamp.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde", rename_all = "snake_case")]
+pub enum TimestampPrecision {
+    /// No fractional seconds, e.g. `2024-11-12T21:10:32Z`.
+    Seconds,
+    /// Three fractional digits, e.g. `2024-11-12T21:10:32.123Z`.
+    #[default]
+    Millis,
+    /// Six fractional digits, e.g. `2024-11-12T21:10:32.123456Z`.
+    Micros,
+    /// Nine fractional digits, e.g. `2024-11-12T21:10:32.123456789Z`.
+    Nanos,
+}
+
+impl TimestampPrecision {
+    fn fraction(self) -> &'static str {
+        match self {
+            TimestampPrecision::Seconds => "",
+            TimestampPrecision::Millis => "%.3f",
+            TimestampPrecision::Micros => "%.6f",
+            TimestampPrecision::Nanos => "%.9f",
+        }
+    }
+}
+
+/// The time zone a timestamp is rendered in, which also determines its suffix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde", rename_all = "snake_case")]
+pub enum TimestampOffset {
+    /// UTC with a `Z` suffix, e.g. `2024-11-12T21:10:32Z`.
+    #[default]
+    Utc,
+    /// Local time with a numeric offset suffix, e.g. `2024-11-12T22:10:32+01:00`.
+    Local,
+}
+
+/// The shape of an RFC 3339 / ISO 8601 timestamp.
+/// Use [`TimestampFormat::pattern`] to get the corresponding [`PatternEncoder`](lum_libs::log4rs::encode::pattern::PatternEncoder) token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde", default)]
+pub struct TimestampFormat {
+    pub precision: TimestampPrecision,
+    pub offset: TimestampOffset,
+}
+
+impl TimestampFormat {
+    /// Creates a new `TimestampFormat` with the given precision and offset.
+    pub fn new(precision: TimestampPrecision, offset: TimestampOffset) -> Self {
+        Self { precision, offset }
+    }
+
+    /// Returns the [`PatternEncoder`](lum_libs::log4rs::encode::pattern::PatternEncoder) date token rendering timestamps in this format,
+    /// e.g. `{d(%Y-%m-%dT%H:%M:%S%.3fZ)(utc)}`.
+    pub fn pattern(&self) -> String {
+        let fraction = self.precision.fraction();
+        match self.offset {
+            TimestampOffset::Utc => format!("{{d(%Y-%m-%dT%H:%M:%S{fraction}Z)(utc)}}"),
+            TimestampOffset::Local => format!("{{d(%Y-%m-%dT%H:%M:%S{fraction}%:z)(local)}}"),
+        }
+    }
+}
+
+/// Returns the given time as UTC date and time, formatted as `2024-11-12` and `21:10:32`.
+pub(crate) fn utc_date_time(time: SystemTime) -> (String, String) {
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let (days, seconds_of_day) = (seconds / 86_400, seconds % 86_400);
+
+    // Converts days since the Unix epoch to a civil date, see http://howardhinnant.github.io/date_algorithms.html.
+    let days = days as i64 + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    (
+        format!("{year:04}-{month:02}-{day:02}"),
+        format!(
+            "{:02}:{:02}:{:02}",
+            seconds_of_day / 3600,
+            seconds_of_day / 60 % 60,
+            seconds_of_day % 60
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use lum_libs::log4rs::encode::{Encode, pattern::PatternEncoder, writer::simple::SimpleWriter};
+
+    use super::*;
+
+    fn render(format: TimestampFormat) -> String {
+        let mut output = SimpleWriter(Vec::new());
+        PatternEncoder::new(&format.pattern())
+            .encode(
+                &mut output,
+                &lum_libs::log::Record::builder()
+                    .args(format_args!(""))
+                    .build(),
+            )
+            .expect("The timestamp can be encoded");
+        String::from_utf8(output.0).expect("The timestamp is UTF-8")
+    }
+
+    #[test]
+    fn presets_render_rfc3339_timestamps_with_the_configured_precision() {
+        let seconds = render(TimestampFormat::new(
+            TimestampPrecision::Seconds,
+            TimestampOffset::Utc,
+        ));
+        let nanos = render(TimestampFormat::new(
+            TimestampPrecision::Nanos,
+            TimestampOffset::Utc,
+        ));
+        let local = render(TimestampFormat::new(
+            TimestampPrecision::Millis,
+            TimestampOffset::Local,
+        ));
+
+        assert_eq!(seconds.len(), "2024-11-12T21:10:32Z".len());
+        assert!(seconds.ends_with('Z') && seconds.as_bytes()[10] == b'T');
+        assert_eq!(nanos.len(), "2024-11-12T21:10:32.123456789Z".len());
+        assert_eq!(local.len(), "2024-11-12T22:10:32.123+01:00".len());
+        assert_eq!(local.as_bytes()[local.len() - 3], b':');
+    }
+
+    #[test]
+    fn utc_date_time_converts_unix_time_to_the_civil_date() {
+        let time = UNIX_EPOCH + Duration::from_secs(1_731_445_832);
+
+        assert_eq!(
+            utc_date_time(time),
+            ("2024-11-12".to_string(), "21:10:32".to_string())
+        );
+        assert_eq!(
+            utc_date_time(UNIX_EPOCH + Duration::from_secs(951_782_400)),
+            ("2000-02-29".to_string(), "00:00:00".to_string())
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use std::collections::HashSet;
+
+use lum_libs::{log4rs::config::runtime::ConfigErrors, parking_lot::Mutex};
+use thiserror::Error;
+
+use crate::{ConfigBuilder, logger};
+
+static DISABLED_APPENDERS: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+
+/// Errors that can occur when enabling or disabling an appender.
+#[derive(Debug, Error)]
+pub enum ToggleError {
+    #[error("The logger has not been set up with a ConfigBuilder")]
+    NotSetUp,
+
+    #[error("No appender named {0} has been added")]
+    UnknownAppender(String),
+
+    #[error("Error while building log4rs configuration: {0}")]
+    Log4rs(#[from] ConfigErrors),
+}
+
+/// A handle to an appender of the configuration built by the [`ConfigBuilder`] the logger was set up with,
+/// e.g. to temporarily silence a noisy sink without removing it from the configuration.
+///
+/// ```text
+/// lum_log::appender("slack").set_enabled(false)?;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AppenderHandle {
+    name: String,
+}
+
+/// Returns a handle to the appender with the given name, see [`AppenderHandle`].
+/// The appender does not need to exist until the handle is used.
+pub fn appender(name: impl Into<String>) -> AppenderHandle {
+    AppenderHandle { name: name.into() }
+}
+
+impl AppenderHandle {
+    /// Returns the name of the appender.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Enables or disables the appender, swapping the active configuration atomically.
+    /// A disabled appender rejects all records, but keeps its state, e.g. open files and connections, and its place in the configuration.
+    /// Appenders stay disabled when the logger is reconfigured, e.g. by switching profiles or applying another builder.
+    pub fn set_enabled(&self, enabled: bool) -> Result<(), ToggleError> {
+        match logger::with_builder(|builder| builder.has_appender(&self.name)) {
+            None => return Err(ToggleError::NotSetUp),
+            Some(false) => return Err(ToggleError::UnknownAppender(self.name.clone())),
+            Some(true) => {}
+        }
+
+        {
+            let mut disabled = DISABLED_APPENDERS.lock();
+            let disabled = disabled.get_or_insert_with(HashSet::new);
+            let changed = match enabled {
+                true => disabled.remove(&self.name),
+                false => disabled.insert(self.name.clone()),
+            };
+            if !changed {
+                return Ok(());
+            }
+        }
+
+        logger::reconfigure()?;
+        Ok(())
+    }
+
+    /// Returns whether the appender is enabled, which it is unless disabled by [`AppenderHandle::set_enabled`].
+    pub fn is_enabled(&self) -> bool {
+        DISABLED_APPENDERS
+            .lock()
+            .as_ref()
+            .is_none_or(|disabled| !disabled.contains(&self.name))
+    }
+}
+
+/// Disables the appenders disabled by [`AppenderHandle::set_enabled`] in the given builder.
+pub(crate) fn apply_disabled(mut builder: ConfigBuilder) -> ConfigBuilder {
+    if let Some(disabled) = DISABLED_APPENDERS.lock().as_ref() {
+        builder.disable_appenders(disabled.iter().cloned());
+    }
+    builder
+}
+
+#[cfg(test)]
+mod tests {
+    use lum_libs::log::{self, LevelFilter};
+
+    use super::*;
+    use crate::testing;
+
+    #[test]
+    fn disabled_appenders_reject_records_until_enabled_again() {
+        let _global = testing::GLOBAL.lock();
+        let (noisy, noisy_records) = testing::channel();
+        let records = testing::capture(
+            ConfigBuilder::new()
+                .root_log_level(LevelFilter::Info)
+                .appender("noisy", noisy),
+        );
+        let handle = appender("noisy");
+
+        handle.set_enabled(false).unwrap();
+        log::info!(target: "toggle_test", "Silenced");
+        assert!(!handle.is_enabled());
+        assert!(matches!(
+            appender("unknown").set_enabled(false),
+            Err(ToggleError::UnknownAppender(_))
+        ));
+
+        handle.set_enabled(true).unwrap();
+        log::info!(target: "toggle_test", "Heard");
+
+        assert!(handle.is_enabled());
+        assert_eq!(testing::messages(&noisy_records), ["Heard"]);
+        assert_eq!(testing::messages(&records), ["Silenced", "Heard"]);
+    }
+}
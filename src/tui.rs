@@ -0,0 +1,272 @@
+use std::{
+    collections::VecDeque,
+    sync::mpsc::{Receiver, TryRecvError},
+};
+
+use lum_libs::{
+    humantime,
+    log::{Level, LevelFilter},
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Widget},
+};
+
+use crate::{default, record::OwnedRecord, subscribe};
+
+/// A ratatui widget showing a live, filterable view of the log stream.
+/// Records are received from [`subscribe::subscribe`] or any other receiver and kept up to a capacity.
+/// Call [`LogViewer::poll`] before rendering to receive new records.
+/// Key handling is left to the application, which can map keys to the filter and scroll methods.
+#[derive(Debug)]
+pub struct LogViewer {
+    receiver: Option<Receiver<OwnedRecord>>,
+    records: VecDeque<OwnedRecord>,
+    capacity: usize,
+    level: LevelFilter,
+    target: Option<String>,
+    search: Option<String>,
+    scroll: usize,
+}
+
+impl Default for LogViewer {
+    /// Creates a `LogViewer` fed by [`subscribe::subscribe`],
+    /// keeping up to [`default::history_capacity`] records and showing all levels and targets.
+    fn default() -> Self {
+        Self::from_receiver(subscribe::subscribe())
+    }
+}
+
+impl LogViewer {
+    /// Same as [`LogViewer::default`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a `LogViewer` fed by the given receiver.
+    pub fn from_receiver(receiver: Receiver<OwnedRecord>) -> Self {
+        Self {
+            receiver: Some(receiver),
+            records: VecDeque::new(),
+            capacity: default::history_capacity(),
+            level: LevelFilter::Trace,
+            target: None,
+            search: None,
+            scroll: 0,
+        }
+    }
+
+    /// Sets the maximum number of records kept. The oldest records are discarded first.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    /// Receives all pending records without blocking.
+    pub fn poll(&mut self) {
+        while let Some(receiver) = &self.receiver {
+            match receiver.try_recv() {
+                Ok(record) => self.push(record),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => self.receiver = None,
+            }
+        }
+    }
+
+    /// Adds the given record to the viewer.
+    pub fn push(&mut self, record: OwnedRecord) {
+        if self.records.len() >= self.capacity {
+            self.records.pop_front();
+        }
+        self.records.push_back(record);
+    }
+
+    /// Shows only records at the given level or above.
+    pub fn set_level(&mut self, level: LevelFilter) {
+        self.level = level;
+        self.scroll = 0;
+    }
+
+    /// Shows only records whose target starts with the given prefix, or all records if `None`.
+    pub fn set_target(&mut self, target: Option<String>) {
+        self.target = target.filter(|target| !target.is_empty());
+        self.scroll = 0;
+    }
+
+    /// Shows only records whose message contains the given text (case-insensitive), or all records if `None`.
+    pub fn set_search(&mut self, search: Option<String>) {
+        self.search = search
+            .filter(|search| !search.is_empty())
+            .map(|search| search.to_lowercase());
+        self.scroll = 0;
+    }
+
+    /// Scrolls the given number of records towards older records.
+    pub fn scroll_up(&mut self, records: usize) {
+        let max = self.visible().count().saturating_sub(1);
+        self.scroll = (self.scroll + records).min(max);
+    }
+
+    /// Scrolls the given number of records towards newer records.
+    pub fn scroll_down(&mut self, records: usize) {
+        self.scroll = self.scroll.saturating_sub(records);
+    }
+
+    /// Scrolls to the newest record and keeps following new records.
+    pub fn follow(&mut self) {
+        self.scroll = 0;
+    }
+
+    /// Returns the records matching the current filters, oldest first.
+    pub fn visible(&self) -> impl DoubleEndedIterator<Item = &OwnedRecord> {
+        self.records.iter().filter(|record| self.matches(record))
+    }
+
+    fn matches(&self, record: &OwnedRecord) -> bool {
+        if record.level > self.level {
+            return false;
+        }
+
+        if let Some(target) = &self.target
+            && !record.target.starts_with(target.as_str())
+        {
+            return false;
+        }
+
+        match &self.search {
+            Some(search) => record.message.to_lowercase().contains(search.as_str()),
+            None => true,
+        }
+    }
+
+    fn title(&self) -> String {
+        let mut title = format!(" Logs [level: {}", self.level);
+        if let Some(target) = &self.target {
+            title.push_str(&format!(" | target: {target}"));
+        }
+        if let Some(search) = &self.search {
+            title.push_str(&format!(" | search: {search}"));
+        }
+        if self.scroll > 0 {
+            title.push_str(&format!(" | scrolled: {}", self.scroll));
+        }
+        title.push_str("] ");
+
+        title
+    }
+
+    fn line(record: &OwnedRecord) -> Line<'_> {
+        let timestamp = humantime::format_rfc3339_seconds(record.timestamp).to_string();
+        let time = timestamp.get(11..19).unwrap_or(&timestamp).to_string();
+
+        Line::from(vec![
+            Span::styled(time, Style::default().fg(Color::DarkGray)),
+            Span::raw(" "),
+            Span::styled(
+                format!("{:<5}", record.level),
+                Style::default()
+                    .fg(level_color(record.level))
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(" "),
+            Span::styled(record.target.as_str(), Style::default().fg(Color::Cyan)),
+            Span::raw(" "),
+            Span::raw(record.message.as_str()),
+        ])
+    }
+}
+
+impl Widget for &LogViewer {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let block = Block::default().borders(Borders::ALL).title(self.title());
+        let height = block.inner(area).height as usize;
+
+        let mut lines = self
+            .visible()
+            .rev()
+            .skip(self.scroll)
+            .take(height)
+            .map(LogViewer::line)
+            .collect::<Vec<_>>();
+        lines.reverse();
+
+        Paragraph::new(lines).block(block).render(area, buf);
+    }
+}
+
+fn level_color(level: Level) -> Color {
+    match level {
+        Level::Error => Color::Red,
+        Level::Warn => Color::Yellow,
+        Level::Info => Color::Green,
+        Level::Debug => Color::Blue,
+        Level::Trace => Color::Magenta,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use super::*;
+    use crate::testing;
+
+    fn rendered(viewer: &LogViewer, height: u16) -> String {
+        let area = Rect::new(0, 0, 80, height);
+        let mut buffer = Buffer::empty(area);
+        viewer.render(area, &mut buffer);
+        buffer
+            .content()
+            .chunks(area.width as usize)
+            .map(|row| row.iter().map(|cell| cell.symbol()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn viewer_filters_scrolls_and_renders_the_newest_matching_records() {
+        let (sender, receiver) = mpsc::channel();
+        let mut viewer = LogViewer::from_receiver(receiver).capacity(3);
+        for (level, target, message) in [
+            (Level::Info, "app", "Dropped by the capacity"),
+            (Level::Debug, "app::db", "Query took 3ms"),
+            (Level::Warn, "app::http", "Slow request"),
+            (Level::Error, "app::db", "Connection lost"),
+        ] {
+            sender
+                .send(testing::owned_record(level, target, message))
+                .unwrap();
+        }
+        viewer.poll();
+
+        let messages = |viewer: &LogViewer| {
+            viewer
+                .visible()
+                .map(|record| record.message.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        assert_eq!(
+            messages(&viewer),
+            "Query took 3ms, Slow request, Connection lost"
+        );
+
+        viewer.set_level(LevelFilter::Warn);
+        viewer.set_target(Some("app::db".to_string()));
+        assert_eq!(messages(&viewer), "Connection lost");
+
+        viewer.set_target(None);
+        viewer.set_search(Some("SLOW".to_string()));
+        assert_eq!(messages(&viewer), "Slow request");
+
+        viewer.set_search(None);
+        // One line fits between the borders, showing the newest record until scrolled up.
+        assert!(rendered(&viewer, 3).contains("Connection lost"));
+        viewer.scroll_up(5);
+        let screen = rendered(&viewer, 3);
+        assert!(screen.contains("Slow request") && screen.contains("scrolled: 1"));
+    }
+}
@@ -0,0 +1,91 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use lum_libs::{log::LevelFilter, parking_lot::Mutex};
+
+use crate::{ConfigBuilder, logger};
+
+#[derive(Debug)]
+struct Override {
+    id: u64,
+    targets: Option<Vec<String>>,
+    level: LevelFilter,
+}
+
+static OVERRIDES: Mutex<Vec<Override>> = Mutex::new(Vec::new());
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Raises the effective log level of the root logger and all configured targets to at least the given level,
+/// until the returned guard is dropped.
+/// This is useful for wrapping a single problematic operation in maximal logging without a restart.
+/// Scopes can be nested; the most verbose active scope wins.
+/// This requires the logger to be set up with [`ConfigBuilder::apply`], otherwise it has no effect.
+pub fn verbose_scope(level: LevelFilter) -> VerbosityGuard {
+    push(None, level)
+}
+
+/// Raises the effective log level of the given targets and their children to at least the given level,
+/// until the returned guard is dropped. See [`verbose_scope`].
+pub fn verbose_scope_for<I, S>(targets: I, level: LevelFilter) -> VerbosityGuard
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    push(Some(targets.into_iter().map(Into::into).collect()), level)
+}
+
+/// Restores the previous log levels when dropped. Returned by [`verbose_scope`] and [`verbose_scope_for`].
+#[derive(Debug)]
+#[must_use = "The verbosity is restored as soon as the guard is dropped"]
+pub struct VerbosityGuard {
+    id: u64,
+}
+
+impl Drop for VerbosityGuard {
+    fn drop(&mut self) {
+        OVERRIDES.lock().retain(|entry| entry.id != self.id);
+        let _ = logger::reconfigure();
+    }
+}
+
+fn push(targets: Option<Vec<String>>, level: LevelFilter) -> VerbosityGuard {
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    OVERRIDES.lock().push(Override { id, targets, level });
+    let _ = logger::reconfigure();
+
+    VerbosityGuard { id }
+}
+
+/// Applies all active verbosity overrides to the given builder.
+pub(crate) fn apply_overrides(mut builder: ConfigBuilder) -> ConfigBuilder {
+    for entry in OVERRIDES.lock().iter() {
+        builder.raise_level(entry.targets.as_deref(), entry.level);
+    }
+    builder
+}
+
+#[cfg(test)]
+mod tests {
+    use lum_libs::log;
+
+    use super::*;
+    use crate::testing;
+
+    #[test]
+    fn scopes_raise_the_level_until_their_guard_is_dropped() {
+        let _global = testing::GLOBAL.lock();
+        let records = testing::capture(ConfigBuilder::new().root_log_level(LevelFilter::Info));
+
+        log::debug!(target: "verbosity_test::db", "Hidden");
+        {
+            let _guard = verbose_scope_for(["verbosity_test::db"], LevelFilter::Debug);
+            log::debug!(target: "verbosity_test::db::pool", "Scoped");
+            log::debug!(target: "verbosity_test::http", "Other target");
+
+            let _guard = verbose_scope(LevelFilter::Trace);
+            log::trace!(target: "verbosity_test::http", "Nested");
+        }
+        log::debug!(target: "verbosity_test::db", "Hidden again");
+
+        assert_eq!(testing::messages(&records), ["Scoped", "Nested"]);
+    }
+}
@@ -0,0 +1,310 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, ErrorKind, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+};
+
+use lum_libs::parking_lot::Mutex;
+
+use crate::default;
+
+const SEGMENT_PREFIX: &str = "wal-";
+const SEGMENT_EXTENSION: &str = "log";
+const CHECKPOINT_FILE: &str = "wal.checkpoint";
+
+/// A position in a [`WriteAheadLog`], pointing behind a record.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct WalPosition {
+    segment: u64,
+    offset: u64,
+}
+
+#[derive(Debug)]
+struct WalState {
+    segments: Vec<u64>,
+    write: WalPosition,
+    read: WalPosition,
+    confirmed: WalPosition,
+}
+
+/// A write-ahead log for at-least-once delivery of records.
+/// Records are durably written by [`WriteAheadLog::append`] before it returns,
+/// read in order by [`WriteAheadLog::next`], and trimmed once [`WriteAheadLog::confirm`] is called after the remote confirmed receipt.
+/// The confirmed position is persisted in a checkpoint file,
+/// so unconfirmed records are delivered again after a restart.
+#[derive(Debug)]
+pub struct WriteAheadLog {
+    directory: PathBuf,
+    segment_bytes: u64,
+    state: Mutex<WalState>,
+}
+
+impl WriteAheadLog {
+    /// Opens the write-ahead log in the given directory, creating it if necessary.
+    /// Reading resumes at the last confirmed position.
+    pub fn open(directory: impl Into<PathBuf>) -> io::Result<Self> {
+        let directory = directory.into();
+        fs::create_dir_all(&directory)?;
+
+        let mut segments = Vec::new();
+        for entry in fs::read_dir(&directory)? {
+            if let Some(segment) = segment_sequence(&entry?.path()) {
+                segments.push(segment);
+            }
+        }
+        segments.sort_unstable();
+
+        let wal = Self {
+            directory,
+            segment_bytes: default::wal_segment_bytes(),
+            state: Mutex::new(WalState {
+                segments: Vec::new(),
+                write: WalPosition::default(),
+                read: WalPosition::default(),
+                confirmed: WalPosition::default(),
+            }),
+        };
+
+        let confirmed = wal.read_checkpoint()?.unwrap_or(WalPosition {
+            segment: segments.first().copied().unwrap_or(0),
+            offset: 0,
+        });
+        let write = match segments.last() {
+            Some(&segment) => WalPosition {
+                segment,
+                offset: truncate_partial_frame(&wal.segment_path(segment))?,
+            },
+            None => confirmed,
+        };
+
+        segments.retain(|&segment| segment >= confirmed.segment);
+        *wal.state.lock() = WalState {
+            segments,
+            write,
+            read: confirmed,
+            confirmed,
+        };
+
+        Ok(wal)
+    }
+
+    /// Returns the directory of the write-ahead log.
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Durably writes the given encoded record.
+    pub fn append(&self, payload: &[u8]) -> io::Result<()> {
+        let length = u32::try_from(payload.len())
+            .map_err(|_| io::Error::new(ErrorKind::InvalidInput, "Record too large to log"))?;
+
+        let mut state = self.state.lock();
+        if state.write.offset >= self.segment_bytes || state.segments.is_empty() {
+            let segment = match state.segments.last() {
+                Some(last) => last + 1,
+                None if state.write.offset > 0 => state.write.segment + 1,
+                None => state.write.segment,
+            };
+            state.segments.push(segment);
+            state.write = WalPosition { segment, offset: 0 };
+        }
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.segment_path(state.write.segment))?;
+        file.write_all(&length.to_le_bytes())?;
+        file.write_all(payload)?;
+        file.sync_data()?;
+
+        state.write.offset += 4 + u64::from(length);
+        Ok(())
+    }
+
+    /// Returns whether there are records that were not read yet.
+    pub fn has_unread(&self) -> bool {
+        let state = self.state.lock();
+        state.read < state.write
+    }
+
+    /// Returns whether all records were confirmed.
+    pub fn is_confirmed(&self) -> bool {
+        let state = self.state.lock();
+        state.confirmed >= state.write
+    }
+
+    /// Reads the next record, returning it with the position to pass to [`WriteAheadLog::confirm`] once it was delivered.
+    pub fn next(&self) -> io::Result<Option<(WalPosition, Vec<u8>)>> {
+        let mut state = self.state.lock();
+
+        loop {
+            if state.read >= state.write {
+                return Ok(None);
+            }
+
+            let mut file = File::open(self.segment_path(state.read.segment))?;
+            file.seek(SeekFrom::Start(state.read.offset))?;
+
+            let mut length = [0; 4];
+            match file.read_exact(&mut length) {
+                Ok(()) => {}
+                Err(error) if error.kind() == ErrorKind::UnexpectedEof => {
+                    let next_segment = state
+                        .segments
+                        .iter()
+                        .copied()
+                        .find(|&segment| segment > state.read.segment);
+                    match next_segment {
+                        Some(segment) => {
+                            state.read = WalPosition { segment, offset: 0 };
+                            continue;
+                        }
+                        None => return Ok(None),
+                    }
+                }
+                Err(error) => return Err(error),
+            }
+
+            let length = u32::from_le_bytes(length);
+            let mut payload = vec![0; length as usize];
+            file.read_exact(&mut payload)?;
+
+            state.read.offset += 4 + u64::from(length);
+            return Ok(Some((state.read, payload)));
+        }
+    }
+
+    /// Rewinds reading to the last confirmed position, e.g. after a record could not be delivered.
+    pub fn rewind(&self) {
+        let mut state = self.state.lock();
+        state.read = state.confirmed;
+    }
+
+    /// Confirms delivery of all records up to the given position,
+    /// persisting the checkpoint and deleting segments that were completely confirmed.
+    pub fn confirm(&self, position: WalPosition) -> io::Result<()> {
+        let mut state = self.state.lock();
+        if position <= state.confirmed {
+            return Ok(());
+        }
+
+        self.write_checkpoint(position)?;
+        state.confirmed = position;
+
+        while let Some(&segment) = state.segments.first() {
+            if segment >= position.segment {
+                break;
+            }
+            fs::remove_file(self.segment_path(segment))?;
+            state.segments.remove(0);
+        }
+
+        Ok(())
+    }
+
+    fn segment_path(&self, segment: u64) -> PathBuf {
+        self.directory
+            .join(format!("{SEGMENT_PREFIX}{segment:020}.{SEGMENT_EXTENSION}"))
+    }
+
+    fn read_checkpoint(&self) -> io::Result<Option<WalPosition>> {
+        let content = match fs::read_to_string(self.directory.join(CHECKPOINT_FILE)) {
+            Ok(content) => content,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(error) => return Err(error),
+        };
+
+        let mut parts = content.split_whitespace().map(str::parse::<u64>);
+        match (parts.next(), parts.next()) {
+            (Some(Ok(segment)), Some(Ok(offset))) => Ok(Some(WalPosition { segment, offset })),
+            _ => Err(io::Error::new(
+                ErrorKind::InvalidData,
+                "Malformed write-ahead log checkpoint",
+            )),
+        }
+    }
+
+    fn write_checkpoint(&self, position: WalPosition) -> io::Result<()> {
+        let path = self.directory.join(CHECKPOINT_FILE);
+        let temporary = path.with_extension("tmp");
+
+        let mut file = File::create(&temporary)?;
+        write!(file, "{} {}", position.segment, position.offset)?;
+        file.sync_data()?;
+
+        fs::rename(temporary, path)
+    }
+}
+
+/// Truncates a frame left incomplete by a crash while writing, returning the resulting length of the segment.
+fn truncate_partial_frame(path: &Path) -> io::Result<u64> {
+    let mut file = OpenOptions::new().read(true).write(true).open(path)?;
+    let length = file.metadata()?.len();
+
+    let mut offset = 0;
+    while offset + 4 <= length {
+        let mut frame_length = [0; 4];
+        file.seek(SeekFrom::Start(offset))?;
+        file.read_exact(&mut frame_length)?;
+
+        let end = offset + 4 + u64::from(u32::from_le_bytes(frame_length));
+        if end > length {
+            break;
+        }
+        offset = end;
+    }
+
+    if offset < length {
+        file.set_len(offset)?;
+        file.sync_data()?;
+    }
+
+    Ok(offset)
+}
+
+fn segment_sequence(path: &Path) -> Option<u64> {
+    if path.extension()? != SEGMENT_EXTENSION {
+        return None;
+    }
+
+    path.file_stem()?
+        .to_str()?
+        .strip_prefix(SEGMENT_PREFIX)?
+        .parse()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    #[test]
+    fn unconfirmed_records_are_read_again_after_a_rewind_or_reopen() {
+        let directory = testing::temp_dir("wal");
+        let wal = WriteAheadLog::open(&directory).unwrap();
+        for payload in [&b"first"[..], b"second", b"third"] {
+            wal.append(payload).unwrap();
+        }
+
+        let (first, payload) = wal.next().unwrap().unwrap();
+        assert_eq!(payload, b"first");
+        wal.confirm(first).unwrap();
+        let (_, payload) = wal.next().unwrap().unwrap();
+        assert_eq!(payload, b"second");
+
+        wal.rewind();
+        let (_, payload) = wal.next().unwrap().unwrap();
+        assert_eq!(payload, b"second");
+        drop(wal);
+
+        let wal = WriteAheadLog::open(&directory).unwrap();
+        let mut unconfirmed = Vec::new();
+        while let Some((position, payload)) = wal.next().unwrap() {
+            unconfirmed.push(payload);
+            wal.confirm(position).unwrap();
+        }
+        assert_eq!(unconfirmed, [&b"second"[..], b"third"]);
+        assert!(wal.is_confirmed() && !wal.has_unread());
+    }
+}
@@ -0,0 +1,173 @@
+use std::{
+    collections::HashMap,
+    sync::LazyLock,
+    thread,
+    time::{Duration, Instant},
+};
+
+use lum_libs::{
+    humantime, log,
+    parking_lot::{Condvar, Mutex},
+};
+
+/// The target of the records logged by [`watchdog`] guards.
+pub const WATCHDOG_TARGET: &str = "lum_log::watchdog";
+
+#[derive(Debug)]
+struct Watched {
+    label: String,
+    thread: Option<String>,
+    started: Instant,
+    deadline: Instant,
+    overdue: bool,
+}
+
+#[derive(Debug, Default)]
+struct Watchdog {
+    watched: Mutex<(u64, HashMap<u64, Watched>)>,
+    condvar: Condvar,
+}
+
+static WATCHDOG: LazyLock<&'static Watchdog> = LazyLock::new(|| {
+    let watchdog: &'static Watchdog = Box::leak(Box::default());
+    thread::Builder::new()
+        .name("lum_log-watchdog".to_string())
+        .spawn(move || watchdog.run())
+        .expect("Failed to spawn the watchdog thread");
+    watchdog
+});
+
+/// Starts watching the current scope, logging a warning to [`WATCHDOG_TARGET`]
+/// if the returned guard is not dropped within the given duration.
+/// If the scope completes after the warning, its total duration is logged as well.
+/// The deadline is checked by a shared background thread, so stuck operations are reported while they are stuck.
+pub fn watchdog(duration: Duration, label: impl Into<String>) -> WatchdogGuard {
+    let now = Instant::now();
+    let watched = Watched {
+        label: label.into(),
+        thread: thread::current().name().map(str::to_string),
+        started: now,
+        deadline: now + duration,
+        overdue: false,
+    };
+
+    let mut lock = WATCHDOG.watched.lock();
+    let (next_id, watched_map) = &mut *lock;
+    let id = *next_id;
+    *next_id += 1;
+    watched_map.insert(id, watched);
+    WATCHDOG.condvar.notify_one();
+
+    WatchdogGuard { id }
+}
+
+/// Stops watching the scope when dropped. Returned by [`watchdog`].
+#[derive(Debug)]
+#[must_use = "The scope is only watched until the guard is dropped"]
+pub struct WatchdogGuard {
+    id: u64,
+}
+
+impl Drop for WatchdogGuard {
+    fn drop(&mut self) {
+        let Some(watched) = WATCHDOG.watched.lock().1.remove(&self.id) else {
+            return;
+        };
+
+        if watched.overdue {
+            log::info!(
+                target: WATCHDOG_TARGET,
+                "{} completed after {}",
+                watched.label,
+                humantime::format_duration(round(watched.started.elapsed()))
+            );
+        }
+    }
+}
+
+impl Watchdog {
+    fn run(&self) {
+        let mut lock = self.watched.lock();
+        loop {
+            let now = Instant::now();
+            let mut overdue = Vec::new();
+            for watched in lock.1.values_mut().filter(|watched| !watched.overdue) {
+                if watched.deadline <= now {
+                    watched.overdue = true;
+                    overdue.push((
+                        watched.label.clone(),
+                        watched.thread.clone(),
+                        now - watched.started,
+                    ));
+                }
+            }
+
+            if !overdue.is_empty() {
+                drop(lock);
+                for (label, thread, elapsed) in overdue {
+                    log::warn!(
+                        target: WATCHDOG_TARGET,
+                        "{label} has not completed after {} (thread {})",
+                        humantime::format_duration(round(elapsed)),
+                        thread.as_deref().unwrap_or("<unnamed>")
+                    );
+                }
+                lock = self.watched.lock();
+                continue;
+            }
+
+            let next_deadline = lock
+                .1
+                .values()
+                .filter(|watched| !watched.overdue)
+                .map(|watched| watched.deadline)
+                .min();
+            match next_deadline {
+                Some(deadline) => {
+                    self.condvar.wait_until(&mut lock, deadline);
+                }
+                None => self.condvar.wait(&mut lock),
+            }
+        }
+    }
+}
+
+/// Rounds the given duration to milliseconds for readable output.
+fn round(duration: Duration) -> Duration {
+    Duration::from_millis(duration.as_millis() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use lum_libs::log::{Level, LevelFilter};
+
+    use super::*;
+    use crate::{ConfigBuilder, testing};
+
+    #[test]
+    fn overdue_scopes_are_reported_while_stuck_and_when_completed() {
+        let _global = testing::GLOBAL.lock();
+        let records = testing::capture(ConfigBuilder::new().root_log_level(LevelFilter::Info));
+
+        drop(watchdog(Duration::from_secs(60), "Fast"));
+        let guard = watchdog(Duration::from_millis(10), "Import");
+        let warning = records
+            .recv_timeout(Duration::from_secs(5))
+            .expect("The overdue scope is reported");
+        drop(guard);
+
+        assert_eq!(warning.level, Level::Warn);
+        assert!(
+            warning
+                .message
+                .starts_with("Import has not completed after")
+        );
+        assert!(warning.message.contains(&format!(
+            "(thread {})",
+            thread::current().name().unwrap_or("<unnamed>")
+        )));
+        let completed = testing::messages(&records);
+        assert_eq!(completed.len(), 1);
+        assert!(completed[0].starts_with("Import completed after"));
+    }
+}
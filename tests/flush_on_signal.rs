@@ -0,0 +1,79 @@
+//! Runs in its own process, as the test re-runs itself as a child process terminated by a signal.
+#![cfg(all(unix, feature = "signals"))]
+
+use std::{
+    env,
+    fs::{self, OpenOptions},
+    io::Write,
+    os::unix::process::ExitStatusExt,
+    path::PathBuf,
+    process::{Command, Stdio},
+    thread,
+    time::Duration,
+};
+
+use lum_log::{
+    ConfigBuilder,
+    backpressure::Backpressure,
+    log::{self, LevelFilter, Record},
+    log4rs::append::Append,
+};
+use signal_hook::{consts::SIGTERM, low_level};
+
+const CHILD_ENV_VAR: &str = "LUM_LOG_SIGNAL_TEST_FILE";
+
+/// An appender taking a while per record before writing its message to a file.
+#[derive(Debug)]
+struct SlowFile(PathBuf);
+
+impl Append for SlowFile {
+    fn append(&self, record: &Record) -> anyhow::Result<()> {
+        thread::sleep(Duration::from_millis(100));
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.0)?;
+        writeln!(file, "{}", record.args())?;
+        Ok(())
+    }
+
+    fn flush(&self) {}
+}
+
+fn run_child(path: PathBuf) -> ! {
+    ConfigBuilder::new()
+        .root_log_level(LevelFilter::Info)
+        .appender("slow", Box::new(SlowFile(path)))
+        .asynchronous("slow", Backpressure::Block)
+        .unwrap()
+        .flush_on_signal(true)
+        .apply()
+        .unwrap();
+
+    log::info!(target: "signal_test", "Buffered");
+    low_level::raise(SIGTERM).unwrap();
+    loop {
+        thread::sleep(Duration::from_secs(1));
+    }
+}
+
+#[test]
+fn buffered_records_are_written_before_a_signal_terminates_the_process() {
+    if let Some(path) = env::var_os(CHILD_ENV_VAR) {
+        run_child(path.into());
+    }
+
+    let path = env::temp_dir().join(format!("lum_log-signal-{}.log", std::process::id()));
+    let _ = fs::remove_file(&path);
+    let status = Command::new(env::current_exe().unwrap())
+        .args([
+            "--exact",
+            "buffered_records_are_written_before_a_signal_terminates_the_process",
+        ])
+        .env(CHILD_ENV_VAR, &path)
+        .stdout(Stdio::null())
+        .status()
+        .unwrap();
+
+    assert_eq!(status.signal(), Some(SIGTERM));
+    let log = fs::read_to_string(&path).unwrap();
+    let _ = fs::remove_file(&path);
+    assert_eq!(log, "Buffered\nReceived SIGTERM, shutting down\n");
+}
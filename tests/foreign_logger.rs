@@ -0,0 +1,35 @@
+//! Runs in its own process, as the global logger can only be set once.
+#![cfg(feature = "std")]
+
+use std::error::Error;
+
+use lum_log::{
+    ConfigBuilder, ConfigBuilderError,
+    log::{self, Log, Metadata, Record},
+};
+
+struct ForeignLogger;
+
+impl Log for ForeignLogger {
+    fn enabled(&self, _metadata: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, _record: &Record) {}
+
+    fn flush(&self) {}
+}
+
+#[test]
+fn apply_fails_with_the_cause_if_a_foreign_logger_is_installed() {
+    log::set_logger(&ForeignLogger).unwrap();
+
+    let error = ConfigBuilder::new().apply().unwrap_err();
+
+    assert!(matches!(
+        error,
+        ConfigBuilderError::ForeignLoggerInstalled(_)
+    ));
+    assert!(error.source().is_some());
+    assert!(!lum_log::is_set_up());
+}
@@ -0,0 +1,87 @@
+//! Runs in its own process, as capturing redirects the stdout and stderr of the whole process.
+#![cfg(all(unix, feature = "std"))]
+
+use std::{
+    fs::File,
+    io::{self, ErrorKind, Write},
+    os::fd::AsRawFd,
+    process::Command,
+    sync::mpsc,
+};
+
+use lum_log::{
+    ConfigBuilder, OwnedRecord,
+    append::ChannelAppender,
+    config::{ConsoleStream, Output},
+    log::{self, Level, LevelFilter},
+    stdio::{self, STDERR_TARGET, STDOUT_TARGET, StdioCapture},
+};
+
+#[test]
+fn captured_output_is_logged_line_by_line_until_the_guard_is_dropped() {
+    let (sender, records) = mpsc::channel::<OwnedRecord>();
+    ConfigBuilder::new()
+        .root_log_level(LevelFilter::Info)
+        .appender("capture", Box::new(ChannelAppender::new(sender)))
+        .output(Output::Console {
+            name: None,
+            stream: ConsoleStream::Stderr,
+            level: Some(LevelFilter::Error),
+        })
+        .unwrap()
+        .apply()
+        .unwrap();
+
+    // Point stderr to a file before capturing, so it is the original stderr the console appender writes to.
+    let path =
+        std::env::temp_dir().join(format!("lum_log_stdio_capture_{}.log", std::process::id()));
+    let original_stderr = File::create(&path).unwrap();
+    // SAFETY: Duplicating and replacing descriptors has no memory safety requirements.
+    let real_stderr = unsafe { libc::dup(libc::STDERR_FILENO) };
+    // SAFETY: Both descriptors are open; `dup2` atomically replaces stderr.
+    unsafe { libc::dup2(original_stderr.as_raw_fd(), libc::STDERR_FILENO) };
+
+    let capture = StdioCapture::start().unwrap();
+    assert!(stdio::is_capturing());
+    assert_eq!(
+        StdioCapture::start().unwrap_err().kind(),
+        ErrorKind::AlreadyExists
+    );
+
+    // The test harness only captures `print!`, so write to the descriptors directly.
+    writeln!(io::stdout(), "first").unwrap();
+    writeln!(io::stderr(), "failed").unwrap();
+    let status = Command::new("sh")
+        .args(["-c", "printf 'second\\n'"])
+        .status()
+        .unwrap();
+    assert!(status.success());
+    log::error!(target: "stdio_test", "Disk full");
+    drop(capture);
+
+    // SAFETY: Both descriptors are open; `dup2` atomically replaces stderr.
+    unsafe {
+        libc::dup2(real_stderr, libc::STDERR_FILENO);
+        libc::close(real_stderr);
+    }
+    let console = std::fs::read_to_string(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+    assert!(console.trim_end().ends_with("Disk full"), "{console}");
+
+    assert!(!stdio::is_capturing());
+    let mut records = records
+        .try_iter()
+        .filter(|record| record.target == STDOUT_TARGET || record.target == STDERR_TARGET)
+        .map(|record| (record.target, record.level, record.message))
+        .collect::<Vec<_>>();
+    // Stdout and stderr are read concurrently, so only the order of each output is kept.
+    records.sort_by_key(|(_, level, _)| *level);
+    assert_eq!(
+        records,
+        [
+            (STDERR_TARGET.to_string(), Level::Warn, "failed".to_string()),
+            (STDOUT_TARGET.to_string(), Level::Info, "first".to_string()),
+            (STDOUT_TARGET.to_string(), Level::Info, "second".to_string()),
+        ]
+    );
+}
@@ -0,0 +1,27 @@
+//! Runs in its own process, as the global logger can only be set once.
+#![cfg(feature = "std")]
+
+use std::thread;
+
+use lum_log::{
+    log::{self, LevelFilter},
+    testing::{self, LEVEL_ENV_VAR},
+};
+
+#[test]
+fn parallel_calls_set_up_the_logger_once_at_the_configured_level() {
+    let callers = (0..8)
+        .map(|_| thread::spawn(testing::try_init_quiet))
+        .collect::<Vec<_>>();
+    for caller in callers {
+        caller.join().unwrap();
+    }
+    testing::try_init_quiet();
+
+    assert!(lum_log::is_set_up());
+    let expected = std::env::var(LEVEL_ENV_VAR)
+        .ok()
+        .and_then(|level| level.trim().parse::<LevelFilter>().ok())
+        .unwrap_or_else(lum_log::default::test_log_level);
+    assert_eq!(log::max_level(), expected);
+}